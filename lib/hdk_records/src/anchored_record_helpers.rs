@@ -16,18 +16,37 @@ use hdk_type_serialization_macros::{
 };
 
 use crate::{
-    RecordAPIResult, DataIntegrityError,
+    RecordAPIResult, DataIntegrityError, ErrorCode, ErrorDetails,
     record_interface::{
         Identified, Identifiable, UniquelyIdentifiable,
         Updateable, UpdateableIdentifier,
     },
-    link_helpers::get_linked_addresses,
-    identity_helpers::calculate_identity_address,
+    link_helpers::{
+        get_linked_addresses,
+        get_earliest_linked_address,
+    },
+    identity_helpers::{
+        calculate_identity_address,
+        read_entry_identity,
+        read_entry_identity_full,
+    },
     records::{
         create_record,
         read_record_entry_by_identity,
-        // read_record_entry_by_header,
-        get_latest_header_hash,
+        read_record_entry_by_header,
+        get_revision_heads,
+        check_revision_is_head,
+        merge_record_revisions,
+    },
+    local_index_helpers::{
+        RootIndexPage,
+        read_root_index_page,
+    },
+    history_helpers::{
+        Timestamp,
+        RevisionMeta,
+        get_revision_history,
+        get_revision_as_of,
     },
     entries::{
         try_entry_from_element,
@@ -57,6 +76,18 @@ fn identity_path_for<A, S>(
     temp_path::path::Path::from(vec![type_root.into(), string_id.into()])
 }
 
+/// Build the tag for links between a record's identity and its `anchor index`.
+///
+/// The tag references the revision which assigned the identifier, so that the link can be
+/// checked against it during validation. Reads match on the leading tag bytes alone.
+///
+fn anchor_link_tag(revision_id: &RevisionHash) -> LinkTag
+{
+    let mut tag = crate::identifiers::RECORD_IDENTITY_ANCHOR_LINK_TAG.to_vec();
+    tag.extend_from_slice(revision_id.1.get_raw_36());
+    LinkTag::new(tag)
+}
+
 /// Determine the underlying `EntryHash` for a given `base_address` identifier, without querying the DHT.
 ///
 fn calculate_anchor_address<I, S>(
@@ -92,12 +123,66 @@ fn read_entry_anchor_id(
 
 /// Given the `EntryHash` of an anchor `Path`, query the identity of the associated entry
 ///
+/// If concurrent writes have caused multiple records to claim the same anchor, the
+/// earliest claim wins. Later records remain accessible by their hash identity only.
+///
 fn read_anchor_identity(
     anchor_path_address: &EntryHash,
 ) -> RecordAPIResult<EntryHash>
 {
-    let mut addrs = get_linked_addresses(anchor_path_address, LinkTag::new(crate::identifiers::RECORD_IDENTITY_ANCHOR_LINK_TAG))?;
-    Ok(addrs.pop().ok_or(DataIntegrityError::IndexNotFound((*anchor_path_address).clone()))?)
+    let maybe_identity = get_earliest_linked_address(anchor_path_address, LinkTag::new(crate::identifiers::RECORD_IDENTITY_ANCHOR_LINK_TAG))?;
+    Ok(maybe_identity.ok_or(DataIntegrityError::IndexNotFound((*anchor_path_address).clone()))?)
+}
+
+/// Determine whether the primary `anchor index` with the given `id_string` is currently
+/// assigned to some record.
+///
+fn anchor_exists<S, I>(
+    entry_type_root_path: &S,
+    id_string: I,
+) -> RecordAPIResult<bool>
+    where S: AsRef<str>,
+        I: AsRef<str>,
+{
+    let anchor_address = calculate_anchor_address(entry_type_root_path, &id_string)?;
+    let addrs = get_linked_addresses(&anchor_address, LinkTag::new(crate::identifiers::RECORD_IDENTITY_ANCHOR_LINK_TAG))?;
+    Ok(!addrs.is_empty())
+}
+
+/// Determine whether a secondary `anchor index` with the given `id_string` has already
+/// been assigned to some record.
+///
+/// @see create_anchor_index
+///
+pub fn anchor_index_exists<S, I>(
+    anchor_root_path: &S,
+    id_string: I,
+) -> RecordAPIResult<bool>
+    where S: AsRef<str>,
+        I: AsRef<str>,
+{
+    let anchor_address = calculate_anchor_address(anchor_root_path, &id_string)?;
+    let addrs = get_linked_addresses(&anchor_address, LinkTag::new(crate::identifiers::RECORD_SECONDARY_ANCHOR_LINK_TAG))?;
+    Ok(!addrs.is_empty())
+}
+
+/// Reads the identity of a record via a secondary `anchor index` assigned
+/// with `create_anchor_index`.
+///
+/// Where concurrent writes have linked multiple records, the earliest is returned.
+///
+pub fn read_anchor_index<A, S, I>(
+    anchor_root_path: &S,
+    id_string: I,
+) -> RecordAPIResult<A>
+    where S: AsRef<str>,
+        I: AsRef<str>,
+        A: DnaAddressable<EntryHash>,
+{
+    let anchor_address = calculate_anchor_address(anchor_root_path, &id_string)?;
+    let identity_address = get_earliest_linked_address(&anchor_address, LinkTag::new(crate::identifiers::RECORD_SECONDARY_ANCHOR_LINK_TAG))?
+        .ok_or(DataIntegrityError::EntryNotFound)?;
+    read_entry_identity_full(&identity_address)
 }
 
 /// Reads an entry via its `anchor index`.
@@ -127,12 +212,144 @@ pub fn read_anchored_record_entry<T, R, B, A, S, I>(
     Ok((revision_id, A::new(dna_info()?.hash, id_string.as_ref().to_string()), entry_data))
 }
 
+/// Given a type of entry, returns one page of the anchored records of that entry
+/// type registered internally with the DHT, in order of creation.
+///
+/// The root index of anchored records also links to their `anchor index` paths,
+/// which are excluded from the results. Pages may therefore hold fewer results
+/// than `limit` even where more records follow.
+///
+pub fn query_anchored_root_index_page<T, R, B, A, S>(
+    entry_type_root_path: &S,
+    after: &Option<String>,
+    limit: usize,
+) -> RecordAPIResult<RootIndexPage<RecordAPIResult<(RevisionHash, A, T)>>>
+    where S: AsRef<str>,
+        T: std::fmt::Debug,
+        B: DnaAddressable<EntryHash>,
+        A: DnaIdentifiable<String>,
+        SerializedBytes: TryInto<R, Error = SerializedBytesError>,
+        Entry: TryFrom<R>,
+        R: std::fmt::Debug + Identified<T, B>,
+{
+    let page = read_root_index_page(entry_type_root_path, after, limit)?;
+    let dna = dna_info()?.hash;
+
+    let mut results = Vec::with_capacity(page.results.len());
+    for identity_address in page.results.iter() {
+        let initial_entries = get_linked_addresses(identity_address, LinkTag::new(crate::identifiers::RECORD_INITIAL_ENTRY_LINK_TAG))?;
+        if initial_entries.is_empty() {
+            continue;
+        }
+        results.push(read_entry_anchor_id(identity_address)
+            .and_then(|id_string| {
+                let (revision_id, _entry_addr, entry_data) = read_record_entry_by_identity::<T, R, B>(identity_address)?;
+                Ok((revision_id, A::new(dna.to_owned(), id_string), entry_data))
+            }));
+    }
+
+    Ok(RootIndexPage {
+        results,
+        start_cursor: page.start_cursor,
+        end_cursor: page.end_cursor,
+        has_previous_page: page.has_previous_page,
+        has_next_page: page.has_next_page,
+        total_count: page.total_count,
+    })
+}
+
+/// Retrieve all current revisions of a record via its `anchor index`.
+///
+/// @see hdk_records::records::get_revision_heads
+///
+pub fn read_anchored_record_revision_heads<S, I>(
+    entry_type_root_path: &S,
+    id_string: I,
+) -> RecordAPIResult<Vec<RevisionHash>>
+    where S: AsRef<str>,
+        I: AsRef<str>,
+{
+    let anchor_address = calculate_anchor_address(entry_type_root_path, &id_string)?;
+    let identity_address = read_anchor_identity(&anchor_address)?;
+    let entry_hash = read_entry_identity(&identity_address)?;
+    get_revision_heads(entry_hash)
+}
+
+/// List every revision of a record via its `anchor index`.
+///
+/// @see hdk_records::history::get_revision_history
+///
+pub fn read_anchored_record_revision_history<S, I>(
+    entry_type_root_path: &S,
+    id_string: I,
+) -> RecordAPIResult<Vec<RevisionMeta>>
+    where S: AsRef<str>,
+        I: AsRef<str>,
+{
+    let anchor_address = calculate_anchor_address(entry_type_root_path, &id_string)?;
+    let identity_address = read_anchor_identity(&anchor_address)?;
+    let entry_hash = read_entry_identity(&identity_address)?;
+    get_revision_history(entry_hash)
+}
+
+/// Read a specific revision of an anchored record, along with its current `anchor index`.
+///
+/// The `anchor index` returned is always the current one, which may differ from the
+/// identifier stored in older revisions if it has since been changed.
+///
+pub fn read_anchored_record_entry_by_header<T, R, B, A, S>(
+    entry_def_id: &S,
+    revision_id: &RevisionHash,
+) -> RecordAPIResult<(A, T)>
+    where S: AsRef<str>,
+        T: std::fmt::Debug,
+        B: DnaAddressable<EntryHash>,
+        A: DnaIdentifiable<String>,
+        SerializedBytes: TryInto<R, Error = SerializedBytesError>,
+        Entry: TryFrom<R>,
+        R: std::fmt::Debug + Identified<T, B>,
+{
+    let (identity, entry_data) = read_record_entry_by_header::<T, R, B>(revision_id)?;
+    let identity_path_address = calculate_identity_address(entry_def_id, &identity)?;
+    let current_id = read_entry_anchor_id(&identity_path_address)?;
+
+    Ok((A::new(dna_info()?.hash, current_id), entry_data))
+}
+
+/// Read an anchored record's entry data as it was at the given point in time.
+///
+/// @see hdk_records::history::read_record_entry_as_of
+///
+pub fn read_anchored_record_entry_as_of<T, R, B, A, S, I>(
+    entry_type_root_path: &S,
+    id_string: I,
+    as_of: &Timestamp,
+) -> RecordAPIResult<(RevisionHash, A, T)>
+    where S: AsRef<str>,
+        I: AsRef<str>,
+        T: std::fmt::Debug,
+        B: DnaAddressable<EntryHash>,
+        A: DnaIdentifiable<String>,
+        SerializedBytes: TryInto<R, Error = SerializedBytesError>,
+        Entry: TryFrom<R>,
+        R: std::fmt::Debug + Identified<T, B>,
+{
+    let anchor_address = calculate_anchor_address(entry_type_root_path, &id_string)?;
+    let identity_address = read_anchor_identity(&anchor_address)?;
+    let revision_id = get_revision_as_of(read_entry_identity(&identity_address)?, as_of)?;
+
+    let (_identity, entry_data) = read_record_entry_by_header::<T, R, B>(&revision_id)?;
+    Ok((revision_id, A::new(dna_info()?.hash, id_string.as_ref().to_string()), entry_data))
+}
+
 /// Creates a new record in the DHT and assigns it a manually specified `anchor index`
 /// that can be used like a primary key. The `create_payload` must also implement
 /// `UniquelyIdentifiable` in order to derive the unique `anchor index` value.
 ///
-/// It is recommended that you include a creation timestamp in newly created records, to avoid
-/// them conflicting with previously entered entries that may be of the same content.
+/// Returns `DataIntegrityError::DuplicateAnchorKey` if the `anchor index` is already in use.
+///
+/// Zomes using anchored records should check the links created here with `validate_anchor_link`.
+/// Should multiple records claim the same identifier, reads resolve to the earliest claim.
 ///
 pub fn create_anchored_record<I, B, A, C, R, E, S>(
     entry_def_id: &S,
@@ -147,9 +364,11 @@ pub fn create_anchored_record<I, B, A, C, R, E, S>(
         Entry: TryFrom<R, Error = E>,
         R: Clone + Identified<I, B>,
 {
-    // determine unique anchor index key
-    // :TODO: deal with collisions
+    // determine unique anchor index key, and ensure it is not already taken
     let entry_id = create_payload.get_anchor_key()?;
+    if anchor_exists(entry_def_id, &entry_id)? {
+        return Err(DataIntegrityError::DuplicateAnchorKey(entry_id));
+    }
 
     // write base record and identity index path
     let (revision_id, entry_internal_id, entry_data) = create_record::<I, R, _,_,_,_>(&entry_def_id, create_payload)?;
@@ -160,17 +379,48 @@ pub fn create_anchored_record<I, B, A, C, R, E, S>(
 
     // link the hash identifier to the manually assigned identifier so we can determine it when reading & updating
     let identifier_hash = calculate_identity_address(entry_def_id, &entry_internal_id)?;
-    create_link(identifier_hash.clone(), path.hash()?, LinkTag::new(crate::identifiers::RECORD_IDENTITY_ANCHOR_LINK_TAG))?;
-    create_link(path.hash()?, identifier_hash.clone(), LinkTag::new(crate::identifiers::RECORD_IDENTITY_ANCHOR_LINK_TAG))?;
+    create_link(identifier_hash.clone(), path.hash()?, anchor_link_tag(&revision_id))?;
+    create_link(path.hash()?, identifier_hash.clone(), anchor_link_tag(&revision_id))?;
 
     Ok((revision_id, A::new(dna_info()?.hash, entry_id), entry_data))
 }
 
+/// Assigns a secondary `anchor index` to an existing hash-identified record, so that it
+/// can also be located by some unique string identifier (eg. a serial number).
+///
+/// Returns `DataIntegrityError::DuplicateAnchorKey` if the identifier is already in use.
+///
+pub fn create_anchor_index<A, S, I>(
+    entry_type_root_path: &S,
+    identity: &A,
+    anchor_root_path: &S,
+    id_string: I,
+) -> RecordAPIResult<()>
+    where S: AsRef<str>,
+        I: AsRef<str>,
+        A: DnaAddressable<EntryHash>,
+{
+    if anchor_index_exists(anchor_root_path, &id_string)? {
+        return Err(DataIntegrityError::DuplicateAnchorKey(id_string.as_ref().to_string()));
+    }
+
+    let path = identity_path_for(anchor_root_path, &id_string);
+    path.ensure()?;
+
+    let identifier_hash = calculate_identity_address(entry_type_root_path, identity)?;
+    create_link(path.hash()?, identifier_hash, LinkTag::new(crate::identifiers::RECORD_SECONDARY_ANCHOR_LINK_TAG))?;
+
+    Ok(())
+}
+
 /// Updates a record via references to its `anchor index`.
 ///
 /// The `update_payload` must contain all data necessary to determine both the existing
 /// `anchor index` ID of the record, and the new `anchor index` that it has been moved to (if any).
 ///
+/// Returns `DataIntegrityError::DuplicateAnchorKey` if the new `anchor index` is already in use,
+/// or `DataIntegrityError::UpdateConflict` if the given revision has already been updated.
+///
 /// @see hdk_records::record_interface::UpdateableIdentifier
 ///
 pub fn update_anchored_record<I, R: Clone, A, B, U, E, S>(
@@ -193,7 +443,8 @@ pub fn update_anchored_record<I, R: Clone, A, B, U, E, S>(
     let prev_entry = previous.entry();
     let identity = previous.identity()?;
     let identity_hash: &EntryHash = identity.as_ref();
-    let maybe_current_id = read_entry_anchor_id(identity_hash);
+    let identity_path_address = calculate_identity_address(entry_def_id, &identity)?;
+    let maybe_current_id = read_entry_anchor_id(&identity_path_address);
 
     // ensure the referenced entry exists and has an anchored identifier path
     match maybe_current_id {
@@ -201,6 +452,16 @@ pub fn update_anchored_record<I, R: Clone, A, B, U, E, S>(
             let maybe_new_id = update_payload.get_new_anchor_key();
             let mut final_id = current_id.clone();
 
+            // ensure we are updating the latest version of the record
+            check_revision_is_head(revision_id, identity_hash)?;
+
+            // check the new identifier is available before writing anything
+            if let Some(new_id) = &maybe_new_id {
+                if *new_id != final_id && anchor_exists(entry_def_id, new_id)? {
+                    return Err(DataIntegrityError::DuplicateAnchorKey(new_id.to_owned()));
+                }
+            }
+
             // apply update payload
             let new_entry = prev_entry.update_with(update_payload);
             let storage: R = new_entry.with_identity(Some(identity_hash.clone()));
@@ -212,20 +473,14 @@ pub fn update_anchored_record<I, R: Clone, A, B, U, E, S>(
             match maybe_new_id {
                 Some(new_id) => {
                     if new_id != final_id {
-                        // clear any old identity path, ensuring the link structure is as expected
-                        let mut addrs = get_linked_addresses(identity_hash, LinkTag::new(crate::identifiers::RECORD_IDENTITY_ANCHOR_LINK_TAG))?;
-                        if addrs.len() != 1 {
-                            return Err(DataIntegrityError::IndexNotFound(identity_hash.to_owned()));
-                        }
-                        let old_link = addrs.pop().unwrap();
-                        let old_link_id = get_latest_header_hash(old_link)?;
-                        let old_link_hash: &HeaderHash = old_link_id.as_ref();
-                        delete_link(old_link_hash.to_owned())?;
+                        // clear any old identity path, so that the old identifier may be reused
+                        remove_anchor_links(&identity_path_address)?;
 
                         // create the new identifier and link to it
                         let path = identity_path_for(&entry_def_id, &new_id);
                         path.ensure()?;
-                        create_link(identity_hash.to_owned(), path.hash()?, LinkTag::new(crate::identifiers::RECORD_IDENTITY_ANCHOR_LINK_TAG))?;
+                        create_link(identity_path_address.to_owned(), path.hash()?, anchor_link_tag(&header_addr))?;
+                        create_link(path.hash()?, identity_path_address.to_owned(), anchor_link_tag(&header_addr))?;
 
                         // reference final ID in record updates to new identifier path
                         final_id = new_id.into();
@@ -241,18 +496,140 @@ pub fn update_anchored_record<I, R: Clone, A, B, U, E, S>(
     }
 }
 
+/// Resolves concurrent updates to an anchored record, returning the retained revision
+/// along with its current `anchor index`.
+///
+/// @see hdk_records::records::merge_record_revisions
+///
+pub fn merge_anchored_record_revisions<T, R, B, A, S>(
+    entry_def_id: &S,
+    revision_id: &RevisionHash,
+    superseded_revisions: &[RevisionHash],
+) -> RecordAPIResult<(RevisionHash, A, T)>
+    where S: AsRef<str>,
+        T: std::fmt::Debug,
+        B: DnaAddressable<EntryHash>,
+        A: DnaIdentifiable<String>,
+        SerializedBytes: TryInto<R, Error = SerializedBytesError>,
+        Entry: TryFrom<R>,
+        R: std::fmt::Debug + Identified<T, B>,
+{
+    // :TODO: revert anchor changes made by superseded revisions, rather than resolving duplicate anchors on read
+    let (revision, identity, entry_data, _superseded) = merge_record_revisions::<T, R, B>(revision_id, superseded_revisions)?;
+    let identity_path_address = calculate_identity_address(entry_def_id, &identity)?;
+    let current_id = read_entry_anchor_id(&identity_path_address)?;
+
+    Ok((revision, A::new(dna_info()?.hash, current_id), entry_data))
+}
+
 /// Removes a record via references to its `anchor index`.
 ///
 /// The index as well as the record's entry data will both be deleted; any failures
-/// are considered an error.
-///
-/// :TODO: This is a stub- include any logic necessary to handle cleanup of associated links.
-///        Not clearing old anchors may cause issues upon subsequent reinsert, which is not yet tested.
+/// are considered an error. Once removed, the record's `anchor index` may be reused.
 ///
-pub fn delete_anchored_record<T, A>(address: &A) -> RecordAPIResult<bool>
-    where SerializedBytes: TryInto<T, Error = SerializedBytesError>,
+pub fn delete_anchored_record<T, R, B, A, S>(
+    entry_def_id: &S,
+    address: &A,
+) -> RecordAPIResult<bool>
+    where S: AsRef<str>,
+        SerializedBytes: TryInto<R, Error = SerializedBytesError>,
         A: AsRef<HeaderHash>,
+        B: DnaAddressable<EntryHash>,
+        Entry: TryFrom<R>,
+        R: Identified<T, B>,
 {
-    delete_entry::<T, A>(address)?;
+    // read identity of the referenced entry to locate its anchor
+    let previous: R = get_entry_by_header(address)?;
+    let identity = previous.identity()?;
+    let identity_path_address = calculate_identity_address(entry_def_id, &identity)?;
+
+    remove_anchor_links(&identity_path_address)?;
+
+    delete_entry::<R, A>(address)?;
     Ok(true)
 }
+
+/// Removes all links between the identity `Path` of a record and its `anchor index`,
+/// in both directions.
+///
+fn remove_anchor_links(identity_path_address: &EntryHash) -> RecordAPIResult<()>
+{
+    let anchor_links = get_links(identity_path_address.to_owned(), Some(LinkTag::new(crate::identifiers::RECORD_IDENTITY_ANCHOR_LINK_TAG)))?;
+
+    for anchor_link in anchor_links {
+        // remove reciprocal link from anchor to this record, leaving any conflicting records' links intact
+        let identity_links = get_links(anchor_link.target.to_owned(), Some(LinkTag::new(crate::identifiers::RECORD_IDENTITY_ANCHOR_LINK_TAG)))?;
+        for identity_link in identity_links.iter().filter(|l| { l.target == *identity_path_address }) {
+            delete_link(identity_link.create_link_hash.to_owned())?;
+        }
+
+        delete_link(anchor_link.create_link_hash)?;
+    }
+
+    Ok(())
+}
+
+//--------------------------------[ VALIDATE ]--------------------------------------
+
+/// Validate a link between a record's identity and its `anchor index`.
+///
+/// The link tag must reference a revision of the record made by the link's author, and the
+/// link must join that record's identity with the anchor for the identifier `get_anchor_key`
+/// reads from the revision. This ensures an identifier can only be claimed by a record which
+/// holds it, and only by an agent who wrote that record. Links with other tags are ignored.
+///
+/// Host errors (eg. a referenced revision not yet being available) are returned in the outer
+/// `Result`, so that validation can be retried.
+///
+pub fn validate_anchor_link<T, R, B, S, F>(
+    entry_def_id: &S,
+    link: &CreateLink,
+    get_anchor_key: F,
+) -> RecordAPIResult<Result<(), ErrorDetails>>
+    where S: AsRef<str>,
+        B: DnaAddressable<EntryHash>,
+        SerializedBytes: TryInto<R, Error = SerializedBytesError>,
+        Entry: TryFrom<R>,
+        R: Identified<T, B>,
+        F: Fn(&T) -> String,
+{
+    let tag_prefix = crate::identifiers::RECORD_IDENTITY_ANCHOR_LINK_TAG;
+    let tag = link.tag.0.as_slice();
+    if !tag.starts_with(tag_prefix) {
+        return Ok(Ok(()));
+    }
+    let invalid = |message: &str| {
+        ErrorDetails::new(ErrorCode::CorruptIndex, message)
+            .with_detail("link", format!("{:?} -> {:?}", link.base_address, link.target_address))
+    };
+
+    // load the revision which claimed the identifier
+    let revision_bytes = &tag[tag_prefix.len()..];
+    if revision_bytes.len() != 36 {
+        return Ok(Err(invalid("Anchor link does not reference the revision claiming its identifier")));
+    }
+    let revision = must_get_valid_element(HeaderHash::from_raw_36(revision_bytes.to_vec()))?;
+    if revision.header().author() != &link.author {
+        return Ok(Err(ErrorDetails::new(ErrorCode::Unauthorized, "Identifiers may only be claimed by the author of the claiming revision")
+            .with_detail("agent", &link.author)));
+    }
+    let storage: R = match revision.entry().as_option() {
+        Some(entry) => match try_decode_entry(entry.to_owned()) {
+            Ok(storage) => storage,
+            Err(_) => return Ok(Err(invalid("Anchor link references a revision of another record type"))),
+        },
+        None => return Ok(Err(invalid("Anchor link references a revision without entry data"))),
+    };
+
+    // ensure the link joins that record's identity with the anchor for its identifier
+    let identity_address = calculate_identity_address(entry_def_id, &storage.identity()?)?;
+    let anchor_address = calculate_anchor_address(entry_def_id, get_anchor_key(&storage.entry()))?;
+    let joins_claim =
+        (link.base_address == identity_address && link.target_address == anchor_address) ||
+        (link.base_address == anchor_address && link.target_address == identity_address);
+
+    if !joins_claim {
+        return Ok(Err(invalid("Anchor link does not match the identifier held by the claiming revision")));
+    }
+    Ok(Ok(()))
+}
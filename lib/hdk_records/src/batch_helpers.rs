@@ -0,0 +1,118 @@
+/**
+ * Batch operation helpers
+ *
+ * Applies a list of operations within a single zome call. Any failed operation
+ * causes the call to return an error, in which case the conductor discards all
+ * source chain writes made by the call and no operation in the batch is applied.
+ *
+ * Records created by earlier operations may be referenced by later operations via
+ * names assigned by the caller, since their identifiers are not known in advance.
+ *
+ * :NOTE: writes made to other cells (eg. indexes in remote DNAs) are not part of
+ *        the calling cell's source chain and are not reverted when a batch fails.
+ *        Handlers should defer such writes until `run_batch` has returned successfully.
+ *
+ * @package Holo-REA
+ * @since   2026-10-19
+ */
+use std::collections::BTreeMap;
+use hdk::prelude::*;
+use hdk_type_serialization_macros::DnaAddressable;
+
+use crate::{
+    RecordAPIResult, DataIntegrityError,
+    ErrorCode, ErrorDetails,
+};
+
+#[derive(Debug, Clone)]
+struct BatchRecord {
+    entry_type: String,
+    dna: DnaHash,
+    identity: EntryHash,
+}
+
+/// Records created earlier in a batch, by the names assigned to them by the caller
+///
+#[derive(Debug, Clone, Default)]
+pub struct BatchReferences {
+    records: BTreeMap<String, BatchRecord>,
+}
+
+impl BatchReferences {
+    /// Assign a name to a record created by the current operation, if one was provided.
+    ///
+    pub fn insert<A, S>(&mut self, name: &Option<String>, entry_type_path: S, address: &A) -> RecordAPIResult<()>
+        where S: AsRef<str>,
+            A: AsRef<DnaHash> + AsRef<EntryHash>,
+    {
+        let name = match name {
+            Some(n) => n,
+            None => return Ok(()),
+        };
+        if self.records.contains_key(name) {
+            return Err(DataIntegrityError::Detailed(
+                ErrorDetails::new(ErrorCode::DuplicateIdentifier, format!("Batch reference '{}' is already assigned to another record", name))
+                    .with_detail("ref", name)
+            ));
+        }
+        let dna: &DnaHash = address.as_ref();
+        let identity: &EntryHash = address.as_ref();
+        self.records.insert(name.to_owned(), BatchRecord {
+            entry_type: entry_type_path.as_ref().to_string(),
+            dna: dna.to_owned(),
+            identity: identity.to_owned(),
+        });
+        Ok(())
+    }
+
+    /// Read the address of a record of the given type created earlier in the batch.
+    ///
+    pub fn resolve<A, S>(&self, name: &str, entry_type_path: S) -> RecordAPIResult<A>
+        where S: AsRef<str>,
+            A: DnaAddressable<EntryHash>,
+    {
+        match self.records.get(name) {
+            Some(record) if record.entry_type == entry_type_path.as_ref() =>
+                Ok(A::new(record.dna.to_owned(), record.identity.to_owned())),
+            Some(record) => Err(DataIntegrityError::Detailed(
+                ErrorDetails::new(ErrorCode::EntryWrongType, format!(
+                    "Batch reference '{}' refers to a {} record, {} expected",
+                    name, record.entry_type, entry_type_path.as_ref(),
+                ))
+                    .with_detail("ref", name)
+            )),
+            None => Err(DataIntegrityError::Detailed(
+                ErrorDetails::new(ErrorCode::ReferenceNotFound, format!("Batch reference '{}' is not assigned by any earlier operation", name))
+                    .with_detail("ref", name)
+            )),
+        }
+    }
+}
+
+/// Apply each of `operations` in order via `handler`, which receives the references
+/// assigned by earlier operations. Processing stops at the first failure, and the
+/// error is reported against the index of the failed operation.
+///
+pub fn run_batch<O, R, F>(operations: Vec<O>, mut handler: F) -> RecordAPIResult<Vec<R>>
+    where F: FnMut(&mut BatchReferences, O) -> RecordAPIResult<R>,
+{
+    let mut references = BatchReferences::default();
+    let mut results = Vec::with_capacity(operations.len());
+
+    for (index, operation) in operations.into_iter().enumerate() {
+        let result = handler(&mut references, operation)
+            .map_err(|e| { DataIntegrityError::Detailed(batch_operation_error(index, e.details())) })?;
+        results.push(result);
+    }
+
+    Ok(results)
+}
+
+fn batch_operation_error(index: usize, mut details: ErrorDetails) -> ErrorDetails {
+    let operation = format!("operations[{}]", index);
+    details.field = Some(match details.field {
+        Some(field) => format!("{}.{}", operation, field),
+        None => operation,
+    });
+    details.with_detail("operation", index)
+}
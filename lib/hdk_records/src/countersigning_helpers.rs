@@ -0,0 +1,160 @@
+/**
+ * Countersigning helpers
+ *
+ * Wraps the HDK countersigning API for writing entries which must be signed by
+ * several agents before they are considered valid. All parties accept the same
+ * `PreflightRequest`, exchange their `PreflightResponse`s, and then each write
+ * the identical entry to their own source chain.
+ *
+ * :NOTE: a countersigned entry must be the only write in the zome call which
+ *        authors it. Any indexing or dependant updates must happen in a
+ *        subsequent call, once the session has completed.
+ *
+ * @package Holo-REA
+ * @since   2026-10-19
+ */
+use hdk::prelude::*;
+use hdk::prelude::create as hdk_create;
+use hdk::info::dna_info;
+
+use crate::{RevisionHash, RecordAPIResult, DataIntegrityError};
+
+/// Build a request for the given agents to countersign the creation of `entry_struct`.
+///
+pub fn build_preflight_request<I, E>(
+    entry_type: EntryType,
+    entry_struct: I,
+    signing_agents: Vec<AgentPubKey>,
+    session_duration_ms: u64,
+) -> RecordAPIResult<PreflightRequest>
+    where WasmError: From<E>,
+        Entry: TryFrom<I, Error = E>,
+{
+    let app_entry_hash = hash_entry(entry_struct)?;
+
+    PreflightRequest::try_new(
+        app_entry_hash,
+        signing_agents.into_iter().map(|agent| { (agent, vec![]) }).collect(),
+        vec![], 0,
+        false,
+        session_times_from_millis(session_duration_ms)?,
+        HeaderBase::Create(CreateBase::new(entry_type)),
+        PreflightBytes(vec![]),
+    ).map_err(|e| { DataIntegrityError::CountersigningError(e.to_string()) })
+}
+
+/// Accept a countersigning session on behalf of the current agent, locking
+/// their source chain until the session completes or expires.
+///
+pub fn accept_preflight_request(request: PreflightRequest) -> RecordAPIResult<PreflightResponse> {
+    match accept_countersigning_preflight_request(request)? {
+        PreflightRequestAcceptance::Accepted(response) => Ok(response),
+        PreflightRequestAcceptance::UnacceptableFutureStart =>
+            Err(DataIntegrityError::CountersigningError("session starts too far in the future".to_string())),
+        PreflightRequestAcceptance::UnacceptableAgentNotFound =>
+            Err(DataIntegrityError::CountersigningError("agent is not a party to the session".to_string())),
+        PreflightRequestAcceptance::Invalid(reason) =>
+            Err(DataIntegrityError::CountersigningError(reason)),
+    }
+}
+
+/// Write a countersigned entry to the current agent's source chain, given the
+/// `PreflightResponse`s of all parties to the session.
+///
+pub fn create_countersigned_entry<I: Clone, E, S: AsRef<str>>(
+    entry_def_id: S,
+    entry_struct: I,
+    mut responses: Vec<PreflightResponse>,
+) -> RecordAPIResult<(RevisionHash, EntryHash)>
+    where WasmError: From<E>,
+        Entry: TryFrom<I, Error = E>,
+{
+    let entry_hash = hash_entry(entry_struct.clone())?;
+
+    // responses must be ordered as the signing agents of the preflight request
+    responses.sort_by_key(|r| { r.agent_state().agent_index().to_owned() });
+    let session_data = CounterSigningSessionData::try_from_responses(responses, vec![])
+        .map_err(|e| { DataIntegrityError::CountersigningError(e.to_string()) })?;
+
+    let entry_data: Result<Entry, E> = entry_struct.try_into();
+    let app_bytes = match entry_data {
+        Ok(Entry::App(bytes)) => bytes,
+        Ok(_) => return Err(DataIntegrityError::EntryWrongType),
+        Err(e) => return Err(DataIntegrityError::Wasm(WasmError::from(e))),
+    };
+
+    let header_hash = hdk_create(CreateInput::new(
+        EntryDefId::App(entry_def_id.as_ref().to_string()),
+        Entry::CounterSign(Box::new(session_data), app_bytes),
+        ChainTopOrdering::Strict,
+    ))?;
+
+    Ok((RevisionHash(dna_info()?.hash, header_hash), entry_hash))
+}
+
+/// Determine the agents who countersigned an entry. Empty if the entry has a single author.
+///
+pub fn get_countersigning_agents(entry: &Entry) -> Vec<AgentPubKey> {
+    match entry {
+        Entry::CounterSign(session_data, _) => session_data.preflight_request().signing_agents().iter()
+            .map(|(agent, _roles)| { agent.to_owned() })
+            .collect(),
+        _ => vec![],
+    }
+}
+
+/// Allow the counterparties to a countersigning session to call the given functions
+/// of the current zome, as is necessary for them to drive the session.
+///
+/// Access is assigned to the given agents only, and requires the returned secret.
+///
+pub fn grant_countersigning_access(functions: &[&str], counterparties: Vec<AgentPubKey>) -> RecordAPIResult<CapSecret> {
+    let this_zome = zome_info()?.name;
+    let mut granted = GrantedFunctions::new();
+    for function in functions {
+        granted.insert((this_zome.to_owned(), FunctionName(function.to_string())));
+    }
+
+    let secret = generate_cap_secret()?;
+    create_cap_grant(CapGrantEntry {
+        tag: "countersigning".to_string(),
+        access: CapAccess::Assigned {
+            secret,
+            assignees: counterparties.into_iter().collect(),
+        },
+        functions: granted,
+    })?;
+
+    Ok(secret)
+}
+
+/// Allow any agent to propose a countersigning session to the current agent, via the
+/// given functions of the current zome.
+///
+/// :NOTE: these functions are open to all agents. They must only record proposals for
+///        later approval, and never lock the source chain or act upon a proposal directly.
+///
+pub fn grant_countersigning_proposal_access(functions: &[&str]) -> RecordAPIResult<()> {
+    let this_zome = zome_info()?.name;
+    let mut granted = GrantedFunctions::new();
+    for function in functions {
+        granted.insert((this_zome.to_owned(), FunctionName(function.to_string())));
+    }
+
+    create_cap_grant(CapGrantEntry {
+        tag: "countersigning_proposals".to_string(),
+        access: CapAccess::Unrestricted,
+        functions: granted,
+    })?;
+
+    Ok(())
+}
+
+/// Determine the time at which the countersigning session for an entry began. None if the entry has a single author.
+///
+pub fn get_countersigning_session_start(entry: &Entry) -> Option<Timestamp> {
+    match entry {
+        Entry::CounterSign(session_data, _) => Some(session_data.preflight_request().session_times().start().to_owned()),
+        _ => None,
+    }
+}
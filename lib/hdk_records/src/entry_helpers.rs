@@ -36,7 +36,7 @@ pub (crate) fn try_decode_entry<T>(entry: Entry) -> RecordAPIResult<T>
     where SerializedBytes: TryInto<T, Error = SerializedBytesError>,
 {
     match entry {
-        Entry::App(content) => {
+        Entry::App(content) | Entry::CounterSign(_, content) => {
             let decoded: T = content.into_sb().try_into()?;
             Ok(decoded)
         },
@@ -48,7 +48,7 @@ pub (crate) fn try_decode_entry<T>(entry: Entry) -> RecordAPIResult<T>
 
 /// Reads an entry from the DHT by its `EntryHash`. The latest live version of the entry will be returned.
 ///
-pub (crate) fn get_entry_by_address<R>(address: &EntryHash) -> RecordAPIResult<R>
+pub fn get_entry_by_address<R>(address: &EntryHash) -> RecordAPIResult<R>
     where SerializedBytes: TryInto<R, Error = SerializedBytesError>,
 {
     // :DUPE: identical to below, only type signature differs
@@ -88,7 +88,7 @@ pub (crate) fn get_entry_by_header<R, I>(address: &I) -> RecordAPIResult<R>
 /// in newly created records, to avoid them conflicting with previously entered
 /// entries that may be of the same content.
 ///
-/// @see hdk::prelude::random_bytes
+/// @see hdk_records::record_interface::generate_record_nonce
 ///
 pub fn create_entry<I: Clone, E, S: AsRef<str>>(
     entry_def_id: S,
@@ -0,0 +1,237 @@
+/**
+ * Helpers for inspecting the revision history of records, for use in audit
+ * trails and point-in-time queries.
+ *
+ * @package HoloREA
+ * @since   2026-10-19
+ */
+use hdk::prelude::*;
+use serde_json::{Value, Map};
+
+use crate::{
+    RevisionHash, DnaAddressable,
+    RecordAPIResult, DataIntegrityError,
+    record_interface::Identified,
+    identity_helpers::{
+        calculate_identity_address,
+        read_entry_identity,
+    },
+    record_helpers::read_record_entry_by_header,
+};
+
+pub use hdk::prelude::Timestamp;
+
+/// Metadata describing a single revision of a record
+///
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RevisionMeta {
+    pub revision_id: RevisionHash,
+    pub previous_revision_id: Option<RevisionHash>,
+    pub author: AgentPubKey,
+    pub timestamp: Timestamp,
+    pub deleted: bool,
+}
+
+/// Describes a change to a single top-level field of a record between two revisions.
+/// `None` values indicate that the field was not present in the revision.
+///
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldChange {
+    pub field: String,
+    pub previous_value: Option<Value>,
+    pub new_value: Option<Value>,
+}
+
+/// Comparison of two revisions of the same record
+///
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RevisionDiff {
+    pub from_revision_id: RevisionHash,
+    pub to_revision_id: RevisionHash,
+    pub changes: Vec<FieldChange>,
+}
+
+//--------------------------------[ READ ]--------------------------------------
+
+/// List every revision of the record whose initial entry has the given `EntryHash`,
+/// including deleted and concurrently forked revisions. Results are ordered oldest-first.
+///
+pub fn get_revision_history(entry_hash: EntryHash) -> RecordAPIResult<Vec<RevisionMeta>> {
+    Ok(read_revision_timeline(entry_hash)?.into_iter()
+        .map(|(revision, _deleted_at)| { revision })
+        .collect())
+}
+
+/// Walk the revision history of a record, returning the metadata of each revision alongside
+/// the time at which it was first deleted (if ever). Results are ordered oldest-first.
+///
+fn read_revision_timeline(entry_hash: EntryHash) -> RecordAPIResult<Vec<(RevisionMeta, Option<Timestamp>)>> {
+    let dna_hash = dna_info()?.hash;
+
+    let mut pending = match get_details(entry_hash, GetOptions { strategy: GetStrategy::Latest })? {
+        Some(Details::Entry(details)) => Ok(details.headers),
+        _ => Err(DataIntegrityError::EntryNotFound),
+    }?;
+
+    let mut history = vec![];
+    while let Some(header) = pending.pop() {
+        let header_hash = header.header_hashed().as_hash().to_owned();
+        match get_details(header_hash.to_owned(), GetOptions { strategy: GetStrategy::Latest })? {
+            Some(Details::Element(details)) => {
+                let previous_revision_id = match header.header() {
+                    Header::Update(update) => Some(RevisionHash(dna_hash.to_owned(), update.original_header_address.to_owned())),
+                    _ => None,
+                };
+                let deleted_at = details.deletes.iter()
+                    .map(|delete| { delete.header().timestamp() })
+                    .min();
+
+                history.push((RevisionMeta {
+                    revision_id: RevisionHash(dna_hash.to_owned(), header_hash),
+                    previous_revision_id,
+                    author: header.header().author().to_owned(),
+                    timestamp: header.header().timestamp(),
+                    deleted: deleted_at.is_some(),
+                }, deleted_at));
+
+                pending.extend(details.updates);
+            },
+            _ => return Err(DataIntegrityError::EntryNotFound),
+        }
+    }
+
+    history.sort_by(|(a, _), (b, _)| {
+        a.timestamp.cmp(&b.timestamp)
+            .then_with(|| a.revision_id.1.cmp(&b.revision_id.1))
+    });
+
+    Ok(history)
+}
+
+/// List every revision of a record, located via its identity address.
+///
+/// @see get_revision_history
+///
+pub fn read_record_revision_history<B, S>(
+    entry_type_root_path: &S,
+    address: &EntryHash,
+) -> RecordAPIResult<Vec<RevisionMeta>>
+    where S: AsRef<str>,
+        B: DnaAddressable<EntryHash>,
+{
+    let identity_address = calculate_identity_address(entry_type_root_path, &B::new(dna_info()?.hash, address.clone()))?;
+    let entry_hash = read_entry_identity(&identity_address)?;
+    get_revision_history(entry_hash)
+}
+
+/// Read a record's entry data as it was at the given point in time, ie. the most recent
+/// revision authored at or before `as_of`.
+///
+/// Records which had been deleted at `as_of` return `DataIntegrityError::EntryNotFound`.
+///
+pub fn read_record_entry_as_of<T, R, B, S>(
+    entry_type_root_path: &S,
+    address: &EntryHash,
+    as_of: &Timestamp,
+) -> RecordAPIResult<(RevisionHash, B, T)>
+    where S: AsRef<str>,
+        T: std::fmt::Debug,
+        B: DnaAddressable<EntryHash>,
+        SerializedBytes: TryInto<R, Error = SerializedBytesError>,
+        Entry: TryFrom<R>,
+        R: std::fmt::Debug + Identified<T, B>,
+{
+    let identity_address = calculate_identity_address(entry_type_root_path, &B::new(dna_info()?.hash, address.clone()))?;
+    let revision_id = get_revision_as_of(read_entry_identity(&identity_address)?, as_of)?;
+
+    let (base_address, entry_data) = read_record_entry_by_header::<T, R, B>(&revision_id)?;
+    Ok((revision_id, base_address, entry_data))
+}
+
+/// Determine the most recent revision of the record whose initial entry has the given
+/// `EntryHash`, as at the given point in time.
+///
+/// Only revisions which were current at `as_of` are considered, ie. those which had not yet
+/// been updated or deleted.
+///
+pub (crate) fn get_revision_as_of(entry_hash: EntryHash, as_of: &Timestamp) -> RecordAPIResult<RevisionHash> {
+    let history = read_revision_timeline(entry_hash)?;
+    let (revision, _deleted_at) = history.iter()
+        .filter(|(revision, _)| { revision.timestamp <= *as_of })
+        .filter(|(_, deleted_at)| { deleted_at.as_ref().map_or(true, |deleted| { *deleted > *as_of }) })
+        .filter(|(revision, _)| { !history.iter().any(|(other, _)| {
+            other.previous_revision_id.as_ref() == Some(&revision.revision_id) && other.timestamp <= *as_of
+        }) })
+        .last()
+        .ok_or(DataIntegrityError::EntryNotFound)?;
+
+    Ok(revision.revision_id.to_owned())
+}
+
+/// Compare the entry data of two revisions of the same record, returning a list of
+/// all top-level fields which differ between them.
+///
+pub fn diff_record_revisions<T, R, B>(
+    from_revision_id: &RevisionHash,
+    to_revision_id: &RevisionHash,
+) -> RecordAPIResult<RevisionDiff>
+    where T: std::fmt::Debug + Serialize,
+        B: DnaAddressable<EntryHash>,
+        SerializedBytes: TryInto<R, Error = SerializedBytesError>,
+        Entry: TryFrom<R>,
+        R: std::fmt::Debug + Identified<T, B>,
+{
+    let (from_identity, from_entry) = read_record_entry_by_header::<T, R, B>(from_revision_id)?;
+    let (to_identity, to_entry) = read_record_entry_by_header::<T, R, B>(to_revision_id)?;
+
+    // ensure both revisions belong to the same record
+    if from_identity != to_identity {
+        return Err(DataIntegrityError::EntryWrongType);
+    }
+
+    let from_fields = entry_fields(&from_entry)?;
+    let to_fields = entry_fields(&to_entry)?;
+
+    let mut changes: Vec<FieldChange> = to_fields.iter()
+        .filter(|(field, value)| { from_fields.get(*field) != Some(*value) })
+        .map(|(field, value)| FieldChange {
+            field: field.to_owned(),
+            previous_value: from_fields.get(field).cloned(),
+            new_value: Some(value.to_owned()),
+        })
+        .collect();
+
+    changes.extend(from_fields.iter()
+        .filter(|(field, _value)| { !to_fields.contains_key(*field) })
+        .map(|(field, value)| FieldChange {
+            field: field.to_owned(),
+            previous_value: Some(value.to_owned()),
+            new_value: None,
+        }));
+
+    Ok(RevisionDiff {
+        from_revision_id: from_revision_id.to_owned(),
+        to_revision_id: to_revision_id.to_owned(),
+        changes,
+    })
+}
+
+/// Decompose entry data into a map of its top-level fields
+///
+fn entry_fields<T>(entry: &T) -> RecordAPIResult<Map<String, Value>>
+    where T: Serialize,
+{
+    match serde_json::to_value(entry) {
+        Ok(Value::Object(fields)) => Ok(fields),
+        Ok(value) => {
+            // non-struct entries are treated as having a single unnamed field
+            let mut fields = Map::new();
+            fields.insert(String::new(), value);
+            Ok(fields)
+        },
+        Err(e) => Err(DataIntegrityError::Serialization(SerializedBytesError::Serialize(e.to_string()))),
+    }
+}
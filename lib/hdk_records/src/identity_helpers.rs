@@ -105,6 +105,23 @@ pub fn read_entry_identity_full<A>(
     }
 }
 
+/// List the identity `Path` addresses of all records of the given entry type
+/// which have been registered in the DHT.
+///
+/// :TODO: sharding strategy for 2-nth order link destinations
+///
+pub fn read_entry_type_identities<S>(
+    entry_type_path: S,
+) -> RecordAPIResult<Vec<EntryHash>>
+    where S: AsRef<str>,
+{
+    let identities: Vec<Link> = entry_type_root_path(entry_type_path).children()?;
+
+    Ok(identities.iter()
+        .map(|link| { link.target.clone() })
+        .collect())
+}
+
 //-------------------------------[ CREATE ]-------------------------------------
 
 /// Creates a `Path` to initialise a unique index for a new entry, and returns
@@ -13,7 +13,7 @@ pub use hdk::info::{agent_info, dna_info};
 // re-expose MaybeUndefined module
 pub use serde_maybe_undefined as maybe_undefined;
 pub use serde_maybe_undefined::MaybeUndefined as MaybeUndefined;
-pub use hdk_rpc_errors::{ OtherCellResult, CrossCellError };
+pub use hdk_rpc_errors::{ OtherCellResult, CrossCellError, ErrorCode, ErrorDetails };
 
 // re-export auth resolver entry def IDs; zomes declaring full `entry_defs()` extern
 // will have to redeclare these manually since they override any others declared with macros
@@ -24,8 +24,13 @@ mod link_helpers;
 mod identity_helpers;
 mod record_helpers;
 mod anchored_record_helpers;
+mod history_helpers;
 mod local_index_helpers;
 mod rpc_helpers;
+mod countersigning_helpers;
+mod validation_helpers;
+mod outbox_helpers;
+mod batch_helpers;
 
 // API interfaces
 
@@ -38,8 +43,13 @@ pub mod entries { pub use crate::entry_helpers::*; }
 pub mod links { pub use crate::link_helpers::*; }
 pub mod records { pub use crate::record_helpers::*; }
 pub mod records_anchored { pub use crate::anchored_record_helpers::*; }
+pub mod history { pub use crate::history_helpers::*; }
 pub mod local_indexes { pub use crate::local_index_helpers::*; }
 pub mod rpc { pub use crate::rpc_helpers::*; }
+pub mod countersigning { pub use crate::countersigning_helpers::*; }
+pub mod validation { pub use crate::validation_helpers::*; }
+pub mod outbox { pub use crate::outbox_helpers::*; }
+pub mod batch { pub use crate::batch_helpers::*; }
 
 // :TODO: these error types may just be duplicating enums from the HDK,
 // revisit this once result handling & serialisation have stabilised.
@@ -56,6 +66,8 @@ pub enum DataIntegrityError {
     EntryError(#[from] EntryError),
     #[error(transparent)]
     Wasm(#[from] WasmError),
+    #[error(transparent)]
+    Detailed(#[from] ErrorDetails),
 
     #[error("No entry at this address")]
     EntryNotFound,
@@ -67,6 +79,16 @@ pub enum DataIntegrityError {
     EmptyQuery,
     #[error("Index at address {0} with malformed bytes {1:?}")]
     CorruptIndexError(EntryHash, Option<Vec<u8>>),
+    #[error("Identifier {0} is already in use by another record")]
+    DuplicateAnchorKey(String),
+    #[error("Revision {0} is not the latest revision of this record. Current revisions are {1:?}")]
+    UpdateConflict(HeaderHash, Vec<HeaderHash>),
+    #[error("Insufficient inventory in resource {0}: {1} available, {2} requested")]
+    InsufficientInventory(EntryHash, f64, f64),
+    #[error("Record {0} cannot be deleted while it is referenced by {1}")]
+    RecordInUse(EntryHash, String),
+    #[error("Countersigning session failed: {0}")]
+    CountersigningError(String),
     #[error("Error in remote call {0}")]
     RemoteRequestError(String),
     #[error("Bad zome RPC response format from {0}")]
@@ -77,13 +99,67 @@ pub enum DataIntegrityError {
 
 pub type RecordAPIResult<T> = Result<T, DataIntegrityError>;
 
+impl DataIntegrityError {
+    /// Describe this error in machine-readable form, for output to clients
+    pub fn details(&self) -> ErrorDetails {
+        match self {
+            DataIntegrityError::Detailed(details) => details.to_owned(),
+            DataIntegrityError::Serialization(_) |
+            DataIntegrityError::EntryError(_) =>
+                ErrorDetails::new(ErrorCode::SerializationError, self.to_string()),
+            DataIntegrityError::Infallible(_) =>
+                ErrorDetails::new(ErrorCode::InternalError, self.to_string()),
+            DataIntegrityError::Wasm(e) => ErrorDetails::from_wasm_error(e)
+                .unwrap_or_else(|| ErrorDetails::new(ErrorCode::InternalError, self.to_string())),
+            DataIntegrityError::EntryNotFound =>
+                ErrorDetails::new(ErrorCode::EntryNotFound, self.to_string()),
+            DataIntegrityError::EntryWrongType =>
+                ErrorDetails::new(ErrorCode::EntryWrongType, self.to_string()),
+            DataIntegrityError::IndexNotFound(address) =>
+                ErrorDetails::new(ErrorCode::IndexNotFound, self.to_string())
+                    .with_detail("address", address),
+            DataIntegrityError::EmptyQuery =>
+                ErrorDetails::new(ErrorCode::EmptyQuery, self.to_string()),
+            DataIntegrityError::CorruptIndexError(address, _) =>
+                ErrorDetails::new(ErrorCode::CorruptIndex, self.to_string())
+                    .with_detail("address", address),
+            DataIntegrityError::DuplicateAnchorKey(id) =>
+                ErrorDetails::new(ErrorCode::DuplicateIdentifier, self.to_string())
+                    .with_detail("identifier", id),
+            DataIntegrityError::UpdateConflict(revision, current) =>
+                ErrorDetails::new(ErrorCode::UpdateConflict, self.to_string())
+                    .at_field("revisionId")
+                    .with_detail("revisionId", revision)
+                    .with_detail("currentRevisions", current.iter().map(|r| r.to_string()).collect::<Vec<String>>().join(",")),
+            DataIntegrityError::InsufficientInventory(resource, available, requested) =>
+                ErrorDetails::new(ErrorCode::InsufficientInventory, self.to_string())
+                    .at_field("resourceQuantity")
+                    .with_detail("resource", resource)
+                    .with_detail("available", available)
+                    .with_detail("requested", requested),
+            DataIntegrityError::RecordInUse(address, referrer) =>
+                ErrorDetails::new(ErrorCode::RecordInUse, self.to_string())
+                    .with_detail("address", address)
+                    .with_detail("referencedBy", referrer),
+            DataIntegrityError::CountersigningError(_) =>
+                ErrorDetails::new(ErrorCode::CountersigningFailed, self.to_string()),
+            DataIntegrityError::RemoteRequestError(_) =>
+                ErrorDetails::new(ErrorCode::RemoteRequestFailed, self.to_string()),
+            DataIntegrityError::RemoteResponseFormatError(_) =>
+                ErrorDetails::new(ErrorCode::RemoteResponseInvalid, self.to_string()),
+            DataIntegrityError::RemoteIndexingError(_) =>
+                ErrorDetails::new(ErrorCode::RemoteIndexingFailed, self.to_string()),
+        }
+    }
+}
+
 // convert internal cell errors for passing to remote cell
 
 impl From<DataIntegrityError> for CrossCellError {
     fn from(e: DataIntegrityError) -> CrossCellError {
         match e {
             DataIntegrityError::IndexNotFound(entry) => CrossCellError::IndexNotFound(entry),
-            _ => CrossCellError::Internal(e.to_string()),
+            _ => CrossCellError::Detailed(e.details()),
         }
     }
 }
@@ -92,13 +168,22 @@ impl From<DataIntegrityError> for CrossCellError {
 
 impl From<DataIntegrityError> for WasmError {
     fn from(e: DataIntegrityError) -> WasmError {
-        WasmError::Guest(e.to_string())
+        match e {
+            // pass through host errors unmodified, so that `must_get_*` failures in validation
+            // are reported as unresolved dependencies rather than invalid data
+            DataIntegrityError::Wasm(e) => e,
+            _ => WasmError::Guest(e.details().encode()),
+        }
     }
 }
 
 impl From<CrossCellError> for DataIntegrityError {
     fn from(e: CrossCellError) -> DataIntegrityError {
-        DataIntegrityError::RemoteRequestError(e.to_string())
+        match e {
+            // retain the original cause of errors raised by the remote zome
+            CrossCellError::Detailed(details) => DataIntegrityError::Detailed(details),
+            _ => DataIntegrityError::RemoteRequestError(e.to_string()),
+        }
     }
 }
 
@@ -108,4 +193,5 @@ pub mod identifiers {
     // Holochain DHT storage type IDs
     pub const RECORD_INITIAL_ENTRY_LINK_TAG: &'static [u8] = b"initial_entry";
     pub const RECORD_IDENTITY_ANCHOR_LINK_TAG: &'static [u8] = b"identity_anchor";
+    pub const RECORD_SECONDARY_ANCHOR_LINK_TAG: &'static [u8] = b"secondary_anchor";
 }
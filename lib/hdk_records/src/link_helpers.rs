@@ -43,6 +43,28 @@ pub fn get_linked_headers(
     pull_links_data(base_address, link_tag, get_link_target_header)
 }
 
+/// Load the earliest-created `EntryHash` linked from the provided `base_address`
+/// with the given `link_tag`, if any.
+///
+/// Used to deterministically resolve conflicts where concurrent writes on different
+/// nodes have linked multiple entries to a base which should only reference one.
+/// Ties in link creation time are broken by comparing link `HeaderHash`es, so that
+/// all nodes observing the same set of links will always agree on the result.
+///
+pub fn get_earliest_linked_address(
+    base_address: &EntryHash,
+    link_tag: LinkTag,
+) -> RecordAPIResult<Option<EntryHash>> {
+    let mut links_result = get_links((*base_address).clone(), Some(link_tag))?;
+
+    links_result.sort_by(|a, b| {
+        a.timestamp.cmp(&b.timestamp)
+            .then_with(|| a.create_link_hash.cmp(&b.create_link_hash))
+    });
+
+    Ok(links_result.first().map(get_link_target_entry))
+}
+
 //-----------------------------------------------------
 
 // :TODO: ensure ordering is latest-first
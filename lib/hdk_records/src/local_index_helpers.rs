@@ -13,7 +13,8 @@ use hdk::prelude::*;
 
 use crate::{
     RevisionHash, DnaAddressable,
-    RecordAPIResult,
+    RecordAPIResult, DataIntegrityError,
+    ErrorCode, ErrorDetails,
     record_interface::Identified,
     identity_helpers::{
         entry_type_root_path,
@@ -44,3 +45,95 @@ pub fn query_root_index<'a, T, R, O, I: AsRef<str>>(
         .map(|link| { read_record_entry_by_identity(&link.target) })
         .collect())
 }
+
+/// A page of results read from the root index of some entry type.
+///
+/// Cursors are the string representations of record identity addresses, and
+/// results are ordered by the time at which their identities were created.
+///
+#[derive(Debug, Clone)]
+pub struct RootIndexPage<T> {
+    pub results: Vec<T>,
+    pub start_cursor: String,
+    pub end_cursor: String,
+    pub has_previous_page: bool,
+    pub has_next_page: bool,
+    pub total_count: usize,
+}
+
+impl<T> RootIndexPage<T> {
+    /// Transform each result in the page, retaining pagination metadata
+    pub fn map<U, F>(self, f: F) -> RootIndexPage<U>
+        where F: FnMut(T) -> U,
+    {
+        RootIndexPage {
+            results: self.results.into_iter().map(f).collect(),
+            start_cursor: self.start_cursor,
+            end_cursor: self.end_cursor,
+            has_previous_page: self.has_previous_page,
+            has_next_page: self.has_next_page,
+            total_count: self.total_count,
+        }
+    }
+}
+
+/// Read a page of the identity addresses linked from the root index of an entry type,
+/// starting after the identity with cursor `after` (or from the first identity if `None`).
+///
+pub (crate) fn read_root_index_page<I: AsRef<str>>(
+    base_entry_type: &I,
+    after: &Option<String>,
+    limit: usize,
+) -> RecordAPIResult<RootIndexPage<EntryHash>>
+{
+    let index_path = entry_type_root_path(base_entry_type);
+    let mut linked_records: Vec<Link> = index_path.children()?.into();
+    linked_records.sort_by(|a, b| {
+        a.timestamp.cmp(&b.timestamp)
+            .then_with(|| { a.target.to_string().cmp(&b.target.to_string()) })
+    });
+
+    let start = match after {
+        None => 0,
+        Some(cursor) => match linked_records.iter().position(|link| { link.target.to_string() == *cursor }) {
+            Some(index) => index + 1,
+            None => return Err(DataIntegrityError::Detailed(
+                ErrorDetails::new(ErrorCode::InvalidFieldValue, format!("No record found for cursor {}", cursor))
+                    .at_field("after")
+            )),
+        },
+    };
+    let end = std::cmp::min(start + limit, linked_records.len());
+    let results: Vec<EntryHash> = linked_records[start..end].iter()
+        .map(|link| { link.target.to_owned() })
+        .collect();
+
+    Ok(RootIndexPage {
+        start_cursor: results.first().map(|h| { h.to_string() }).unwrap_or_default(),
+        end_cursor: results.last().map(|h| { h.to_string() }).unwrap_or_default(),
+        has_previous_page: start > 0,
+        has_next_page: end < linked_records.len(),
+        total_count: linked_records.len(),
+        results,
+    })
+}
+
+/// Given a type of entry, returns one page of the records of that entry type registered
+/// internally with the DHT, in order of creation.
+///
+/// :TODO: sharding strategy for 2-nth order link destinations
+///
+pub fn query_root_index_page<T, R, O, I: AsRef<str>>(
+    base_entry_type: &I,
+    after: &Option<String>,
+    limit: usize,
+) -> RecordAPIResult<RootIndexPage<RecordAPIResult<(RevisionHash, O, T)>>>
+    where T: std::fmt::Debug,
+        O: DnaAddressable<EntryHash>,
+        SerializedBytes: TryInto<R, Error = SerializedBytesError>,
+        Entry: TryFrom<R>,
+        R: std::fmt::Debug + Identified<T, O>,
+{
+    Ok(read_root_index_page(base_entry_type, after, limit)?
+        .map(|identity| { read_record_entry_by_identity(&identity) }))
+}
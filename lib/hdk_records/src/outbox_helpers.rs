@@ -0,0 +1,193 @@
+/**
+ * Replication outbox
+ *
+ * Durable queue of writes which must be replicated to cooperating DNAs. Requests
+ * are stored as private entries on the agent's source chain before delivery is
+ * attempted, and the outcome of the latest attempt is recorded alongside them.
+ * Requests which could not be delivered are retried upon subsequent calls to
+ * `deliver_replications`, until they succeed.
+ *
+ * Zomes using the outbox must register `ReplicationRequest::entry_def()` and
+ * `ReplicationAttempt::entry_def()` in their `entry_defs` callback.
+ *
+ * @package Holo-REA
+ * @since   2026-10-19
+ */
+use std::collections::{HashMap, HashSet};
+use hdk::prelude::*;
+use holo_hash::DnaHash;
+
+use crate::RecordAPIResult;
+
+/// Query the replication state of writes to cooperating DNAs, optionally only for a single record
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReplicationStatusParams<T> {
+    #[serde(default)]
+    pub address: Option<T>,
+}
+
+/// Delivery state of a write which must be replicated to a cooperating DNA
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplicationStatus {
+    pub request_id: HeaderHash,
+    pub record: EntryHash,
+    pub method: String,
+    pub delivered: bool,
+    pub attempts: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+/// A write to a record in the local DNA which must be replicated to another DNA
+///
+#[hdk_entry(id = "replication_request", visibility = "private")]
+#[derive(Clone)]
+pub struct ReplicationRequest {
+    pub record: EntryHash,
+    pub to_dna: DnaHash,
+    pub method: String,
+    pub payload: ExternIO,
+}
+
+impl ReplicationRequest {
+    pub fn decode_payload<T>(&self) -> RecordAPIResult<T>
+        where T: serde::de::DeserializeOwned + std::fmt::Debug,
+    {
+        Ok(self.payload.decode()?)
+    }
+}
+
+// allow requests to be passed directly as the target of `call_zome_method`
+impl AsRef<DnaHash> for ReplicationRequest {
+    fn as_ref(&self) -> &DnaHash {
+        &self.to_dna
+    }
+}
+
+/// The outcome of the latest attempt to deliver a `ReplicationRequest`. `error` is empty if delivery succeeded.
+///
+/// A single entry is created for each request upon its first attempt, and updated upon each retry.
+///
+#[hdk_entry(id = "replication_attempt", visibility = "private")]
+#[derive(Clone)]
+pub struct ReplicationAttempt {
+    pub request: HeaderHash,
+    pub attempts: u32,
+    pub error: Option<String>,
+}
+
+/// Add a write to the outbox. It is not sent until `deliver_replications` is next called.
+///
+pub fn queue_replication<H, I, S>(
+    record: &EntryHash,
+    to_registered_dna: &H,
+    remote_permission_id: &S,
+    payload: I,
+) -> RecordAPIResult<HeaderHash>
+    where S: AsRef<str>,
+        H: AsRef<DnaHash>,
+        I: serde::Serialize + std::fmt::Debug,
+{
+    let request = ReplicationRequest {
+        record: record.to_owned(),
+        to_dna: to_registered_dna.as_ref().to_owned(),
+        method: remote_permission_id.as_ref().to_string(),
+        payload: ExternIO::encode(payload)?,
+    };
+    Ok(create_entry(&request)?)
+}
+
+/// Attempt to send all undelivered requests in the outbox, in the order they were queued.
+/// `deliver` performs the remote call appropriate to each request.
+///
+/// Where a request fails, any later requests for the same record are held back so
+/// that cooperating DNAs always receive writes in their original order.
+///
+/// Returns the status of every request which was pending prior to the call.
+///
+pub fn deliver_replications<F>(deliver: F) -> RecordAPIResult<Vec<ReplicationStatus>>
+    where F: Fn(&ReplicationRequest) -> RecordAPIResult<()>,
+{
+    let mut blocked: HashSet<EntryHash> = HashSet::new();
+    let mut results = vec![];
+
+    for (request_id, request, mut latest_attempt) in read_outbox()? {
+        if is_delivered(&latest_attempt) {
+            continue;
+        }
+        if !blocked.contains(&request.record) {
+            let attempt = ReplicationAttempt {
+                request: request_id.to_owned(),
+                attempts: latest_attempt.as_ref().map_or(0, |(_, a)| { a.attempts }) + 1,
+                error: deliver(&request).err().map(|e| { e.to_string() }),
+            };
+            let attempt_id = match &latest_attempt {
+                Some((prev_attempt_id, _)) => update_entry(prev_attempt_id.to_owned(), &attempt)?,
+                None => create_entry(&attempt)?,
+            };
+            latest_attempt = Some((attempt_id, attempt));
+        }
+        if !is_delivered(&latest_attempt) {
+            blocked.insert(request.record.to_owned());
+        }
+        results.push(construct_status(&request_id, &request, &latest_attempt));
+    }
+
+    Ok(results)
+}
+
+/// Read the status of all requests in the outbox, optionally only those for a single record.
+///
+pub fn read_replication_status(record: Option<&EntryHash>) -> RecordAPIResult<Vec<ReplicationStatus>> {
+    Ok(read_outbox()?.iter()
+        .filter(|(_, request, _)| { record.map_or(true, |r| { *r == request.record }) })
+        .map(|(request_id, request, latest_attempt)| { construct_status(request_id, request, latest_attempt) })
+        .collect())
+}
+
+/// Load all requests in the outbox in the order they were queued, along with the
+/// latest delivery attempt for each and the `HeaderHash` it was recorded at.
+///
+fn read_outbox() -> RecordAPIResult<Vec<(HeaderHash, ReplicationRequest, Option<(HeaderHash, ReplicationAttempt)>)>> {
+    let requests = query(ChainQueryFilter::new()
+        .entry_type(hdk::entry_type!(ReplicationRequest)?)
+        .header_type(HeaderType::Create)
+        .include_entries(true))?;
+    // includes both creates & updates, in chain order
+    let attempts = query(ChainQueryFilter::new()
+        .entry_type(hdk::entry_type!(ReplicationAttempt)?)
+        .include_entries(true))?;
+
+    let mut latest_attempts: HashMap<HeaderHash, (HeaderHash, ReplicationAttempt)> = HashMap::new();
+    for element in attempts {
+        if let Some(attempt) = element.entry().to_app_option::<ReplicationAttempt>()? {
+            latest_attempts.insert(attempt.request.to_owned(), (element.header_address().to_owned(), attempt));
+        }
+    }
+
+    let mut outbox = vec![];
+    for element in requests {
+        if let Some(request) = element.entry().to_app_option::<ReplicationRequest>()? {
+            let request_id = element.header_address().to_owned();
+            let latest_attempt = latest_attempts.remove(&request_id);
+            outbox.push((request_id, request, latest_attempt));
+        }
+    }
+    Ok(outbox)
+}
+
+fn is_delivered(latest_attempt: &Option<(HeaderHash, ReplicationAttempt)>) -> bool {
+    latest_attempt.as_ref().map_or(false, |(_, a)| { a.error.is_none() })
+}
+
+fn construct_status(request_id: &HeaderHash, request: &ReplicationRequest, latest_attempt: &Option<(HeaderHash, ReplicationAttempt)>) -> ReplicationStatus {
+    ReplicationStatus {
+        request_id: request_id.to_owned(),
+        record: request.record.to_owned(),
+        method: request.method.to_owned(),
+        delivered: is_delivered(latest_attempt),
+        attempts: latest_attempt.as_ref().map_or(0, |(_, a)| { a.attempts }),
+        last_error: latest_attempt.as_ref().and_then(|(_, a)| { a.error.to_owned() }),
+    }
+}
@@ -36,30 +36,126 @@ fn get_header_hash(shh: element::SignedHeaderHashed) -> HeaderHash {
 
 //--------------------------------[ READ ]--------------------------------------
 
-/// Retrieve the latest available RevisionHash for a given EntryHash.
+/// Walk the update history of a record from its initial entry, returning all headers
+/// which have been neither updated nor deleted. Results are ordered oldest-first.
 ///
-/// Useful in coordinating updates between different entry types.
+/// Updates are followed one at a time for as long as the history is linear; the full
+/// tree of revisions is only walked once a fork is encountered.
 ///
-pub fn get_latest_header_hash(entry_hash: EntryHash) -> RecordAPIResult<RevisionHash> {
-    Ok(RevisionHash(dna_info()?.hash, (match get_details(entry_hash, GetOptions { strategy: GetStrategy::Latest })? {
+fn read_revision_heads(entry_hash: EntryHash) -> RecordAPIResult<Vec<element::SignedHeaderHashed>> {
+    let mut pending = match get_details(entry_hash, GetOptions { strategy: GetStrategy::Latest })? {
         Some(Details::Entry(details)) => match details.entry_dht_status {
-            metadata::EntryDhtStatus::Live => match details.updates.len() {
-                0 => {
-                    // no updates yet, latest header hash is the first one
-                    Ok(get_header_hash(details.headers.first().unwrap().to_owned()))
-                },
-                _ => {
-                    // updates exist, find most recent header
-                    let mut sortlist = details.updates.to_vec();
-                    sortlist.sort_by_key(|update| update.header().timestamp().as_micros());
-                    let last = sortlist.last().unwrap().to_owned();
-                    Ok(get_header_hash(last))
-                },
-            },
+            metadata::EntryDhtStatus::Live => Ok(details.headers),
             _ => Err(DataIntegrityError::EntryNotFound),
         },
         _ => Err(DataIntegrityError::EntryNotFound),
-    })?))
+    }?;
+
+    // fast path: follow the chain of updates whilst there is a single head
+    while pending.len() == 1 {
+        let header = pending.pop().unwrap();
+        match get_details(get_header_hash(header.to_owned()), GetOptions { strategy: GetStrategy::Latest })? {
+            Some(Details::Element(details)) => {
+                if !details.deletes.is_empty() {
+                    return Ok(vec![]);
+                }
+                if details.updates.is_empty() {
+                    return Ok(vec![header]);
+                }
+                pending = details.updates;
+            },
+            _ => return Err(DataIntegrityError::EntryNotFound),
+        }
+    }
+
+    // history has forked; walk every branch to find all remaining heads
+    let mut heads = vec![];
+    while let Some(header) = pending.pop() {
+        match get_details(get_header_hash(header.to_owned()), GetOptions { strategy: GetStrategy::Latest })? {
+            Some(Details::Element(details)) => {
+                // deleted revisions (and anything derived from them) are no longer live
+                if !details.deletes.is_empty() {
+                    continue;
+                }
+                match details.updates.len() {
+                    0 => heads.push(header),
+                    _ => pending.extend(details.updates),
+                }
+            },
+            _ => return Err(DataIntegrityError::EntryNotFound),
+        }
+    }
+
+    heads.sort_by(|a, b| {
+        a.header().timestamp().cmp(&b.header().timestamp())
+            .then_with(|| a.header_hashed().as_hash().cmp(b.header_hashed().as_hash()))
+    });
+
+    Ok(heads)
+}
+
+/// Retrieve all current revisions of the record whose initial entry has the given `EntryHash`,
+/// ordered oldest-first.
+///
+/// Under normal operation there will be a single result. Multiple results indicate that
+/// concurrent updates have forked the record's history, which can be resolved
+/// via `merge_record_revisions`.
+///
+pub fn get_revision_heads(entry_hash: EntryHash) -> RecordAPIResult<Vec<RevisionHash>> {
+    let dna_hash = dna_info()?.hash;
+    Ok(read_revision_heads(entry_hash)?.iter()
+        .map(|header| { RevisionHash(dna_hash.to_owned(), get_header_hash(header.to_owned())) })
+        .collect())
+}
+
+/// Retrieve the latest available RevisionHash for a given EntryHash.
+///
+/// Where concurrent updates have forked the record, the most recent revision is returned.
+///
+/// Useful in coordinating updates between different entry types.
+///
+pub fn get_latest_header_hash(entry_hash: EntryHash) -> RecordAPIResult<RevisionHash> {
+    let mut heads = get_revision_heads(entry_hash)?;
+    Ok(heads.pop().ok_or(DataIntegrityError::EntryNotFound)?)
+}
+
+/// Ensure that the given `revision` is a current revision of the record whose initial entry has
+/// the given `EntryHash`, returning `DataIntegrityError::UpdateConflict` if it has been superseded.
+///
+/// Only the given revision is inspected where it has been neither updated nor deleted. The full
+/// history of the record is walked only upon conflict, in order to report its current heads.
+///
+pub (crate) fn check_revision_is_head(revision: &RevisionHash, entry_hash: &EntryHash) -> RecordAPIResult<()> {
+    match get_details(revision.1.to_owned(), GetOptions { strategy: GetStrategy::Latest })? {
+        Some(Details::Element(details)) => {
+            if details.updates.is_empty() && details.deletes.is_empty() {
+                return Ok(());
+            }
+        },
+        _ => return Err(DataIntegrityError::EntryNotFound),
+    }
+
+    let heads = get_revision_heads(entry_hash.to_owned())?;
+    Err(DataIntegrityError::UpdateConflict(
+        revision.as_ref().to_owned(),
+        heads.iter().map(|head| { head.as_ref().to_owned() }).collect(),
+    ))
+}
+
+/// Retrieve all current revisions of a record, located via its identity address.
+///
+/// @see get_revision_heads
+///
+pub fn read_record_revision_heads<B, S>(
+    entry_type_root_path: &S,
+    address: &EntryHash,
+) -> RecordAPIResult<Vec<RevisionHash>>
+    where S: AsRef<str>,
+        B: DnaAddressable<EntryHash>,
+{
+    let identity_address = calculate_identity_address(entry_type_root_path, &B::new(dna_info()?.hash, address.clone()))?;
+    let entry_hash = read_entry_identity(&identity_address)?;
+    get_revision_heads(entry_hash)
 }
 
 /// Retrive the specific version of an entry specified by the given `RevisionHash`
@@ -79,11 +175,9 @@ pub fn read_record_entry_by_header<T, R, B>(
 
 /// Read a record's entry data by its identity index
 ///
-/// :TODO: Currently, the most recent version of the given entry will
-///        be provided instead of the exact entry specified.
-///        We should also check for multiple live headers, and throw a
-///        conflict error if necessary. But core may implement this for
-///        us eventually. (@see EntryDhtStatus)
+/// The most recent revision of the record is always returned. Use `read_record_entry_by_header`
+/// to read a specific revision, or `history::read_record_entry_as_of` to read the record as it
+/// was at some point in time.
 ///
 pub (crate) fn read_record_entry_by_identity<T, R, B>(
     identity_address: &EntryHash,
@@ -144,8 +238,8 @@ pub fn create_record<I, R: Clone, B, C, E, S>(
 {
     // convert the type's CREATE payload into internal storage struct
     let entry_data: I = create_payload.into();
-    // wrap data with null identity for origin record
-    let storage = entry_data.with_identity(None);
+    // wrap data with null identity & unique nonce for origin record
+    let storage = entry_data.with_new_identity()?;
 
     // write underlying entry
     let (header_hash, entry_hash) = create_entry(&entry_def_id, storage)?;
@@ -160,6 +254,30 @@ pub fn create_record<I, R: Clone, B, C, E, S>(
     Ok((header_hash, identity, entry_data))
 }
 
+/// Creates the identity index for a record whose initial entry was written separately
+/// (eg. in a countersigning session, where the entry must be the only write).
+///
+/// Any party to the record may call this; the identity is only linked to the initial entry once.
+///
+pub fn create_record_identity<B, S>(
+    entry_def_id: S,
+    entry_hash: &EntryHash,
+) -> RecordAPIResult<B>
+    where S: AsRef<str>,
+        B: DnaAddressable<EntryHash>,
+{
+    let identity = B::new(dna_info()?.hash, entry_hash.clone());
+    let identity_address = create_entry_identity(&entry_def_id, &identity)?;
+
+    // link the identifier to the actual entry
+    let tag = LinkTag::new(crate::identifiers::RECORD_INITIAL_ENTRY_LINK_TAG);
+    if get_links(identity_address.to_owned(), Some(tag.to_owned()))?.is_empty() {
+        create_link(identity_address, entry_hash.to_owned(), tag)?;
+    }
+
+    Ok(identity)
+}
+
 //-------------------------------[ UPDATE ]-------------------------------------
 
 /// Updates a record in the DHT by its `HeaderHash` (revision ID)
@@ -167,7 +285,12 @@ pub fn create_record<I, R: Clone, B, C, E, S>(
 /// The way in which the input update payload is applied to the existing
 /// entry data is up to the implementor of `Updateable<U>` for the entry type.
 ///
-/// :TODO: prevent multiple updates to the same HeaderHash under standard operations
+/// Returns `DataIntegrityError::UpdateConflict` if the given revision has already been
+/// updated, to prevent clients from unknowingly forking the record's history.
+///
+/// :NOTE: this check is performed against the author's current view of the DHT.
+///        Concurrent writers may still create forks, which are detectable with
+///        `get_revision_heads` and resolvable with `merge_record_revisions`.
 ///
 /// @see hdk_records::record_interface::Updateable
 ///
@@ -190,6 +313,9 @@ pub fn update_record<I, R: Clone, B, U, E, S>(
     let identity = previous.identity()?;
     let identity_hash: &EntryHash = identity.as_ref();
 
+    // ensure we are updating the latest version of the record
+    check_revision_is_head(address, identity_hash)?;
+
     // apply update payload
     let new_entry = prev_entry.update_with(update_payload);
     let storage: R = new_entry.with_identity(Some(identity_hash.clone()));
@@ -200,6 +326,101 @@ pub fn update_record<I, R: Clone, B, U, E, S>(
     Ok((header_addr, identity, new_entry, prev_entry))
 }
 
+/// Resolves concurrent updates to a record by retaining the revision `revision_id` and marking
+/// all other current revisions (`superseded_revisions`) as deleted, such that `revision_id`
+/// becomes the sole head of the record's history.
+///
+/// All current revisions must be accounted for, else `DataIntegrityError::UpdateConflict` is
+/// returned to indicate that further updates have been made since the caller last read the record.
+///
+/// Alongside the retained entry, returns the entry data of each superseded revision paired with
+/// that of the revision it was derived from. Callers must pass these to `get_index_changes` in
+/// order to revert any index links written by the superseded revisions.
+///
+pub fn merge_record_revisions<T, R, B>(
+    revision_id: &RevisionHash,
+    superseded_revisions: &[RevisionHash],
+) -> RecordAPIResult<(RevisionHash, B, T, Vec<(T, T)>)>
+    where T: std::fmt::Debug,
+        B: DnaAddressable<EntryHash>,
+        SerializedBytes: TryInto<R, Error = SerializedBytesError>,
+        Entry: TryFrom<R>,
+        R: std::fmt::Debug + Identified<T, B>,
+{
+    let (identity, entry_data) = read_record_entry_by_header::<T, R, B>(revision_id)?;
+    let identity_hash: &EntryHash = identity.as_ref();
+
+    // ensure all superseded revisions belong to the same record, and load the revisions they replaced
+    let mut superseded_entries = vec![];
+    for revision in superseded_revisions {
+        let (other_identity, superseded_entry): (B, T) = read_record_entry_by_header::<T, R, B>(revision)?;
+        if other_identity != identity {
+            return Err(DataIntegrityError::EntryWrongType);
+        }
+        let parent_entry = match get_previous_revision(revision)? {
+            Some(parent) => read_record_entry_by_header::<T, R, B>(&parent)?.1,
+            None => read_record_entry_by_header::<T, R, B>(revision)?.1,
+        };
+        superseded_entries.push((superseded_entry, parent_entry));
+    }
+
+    // ensure the set of revisions being merged covers all current heads of the record
+    let heads = get_revision_heads(identity_hash.to_owned())?;
+    let all_heads_merged = heads.len() == superseded_revisions.len() + 1
+        && heads.iter().all(|head| { head == revision_id || superseded_revisions.contains(head) });
+    if !all_heads_merged {
+        return Err(DataIntegrityError::UpdateConflict(
+            revision_id.as_ref().to_owned(),
+            heads.iter().map(|head| { head.as_ref().to_owned() }).collect(),
+        ));
+    }
+
+    for revision in superseded_revisions {
+        delete_entry::<R, _>(revision)?;
+    }
+
+    Ok((revision_id.to_owned(), identity, entry_data, superseded_entries))
+}
+
+/// Determine the revision which the given revision of a record was an update to, if any.
+///
+fn get_previous_revision(revision: &RevisionHash) -> RecordAPIResult<Option<RevisionHash>> {
+    match get(revision.1.to_owned(), GetOptions::default())? {
+        Some(element) => match element.header() {
+            Header::Update(update) => Ok(Some(RevisionHash(revision.0.to_owned(), update.original_header_address.to_owned()))),
+            _ => Ok(None),
+        },
+        None => Err(DataIntegrityError::EntryNotFound),
+    }
+}
+
+/// Determine the values to add to & remove from the index of a record field, as the record
+/// moves from its `prev` entry to its `new` entry. `values` reads the indexed values of the field.
+///
+/// Where `prev` is a revision superseded by `merge_record_revisions`, `parent` must be the entry
+/// it was derived from. Only the links which the superseded revision added or removed are then
+/// reverted, leaving those written by the retained revision intact.
+///
+pub fn get_index_changes<T, V, F>(new: &T, prev: &T, parent: Option<&T>, values: F) -> (Vec<V>, Vec<V>)
+    where V: Clone + PartialEq,
+        F: Fn(&T) -> Vec<V>,
+{
+    let new_values = values(new);
+    let prev_values = values(prev);
+    let parent_values = parent.map(|p| { values(p) });
+
+    let added = new_values.iter()
+        .filter(|v| { !prev_values.contains(*v) })
+        .filter(|v| { parent_values.as_ref().map_or(true, |p| { p.contains(*v) }) })
+        .cloned().collect();
+    let removed = prev_values.iter()
+        .filter(|v| { !new_values.contains(*v) })
+        .filter(|v| { parent_values.as_ref().map_or(true, |p| { !p.contains(*v) }) })
+        .cloned().collect();
+
+    (added, removed)
+}
+
 //-------------------------------[ DELETE ]-------------------------------------
 
 /// Removes a record of the given `HeaderHash` from the DHT by marking it as deleted.
@@ -41,6 +41,18 @@ pub trait Identified<T, A>
 ///
 pub trait Identifiable<T> {
     fn with_identity(&self, id_hash: Option<EntryHash>) -> T;
+    fn with_new_identity(&self) -> RecordAPIResult<T>;
+}
+
+/// Number of random bytes used to differentiate the initial entries of new records.
+///
+pub const RECORD_NONCE_LENGTH: u32 = 32;
+
+/// Generate a random nonce for the initial entry of a new record, so that records
+/// created with identical field data are never assigned the same identity.
+///
+pub fn generate_record_nonce() -> RecordAPIResult<Vec<u8>> {
+    Ok(random_bytes(RECORD_NONCE_LENGTH)?.to_vec())
 }
 
 /// Compose an `Identified` structure around the provided entry struct, in order to provide
@@ -49,6 +61,9 @@ pub trait Identifiable<T> {
 /// In addition, the original entry struct receives an `Identifiable` trait impl that can be used
 /// to generate the storage data struct by assigning the previously known unique entry identifier.
 ///
+/// The first entry of each record is assigned a random nonce in place of an identifier, so that
+/// separately created records with identical field data do not collide on identity.
+///
 #[macro_export]
 macro_rules! generate_record_entry {
     ( $( $t:ident, $id:ident, $to:ident );+ ) => {
@@ -59,6 +74,8 @@ macro_rules! generate_record_entry {
                 pub struct $to {
                     entry: $t,
                     id_hash: Option<$crate::EntryHash>, // :NOTE: None for first record
+                    #[serde(default)]
+                    id_nonce: Option<Vec<u8>>,  // :NOTE: only set for first record
                 }
 
                 app_entry!($to);
@@ -91,8 +108,18 @@ macro_rules! generate_record_entry {
                         $to {
                             entry: self.to_owned(),
                             id_hash,
+                            id_nonce: None,
                         }
                     }
+
+                    fn with_new_identity(&self) -> $crate::RecordAPIResult<$to>
+                    {
+                        Ok($to {
+                            entry: self.to_owned(),
+                            id_hash: None,
+                            id_nonce: Some($crate::record_interface::generate_record_nonce()?),
+                        })
+                    }
                 }
 
             // }
@@ -59,6 +59,24 @@ pub fn call_local_zome_method<C, F, R, I, S>(
         F: FnOnce(C) -> Option<String>,
         I: serde::Serialize + std::fmt::Debug,
         R: serde::de::DeserializeOwned + std::fmt::Debug,
+{
+    let local_zome_id = read_local_zome_name(zome_name_from_config, &method_name)?;
+    call_configured_zome_method(&local_zome_id, method_name, payload)
+}
+
+/**
+ * Determine the name of a zome in the local DNA from DNA properties, so that
+ * repeated calls can be made via `call_configured_zome_method` without re-reading
+ * configuration each time. `method_name` is used only for error reporting.
+ */
+pub fn read_local_zome_name<C, F, S>(
+    zome_name_from_config: F,
+    method_name: S,
+) -> OtherCellResult<ZomeName>
+    where S: AsRef<str>,
+        C: std::fmt::Debug,
+        SerializedBytes: TryInto<C, Error = SerializedBytesError>,
+        F: FnOnce(C) -> Option<String>,
 {
     let this_zome = zome_info()?.name;
     let remote_local_zome_method = FunctionName(method_name.as_ref().to_string());
@@ -69,14 +87,50 @@ pub fn call_local_zome_method<C, F, R, I, S>(
 
     match zome_name_from_config(zome_props) {
         None => Err(CrossCellError::NotConfigured(this_zome, remote_local_zome_method)),
-        Some(local_zome_id) => {
-            let resp = call(CallTargetCell::Local, ZomeName(local_zome_id), remote_local_zome_method, None, payload)
-                .map_err(CrossCellError::from)?;
-
-            handle_resp(resp)
-        },
+        Some(local_zome_id) => Ok(ZomeName(local_zome_id)),
     }
+}
+
+/**
+ * Call a zome in the local DNA previously determined with `read_local_zome_name`.
+ */
+pub fn call_configured_zome_method<R, I, S>(
+    local_zome_id: &ZomeName,
+    method_name: S,
+    payload: I,
+) -> OtherCellResult<R>
+    where S: AsRef<str>,
+        I: serde::Serialize + std::fmt::Debug,
+        R: serde::de::DeserializeOwned + std::fmt::Debug,
+{
+    let resp = call(CallTargetCell::Local, local_zome_id.to_owned(), FunctionName(method_name.as_ref().to_string()), None, payload)
+        .map_err(CrossCellError::from)?;
 
+    handle_resp(resp)
+}
+
+/**
+ * Helper for calling the same zome as the caller on another agent's cell within the current DNA.
+ * Used to coordinate with counterparties, eg. in countersigning sessions.
+ *
+ * The called functions must be made available to other agents via a capability grant,
+ * the secret for which is passed as `cap_secret`.
+ */
+pub fn call_agent_zome_method<R, I, S>(
+    agent: &AgentPubKey,
+    method_name: S,
+    cap_secret: Option<CapSecret>,
+    payload: I,
+) -> OtherCellResult<R>
+    where S: AsRef<str>,
+        I: serde::Serialize + std::fmt::Debug,
+        R: serde::de::DeserializeOwned + std::fmt::Debug,
+{
+    let this_zome = zome_info()?.name;
+    let resp = call_remote(agent.to_owned(), this_zome, FunctionName(method_name.as_ref().to_string()), cap_secret, payload)
+        .map_err(CrossCellError::from)?;
+
+    handle_resp(resp)
 }
 
 fn handle_resp<R>(
@@ -93,6 +147,6 @@ fn handle_resp<R>(
         ZomeCallResponse::NetworkError(msg) =>
             Err(CrossCellError::NetworkError(msg)),
         ZomeCallResponse::CountersigningSession(msg) =>
-            Err(CrossCellError::NetworkError(msg)),
+            Err(CrossCellError::CountersigningSession(msg)),
     }
 }
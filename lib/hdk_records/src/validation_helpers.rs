@@ -0,0 +1,109 @@
+/**
+ * Validation helpers
+ *
+ * Shared logic for validating modifications to records. Update and delete
+ * operations are checked against the revision they modify, and may only be
+ * authored by the agent who created the record or by agents the previous
+ * revision of the record grants access to.
+ *
+ * Records referenced by others must also exist, where they reside in the same DNA.
+ *
+ * :TODO: `get_original_author` walks the full revision chain of a record on every
+ *        validation, memoise or reference the creating header directly if this
+ *        becomes a bottleneck.
+ *
+ * @package Holo-REA
+ * @since   2026-10-19
+ */
+use hdk::prelude::*;
+use hdk::info::dna_info;
+
+use crate::{RecordAPIResult, ErrorCode, ErrorDetails};
+
+/// The revision of a record being modified by an update or delete operation
+///
+#[derive(Debug, Clone)]
+pub struct ModifiedRevision {
+    pub original_author: AgentPubKey,
+    pub entry: Entry,
+}
+
+/// Load the previous revision of a record, if the given header modifies one.
+/// Returns `None` for any header which is not an update or delete.
+///
+pub fn get_modified_revision(header: &Header) -> RecordAPIResult<Option<ModifiedRevision>> {
+    let (revision, entry_address) = match header {
+        Header::Update(update) => (&update.original_header_address, &update.original_entry_address),
+        Header::Delete(delete) => (&delete.deletes_address, &delete.deletes_entry_address),
+        _ => return Ok(None),
+    };
+
+    Ok(Some(ModifiedRevision {
+        original_author: get_original_author(revision)?,
+        entry: must_get_entry(entry_address.to_owned())?.into_content(),
+    }))
+}
+
+/// Determine the agent who created a record, given the hash of any of its revisions.
+///
+pub fn get_original_author(revision: &HeaderHash) -> RecordAPIResult<AgentPubKey> {
+    let mut header = must_get_header(revision.to_owned())?.header().to_owned();
+    while let Header::Update(update) = header {
+        header = must_get_header(update.original_header_address)?.header().to_owned();
+    }
+    Ok(header.author().to_owned())
+}
+
+/// Ensure the author of an update or delete operation is either the original
+/// author of the record or one of the `authorised_agents` granted access by
+/// the previous revision.
+///
+pub fn validate_modification_author(header: &Header, modified: &ModifiedRevision, authorised_agents: &[AgentPubKey]) -> Result<(), ErrorDetails> {
+    let author = header.author();
+    if *author == modified.original_author || authorised_agents.contains(author) {
+        return Ok(());
+    }
+    Err(ErrorDetails::new(ErrorCode::Unauthorized, format!("Agent {} is not authorised to modify this record", author))
+        .with_detail("agent", author))
+}
+
+/// Build the error for a record which omits all of a set of alternative fields,
+/// at least one of which is required. The error is reported against the first field.
+///
+pub fn missing_alternative_fields<S: Into<String>>(message: S, fields: &[&str]) -> ErrorDetails {
+    ErrorDetails::new(ErrorCode::MissingRequiredField, message)
+        .at_field(fields[0])
+        .with_detail("alternatives", fields.join(","))
+}
+
+/// Ensure that a record referenced by another exists. References to records in
+/// other DNAs cannot be checked during validation, and are ignored.
+///
+/// A missing record causes validation to be retried once it can be retrieved,
+/// since it may not yet have propagated to the validating agent.
+///
+pub fn must_get_local_reference<A>(address: &A) -> RecordAPIResult<()>
+    where A: AsRef<DnaHash> + AsRef<EntryHash>,
+{
+    let dna: &DnaHash = address.as_ref();
+    if *dna != dna_info()?.hash {
+        return Ok(());
+    }
+    let entry_address: &EntryHash = address.as_ref();
+    must_get_entry(entry_address.to_owned())?;
+    Ok(())
+}
+
+/// Ensure that all of the optional references of a record exist.
+///
+/// @see must_get_local_reference
+///
+pub fn must_get_local_references<'a, A, I>(addresses: I) -> RecordAPIResult<()>
+    where A: 'a + AsRef<DnaHash> + AsRef<EntryHash>,
+        I: IntoIterator<Item = &'a Option<A>>,
+{
+    for address in addresses.into_iter().flatten() {
+        must_get_local_reference(address)?;
+    }
+    Ok(())
+}
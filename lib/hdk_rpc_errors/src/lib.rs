@@ -1,8 +1,132 @@
+use std::collections::BTreeMap;
 use thiserror::Error;
 use hdk::prelude::*;
 
 use holo_hash::{EntryHash, DnaHash};
 
+// delimiters for structured error details embedded in error strings returned by the conductor
+pub const ERROR_DETAILS_START: &str = "<hREA-error>";
+pub const ERROR_DETAILS_END: &str = "</hREA-error>";
+
+/// Stable identifiers for the kinds of error which zome APIs may return.
+/// Client applications should match on these rather than on error messages.
+///
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    // validation
+    MissingRequiredField,
+    InvalidFieldValue,
+    InvalidAction,
+    ImmutableField,
+    Unauthorized,
+    CreditLimitExceeded,
+    InsufficientInventory,
+    InvalidCountersignature,
+
+    // record storage
+    EntryNotFound,
+    EntryWrongType,
+    EntryTooLarge,
+    IndexNotFound,
+    CorruptIndex,
+    EmptyQuery,
+    DuplicateIdentifier,
+    ReferenceNotFound,
+    UpdateConflict,
+    RecordInUse,
+    CountersigningFailed,
+
+    // communication between zomes & cells
+    RemoteRequestFailed,
+    RemoteResponseInvalid,
+    RemoteIndexingFailed,
+    NetworkError,
+    CellAuthFailed,
+    NotConfigured,
+
+    SerializationError,
+    InternalError,
+}
+
+/// Serialisable, machine-readable description of an error.
+///
+/// `field` is the path to the offending field of the request, where the error relates
+/// to one (eg. `resourceQuantity`). `details` provides any values relevant to the error.
+///
+#[derive(Error, Serialize, Deserialize, SerializedBytes, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[error("{message}")]
+pub struct ErrorDetails {
+    pub code: ErrorCode,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub details: BTreeMap<String, String>,
+}
+
+impl ErrorDetails {
+    pub fn new<S: Into<String>>(code: ErrorCode, message: S) -> Self {
+        ErrorDetails {
+            code,
+            message: message.into(),
+            field: None,
+            details: BTreeMap::new(),
+        }
+    }
+
+    /// Associate the error with a field of the request
+    pub fn at_field<S: Into<String>>(mut self, field: S) -> Self {
+        self.field = Some(field.into());
+        self
+    }
+
+    /// Attach a value relevant to the error
+    pub fn with_detail<K: Into<String>, V: ToString>(mut self, key: K, value: V) -> Self {
+        self.details.insert(key.into(), value.to_string());
+        self
+    }
+
+    /// Encode for transmission as a string (eg. in a `WasmError` or validation result),
+    /// such that clients may locate and decode the details wherever the string is embedded.
+    pub fn encode(&self) -> String {
+        match serde_json::to_string(self) {
+            Ok(json) => format!("{}{}{}", ERROR_DETAILS_START, json, ERROR_DETAILS_END),
+            Err(_) => self.message.to_owned(),
+        }
+    }
+
+    /// Read back error details previously `encode`d into a string
+    pub fn decode<S: AsRef<str>>(encoded: S) -> Option<Self> {
+        let encoded = encoded.as_ref();
+        let start = encoded.find(ERROR_DETAILS_START)? + ERROR_DETAILS_START.len();
+        let end = start + encoded[start..].find(ERROR_DETAILS_END)?;
+
+        // details may have been escaped when nested within the messages of other errors
+        let mut json = encoded[start..end].to_string();
+        loop {
+            if let Ok(details) = serde_json::from_str(&json) {
+                return Some(details);
+            }
+            if !json.contains("\\\"") {
+                return None;
+            }
+            json = json.replace("\\\"", "\"").replace("\\\\", "\\");
+        }
+    }
+
+    /// Read back error details from the message of an error returned by a zome call
+    pub fn from_wasm_error(e: &WasmError) -> Option<Self> {
+        match e {
+            WasmError::Guest(msg) |
+            WasmError::CallError(msg) |
+            WasmError::Host(msg) => Self::decode(msg),
+            _ => None,
+        }
+    }
+}
+
 // serializable error and result type for communicating errors between cells
 
 #[derive(Error, Serialize, Deserialize, SerializedBytes, Debug, Clone)]
@@ -11,6 +135,8 @@ pub enum CrossCellError {
     Serialization(#[from] SerializedBytesError),
     #[error(transparent)]
     Wasm(#[from] WasmError),
+    #[error(transparent)]
+    Detailed(#[from] ErrorDetails),
 
     #[error("Entry size of {0} exceeded maximum allowable")]
     EntryTooLarge(usize),
@@ -26,12 +152,37 @@ pub enum CrossCellError {
     Internal(String),
     #[error("Local zome call failed: {0} zome is not configured for target {1}")]
     NotConfigured(ZomeName, FunctionName),
+    #[error("Remote zome call could not be made during a countersigning session: {0}")]
+    CountersigningSession(String),
 }
 
 pub type OtherCellResult<T> = Result<T, CrossCellError>;
 
+impl CrossCellError {
+    /// Describe this error in machine-readable form
+    pub fn details(&self) -> ErrorDetails {
+        let code = match self {
+            CrossCellError::Detailed(details) => return details.to_owned(),
+            CrossCellError::Wasm(e) => match ErrorDetails::from_wasm_error(e) {
+                Some(details) => return details,
+                None => ErrorCode::InternalError,
+            },
+            CrossCellError::Serialization(_) => ErrorCode::SerializationError,
+            CrossCellError::EntryTooLarge(_) => ErrorCode::EntryTooLarge,
+            CrossCellError::IndexNotFound(_) => ErrorCode::IndexNotFound,
+            CrossCellError::NetworkError(_) => ErrorCode::NetworkError,
+            CrossCellError::Unauthorized(..) => ErrorCode::Unauthorized,
+            CrossCellError::CellAuthFailed(..) => ErrorCode::CellAuthFailed,
+            CrossCellError::Internal(_) => ErrorCode::InternalError,
+            CrossCellError::NotConfigured(..) => ErrorCode::NotConfigured,
+            CrossCellError::CountersigningSession(_) => ErrorCode::CountersigningFailed,
+        };
+        ErrorDetails::new(code, self.to_string())
+    }
+}
+
 impl From<CrossCellError> for WasmError {
     fn from(e: CrossCellError) -> WasmError {
-        WasmError::CallError(e.to_string())
+        WasmError::CallError(e.details().encode())
     }
 }
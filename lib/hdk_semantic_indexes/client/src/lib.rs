@@ -39,6 +39,7 @@ use hdk_records::{
 use hdk_semantic_indexes_zome_rpc::{
     ByAddress,
     RemoteEntryLinkRequest, RemoteEntryLinkResponse,
+    KeyedIndexLinkRequest,
 };
 
 //-------------------------------[ MACRO LAYER ]-------------------------------------
@@ -289,6 +290,55 @@ macro_rules! update_index {
             )
         }
     };
+
+    // string-keyed indexes (eg. classification URLs), add only
+    (
+        Keyed(
+            $record_type:ident($record_id:expr).$rel:ident($add_keys:expr)
+        )
+    ) => {
+        paste! {
+            update_keyed_index(
+                [<read_ $record_type:lower:snake _index_zome>],
+                &stringify!([<_internal_index_ $record_type:lower:snake _ $rel:lower:snake>]),
+                $record_id,
+                $add_keys,
+                &vec![].as_slice(),
+            )
+        }
+    };
+    // string-keyed indexes, remove only
+    (
+        Keyed(
+            $record_type:ident($record_id:expr).$rel:ident.not($remove_keys:expr)
+        )
+    ) => {
+        paste! {
+            update_keyed_index(
+                [<read_ $record_type:lower:snake _index_zome>],
+                &stringify!([<_internal_index_ $record_type:lower:snake _ $rel:lower:snake>]),
+                $record_id,
+                &vec![].as_slice(),
+                $remove_keys,
+            )
+        }
+    };
+    // string-keyed indexes, add & remove
+    (
+        Keyed(
+            $record_type:ident($record_id:expr).$rel:ident($add_keys:expr).not($remove_keys:expr)
+        )
+    ) => {
+        paste! {
+            update_keyed_index(
+                [<read_ $record_type:lower:snake _index_zome>],
+                &stringify!([<_internal_index_ $record_type:lower:snake _ $rel:lower:snake>]),
+                $record_id,
+                $add_keys,
+                $remove_keys,
+            )
+        }
+    };
 }
 
 
@@ -554,6 +604,35 @@ pub fn update_local_index<C, F, G, A, B, S>(
     Ok(RemoteEntryLinkResponse { indexes_created, indexes_removed })
 }
 
+/// Request for a DNA-local index zome to link a record to a set of string-based keys
+/// (eg. classification URLs), and unlink it from any `remove_keys`.
+///
+/// Unlike record relationships, keyed indexes are only managed on one side; the
+/// keys themselves are not records and have no index zome of their own.
+///
+pub fn update_keyed_index<C, F, A, K, S>(
+    zome_name_from_config: F,
+    fn_name: &S,
+    source: &A,
+    add_keys: &[K],
+    remove_keys: &[K],
+) -> RecordAPIResult<RemoteEntryLinkResponse>
+    where S: AsRef<str>,
+        C: std::fmt::Debug,
+        SerializedBytes: TryInto<C, Error = SerializedBytesError>,
+        F: FnOnce(C) -> Option<String>,
+        A: DnaAddressable<EntryHash>,
+        K: Clone + Into<String>,
+{
+    Ok(call_local_zome_method(
+        zome_name_from_config, fn_name,
+        KeyedIndexLinkRequest::new(
+            source,
+            add_keys, remove_keys,
+        )
+    )?)
+}
+
 fn merge_indexing_results(
     foreign_zome_results: &[OtherCellResult<RemoteEntryLinkResponse>],
     response_accessor: impl Fn(&RemoteEntryLinkResponse) -> Vec<OtherCellResult<HeaderHash>>,
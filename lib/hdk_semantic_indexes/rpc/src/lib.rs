@@ -26,6 +26,44 @@ pub struct ByAddress<T> {
     pub address: T,
 }
 
+/// Resolve concurrent revisions of a record by retaining `revision_id` and superseding all others
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeRevisions {
+    pub revision_id: RevisionHash,
+    pub superseded_revisions: Vec<RevisionHash>,
+}
+
+/// Options for checking the links managed by an index zome
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VerifyIndexesParams {
+    // restore any missing links and remove orphaned ones, as well as reporting them
+    #[serde(default)]
+    pub repair: bool,
+}
+
+/// Kinds of problem which may be detected in an index
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum IndexInconsistencyType {
+    // a link exists without the link pointing back from its target
+    MissingReciprocal,
+    // a record references the target in its fields, but is not linked to it
+    MissingLink,
+    // a record is linked to the target, but does not reference it in its fields
+    OrphanedLink,
+}
+
+/// A problem detected in an index, between the identity `Path`s of two records (or a record and a key)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexInconsistency {
+    pub index: String,
+    pub problem: IndexInconsistencyType,
+    pub base: EntryHash,
+    pub target: EntryHash,
+    pub repaired: bool,
+}
+
 /// Common request format (zome trait) for linking remote entries in cooperating DNAs
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RemoteEntryLinkRequest<A, B>
@@ -74,6 +112,51 @@ impl<A, B> RemoteEntryLinkRequest<A, B>
     }
 }
 
+/// Request format for linking a record to a set of string-based keys (eg. classification URLs)
+/// in its DNA-local index zome
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct KeyedIndexLinkRequest<A>
+    where A: DnaAddressable<EntryHash>,
+{
+    pub target_entry: A,
+    pub added_keys: Vec<String>,
+    pub removed_keys: Vec<String>,
+}
+
+impl<A> TryFrom<&KeyedIndexLinkRequest<A>> for SerializedBytes
+    where A: DnaAddressable<EntryHash>,
+{
+    type Error = SerializedBytesError;
+    fn try_from(t: &KeyedIndexLinkRequest<A>) -> Result<SerializedBytes, SerializedBytesError> {
+        encode(t).map(|v|
+            SerializedBytes::from(UnsafeBytes::from(v))
+        )
+    }
+}
+
+impl<A> TryFrom<KeyedIndexLinkRequest<A>> for SerializedBytes
+    where A: DnaAddressable<EntryHash>,
+{
+    type Error = SerializedBytesError;
+    fn try_from(t: KeyedIndexLinkRequest<A>) -> Result<SerializedBytes, SerializedBytesError> {
+        SerializedBytes::try_from(&t)
+    }
+}
+
+impl<A> KeyedIndexLinkRequest<A>
+    where A: DnaAddressable<EntryHash>,
+{
+    pub fn new<K>(target_entry: &A, add_keys: &[K], remove_keys: &[K]) -> Self
+        where K: Clone + Into<String>,
+    {
+        KeyedIndexLinkRequest {
+            target_entry: (*target_entry).clone(),
+            added_keys: add_keys.iter().cloned().map(|k| { k.into() }).collect(),
+            removed_keys: remove_keys.iter().cloned().map(|k| { k.into() }).collect(),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone)]
 pub struct RemoteEntryLinkResponse {
     pub indexes_created: Vec<OtherCellResult<HeaderHash>>,
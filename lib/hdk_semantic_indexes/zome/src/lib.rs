@@ -12,8 +12,10 @@ use hdk_records::{
         calculate_identity_address,
         create_entry_identity,
         read_entry_identity_full,
+        read_entry_type_identities,
     },
-    links::{get_linked_addresses, get_linked_headers},
+    links::get_linked_addresses,
+    entries::get_entry_by_address,
     rpc::call_local_zome_method,
 };
 pub use hdk_records::{ RecordAPIResult, DataIntegrityError };
@@ -94,6 +96,64 @@ pub fn query_index<'a, T, O, C, F, A, S, I, J>(
     Ok(entries)
 }
 
+/// Reads and returns all string keys (eg. classification URLs) referenced by the
+/// given keyed index from `base_entry_type.base_address` via `link_tag`.
+///
+pub fn read_keyed_index<'a, A, S, I>(
+    base_entry_type: &I,
+    base_address: &A,
+    link_tag: &S,
+) -> RecordAPIResult<Vec<String>>
+    where S: 'a + AsRef<[u8]> + ?Sized,
+        I: AsRef<str>,
+        A: DnaAddressable<EntryHash>,
+{
+    let index_address = calculate_identity_address(base_entry_type, base_address)?;
+    let refd_key_addresses = get_linked_addresses(&index_address, LinkTag::new(link_tag.as_ref()))?;
+
+    let (existing_key_results, read_errors): (Vec<RecordAPIResult<String>>, Vec<RecordAPIResult<String>>) = refd_key_addresses.iter()
+        .map(read_key_path)
+        .partition(Result::is_ok);
+
+    throw_any_error(read_errors)?;
+
+    Ok(existing_key_results.iter().cloned()
+        .map(Result::unwrap)
+        .collect())
+}
+
+/// Given a string key (eg. a classification URL) to query from, returns a Vec of all
+/// records linked to it via the given link tag, decoded into the requested type `T`.
+///
+/// Use this method to query records for a keyed query edge in full.
+///
+pub fn query_keyed_index<'a, T, O, C, F, S, I, J, K>(
+    key_type: &I,
+    key: &K,
+    link_tag: &S,
+    foreign_zome_name_from_config: &F,
+    foreign_read_method_name: &J,
+) -> RecordAPIResult<Vec<RecordAPIResult<T>>>
+    where I: AsRef<str>,
+        J: AsRef<str>,
+        K: AsRef<str>,
+        S: 'a + AsRef<[u8]> + ?Sized,
+        O: DnaAddressable<EntryHash>,
+        T: serde::de::DeserializeOwned + std::fmt::Debug,
+        C: std::fmt::Debug,
+        SerializedBytes: TryInto<C, Error = SerializedBytesError>,
+        F: Fn(C) -> Option<String>,
+{
+    let key_address = key_path_for(key_type, key).hash()?;
+    let addrs_result = get_linked_addresses(&key_address, LinkTag::new(link_tag.as_ref()))?;
+    let entries = retrieve_foreign_records::<T, O, C, F, J>(
+        foreign_zome_name_from_config,
+        foreign_read_method_name,
+        &addrs_result,
+    );
+    Ok(entries)
+}
+
 /// Fetches all referenced record entries found corresponding to the input
 /// identity addresses.
 ///
@@ -283,6 +343,75 @@ fn create_index<A, B, S, I>(
     ])
 }
 
+/// Respond to a request from a record zome to link one of its records to a set of string-based
+/// keys (eg. classification URLs), and to remove links to any `removed_keys`.
+///
+/// Each key is represented by a `Path` under `key_type`, which is linked bidirectionally with
+/// the identity `Path` of the `dest` record.
+///
+pub fn sync_keyed_index<A, S, I>(
+    key_type: &I,
+    dest_entry_type: &I,
+    dest: &A,
+    added_keys: &[String],
+    removed_keys: &[String],
+    link_tag: &S,
+    link_tag_reciprocal: &S,
+) -> OtherCellResult<RemoteEntryLinkResponse>
+    where S: AsRef<[u8]> + ?Sized,
+        I: AsRef<str>,
+        A: DnaAddressable<EntryHash>,
+{
+    let indexes_created = added_keys.iter()
+        .flat_map(|key| {
+            match create_keyed_index(key_type, key, dest_entry_type, dest, link_tag, link_tag_reciprocal) {
+                Ok(created) => created,
+                Err(e) => vec![Err(e)],
+            }
+        })
+        .map(|r| { convert_errors(&r) })
+        .collect();
+
+    let indexes_removed = removed_keys.iter()
+        .flat_map(|key| {
+            match delete_keyed_index(key_type, key, dest_entry_type, dest, link_tag, link_tag_reciprocal) {
+                Ok(deleted) => deleted,
+                Err(e) => vec![Err(e)],
+            }
+        })
+        .map(|r| { convert_errors(&r) })
+        .collect();
+
+    Ok(RemoteEntryLinkResponse { indexes_created, indexes_removed })
+}
+
+/// Creates a bidirectional link between a string key `Path` and a record identity, and returns
+/// a vector of the `HeaderHash`es of the (respectively) forward & reciprocal links created.
+fn create_keyed_index<A, S, I, K>(
+    key_type: &I,
+    key: &K,
+    dest_entry_type: &I,
+    dest: &A,
+    link_tag: &S,
+    link_tag_reciprocal: &S,
+) -> RecordAPIResult<Vec<RecordAPIResult<HeaderHash>>>
+    where I: AsRef<str>,
+        K: AsRef<str>,
+        S: AsRef<[u8]> + ?Sized,
+        A: DnaAddressable<EntryHash>,
+{
+    let key_path = key_path_for(key_type, key);
+    key_path.ensure()?;
+    let key_hash = key_path.hash()?;
+    let dest_hash = calculate_identity_address(dest_entry_type, dest)?;
+
+    Ok(vec! [
+        // :TODO: prevent duplicates- is there an efficient way to ensure a link of a given tag exists?
+        Ok(create_link(key_hash.clone(), dest_hash.clone(), LinkTag::new(link_tag.as_ref()))?),
+        Ok(create_link(dest_hash, key_hash, LinkTag::new(link_tag_reciprocal.as_ref()))?),
+    ])
+}
+
 //-------------------------------[ DELETE ]-------------------------------------
 
 /// Deletes a set of links between a remote record reference and some set
@@ -339,10 +468,9 @@ fn delete_dest_indexes<'a, A, B, S, I>(
     })
 }
 
-/// Deletes a bidirectional link between two entry addresses. Any active links between
-/// the given addresses using the given tags will be deleted.
-///
-/// :TODO: this should probably only delete the referenced IDs, at the moment it clears anything matching tags.
+/// Deletes a bidirectional link between two entry addresses. Only active links between
+/// the given addresses using the given tags are deleted; links from either address to
+/// other records are left intact.
 ///
 fn delete_index<'a, A, B, S, I>(
     source_entry_type: &I,
@@ -362,8 +490,49 @@ fn delete_index<'a, A, B, S, I>(
     let address_source = calculate_identity_address(source_entry_type, source)?;
     let address_dest = calculate_identity_address(dest_entry_type, dest)?;
 
-    let mut links = get_linked_headers(&address_source, tag_source)?;
-    links.append(& mut get_linked_headers(&address_dest, tag_dest)?);
+    let mut links: Vec<HeaderHash> = get_links(address_source.clone(), Some(tag_source))?.iter()
+        .filter(|l| { l.target == address_dest })
+        .map(|l| { l.create_link_hash.clone() })
+        .collect();
+    links.append(&mut get_links(address_dest, Some(tag_dest))?.iter()
+        .filter(|l| { l.target == address_source })
+        .map(|l| { l.create_link_hash.clone() })
+        .collect());
+
+    Ok(links
+        .iter().cloned()
+        .map(|l| { Ok(delete_link(l)?) })
+        .collect()
+    )
+}
+
+/// Deletes the bidirectional links between a string key `Path` and a record identity.
+/// As with `delete_index`, only links between the given key and record are removed.
+///
+fn delete_keyed_index<A, S, I, K>(
+    key_type: &I,
+    key: &K,
+    dest_entry_type: &I,
+    dest: &A,
+    link_tag: &S,
+    link_tag_reciprocal: &S,
+) -> RecordAPIResult<Vec<RecordAPIResult<HeaderHash>>>
+    where I: AsRef<str>,
+        K: AsRef<str>,
+        S: AsRef<[u8]> + ?Sized,
+        A: DnaAddressable<EntryHash>,
+{
+    let key_hash = key_path_for(key_type, key).hash()?;
+    let dest_hash = calculate_identity_address(dest_entry_type, dest)?;
+
+    let mut links: Vec<HeaderHash> = get_links(key_hash.clone(), Some(LinkTag::new(link_tag.as_ref())))?.iter()
+        .filter(|l| { l.target == dest_hash })
+        .map(|l| { l.create_link_hash.clone() })
+        .collect();
+    links.append(&mut get_links(dest_hash, Some(LinkTag::new(link_tag_reciprocal.as_ref())))?.iter()
+        .filter(|l| { l.target == key_hash })
+        .map(|l| { l.create_link_hash.clone() })
+        .collect());
 
     Ok(links
         .iter().cloned()
@@ -372,8 +541,234 @@ fn delete_index<'a, A, B, S, I>(
     )
 }
 
+//-------------------------------[ VERIFY ]-------------------------------------
+
+/// Check that every link between `base_entry_type` and `target_entry_type` records via `link_tag`
+/// is paired with a link back from its target via `link_tag_reciprocal`, and vice versa.
+///
+/// If `repair` is set, any missing reciprocal links are created.
+///
+/// :TODO: links to records in remote DNAs can only be checked against the local side of the index,
+///        compare with the reciprocal index in the foreign cell as well.
+///
+pub fn verify_reciprocal_index<I, S>(
+    base_entry_type: &I,
+    target_entry_type: &I,
+    link_tag: &S,
+    link_tag_reciprocal: &S,
+    repair: bool,
+) -> RecordAPIResult<Vec<IndexInconsistency>>
+    where I: AsRef<str>,
+        S: AsRef<str> + ?Sized,
+{
+    let mut found = find_missing_reciprocal_links(base_entry_type, link_tag, link_tag_reciprocal, repair)?;
+    found.append(&mut find_missing_reciprocal_links(target_entry_type, link_tag_reciprocal, link_tag, repair)?);
+    Ok(found)
+}
+
+/// Check the links from `base_entry_type` records via `link_tag` against the record field they
+/// were built from. Each record is read from the record storage zome, and `read_source_field`
+/// determines the identifiers it references.
+///
+/// If `repair` is set, missing links are created and orphaned ones deleted (along with their reciprocals).
+///
+/// Records which cannot be read (eg. because they have been deleted) have all their links reported
+/// as orphaned, but these are never repaired since the failure may only be temporary.
+///
+/// :TODO: only records which have previously been indexed are checked. Records for which every
+///        index update failed have no identity `Path` in this zome to be found by.
+///
+pub fn verify_source_index<T, A, B, C, F, G, I, J, S>(
+    base_entry_type: &I,
+    target_entry_type: &I,
+    link_tag: &S,
+    link_tag_reciprocal: &S,
+    foreign_zome_name_from_config: &F,
+    foreign_read_method_name: &J,
+    read_source_field: G,
+    repair: bool,
+) -> RecordAPIResult<Vec<IndexInconsistency>>
+    where I: AsRef<str>,
+        J: AsRef<str>,
+        S: AsRef<str> + ?Sized,
+        A: DnaAddressable<EntryHash>,
+        B: DnaAddressable<EntryHash>,
+        T: serde::de::DeserializeOwned + std::fmt::Debug,
+        C: std::fmt::Debug,
+        SerializedBytes: TryInto<C, Error = SerializedBytesError>,
+        F: Fn(C) -> Option<String>,
+        G: Fn(&T) -> Vec<B>,
+{
+    let read_record = retrieve_foreign_record::<T, A, C, F, J>(foreign_zome_name_from_config, foreign_read_method_name);
+    let mut found = vec![];
+
+    for base in read_entry_type_identities(base_entry_type)? {
+        let linked = get_linked_addresses(&base, LinkTag::new(link_tag.as_ref()))?;
+        let record = match read_record(&base) {
+            Ok(record) => record,
+            Err(_) => {
+                found.extend(linked.iter().map(|target| {
+                    index_inconsistency(link_tag, IndexInconsistencyType::OrphanedLink, &base, target, false)
+                }));
+                continue;
+            },
+        };
+
+        // check all referenced records are linked
+        let mut referenced = vec![];
+        for dest in read_source_field(&record).iter() {
+            let target = calculate_identity_address(target_entry_type, dest)?;
+            if !linked.contains(&target) {
+                if repair {
+                    create_entry_identity(target_entry_type, dest)?;
+                    create_link(base.clone(), target.clone(), LinkTag::new(link_tag.as_ref()))?;
+                    if !get_linked_addresses(&target, LinkTag::new(link_tag_reciprocal.as_ref()))?.contains(&base) {
+                        create_link(target.clone(), base.clone(), LinkTag::new(link_tag_reciprocal.as_ref()))?;
+                    }
+                }
+                found.push(index_inconsistency(link_tag, IndexInconsistencyType::MissingLink, &base, &target, repair));
+            }
+            referenced.push(target);
+        }
+
+        // check all linked records are referenced
+        for target in linked.iter().filter(|target| { !referenced.contains(*target) }) {
+            if repair {
+                delete_links_between(&base, target, link_tag)?;
+                delete_links_between(target, &base, link_tag_reciprocal)?;
+            }
+            found.push(index_inconsistency(link_tag, IndexInconsistencyType::OrphanedLink, &base, target, repair));
+        }
+    }
+
+    Ok(found)
+}
+
+/// Normalises the forms of record field which may be indexed (single, optional or
+/// multiple references) to a list of the referenced identifiers.
+///
+pub struct IndexedValues<A>(pub Vec<A>);
+
+impl<A> From<A> for IndexedValues<A> {
+    fn from(value: A) -> Self {
+        IndexedValues(vec![value])
+    }
+}
+
+impl<A> From<Option<A>> for IndexedValues<A> {
+    fn from(value: Option<A>) -> Self {
+        IndexedValues(value.into_iter().collect())
+    }
+}
+
+impl<A> From<Vec<A>> for IndexedValues<A> {
+    fn from(values: Vec<A>) -> Self {
+        IndexedValues(values)
+    }
+}
+
+impl<A> From<Option<Vec<A>>> for IndexedValues<A> {
+    fn from(values: Option<Vec<A>>) -> Self {
+        IndexedValues(values.unwrap_or_default())
+    }
+}
+
+/// Helper for index verification to check the reciprocal of every link from `entry_type` records via `link_tag`.
+fn find_missing_reciprocal_links<I, S>(
+    entry_type: &I,
+    link_tag: &S,
+    link_tag_reciprocal: &S,
+    repair: bool,
+) -> RecordAPIResult<Vec<IndexInconsistency>>
+    where I: AsRef<str>,
+        S: AsRef<str> + ?Sized,
+{
+    let mut found = vec![];
+
+    for base in read_entry_type_identities(entry_type)? {
+        for target in get_linked_addresses(&base, LinkTag::new(link_tag.as_ref()))? {
+            if get_linked_addresses(&target, LinkTag::new(link_tag_reciprocal.as_ref()))?.contains(&base) {
+                continue;
+            }
+            if repair {
+                create_link(target.clone(), base.clone(), LinkTag::new(link_tag_reciprocal.as_ref()))?;
+            }
+            found.push(index_inconsistency(link_tag_reciprocal, IndexInconsistencyType::MissingReciprocal, &target, &base, repair));
+        }
+    }
+
+    Ok(found)
+}
+
+/// Deletes all links from `base` to `target` with the given tag, leaving any others from `base` intact.
+fn delete_links_between<S>(
+    base: &EntryHash,
+    target: &EntryHash,
+    link_tag: &S,
+) -> RecordAPIResult<()>
+    where S: AsRef<str> + ?Sized,
+{
+    for link in get_links(base.clone(), Some(LinkTag::new(link_tag.as_ref())))? {
+        if link.target == *target {
+            delete_link(link.create_link_hash)?;
+        }
+    }
+    Ok(())
+}
+
+fn index_inconsistency<S>(
+    link_tag: &S,
+    problem: IndexInconsistencyType,
+    base: &EntryHash,
+    target: &EntryHash,
+    repaired: bool,
+) -> IndexInconsistency
+    where S: AsRef<str> + ?Sized,
+{
+    IndexInconsistency {
+        index: link_tag.as_ref().to_string(),
+        problem,
+        base: base.clone(),
+        target: target.clone(),
+        repaired,
+    }
+}
+
 //--------------------------[ UTILITIES  / INTERNALS ]---------------------
 
+/// Represent string-keyed index nodes (eg. classification URLs) using native Holochain `Path` construct
+///
+/// :DUPE: same layout as anchored record identifiers in `hdk_records::records_anchored`
+///
+fn key_path_for<I, K>(
+    key_type: &I,
+    key: &K,
+) -> temp_path::path::Path
+    where I: AsRef<str>,
+        K: AsRef<str>,
+{
+    let type_root = key_type.as_ref().as_bytes().to_vec();
+    let string_key = key.as_ref().as_bytes().to_vec();
+
+    temp_path::path::Path::from(vec![type_root.into(), string_key.into()])
+}
+
+/// Given the `EntryHash` of a string key `Path`, read back the key it was created for.
+///
+fn read_key_path(
+    key_path_address: &EntryHash,
+) -> RecordAPIResult<String>
+{
+    let key_path: temp_path::path::Path = get_entry_by_address(key_path_address)?;
+    let components: &Vec<temp_path::path::Component> = key_path.as_ref();
+    let key_bytes = components.last()
+        .ok_or(DataIntegrityError::CorruptIndexError(key_path_address.clone(), None))?
+        .as_ref();
+
+    String::from_utf8(key_bytes.to_vec())
+        .map_err(|_| { DataIntegrityError::CorruptIndexError(key_path_address.clone(), Some(key_bytes.to_vec())) })
+}
+
 /// Returns the first error encountered (if any). Best used with the `?` operator.
 fn throw_any_error<T>(mut errors: Vec<RecordAPIResult<T>>) -> RecordAPIResult<()> {
     if errors.len() == 0 {
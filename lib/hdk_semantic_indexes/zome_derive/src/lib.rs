@@ -63,7 +63,7 @@ pub fn index_zome(attribs: TokenStream, input: TokenStream) -> TokenStream {
 
             let path = match &field.ty {
                 Type::Path(TypePath { path, .. }) => path,
-                _ => panic!("expected index type of Local or Remote"),
+                _ => panic!("expected index type of Local, Remote or Keyed"),
             };
             let (index_type, args) = match path.segments.first() {
                 Some(PathSegment { arguments: AngleBracketed(AngleBracketedGenericArguments { args, .. }), ident, .. }) => (ident, args),
@@ -80,23 +80,39 @@ pub fn index_zome(attribs: TokenStream, input: TokenStream) -> TokenStream {
             let related_record_type_str_attribute = related_record_type.to_case(Case::Snake);
             let reciprocal_index_name = format_ident!("{}_{}", related_record_type_str_attribute, related_relationship_name);
 
+            // fields marked `#[source_field]` mirror a field of the same name in the indexed record
+            let is_source_field = field.attrs.iter().any(|attr| { attr.path.is_ident("source_field") });
+            if is_source_field && index_type.to_string() == "Keyed" {
+                panic!("source_field is not supported for Keyed indexes");
+            }
+
             (
                 index_type, relationship_name,
                 related_record_type_str_attribute,
                 related_index_field_type, related_index_name,
-                reciprocal_index_name,
+                reciprocal_index_name, is_source_field,
             )
         });
 
     let index_accessors = all_indexes.clone()
         .map(|(
-            _index_type, relationship_name,
+            index_type, relationship_name,
             _related_record_type_str_attribute,
             related_index_field_type, related_index_name,
-            _reciprocal_index_name,
+            _reciprocal_index_name, _is_source_field,
         )| {
             let local_dna_read_method_name = format_ident!("_internal_read_{}_{}", record_type_str_attribute, relationship_name);
 
+            // string-keyed indexes return the keys themselves, rather than record identifiers
+            if index_type.to_string() == "Keyed" {
+                return quote! {
+                    #[hdk_extern]
+                    fn #local_dna_read_method_name(ByAddress { address }: ByAddress<#record_index_field_type>) -> ExternResult<Vec<String>> {
+                        Ok(read_keyed_index(&stringify!(#record_type_str_attribute), &address, &stringify!(#related_index_name))?)
+                    }
+                };
+            }
+
             quote! {
                 #[hdk_extern]
                 fn #local_dna_read_method_name(ByAddress { address }: ByAddress<#record_index_field_type>) -> ExternResult<Vec<#related_index_field_type>> {
@@ -110,15 +126,32 @@ pub fn index_zome(attribs: TokenStream, input: TokenStream) -> TokenStream {
             index_type, relationship_name,
             related_record_type_str_attribute,
             related_index_field_type, related_index_name,
-            reciprocal_index_name,
+            reciprocal_index_name, _is_source_field,
         )| {
             // :TODO: differentiate Local/Remote indexes as necessitated by final HC core APIs
             let dna_update_method_name = match index_type.to_string().as_ref() {
-                "Local" => format_ident!("_internal_index_{}_{}", record_type_str_attribute, relationship_name),
+                "Local" | "Keyed" => format_ident!("_internal_index_{}_{}", record_type_str_attribute, relationship_name),
                 "Remote" => format_ident!("index_{}_{}", record_type_str_attribute, relationship_name),
-                _ => panic!("expected index type of Local or Remote"),
+                _ => panic!("expected index type of Local, Remote or Keyed"),
             };
 
+            if index_type.to_string() == "Keyed" {
+                return quote! {
+                    #[hdk_extern]
+                    fn #dna_update_method_name(indexes: KeyedIndexLinkRequest<#record_index_field_type>) -> ExternResult<RemoteEntryLinkResponse> {
+                        let KeyedIndexLinkRequest { target_entry, added_keys, removed_keys } = indexes;
+
+                        Ok(sync_keyed_index(
+                            &stringify!(#related_record_type_str_attribute),
+                            &stringify!(#record_type_str_attribute), &target_entry,
+                            added_keys.as_slice(),
+                            removed_keys.as_slice(),
+                            &stringify!(#reciprocal_index_name), &stringify!(#related_index_name),
+                        )?)
+                    }
+                };
+            }
+
             quote! {
                 #[hdk_extern]
                 fn #dna_update_method_name(indexes: RemoteEntryLinkRequest<#related_index_field_type, #record_index_field_type>) -> ExternResult<RemoteEntryLinkResponse> {
@@ -135,15 +168,69 @@ pub fn index_zome(attribs: TokenStream, input: TokenStream) -> TokenStream {
             }
         });
 
-    let query_handlers = all_indexes
+    let index_verifiers = all_indexes.clone()
         .map(|(
             _index_type, relationship_name,
             related_record_type_str_attribute,
+            related_index_field_type, related_index_name,
+            reciprocal_index_name, is_source_field,
+        )| {
+            let verify_reciprocal = quote! {
+                inconsistencies.append(&mut verify_reciprocal_index(
+                    &stringify!(#record_type_str_attribute), &stringify!(#related_record_type_str_attribute),
+                    &stringify!(#related_index_name), &stringify!(#reciprocal_index_name),
+                    repair,
+                )?);
+            };
+
+            if !is_source_field {
+                return verify_reciprocal;
+            }
+
+            // check links against the indexed record before their reciprocals, so that orphaned links are not reinstated
+            let record_field_ident = format_ident!("{}", record_type_str_attribute);
+            let source_field_ident = format_ident!("{}", relationship_name);
+            quote! {
+                inconsistencies.append(&mut verify_source_index::<ResponseData, #record_index_field_type, #related_index_field_type, _,_,_,_,_,_>(
+                    &stringify!(#record_type_str_attribute), &stringify!(#related_record_type_str_attribute),
+                    &stringify!(#related_index_name), &stringify!(#reciprocal_index_name),
+                    &read_index_target_zome,
+                    &READ_FN_NAME,
+                    |record: &ResponseData| {
+                        IndexedValues::<#related_index_field_type>::from(record.#record_field_ident.#source_field_ident.to_owned()).0
+                    },
+                    repair,
+                )?);
+                #verify_reciprocal
+            }
+        });
+
+    let query_handlers = all_indexes
+        .map(|(
+            index_type, relationship_name,
+            related_record_type_str_attribute,
             _related_index_field_type, _related_index_name,
-            reciprocal_index_name,
+            reciprocal_index_name, _is_source_field,
         )| {
             let query_field_ident = format_ident!("{}", relationship_name);
 
+            if index_type.to_string() == "Keyed" {
+                return quote! {
+                    match &params.#query_field_ident {
+                        Some(#query_field_ident) => {
+                            entries_result = query_keyed_index::<ResponseData, #record_index_field_type, _,_,_,_,_,_>(
+                                &stringify!(#related_record_type_str_attribute),
+                                &String::from(#query_field_ident.to_owned()),
+                                &stringify!(#reciprocal_index_name),
+                                &read_index_target_zome,
+                                &READ_FN_NAME,
+                            );
+                        },
+                        _ => (),
+                    };
+                };
+            }
+
             quote! {
                 match &params.#query_field_ident {
                     Some(#query_field_ident) => {
@@ -197,6 +284,16 @@ pub fn index_zome(attribs: TokenStream, input: TokenStream) -> TokenStream {
             #index_mutators
         )*
 
+        // public zome API for checking (and optionally repairing) the links managed by this zome
+        #[hdk_extern]
+        fn verify_indexes(VerifyIndexesParams { repair }: VerifyIndexesParams) -> ExternResult<Vec<IndexInconsistency>> {
+            let mut inconsistencies = vec![];
+            #(
+                #index_verifiers
+            )*
+            Ok(inconsistencies)
+        }
+
         // define query results structure as a flat array which separates errors into own list
         #[derive(Debug, Serialize, Deserialize)]
         struct QueryResults {
@@ -7,6 +7,7 @@
 use serde::{Deserializer, Serializer, de::Error};
 
 use hdk::prelude::*;
+use hdk_rpc_errors::{ ErrorCode, ErrorDetails };
 use vf_attributes_hdk::{ ActionId, ProcessAddress, EconomicResourceAddress };
 
 pub mod builtins;
@@ -108,33 +109,43 @@ pub struct Action {
 /**
  * Validation for EconomicEvent, Commitment and Process to ensure correct use of actions & Processes
  */
-pub fn validate_flow_action(action_id: ActionId, input_process: Option<ProcessAddress>, output_process: Option<ProcessAddress>) -> Result<(), String> {
+pub fn validate_flow_action(action_id: ActionId, input_process: Option<ProcessAddress>, output_process: Option<ProcessAddress>) -> Result<(), ErrorDetails> {
     if let Some(action) = get_builtin_action(action_id.as_ref()) {
         match action.input_output {
             ProcessType::NotApplicable => if input_process.is_some() || output_process.is_some() {
-                Err(format!("EconomicEvent of '{:}' action cannot link to processes", action.id).into())
+                Err(ErrorDetails::new(ErrorCode::InvalidAction, format!("EconomicEvent of '{:}' action cannot link to processes", action.id))
+                    .at_field(if input_process.is_some() { "inputOf" } else { "outputOf" })
+                    .with_detail("action", action.id))
             } else { Ok(()) },
             ProcessType::Input => if input_process.is_none() {
-                Err(format!("EconomicEvent input process required for '{:}' action", action.id).into())
+                Err(ErrorDetails::new(ErrorCode::MissingRequiredField, format!("EconomicEvent input process required for '{:}' action", action.id))
+                    .at_field("inputOf")
+                    .with_detail("action", action.id))
             } else { Ok(()) },
             ProcessType::Output => if output_process.is_none() {
-                Err(format!("EconomicEvent output process required for '{:}' action", action.id).into())
+                Err(ErrorDetails::new(ErrorCode::MissingRequiredField, format!("EconomicEvent output process required for '{:}' action", action.id))
+                    .at_field("outputOf")
+                    .with_detail("action", action.id))
             } else { Ok(()) },
         }
     } else {
-        Err("Unknown action".to_string())
+        Err(ErrorDetails::new(ErrorCode::InvalidAction, "Unknown action")
+            .at_field("action")
+            .with_detail("action", String::from(action_id)))
     }
 }
 
-pub fn validate_move_inventories(resouce_inventoried_as: Option<EconomicResourceAddress>, to_resource_inventoried_as: Option<EconomicResourceAddress>) -> Result<(), String> {
+pub fn validate_move_inventories(resouce_inventoried_as: Option<EconomicResourceAddress>, to_resource_inventoried_as: Option<EconomicResourceAddress>) -> Result<(), ErrorDetails> {
     match resouce_inventoried_as {
         Some(_) => match to_resource_inventoried_as {
             Some(_) => Ok(()),
-            None => Err("inventoried move EconomicEvent requires both source and destination inventory fields".into()),
+            None => Err(ErrorDetails::new(ErrorCode::MissingRequiredField, "inventoried move EconomicEvent requires both source and destination inventory fields")
+                .at_field("toResourceInventoriedAs")),
         },
         None => match to_resource_inventoried_as {
             None => Ok(()),
-            Some(_) => Err("non-inventoried move EconomicEvent must omit inventory fields".into()),
+            Some(_) => Err(ErrorDetails::new(ErrorCode::InvalidFieldValue, "non-inventoried move EconomicEvent must omit inventory fields")
+                .at_field("toResourceInventoriedAs")),
         },
     }
 }
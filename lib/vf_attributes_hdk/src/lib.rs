@@ -5,7 +5,38 @@ pub use chrono::{ FixedOffset, Utc, DateTime };
 pub use holo_hash::{ AgentPubKey, EntryHash, HeaderHash };
 pub use holochain_zome_types::timestamp::Timestamp;
 pub use hdk_type_serialization_macros::{RevisionHash, DnaAddressable};
-pub use hdk_semantic_indexes_zome_rpc::{ByHeader, ByAddress};
+pub use hdk_semantic_indexes_zome_rpc::{ByHeader, ByAddress, MergeRevisions};
+
+/// Query a record's state as it was at some point in time
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ByAddressAsOf<T> {
+    pub address: T,
+    pub as_of: Timestamp,
+}
+
+/// Compare two revisions of the same record
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ByRevisionPair {
+    pub from_revision_id: RevisionHash,
+    pub to_revision_id: RevisionHash,
+}
+
+/// How to treat events which would take a resource's inventory below zero
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum NegativeInventoryPolicy {
+    Allow,
+    Warn,
+    Reject,
+}
+
+impl Default for NegativeInventoryPolicy {
+    fn default() -> Self {
+        Self::Allow
+    }
+}
 
 simple_alias!(ActionId => String);
 
@@ -18,6 +49,117 @@ addressable_identifier!(UnitInternalAddress => EntryHash);
 
 addressable_identifier!(AgentAddress => AgentPubKey);
 
+/// Agents are not records, but semantic indexes require `EntryHash`-based identifiers.
+/// This wrapper retypes an agent's public key so that agents can be indexed as though they
+/// were records. It serializes identically to `AgentAddress`, so the two are interchangeable
+/// in I/O structs.
+///
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(from = "AgentAddress", into = "AgentAddress")]
+pub struct AgentRecordAddress(pub DnaHash, pub EntryHash);
+
+impl DnaAddressable<EntryHash> for AgentRecordAddress {
+    fn new(dna: DnaHash, identifier: EntryHash) -> Self {
+        Self(dna, identifier)
+    }
+}
+
+impl AsRef<EntryHash> for AgentRecordAddress {
+    fn as_ref(&self) -> &EntryHash {
+        &self.1
+    }
+}
+
+impl AsRef<DnaHash> for AgentRecordAddress {
+    fn as_ref(&self) -> &DnaHash {
+        &self.0
+    }
+}
+
+impl Into<Vec<u8>> for AgentRecordAddress {
+    fn into(self) -> Vec<u8> {
+        extern_id_to_bytes::<Self, EntryHash>(&self)
+    }
+}
+
+impl From<AgentAddress> for AgentRecordAddress {
+    fn from(a: AgentAddress) -> Self {
+        Self(a.0, EntryHash::from_raw_36(a.1.get_raw_36().to_vec()))
+    }
+}
+
+impl From<AgentRecordAddress> for AgentAddress {
+    fn from(a: AgentRecordAddress) -> Self {
+        Self(a.0, AgentPubKey::from_raw_36(a.1.get_raw_36().to_vec()))
+    }
+}
+
+/// A stored `in_scope_of` reference.
+///
+/// Entries written before scopes were resolved to agents stored `in_scope_of` as free strings.
+/// These cannot be resolved to agent identities, but are retained so that no data is lost when
+/// older records are updated.
+///
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[serde(untagged)]
+pub enum ScopeReference {
+    Agent(AgentAddress),
+    Legacy(String),
+}
+
+/// Determine the set of agents to index as being involved in a flow
+pub fn get_involved_agents(provider: &Option<AgentAddress>, receiver: &Option<AgentAddress>) -> Vec<AgentRecordAddress> {
+    let mut agents: Vec<AgentRecordAddress> = provider.iter()
+        .chain(receiver.iter())
+        .map(|agent| { agent.to_owned().into() })
+        .collect();
+    agents.dedup();
+    agents
+}
+
+/// Determine the set of agents to index as the scope of a record
+pub fn get_scope_agents(in_scope_of: &Option<Vec<ScopeReference>>) -> Vec<AgentRecordAddress> {
+    get_scoped_agents(in_scope_of).iter()
+        .flatten()
+        .map(|agent| { agent.to_owned().into() })
+        .collect()
+}
+
+/// Read the agents a record is in the scope of, omitting any legacy free-text scopes
+pub fn get_scoped_agents(in_scope_of: &Option<Vec<ScopeReference>>) -> Option<Vec<AgentAddress>> {
+    in_scope_of.as_ref().map(|scopes| {
+        scopes.iter()
+            .filter_map(|scope| match scope {
+                ScopeReference::Agent(agent) => Some(agent.to_owned()),
+                ScopeReference::Legacy(_) => None,
+            })
+            .collect()
+    })
+}
+
+/// Determine the stored scope for a record given a new set of agents.
+///
+/// Any legacy free-text scopes in the `previous` revision are carried forward, as they cannot be
+/// expressed (and so cannot be intentionally removed) through the agent-based API.
+///
+pub fn update_scope_references(previous: &Option<Vec<ScopeReference>>, agents: Option<Vec<AgentAddress>>) -> Option<Vec<ScopeReference>> {
+    let legacy: Vec<ScopeReference> = previous.iter()
+        .flatten()
+        .filter(|scope| matches!(scope, ScopeReference::Legacy(_)))
+        .cloned()
+        .collect();
+
+    if agents.is_none() && legacy.is_empty() {
+        return None;
+    }
+
+    Some(agents.into_iter()
+        .flatten()
+        .map(ScopeReference::Agent)
+        .chain(legacy)
+        .collect())
+}
+
 addressable_identifier!(EconomicEventAddress => EntryHash);
 addressable_identifier!(EconomicResourceAddress => EntryHash);
 addressable_identifier!(ProductBatchAddress => EntryHash);
@@ -0,0 +1,277 @@
+/**
+ * ValueFlows JSON-LD serialisation helpers
+ *
+ * Shared by record zomes to export their records as JSON-LD documents using
+ * the ValueFlows vocabulary, for consumption by other ValueFlows implementations.
+ *
+ * References between records are expressed as IRIs under the `hrea:` scheme,
+ * in the form `hrea:<DnaHash>/<identifier>`. These are stable for the lifetime
+ * of a record and unique across all DNAs in a network.
+ *
+ * Exports are paged, since a DNA may hold more records than can be returned by
+ * a single zome call. Each page is a complete JSON-LD document.
+ *
+ * @package Holo-REA
+ * @since   2026-10-19
+ */
+use hdk::prelude::*;
+use serde_json::{json, Map, Value};
+use chrono::{TimeZone, Utc};
+
+use hdk_records::{
+    RecordAPIResult,
+    local_indexes::RootIndexPage,
+};
+use hdk_relay_pagination::PageInfo;
+use vf_measurement::QuantityValue;
+use vf_attributes_hdk::{
+    ActionId, ExternalURL, UnitId,
+    AgentAddress, AgentRecordAddress, LocationAddress,
+    EconomicEventAddress, EconomicResourceAddress, ProductBatchAddress, ProcessAddress,
+    CommitmentAddress, FulfillmentAddress, IntentAddress, SatisfactionAddress,
+    PlanAddress, AgreementAddress,
+    ResourceSpecificationAddress, ProcessSpecificationAddress,
+    ProposedIntentAddress, ProposalAddress, ProposedToAddress,
+};
+
+pub const VF_NAMESPACE: &str = "https://w3id.org/valueflows#";
+pub const OM2_NAMESPACE: &str = "http://www.ontology-of-units-of-measure.org/resource/om-2/";
+pub const IRI_SCHEME: &str = "hrea";
+
+/// Number of records returned per page where the caller does not specify a limit
+pub const DEFAULT_EXPORT_PAGE_SIZE: usize = 100;
+
+/// JSON-LD context applied to all exported documents
+///
+pub fn vf_context() -> Value {
+    json!({
+        "@vocab": VF_NAMESPACE,
+        "vf": VF_NAMESPACE,
+        "om2": OM2_NAMESPACE,
+        "rdfs": "http://www.w3.org/2000/01/rdf-schema#",
+        "xsd": "http://www.w3.org/2001/XMLSchema#",
+        "hasNumericalValue": { "@id": "om2:hasNumericalValue", "@type": "xsd:double" },
+        "hasUnit": { "@id": "om2:hasUnit" },
+        "label": { "@id": "rdfs:label" },
+        "symbol": { "@id": "om2:symbol" },
+        "hasPointInTime": { "@type": "xsd:dateTime" },
+        "hasBeginning": { "@type": "xsd:dateTime" },
+        "hasEnd": { "@type": "xsd:dateTime" },
+        "due": { "@type": "xsd:dateTime" },
+        "created": { "@type": "xsd:dateTime" },
+        // pagination metadata is for the client only and is not part of the graph
+        "pageInfo": null,
+    })
+}
+
+//---------------- IDENTIFIERS ----------------
+
+/// Types which can be expressed as an IRI in exported documents
+pub trait ToIri {
+    fn to_iri(&self) -> String;
+}
+
+macro_rules! dna_scoped_iri {
+    ( $( $t:ty ),* ) => {
+        $(
+            impl ToIri for $t {
+                fn to_iri(&self) -> String {
+                    format!("{}:{}/{}", IRI_SCHEME, self.0, self.1)
+                }
+            }
+        )*
+    };
+}
+
+dna_scoped_iri!(
+    AgentAddress, AgentRecordAddress, LocationAddress,
+    EconomicEventAddress, EconomicResourceAddress, ProductBatchAddress, ProcessAddress,
+    CommitmentAddress, FulfillmentAddress, IntentAddress, SatisfactionAddress,
+    PlanAddress, AgreementAddress,
+    ResourceSpecificationAddress, ProcessSpecificationAddress,
+    ProposedIntentAddress, ProposalAddress, ProposedToAddress
+);
+
+impl ToIri for UnitId {
+    fn to_iri(&self) -> String {
+        format!("{}:{}/{}", IRI_SCHEME, self.0, encode_iri_component(&self.1))
+    }
+}
+
+impl ToIri for ExternalURL {
+    fn to_iri(&self) -> String {
+        self.as_ref().to_string()
+    }
+}
+
+/// Percent-encode any characters of a manually assigned identifier which are not
+/// permitted in an IRI path segment.
+///
+fn encode_iri_component(id: &str) -> String {
+    id.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+//---------------- NODES ----------------
+
+/// A single node in an exported JSON-LD graph
+///
+/// Constructed via chained builder methods, which omit any empty values.
+///
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(transparent)]
+pub struct JsonLdNode(Map<String, Value>);
+
+impl JsonLdNode {
+    pub fn new<I: ToIri>(node_type: &str, id: &I) -> Self {
+        let mut node = Map::new();
+        node.insert("@id".to_string(), Value::String(id.to_iri()));
+        node.insert("@type".to_string(), Value::String(node_type.to_string()));
+        Self(node)
+    }
+
+    /// Assign a literal value
+    pub fn literal<V: Serialize>(mut self, key: &str, value: &V) -> Self {
+        if let Ok(v) = serde_json::to_value(value) {
+            self.0.insert(key.to_string(), v);
+        }
+        self
+    }
+
+    /// Assign an optional literal value
+    pub fn field<V: Serialize>(self, key: &str, value: &Option<V>) -> Self {
+        match value {
+            Some(v) => self.literal(key, v),
+            None => self,
+        }
+    }
+
+    /// Assign an optional Holochain timestamp, as an RFC 3339 date
+    pub fn timestamp(self, key: &str, value: &Option<Timestamp>) -> Self {
+        self.field(key, &value.as_ref().and_then(timestamp_to_rfc3339))
+    }
+
+    /// Assign a reference to another node
+    pub fn reference<I: ToIri>(mut self, key: &str, value: &Option<I>) -> Self {
+        if let Some(v) = value {
+            self.0.insert(key.to_string(), iri_node(v));
+        }
+        self
+    }
+
+    /// Assign a set of references to other nodes
+    pub fn references<I: ToIri>(mut self, key: &str, values: &[I]) -> Self {
+        if !values.is_empty() {
+            self.0.insert(key.to_string(), Value::Array(values.iter().map(iri_node).collect()));
+        }
+        self
+    }
+
+    /// Assign an optional set of references to other nodes
+    pub fn reference_list<I: ToIri>(self, key: &str, values: &Option<Vec<I>>) -> Self {
+        match values {
+            Some(v) => self.references(key, v.as_slice()),
+            None => self,
+        }
+    }
+
+    /// Assign an `om2:Measure`
+    pub fn quantity(mut self, key: &str, value: &Option<QuantityValue>) -> Self {
+        if let Some(q) = value {
+            let mut measure = Map::new();
+            measure.insert("@type".to_string(), Value::String("om2:Measure".to_string()));
+            measure.insert("hasNumericalValue".to_string(), json!(q.get_numerical_value()));
+            if let Some(unit) = q.get_unit() {
+                measure.insert("hasUnit".to_string(), iri_node(&unit));
+            }
+            self.0.insert(key.to_string(), Value::Object(measure));
+        }
+        self
+    }
+
+    /// Assign a reference to one of the ValueFlows action individuals
+    pub fn action(mut self, key: &str, value: &ActionId) -> Self {
+        self.0.insert(key.to_string(), json!({ "@id": format!("vf:{}", value.as_ref()) }));
+        self
+    }
+}
+
+fn iri_node<I: ToIri>(id: &I) -> Value {
+    json!({ "@id": id.to_iri() })
+}
+
+fn timestamp_to_rfc3339(t: &Timestamp) -> Option<String> {
+    let micros = t.as_micros();
+    Utc.timestamp_opt(micros.div_euclid(1_000_000), (micros.rem_euclid(1_000_000) * 1_000) as u32)
+        .single()
+        .map(|d| { d.to_rfc3339() })
+}
+
+//---------------- PAGES ----------------
+
+#[derive(Clone, Serialize, Deserialize, SerializedBytes, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportParams {
+    #[serde(default)]
+    pub after: Option<String>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+impl ExportParams {
+    pub fn get_limit(&self) -> usize {
+        self.limit.unwrap_or(DEFAULT_EXPORT_PAGE_SIZE)
+    }
+}
+
+/// A page of exported records, as a JSON-LD document.
+///
+/// Further pages may be requested by passing `pageInfo.endCursor` as the `after`
+/// parameter of the next export call, until `pageInfo.hasNextPage` is `false`.
+///
+#[derive(Clone, Serialize, Deserialize, SerializedBytes, Debug)]
+pub struct ExportPage {
+    #[serde(rename = "@context")]
+    pub context: Value,
+    #[serde(rename = "@graph")]
+    pub graph: Vec<JsonLdNode>,
+    #[serde(rename = "pageInfo")]
+    pub page_info: PageInfo,
+}
+
+/// Build an export document from a page of records read from some root index,
+/// converting each record with `to_node`.
+///
+/// Records which have been deleted or cannot be read are omitted, as for list queries.
+/// Pages may therefore hold fewer than `limit` records even where more records follow.
+///
+pub fn build_export_page<T, F>(
+    page: RootIndexPage<RecordAPIResult<T>>,
+    limit: usize,
+    mut to_node: F,
+) -> RecordAPIResult<ExportPage>
+    where F: FnMut(T) -> RecordAPIResult<JsonLdNode>,
+{
+    let graph = page.results.into_iter()
+        .filter_map(Result::ok)
+        .map(|record| { to_node(record) })
+        .filter_map(Result::ok) // :TODO: handle internal errors in record construction (eg. corrupted DHT links)
+        .collect();
+
+    Ok(ExportPage {
+        context: vf_context(),
+        graph,
+        page_info: PageInfo {
+            start_cursor: page.start_cursor,
+            end_cursor: page.end_cursor,
+            has_previous_page: page.has_previous_page,
+            has_next_page: page.has_next_page,
+            total_count: Some(page.total_count),
+            page_limit: Some(limit),
+        },
+    })
+}
@@ -216,19 +216,84 @@ const encodeFields = (args: any): any => {
 // explicit type-loss at the boundary
 export type BoundZomeFn = (args: any) => any;
 
+//----------------------------------------------------------------------------------------------------------------------
+// Zome error handling
+//----------------------------------------------------------------------------------------------------------------------
+
+const ERROR_DETAILS_PATTERN = /<hREA-error>([\s\S]*?)<\/hREA-error>/
+
+export interface ErrorDetails {
+  code: string,
+  message: string,
+  field?: string,
+  details?: { [k: string]: string },
+}
+
+/**
+ * Error thrown for failed zome calls. Where the zome reported structured error details,
+ * these are exposed as `code`, `field` & `details` and passed through to GraphQL
+ * clients as error `extensions`.
+ */
+export class ZomeCallError extends Error {
+  code: string
+  field?: string
+  details: { [k: string]: string }
+  extensions: { [k: string]: any }
+
+  constructor (details: ErrorDetails) {
+    super(details.message)
+    this.name = 'ZomeCallError'
+    this.code = details.code
+    this.field = details.field
+    this.details = details.details || {}
+    this.extensions = { code: this.code, field: this.field, details: this.details }
+  }
+}
+
+/**
+ * Locate structured error details within the message of a conductor error.
+ * Details nested within other errors may have been escaped one or more times.
+ */
+export function decodeErrorDetails (message: string): ErrorDetails | null {
+  const match = ERROR_DETAILS_PATTERN.exec(message)
+  if (!match) return null
+
+  let json = match[1]
+  for (;;) {
+    try {
+      return JSON.parse(json)
+    } catch (e) {
+      if (json.indexOf('\\"') === -1) return null
+      json = json.replace(/\\"/g, '"').replace(/\\\\/g, '\\')
+    }
+  }
+}
+
+function toZomeCallError (e: any): Error {
+  const message = (e && e.data && e.data.data) || (e && e.message) || String(e)
+  const details = decodeErrorDetails(typeof message === 'string' ? message : JSON.stringify(message))
+  if (!details) return e
+  return new ZomeCallError(details)
+}
+
 /**
  * Higher-order function to generate async functions for calling zome RPC methods
  */
 const zomeFunction = (socketURI: string, cell_id: CellId, zome_name: string, fn_name: string): BoundZomeFn => async (args) => {
   const { callZome } = await getConnection(socketURI)
-  const res = await callZome({
-    cap_secret: null, // :TODO:
-    cell_id,
-    zome_name,
-    fn_name,
-    provenance: cell_id[1],
-    payload: encodeFields(args),
-  })
+  let res
+  try {
+    res = await callZome({
+      cap_secret: null, // :TODO:
+      cell_id,
+      zome_name,
+      fn_name,
+      provenance: cell_id[1],
+      payload: encodeFields(args),
+    })
+  } catch (e) {
+    throw toZomeCallError(e)
+  }
   decodeFields(res)
   return res
 }
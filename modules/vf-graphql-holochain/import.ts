@@ -0,0 +1,738 @@
+/**
+ * ValueFlows JSON-LD & Turtle importer
+ *
+ * Reads ValueFlows records serialised as JSON-LD or Turtle- by other REA tools, or by the
+ * `export_*` zome methods of another hREA network- and recreates them via the zome APIs of
+ * the configured DNAs.
+ *
+ * Records are created in dependency order: units & specifications first, then agreements &
+ * processes, then commitments & intents, and finally economic events. Economic resources are
+ * created as the inventory effect of the earliest event which affects them, so that the
+ * remaining events update their quantities as they would have in the source system.
+ *
+ * External IRIs are remapped to the identifiers of the newly created records, and a report
+ * of the mapping (along with any failures & warnings) is returned to the caller.
+ *
+ * @package: HoloREA
+ * @since:   2026-10-19
+ */
+
+import * as jsonld from 'jsonld'
+import { Parser } from 'n3'
+import { Base64 } from 'js-base64'
+
+import { DNAIdMappings } from './types'
+import { mapZomeFn, decodeErrorDetails, ZomeCallError, BoundZomeFn } from './connection'
+
+//----------------------------------------------------------------------------------------------------------------------
+// Public API
+//----------------------------------------------------------------------------------------------------------------------
+
+export type ImportFormat = 'application/ld+json' | 'text/turtle'
+
+// A JSON-LD document (as a string or parsed object) or Turtle string.
+// Arrays are treated as multiple documents, eg. successive pages of an hREA export.
+export type ImportSource = string | object
+
+export interface ImportOptions {
+  // Mapping of DNA identifiers to runtime `CellId`s to create records in
+  dnaConfig: DNAIdMappings,
+  // Holochain conductor URI to connect to
+  conductorUri: string,
+  // Source format. If not specified, strings beginning with `{` or `[` are read as JSON-LD, anything else as Turtle.
+  format?: ImportFormat,
+  // Mapping of agent IRIs in the source data to agent IDs in the destination network
+  agents?: { [iri: string]: string },
+  // Agent ID to assign to any agent reference not found in `agents`
+  defaultAgent?: string,
+}
+
+export interface ImportedRecord {
+  iri: string,
+  type: string,
+  id: string,
+}
+
+export interface FailedRecord {
+  iri: string,
+  type: string,
+  reason: string,
+  code?: string,
+}
+
+export interface ImportWarning {
+  iri: string,
+  message: string,
+}
+
+export interface ImportReport {
+  // source IRI => ID of the record created for it
+  mapping: { [iri: string]: string },
+  created: ImportedRecord[],
+  failed: FailedRecord[],
+  warnings: ImportWarning[],
+}
+
+/**
+ * Import ValueFlows records from one or more JSON-LD or Turtle documents.
+ *
+ * Records which fail to import (and any records which depend on them) are listed in
+ * the `failed` section of the report; all other records are still imported.
+ */
+export async function importValueFlows (source: ImportSource | ImportSource[], options: ImportOptions): Promise<ImportReport> {
+  const docs = Array.isArray(source) ? source : [source]
+  const quads: Quad[] = []
+  for (let i = 0; i < docs.length; i++) {
+    quads.push(...relabelBlankNodes(await parseDocument(docs[i], options.format), i))
+  }
+
+  const importer = new Importer(readGraph(quads), options)
+  return importer.run()
+}
+
+//----------------------------------------------------------------------------------------------------------------------
+// Parsing
+//----------------------------------------------------------------------------------------------------------------------
+
+const VF = 'https://w3id.org/valueflows#'
+const OM2 = 'http://www.ontology-of-units-of-measure.org/resource/om-2/'
+const RDF_TYPE = 'http://www.w3.org/1999/02/22-rdf-syntax-ns#type'
+const RDFS_LABEL = 'http://www.w3.org/2000/01/rdf-schema#label'
+
+const HREA_IRI_PATTERN = /^hrea:([^/]+)\/(.+)$/
+
+// subset of the RDF/JS data model, common to the output of both parsers
+interface Term {
+  termType: string,
+  value: string,
+}
+
+interface Quad {
+  subject: Term,
+  predicate: Term,
+  object: Term,
+  graph: Term,
+}
+
+interface SourceNode {
+  key: string,
+  types: string[],
+  props: { [property: string]: Term[] },
+  order: number,
+}
+
+async function parseDocument (doc: ImportSource, format?: ImportFormat): Promise<Quad[]> {
+  if (typeof doc === 'string') {
+    const isJSON = format ? format === 'application/ld+json' : /^\s*[[{]/.test(doc)
+    if (!isJSON) {
+      return new Parser({ format: 'text/turtle' }).parse(doc) as any as Quad[]
+    }
+    doc = JSON.parse(doc)
+  }
+  return (await jsonld.toRDF(doc as any)) as any as Quad[]
+}
+
+// blank node labels are only unique within a single document
+function relabelBlankNodes (quads: Quad[], docIdx: number): Quad[] {
+  const relabel = (t: Term): Term => (t.termType === 'BlankNode'
+    ? { termType: 'BlankNode', value: `_:d${docIdx}.${t.value.replace(/^_:/, '')}` }
+    : t)
+
+  return quads.map(q => ({ ...q, subject: relabel(q.subject), object: relabel(q.object) }))
+}
+
+// Convert ValueFlows & OM2 predicates to plain property names. Other vocabularies are ignored.
+function propertyName (predicate: string): string | null {
+  if (predicate === RDFS_LABEL) return 'label'
+  if (predicate.startsWith(VF)) return predicate.substr(VF.length)
+  if (predicate.startsWith(OM2)) return predicate.substr(OM2.length)
+  return null
+}
+
+function readGraph (quads: Quad[]): Map<string, SourceNode> {
+  const nodes = new Map<string, SourceNode>()
+
+  quads
+    .filter(q => q.graph.termType === 'DefaultGraph')
+    .forEach(q => {
+      const key = q.subject.value
+      let node = nodes.get(key)
+      if (!node) {
+        node = { key, types: [], props: {}, order: nodes.size }
+        nodes.set(key, node)
+      }
+
+      if (q.predicate.value === RDF_TYPE) {
+        node.types.push(q.object.value)
+        return
+      }
+      const prop = propertyName(q.predicate.value)
+      if (prop) {
+        node.props[prop] = (node.props[prop] || []).concat([q.object])
+      }
+    })
+
+  return nodes
+}
+
+function localName (iri: string): string {
+  return decodeURIComponent(iri.replace(/^.*[#/:]/, ''))
+}
+
+//----------------------------------------------------------------------------------------------------------------------
+// Record definitions
+//----------------------------------------------------------------------------------------------------------------------
+
+type FieldKind =
+  'string' | 'boolean' | 'dateTime' | 'timestamp' | 'url' | 'urls' |
+  'action' | 'quantity' | 'unit' | 'agent' | 'agents' | 'record'
+
+// [source property, request field, kind]
+type FieldDef = [string, string, FieldKind]
+
+interface RecordDef {
+  // import order. Records in lower tiers are created first where dependencies allow.
+  tier: number,
+  instance: string,
+  zome: string,
+  fn: string,
+  requestField: string,
+  responseField: string,
+  fields: FieldDef[],
+  required: string[],
+}
+
+const PLANNED_FLOW_FIELDS: FieldDef[] = [
+  ['action', 'action', 'action'],
+  ['note', 'note', 'string'],
+  ['inputOf', 'inputOf', 'record'],
+  ['outputOf', 'outputOf', 'record'],
+  ['provider', 'provider', 'agent'],
+  ['receiver', 'receiver', 'agent'],
+  ['resourceInventoriedAs', 'resourceInventoriedAs', 'record'],
+  ['resourceClassifiedAs', 'resourceClassifiedAs', 'urls'],
+  ['resourceConformsTo', 'resourceConformsTo', 'record'],
+  ['resourceQuantity', 'resourceQuantity', 'quantity'],
+  ['effortQuantity', 'effortQuantity', 'quantity'],
+  ['hasBeginning', 'hasBeginning', 'dateTime'],
+  ['hasEnd', 'hasEnd', 'dateTime'],
+  ['hasPointInTime', 'hasPointInTime', 'dateTime'],
+  ['due', 'due', 'dateTime'],
+  ['atLocation', 'atLocation', 'record'],
+  ['agreedIn', 'agreedIn', 'url'],
+  ['finished', 'finished', 'boolean'],
+  ['inScopeOf', 'inScopeOf', 'agents'],
+]
+
+const RECORD_DEFS: { [type: string]: RecordDef } = {
+  [`${OM2}Unit`]: {
+    tier: 0, instance: 'specification', zome: 'unit', fn: 'create_unit', requestField: 'unit', responseField: 'unit',
+    fields: [
+      ['label', 'label', 'string'],
+      ['symbol', 'symbol', 'string'],
+    ],
+    required: ['label', 'symbol'],
+  },
+  [`${VF}ResourceSpecification`]: {
+    tier: 1, instance: 'specification', zome: 'resource_specification', fn: 'create_resource_specification', requestField: 'resourceSpecification', responseField: 'resourceSpecification',
+    fields: [
+      ['name', 'name', 'string'],
+      ['image', 'image', 'url'],
+      ['defaultUnitOfEffort', 'defaultUnitOfEffort', 'unit'],
+      ['note', 'note', 'string'],
+    ],
+    required: ['name'],
+  },
+  [`${VF}ProcessSpecification`]: {
+    tier: 1, instance: 'specification', zome: 'process_specification', fn: 'create_process_specification', requestField: 'processSpecification', responseField: 'processSpecification',
+    fields: [
+      ['name', 'name', 'string'],
+      ['note', 'note', 'string'],
+    ],
+    required: ['name'],
+  },
+  [`${VF}Agreement`]: {
+    tier: 2, instance: 'agreement', zome: 'agreement', fn: 'create_agreement', requestField: 'agreement', responseField: 'agreement',
+    fields: [
+      ['name', 'name', 'string'],
+      ['created', 'created', 'dateTime'],
+      ['note', 'note', 'string'],
+    ],
+    required: [],
+  },
+  [`${VF}Process`]: {
+    tier: 3, instance: 'observation', zome: 'process', fn: 'create_process', requestField: 'process', responseField: 'process',
+    fields: [
+      ['name', 'name', 'string'],
+      ['hasBeginning', 'hasBeginning', 'timestamp'],
+      ['hasEnd', 'hasEnd', 'timestamp'],
+      ['classifiedAs', 'classifiedAs', 'urls'],
+      ['basedOn', 'basedOn', 'record'],
+      ['plannedWithin', 'plannedWithin', 'record'],
+      ['finished', 'finished', 'boolean'],
+      ['inScopeOf', 'inScopeOf', 'agents'],
+      ['note', 'note', 'string'],
+    ],
+    required: ['name'],
+  },
+  [`${VF}Commitment`]: {
+    tier: 4, instance: 'planning', zome: 'commitment', fn: 'create_commitment', requestField: 'commitment', responseField: 'commitment',
+    fields: PLANNED_FLOW_FIELDS.concat([
+      ['plannedWithin', 'plan', 'record'],
+      ['clauseOf', 'clauseOf', 'record'],
+      ['independentDemandOf', 'independentDemandOf', 'record'],
+    ]),
+    required: ['action', 'provider', 'receiver'],
+  },
+  [`${VF}Intent`]: {
+    tier: 4, instance: 'planning', zome: 'intent', fn: 'create_intent', requestField: 'intent', responseField: 'intent',
+    fields: PLANNED_FLOW_FIELDS.concat([
+      ['image', 'image', 'url'],
+      ['availableQuantity', 'availableQuantity', 'quantity'],
+    ]),
+    required: ['action'],
+  },
+  [`${VF}EconomicEvent`]: {
+    tier: 5, instance: 'observation', zome: 'economic_event', fn: 'create_economic_event', requestField: 'event', responseField: 'economicEvent',
+    fields: PLANNED_FLOW_FIELDS.filter(([prop]) => prop !== 'due' && prop !== 'finished').concat([
+      ['toResourceInventoriedAs', 'toResourceInventoriedAs', 'record'],
+      ['realizationOf', 'realizationOf', 'record'],
+      ['triggeredBy', 'triggeredBy', 'record'],
+    ]),
+    required: ['action', 'provider', 'receiver'],
+  },
+}
+
+const RESOURCE_TYPE = `${VF}EconomicResource`
+
+// fields of an EconomicResource which can be set when creating it via an event's `newInventoriedResource`
+const RESOURCE_FIELDS: FieldDef[] = [
+  ['conformsTo', 'conformsTo', 'record'],
+  ['trackingIdentifier', 'trackingIdentifier', 'string'],
+  ['lot', 'lot', 'record'],
+  ['image', 'image', 'url'],
+  ['containedIn', 'containedIn', 'record'],
+  ['currentLocation', 'currentLocation', 'record'],
+  ['note', 'note', 'string'],
+]
+
+// quantities compared against the destination network once all events have been imported
+const RESOURCE_QUANTITY_FIELDS = ['accountingQuantity', 'onhandQuantity']
+
+//----------------------------------------------------------------------------------------------------------------------
+// Import process
+//----------------------------------------------------------------------------------------------------------------------
+
+class ImportError extends Error {
+  code: string
+
+  constructor (message: string, code: string) {
+    super(message)
+    this.code = code
+  }
+}
+
+class Importer {
+  nodes: Map<string, SourceNode>
+  options: ImportOptions
+  report: ImportReport
+  localDNAs: Set<string>
+  // record nodes to be imported, and the type definition each is to be created with
+  records: Map<string, RecordDef>
+  // resource IRI => key of the event which creates it
+  resourceCreators: Map<string, string>
+  failedKeys: Set<string>
+
+  constructor (nodes: Map<string, SourceNode>, options: ImportOptions) {
+    this.nodes = nodes
+    this.options = options
+    this.report = { mapping: {}, created: [], failed: [], warnings: [] }
+    this.localDNAs = new Set(Object.values(options.dnaConfig || {})
+      .filter(cell => !!cell)
+      .map(cell => `u${Base64.fromUint8Array(cell[0], true)}`))
+    this.records = new Map()
+    this.resourceCreators = new Map()
+    this.failedKeys = new Set()
+
+    nodes.forEach(node => {
+      const def = node.types.map(t => RECORD_DEFS[t]).find(d => !!d)
+      if (def) this.records.set(node.key, def)
+    })
+    this.assignResourceCreators()
+  }
+
+  async run (): Promise<ImportReport> {
+    const pending = new Map<string, Set<string>>()
+    this.records.forEach((def, key) => {
+      pending.set(key, this.getDependencies(key))
+    })
+
+    while (pending.size) {
+      const ready = Array.from(pending.keys())
+        .filter(key => Array.from(pending.get(key) as Set<string>).every(dep => this.isSettled(dep)))
+
+      if (!ready.length) {
+        pending.forEach((deps, key) => {
+          this.fail(key, 'record is part of a reference cycle and cannot be created', 'REFERENCE_CYCLE')
+        })
+        break
+      }
+
+      ready.sort((a, b) => this.compareImportOrder(a, b))
+      const next = ready[0]
+      pending.delete(next)
+      await this.importRecord(next)
+    }
+
+    await this.verifyInventory()
+
+    return this.report
+  }
+
+  //--------------------------------------------------------------------------------------------------------------------
+  // Ordering
+
+  // Each resource is created by the earliest event recording it as `resourceInventoriedAs`.
+  assignResourceCreators () {
+    const events = Array.from(this.records.keys())
+      .filter(key => (this.records.get(key) as RecordDef).responseField === 'economicEvent')
+      .sort((a, b) => this.compareImportOrder(a, b))
+
+    events.forEach(key => {
+      const resource = this.iri(this.nodes.get(key) as SourceNode, 'resourceInventoriedAs')
+      if (resource && this.isResource(resource) && !this.resourceCreators.has(resource)) {
+        this.resourceCreators.set(resource, key)
+      }
+    })
+
+    this.nodes.forEach(node => {
+      if (this.isResource(node.key) && !this.resourceCreators.has(node.key)) {
+        this.fail(node.key, 'no economic event in the import creates this resource', 'MISSING_INVENTORY_EFFECT')
+      }
+    })
+  }
+
+  getDependencies (key: string): Set<string> {
+    const deps = new Set<string>()
+    const visit = (nodeKey: string, seen: Set<string>) => {
+      const node = this.nodes.get(nodeKey)
+      if (!node || seen.has(nodeKey)) return
+      seen.add(nodeKey)
+
+      Object.values(node.props).forEach(terms => terms.forEach(t => {
+        if (t.termType === 'Literal') return
+        const dep = this.dependencyFor(t.value, key)
+        if (dep) deps.add(dep)
+        // recurse into embedded nodes (quantities) & the fields used to create resources
+        if (t.termType === 'BlankNode' || (this.isResource(t.value) && this.resourceCreators.get(t.value) === key)) {
+          visit(t.value, seen)
+        }
+      }))
+    }
+    visit(key, new Set())
+    return deps
+  }
+
+  // determine the record which must be created before `forKey` in order to reference `iri`
+  dependencyFor (iri: string, forKey: string): string | null {
+    if (this.isResource(iri)) {
+      const creator = this.resourceCreators.get(iri)
+      if (creator === forKey) return null
+      return creator || iri
+    }
+    if (this.records.has(iri) && iri !== forKey) return iri
+    return null
+  }
+
+  isSettled (key: string): boolean {
+    return this.failedKeys.has(key) || this.report.mapping[key] !== undefined
+  }
+
+  compareImportOrder (a: string, b: string): number {
+    const nodeA = this.nodes.get(a) as SourceNode
+    const nodeB = this.nodes.get(b) as SourceNode
+    const tierA = (this.records.get(a) as RecordDef).tier
+    const tierB = (this.records.get(b) as RecordDef).tier
+    if (tierA !== tierB) return tierA - tierB
+
+    const timeA = this.eventTime(nodeA)
+    const timeB = this.eventTime(nodeB)
+    if (timeA !== timeB) return timeA - timeB
+
+    return nodeA.order - nodeB.order
+  }
+
+  eventTime (node: SourceNode): number {
+    const time = this.literal(node, 'hasPointInTime') || this.literal(node, 'hasBeginning') || this.literal(node, 'hasEnd')
+    const parsed = time ? Date.parse(time) : NaN
+    return isNaN(parsed) ? Infinity : parsed
+  }
+
+  //--------------------------------------------------------------------------------------------------------------------
+  // Record creation
+
+  async importRecord (key: string) {
+    const node = this.nodes.get(key) as SourceNode
+    const def = this.records.get(key) as RecordDef
+    const type = this.typeOf(key)
+
+    if (!(this.options.dnaConfig as any)[def.instance]) {
+      this.fail(key, `no '${def.instance}' DNA is configured to create this record in`, 'DNA_NOT_CONFIGURED')
+      return
+    }
+
+    // events which are the first inventory effect of some resource create it as `newInventoriedResource`
+    const inventoried = this.iri(node, 'resourceInventoriedAs')
+    const resourceKey = inventoried && this.resourceCreators.get(inventoried) === key ? inventoried : undefined
+
+    let request: { [k: string]: any }
+    try {
+      const fields = resourceKey ? def.fields.filter(([prop]) => prop !== 'resourceInventoriedAs') : def.fields
+      request = { [def.requestField]: await this.buildFields(node, fields, def.required) }
+      if (resourceKey) {
+        request.newInventoriedResource = await this.buildFields(this.nodes.get(resourceKey) as SourceNode, RESOURCE_FIELDS, [])
+      }
+    } catch (e: any) {
+      this.fail(key, e.message, e.code)
+      return
+    }
+
+    let response
+    try {
+      response = await this.zomeFn(def)(request)
+    } catch (e: any) {
+      const details = e instanceof ZomeCallError ? e : decodeErrorDetails(e.message || String(e))
+      this.fail(key, e.message || String(e), details ? details.code : undefined)
+      return
+    }
+
+    this.created(key, type, response[def.responseField].id)
+    if (resourceKey && response.economicResource) {
+      this.created(resourceKey, this.typeOf(resourceKey), response.economicResource.id)
+    }
+  }
+
+  async buildFields (node: SourceNode, fields: FieldDef[], required: string[]): Promise<{ [k: string]: any }> {
+    const request: { [k: string]: any } = {}
+    for (const [prop, field, kind] of fields) {
+      const terms = node.props[prop]
+      if (!terms || !terms.length) continue
+      const value = await this.readValue(node, prop, terms, kind)
+      if (value !== undefined) request[field] = value
+    }
+
+    const missing = required.filter(field => request[field] === undefined)
+    if (missing.length) {
+      throw new ImportError(`missing required field(s): ${missing.join(', ')}`, 'MISSING_REQUIRED_FIELD')
+    }
+    return request
+  }
+
+  async readValue (node: SourceNode, prop: string, terms: Term[], kind: FieldKind): Promise<any> {
+    const value = terms[0].value
+    switch (kind) {
+      case 'string':
+        return value
+      case 'boolean':
+        return value === 'true' || value === '1'
+      case 'dateTime':
+        return value
+      case 'timestamp': {
+        const time = Date.parse(value)
+        if (isNaN(time)) throw new ImportError(`invalid date in ${prop}: ${value}`, 'INVALID_FIELD_VALUE')
+        return time * 1000  // Holochain timestamps are in microseconds
+      }
+      case 'url':
+        return value
+      case 'urls':
+        return terms.map(t => t.value)
+      case 'action':
+        return localName(value)
+      case 'quantity':
+        return this.readQuantity(node, value)
+      case 'unit':
+        return this.resolveUnit(node.key, value)
+      case 'agent':
+        return this.resolveAgent(node.key, value)
+      case 'agents':
+        return terms.map(t => this.resolveAgent(node.key, t.value))
+      case 'record':
+        return this.resolveRecord(node.key, value)
+    }
+  }
+
+  async readQuantity (parent: SourceNode, key: string) {
+    const measure = this.nodes.get(key)
+    const numericValue = measure ? this.literal(measure, 'hasNumericalValue') : undefined
+    if (!measure || numericValue === undefined || isNaN(parseFloat(numericValue))) {
+      throw new ImportError(`invalid quantity ${key}`, 'INVALID_FIELD_VALUE')
+    }
+
+    const quantity: { [k: string]: any } = { hasNumericalValue: parseFloat(numericValue) }
+    const unit = this.iri(measure, 'hasUnit')
+    if (unit) quantity.hasUnit = await this.resolveUnit(parent.key, unit)
+    return quantity
+  }
+
+  //--------------------------------------------------------------------------------------------------------------------
+  // Reference resolution
+
+  resolveRecord (fromKey: string, iri: string): string | undefined {
+    if (this.report.mapping[iri] !== undefined) return this.report.mapping[iri]
+    this.assertNotFailed(iri)
+
+    const local = this.localId(iri)
+    if (local) return local
+
+    this.warn(fromKey, `reference to ${iri} is not part of this import and was omitted`)
+    return undefined
+  }
+
+  async resolveUnit (fromKey: string, iri: string): Promise<string> {
+    if (this.report.mapping[iri] !== undefined) return this.report.mapping[iri]
+    this.assertNotFailed(iri)
+
+    const local = this.localId(iri)
+    if (local) return local
+
+    // units referenced by IRI only (eg. from the OM2 vocabulary) reuse any existing unit with the same symbol
+    const symbol = localName(iri)
+    const existing = await this.readUnit(symbol)
+    if (existing) {
+      this.report.mapping[iri] = existing
+      this.warn(fromKey, `unit ${iri} is not part of this import and was mapped to existing unit '${symbol}'`)
+      return existing
+    }
+
+    // ...or are created on demand
+    let response
+    try {
+      response = await this.zomeFn(RECORD_DEFS[`${OM2}Unit`])({ unit: { label: symbol, symbol } })
+    } catch (e: any) {
+      throw new ImportError(`unit ${iri} could not be created: ${e.message || String(e)}`, 'DEPENDENCY_FAILED')
+    }
+    this.created(iri, 'Unit', response.unit.id)
+    this.warn(fromKey, `unit ${iri} is not part of this import and was created as '${symbol}'`)
+    return response.unit.id
+  }
+
+  // unit IDs are derived from their symbol, so an existing unit can be looked up directly
+  async readUnit (symbol: string): Promise<string | undefined> {
+    const cell = (this.options.dnaConfig as any).specification
+    if (!cell) return undefined
+
+    const specDna = `u${Base64.fromUint8Array(cell[0], true)}`
+    try {
+      const response = await mapZomeFn(this.options.dnaConfig, this.options.conductorUri, 'specification', 'unit', 'get_unit')({ id: `${symbol}:${specDna}` })
+      return response.unit.id
+    } catch (e) {
+      return undefined
+    }
+  }
+
+  resolveAgent (fromKey: string, iri: string): string {
+    const { agents = {}, defaultAgent } = this.options
+    if (agents[iri]) return agents[iri]
+
+    const local = this.localId(iri)
+    if (local) return local
+
+    if (defaultAgent) return defaultAgent
+    throw new ImportError(`no agent mapping provided for ${iri}`, 'UNMAPPED_AGENT')
+  }
+
+  assertNotFailed (iri: string) {
+    const key = this.isResource(iri) ? iri : (this.records.has(iri) ? iri : null)
+    if (key) {
+      throw new ImportError(`depends on ${iri}, which could not be imported`, 'DEPENDENCY_FAILED')
+    }
+  }
+
+  // hREA IRIs referencing records in the DNAs being imported into are used as-is
+  localId (iri: string): string | undefined {
+    const match = HREA_IRI_PATTERN.exec(iri)
+    if (!match || !this.localDNAs.has(match[1])) return undefined
+    return `${decodeURIComponent(match[2])}:${match[1]}`
+  }
+
+  //--------------------------------------------------------------------------------------------------------------------
+  // Verification
+
+  // compare resource quantities resulting from imported events against those in the source data
+  async verifyInventory () {
+    const readResource = mapZomeFn(this.options.dnaConfig, this.options.conductorUri, 'observation', 'economic_resource', 'get_economic_resource')
+
+    for (const imported of this.report.created.filter(r => r.type === 'EconomicResource')) {
+      const source = this.nodes.get(imported.iri) as SourceNode
+      let resource
+      try {
+        resource = (await readResource({ address: imported.id })).economicResource
+      } catch (e: any) {
+        this.warn(imported.iri, `imported resource could not be read for verification: ${e.message || String(e)}`)
+        continue
+      }
+
+      for (const field of RESOURCE_QUANTITY_FIELDS) {
+        const measureKey = this.iri(source, field)
+        const measure = measureKey ? this.nodes.get(measureKey) : undefined
+        const expected = measure ? this.literal(measure, 'hasNumericalValue') : undefined
+        if (expected === undefined) continue
+
+        const actual = resource[field] ? resource[field].hasNumericalValue : undefined
+        if (actual !== parseFloat(expected)) {
+          this.warn(imported.iri, `${field} of imported resource is ${actual}, expected ${parseFloat(expected)}`)
+        }
+      }
+    }
+  }
+
+  //--------------------------------------------------------------------------------------------------------------------
+  // Helpers
+
+  typeOf (key: string): string {
+    const node = this.nodes.get(key)
+    const type = node && node.types.find(t => !!RECORD_DEFS[t] || t === RESOURCE_TYPE)
+    return type ? localName(type) : 'unknown'
+  }
+
+  isResource (key: string): boolean {
+    const node = this.nodes.get(key)
+    return !!node && node.types.indexOf(RESOURCE_TYPE) !== -1
+  }
+
+  literal (node: SourceNode, prop: string): string | undefined {
+    const term = (node.props[prop] || []).find(t => t.termType === 'Literal')
+    return term ? term.value : undefined
+  }
+
+  iri (node: SourceNode, prop: string): string | undefined {
+    const term = (node.props[prop] || []).find(t => t.termType !== 'Literal')
+    return term ? term.value : undefined
+  }
+
+  zomeFn (def: RecordDef): BoundZomeFn {
+    return mapZomeFn(this.options.dnaConfig, this.options.conductorUri, def.instance, def.zome, def.fn)
+  }
+
+  created (iri: string, type: string, id: string) {
+    this.report.mapping[iri] = id
+    this.report.created.push({ iri, type, id })
+  }
+
+  fail (iri: string, reason: string, code?: string) {
+    if (this.failedKeys.has(iri)) return
+    this.failedKeys.add(iri)
+    this.report.failed.push({ iri, type: this.typeOf(iri), reason, code })
+
+    // resources cannot be created without the event which creates them
+    this.resourceCreators.forEach((creator, resource) => {
+      if (creator === iri) {
+        this.fail(resource, `depends on ${iri}, which could not be imported`, 'DEPENDENCY_FAILED')
+      }
+    })
+  }
+
+  warn (iri: string, message: string) {
+    this.report.warnings.push({ iri, message })
+  }
+}
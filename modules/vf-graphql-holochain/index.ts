@@ -12,7 +12,8 @@ import { makeExecutableSchema } from '@graphql-tools/schema'
 
 import { APIOptions, ResolverOptions, DEFAULT_VF_MODULES, DNAMappings, CellId } from './types'
 import generateResolvers from './resolvers'
-import { mapZomeFn, openConnection } from './connection'
+import { mapZomeFn, openConnection, ZomeCallError, decodeErrorDetails } from './connection'
+import { importValueFlows, ImportOptions, ImportReport } from './import'
 const { buildSchema, printSchema } = require('@valueflows/vf-graphql')
 
 export {
@@ -21,6 +22,10 @@ export {
   // direct access to Holochain zome method bindings for authoring own custom resolvers bound to non-REA DNAs
   openConnection,
   mapZomeFn,
+  // structured errors returned by zome calls, for displaying field-level validation feedback
+  ZomeCallError, decodeErrorDetails,
+  // migration of records from other ValueFlows implementations
+  importValueFlows, ImportOptions, ImportReport,
   // types that wrapper libraries may need to manage conductor DNA connection logic
   DNAMappings, CellId,
 }
@@ -0,0 +1,105 @@
+const {
+  buildConfig,
+  buildRunner,
+  buildPlayer,
+  mockAgentId,
+  mockIdentifier,
+} = require('../init')
+
+const runner = buildRunner()
+
+const providerId = mockAgentId(false)
+const receiverId = mockAgentId(false)
+const otherId = mockAgentId(false)
+
+const testFlowProps = {
+  action: 'transfer-custody',
+  hasPointInTime: '2019-11-19T12:12:42.739+01:00',
+  resourceClassifiedAs: ['resource-type-uri'],
+  resourceQuantity: { hasNumericalValue: 1, hasUnit: mockIdentifier(false) },
+}
+
+runner.registerScenario('flow records indexed by provider & receiver agents', async (s, t) => {
+  const { cells: [observation, planning] } = await buildPlayer(s, buildConfig({ playerName: 'alice' }), ['observation', 'planning'])
+
+  // SCENARIO: write records
+  let resp = await observation.call('economic_event', 'create_economic_event', { event: { provider: providerId, receiver: receiverId, ...testFlowProps } })
+  t.ok(resp.economicEvent && resp.economicEvent.id, 'event created successfully')
+  const eventId = resp.economicEvent.id
+
+  resp = await planning.call('commitment', 'create_commitment', { commitment: { provider: providerId, receiver: receiverId, inScopeOf: [receiverId], ...testFlowProps } })
+  t.ok(resp.commitment && resp.commitment.id, 'commitment created successfully')
+  const commitmentId = resp.commitment.id
+  const commitmentRevisionId = resp.commitment.revisionId
+
+  resp = await planning.call('intent', 'create_intent', { intent: { provider: providerId, ...testFlowProps } })
+  t.ok(resp.intent && resp.intent.id, 'intent created successfully')
+  const intentId = resp.intent.id
+
+  resp = await planning.call('commitment', 'create_commitment', { commitment: { provider: providerId, receiver: receiverId, inScopeOf: [receiverId], ...testFlowProps } })
+  const otherCommitmentId = resp.commitment.id
+  await s.consistency()
+
+  // ASSERT: event queries
+  resp = await observation.call('economic_event_index', 'query_economic_events', { params: { provider: providerId } })
+  t.deepEqual(resp.results.map(r => r.economicEvent.id), [eventId], 'events queryable by provider')
+  resp = await observation.call('economic_event_index', 'query_economic_events', { params: { receiver: receiverId } })
+  t.deepEqual(resp.results.map(r => r.economicEvent.id), [eventId], 'events queryable by receiver')
+  resp = await observation.call('economic_event_index', 'query_economic_events', { params: { involvedAgent: receiverId } })
+  t.deepEqual(resp.results.map(r => r.economicEvent.id), [eventId], 'events queryable by involved agent')
+  resp = await observation.call('economic_event_index', 'query_economic_events', { params: { provider: receiverId } })
+  t.equal(resp.results.length, 0, 'event provider index does not include receiver')
+
+  // ASSERT: commitment queries
+  resp = await planning.call('commitment_index', 'query_commitments', { params: { provider: providerId } })
+  t.deepEqual(resp.results.map(r => r.commitment.id.toString()).sort(), [commitmentId, otherCommitmentId].map(id => id.toString()).sort(), 'commitments queryable by provider')
+  resp = await planning.call('commitment_index', 'query_commitments', { params: { involvedAgent: providerId } })
+  t.deepEqual(resp.results.map(r => r.commitment.id.toString()).sort(), [commitmentId, otherCommitmentId].map(id => id.toString()).sort(), 'commitments queryable by involved agent')
+  resp = await planning.call('commitment', 'get_commitment', { address: commitmentId })
+  t.equal(resp.commitment.involvedAgents.length, 2, 'commitment involvedAgents field populated')
+
+  // ASSERT: intent queries
+  resp = await planning.call('intent_index', 'query_intents', { params: { provider: providerId } })
+  t.deepEqual(resp.results.map(r => r.intent.id), [intentId], 'intents queryable by provider')
+  resp = await planning.call('intent_index', 'query_intents', { params: { receiver: providerId } })
+  t.equal(resp.results.length, 0, 'intent without receiver not indexed by receiver')
+
+  // ASSERT: commitment parties cannot be reassigned
+  try {
+    await planning.call('commitment', 'update_commitment', { commitment: { revisionId: commitmentRevisionId, receiver: otherId } })
+    t.fail('commitment receiver changed')
+  } catch (e) {
+    t.ok(e.toString().match(/receiver cannot be changed/), 'commitment receiver change rejected')
+  }
+
+  // SCENARIO: move commitment into another agent's scope
+  await planning.call('commitment', 'update_commitment', { commitment: { revisionId: commitmentRevisionId, inScopeOf: [otherId] } })
+  await s.consistency()
+
+  // ASSERT: stale agent indexes removed
+  resp = await planning.call('commitment_index', 'query_commitments', { params: { inScopeOf: receiverId } })
+  t.deepEqual(resp.results.map(r => r.commitment.id), [otherCommitmentId], 'commitment scope index removed on update')
+  resp = await planning.call('commitment_index', 'query_commitments', { params: { inScopeOf: otherId } })
+  t.deepEqual(resp.results.map(r => r.commitment.id), [commitmentId], 'commitment scope index updated')
+
+  // ASSERT: the agent's other records remain indexed
+  resp = await planning.call('commitment_index', 'query_commitments', { params: { receiver: receiverId } })
+  t.equal(resp.results.length, 2, 'commitments of previous scope agent still indexed by receiver')
+  resp = await planning.call('commitment_index', 'query_commitments', { params: { provider: providerId } })
+  t.equal(resp.results.length, 2, 'commitments of unchanged provider still indexed')
+
+  // SCENARIO: delete a commitment
+  resp = await planning.call('commitment', 'get_commitment', { address: commitmentId })
+  await planning.call('commitment', 'delete_commitment', { address: resp.commitment.revisionId })
+  await s.consistency()
+
+  // ASSERT: only the deleted record is removed from agent indexes
+  resp = await planning.call('commitment_index', 'query_commitments', { params: { provider: providerId } })
+  t.deepEqual(resp.results.map(r => r.commitment.id), [otherCommitmentId], 'deleted commitment removed from provider index')
+  resp = await planning.call('commitment_index', 'query_commitments', { params: { involvedAgent: providerId } })
+  t.deepEqual(resp.results.map(r => r.commitment.id), [otherCommitmentId], 'other commitments of provider still indexed')
+  resp = await planning.call('intent_index', 'query_intents', { params: { provider: providerId } })
+  t.deepEqual(resp.results.map(r => r.intent.id), [intentId], 'intents of provider unaffected')
+})
+
+runner.run()
@@ -0,0 +1,64 @@
+const {
+  buildConfig,
+  buildRunner,
+  buildPlayer,
+  mockAgentId,
+  mockIdentifier,
+  mockAddress,
+} = require('../init')
+
+const runner = buildRunner()
+
+const WHEAT = 'http://www.productontology.org/id/Wheat'
+const BARLEY = 'http://www.productontology.org/id/Barley'
+
+const testFlowProps = {
+  provider: mockAgentId(false),
+  receiver: mockAgentId(false),
+  hasPointInTime: '2019-11-19T04:29:55.056Z',
+  resourceQuantity: { hasNumericalValue: 1, hasUnit: mockIdentifier(false) },
+}
+
+runner.registerScenario('records queryable by classification URL', async (s, t) => {
+  const { cells: [observation, planning] } = await buildPlayer(s, buildConfig({ playerName: 'alice' }), ['observation', 'planning'])
+
+  // SCENARIO: write records
+  let resp = await observation.call('economic_event', 'create_economic_event', {
+    event: { action: 'raise', resourceClassifiedAs: [WHEAT], ...testFlowProps },
+    new_inventoried_resource: { conformsTo: mockAddress(false) },
+  })
+  const eventId = resp.economicEvent.id
+  const resourceId = resp.economicResource.id
+  const resourceRevisionId = resp.economicResource.revisionId
+
+  resp = await planning.call('commitment', 'create_commitment', { commitment: { action: 'produce', resourceClassifiedAs: [WHEAT, BARLEY], ...testFlowProps } })
+  const commitmentId = resp.commitment.id
+
+  resp = await observation.call('process', 'create_process', { process: { name: 'harvesting', classifiedAs: [BARLEY] } })
+  const processId = resp.process.id
+  await s.consistency()
+
+  // ASSERT: classification queries
+  resp = await observation.call('economic_resource_index', 'query_economic_resources', { params: { classifiedAs: WHEAT } })
+  t.deepEqual(resp.results.map(r => r.economicResource.id), [resourceId], 'resources queryable by classification')
+  resp = await observation.call('economic_event_index', 'query_economic_events', { params: { classifiedAs: WHEAT } })
+  t.deepEqual(resp.results.map(r => r.economicEvent.id), [eventId], 'events queryable by resource classification')
+  resp = await planning.call('commitment_index', 'query_commitments', { params: { classifiedAs: BARLEY } })
+  t.deepEqual(resp.results.map(r => r.commitment.id), [commitmentId], 'commitments queryable by resource classification')
+  resp = await observation.call('process_index', 'query_processes', { params: { classifiedAs: BARLEY } })
+  t.deepEqual(resp.results.map(r => r.process.id), [processId], 'processes queryable by classification')
+  resp = await observation.call('process_index', 'query_processes', { params: { classifiedAs: WHEAT } })
+  t.equal(resp.results.length, 0, 'unrelated classifications not returned')
+
+  // SCENARIO: reclassify resource
+  await observation.call('economic_resource', 'update_economic_resource', { resource: { revisionId: resourceRevisionId, classifiedAs: [BARLEY] } })
+  await s.consistency()
+
+  // ASSERT: classification index updated
+  resp = await observation.call('economic_resource_index', 'query_economic_resources', { params: { classifiedAs: WHEAT } })
+  t.equal(resp.results.length, 0, 'stale resource classification removed on update')
+  resp = await observation.call('economic_resource_index', 'query_economic_resources', { params: { classifiedAs: BARLEY } })
+  t.deepEqual(resp.results.map(r => r.economicResource.id), [resourceId], 'new resource classification added on update')
+})
+
+runner.run()
@@ -0,0 +1,93 @@
+const {
+  buildConfig,
+  buildRunner,
+  buildPlayer,
+  mockIdentifier,
+  mockAgentId,
+  mockAddress,
+} = require('../init')
+
+const runner = buildRunner()
+
+const config = buildConfig()
+
+const testCommitmentProps = {
+  action: 'produce',
+  resourceClassifiedAs: ['some-resource-type'],
+  resourceQuantity: { hasNumericalValue: 1, hasUnit: mockIdentifier() },
+  provider: mockAgentId(),
+  receiver: mockAgentId(),
+  due: '2019-11-19T04:29:55.056Z',
+}
+
+runner.registerScenario('index zomes detect and repair inconsistent links', async (s, t) => {
+  const { cells: [planning] } = await buildPlayer(s, config, ['planning'])
+
+  let resp = await planning.call('commitment', 'create_commitment', { commitment: { note: 'fulfilled commitment', ...testCommitmentProps } })
+  const commitmentId = resp.commitment.id
+  resp = await planning.call('commitment', 'create_commitment', { commitment: { note: 'unrelated commitment', ...testCommitmentProps } })
+  const otherCommitmentId = resp.commitment.id
+  resp = await planning.call('fulfillment', 'create_fulfillment', { fulfillment: {
+    fulfills: commitmentId,
+    fulfilledBy: mockAddress(),
+    note: 'fulfillment indicating the relationship',
+  } })
+  await s.consistency()
+  const fulfillmentId = resp.fulfillment.id
+
+  // ASSERT: indexes are consistent after normal operation
+  resp = await planning.call('fulfillment_index', 'verify_indexes', {})
+  t.deepEqual(resp, [], 'fulfillment indexes consistent')
+  resp = await planning.call('commitment_index', 'verify_indexes', {})
+  t.deepEqual(resp, [], 'commitment indexes consistent')
+
+  // SCENARIO: index links are lost
+  await planning.call('fulfillment_index', '_internal_index_fulfillment_fulfills', {
+    remote_entry: commitmentId,
+    target_entries: [],
+    removed_entries: [fulfillmentId],
+  })
+  await s.consistency()
+
+  // ASSERT: missing links are reported against the source record
+  resp = await planning.call('fulfillment_index', 'verify_indexes', {})
+  t.equal(resp.length, 1, 'missing link detected')
+  t.equal(resp[0].index, 'fulfillment_fulfills', 'affected index reported')
+  t.equal(resp[0].problem, 'MissingLink', 'missing link reported')
+  t.equal(resp[0].repaired, false, 'links not repaired unless requested')
+
+  // ASSERT: missing links can be repaired
+  resp = await planning.call('fulfillment_index', 'verify_indexes', { repair: true })
+  t.equal(resp.length, 1, 'missing link reported during repair')
+  t.equal(resp[0].repaired, true, 'missing link repaired')
+  await s.consistency()
+
+  resp = await planning.call('fulfillment_index', 'verify_indexes', {})
+  t.deepEqual(resp, [], 'fulfillment indexes consistent after repair')
+  resp = await planning.call('commitment', 'get_commitment', { address: commitmentId })
+  t.deepEqual(resp.commitment.fulfilledBy, [fulfillmentId], 'reciprocal link restored')
+
+  // SCENARIO: index links are created which the record does not reference
+  await planning.call('fulfillment_index', '_internal_index_fulfillment_fulfills', {
+    remote_entry: otherCommitmentId,
+    target_entries: [fulfillmentId],
+    removed_entries: [],
+  })
+  await s.consistency()
+
+  // ASSERT: orphaned links are reported and removed
+  resp = await planning.call('fulfillment_index', 'verify_indexes', { repair: true })
+  t.equal(resp.length, 1, 'orphaned link detected')
+  t.equal(resp[0].problem, 'OrphanedLink', 'orphaned link reported')
+  t.equal(resp[0].repaired, true, 'orphaned link repaired')
+  await s.consistency()
+
+  resp = await planning.call('fulfillment_index', 'verify_indexes', {})
+  t.deepEqual(resp, [], 'fulfillment indexes consistent after removing orphaned links')
+  resp = await planning.call('commitment', 'get_commitment', { address: otherCommitmentId })
+  t.notOk(resp.commitment.fulfilledBy && resp.commitment.fulfilledBy.length, 'orphaned reciprocal link removed')
+  resp = await planning.call('commitment', 'get_commitment', { address: commitmentId })
+  t.deepEqual(resp.commitment.fulfilledBy, [fulfillmentId], 'valid links retained')
+})
+
+runner.run()
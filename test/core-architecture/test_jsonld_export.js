@@ -0,0 +1,153 @@
+const {
+  buildConfig,
+  buildRunner,
+  buildPlayer,
+  mockAgentId,
+} = require('../init')
+
+const runner = buildRunner()
+
+const config = buildConfig()
+
+const VF_NAMESPACE = 'https://w3id.org/valueflows#'
+
+async function exportAll (cell, zome, method, limit) {
+  const graph = []
+  let after = null
+  let pages = 0
+  let resp
+  do {
+    resp = await cell.call(zome, method, { after, limit })
+    graph.push(...resp['@graph'])
+    after = resp.pageInfo.endCursor
+    pages += 1
+  } while (resp.pageInfo.hasNextPage)
+  return { graph, pages, lastPage: resp }
+}
+
+runner.registerScenario('records can be exported as ValueFlows JSON-LD', async (s, t) => {
+  const { cells: [observation, specification] } = await buildPlayer(s, config, ['observation', 'specification'])
+
+  // SCENARIO: create records across DNAs
+  let resp = await specification.call('unit', 'create_unit', { unit: { label: 'kilograms', symbol: 'kg' } })
+  await s.consistency()
+  const unitId = resp.unit.id
+
+  resp = await specification.call('resource_specification', 'create_resource_specification', { resourceSpecification: { name: 'apples' } })
+  await s.consistency()
+  const specId = resp.resourceSpecification.id
+
+  resp = await observation.call('process', 'create_process', { process: {
+    name: 'orchard harvest',
+    hasBeginning: Date.parse('2019-11-19T04:00:00.000Z') * 1000,  // Holochain timestamps are in microseconds
+  } })
+  await s.consistency()
+  const processId = resp.process.id
+
+  resp = await observation.call('economic_event', 'create_economic_event', {
+    event: {
+      action: 'produce',
+      outputOf: processId,
+      resourceConformsTo: specId,
+      resourceQuantity: { hasNumericalValue: 10, hasUnit: unitId },
+      provider: mockAgentId(false),
+      receiver: mockAgentId(false),
+      hasPointInTime: '2019-11-19T04:29:55.056Z',
+    },
+    newInventoriedResource: { name: 'apples' },
+  })
+  await s.consistency()
+
+  resp = await observation.call('economic_event', 'create_economic_event', {
+    event: {
+      action: 'consume',
+      resourceInventoriedAs: resp.economicResource.id,
+      resourceQuantity: { hasNumericalValue: 2, hasUnit: unitId },
+      provider: mockAgentId(false),
+      receiver: mockAgentId(false),
+      hasPointInTime: '2019-11-20T04:29:55.056Z',
+    },
+  })
+  await s.consistency()
+
+  // ASSERT: export documents carry the ValueFlows context
+  resp = await observation.call('economic_event', 'export_economic_events', {})
+  t.equal(resp['@context']['@vocab'], VF_NAMESPACE, 'ValueFlows context included')
+  t.equal(resp['@graph'].length, 2, 'all events exported')
+  t.equal(resp.pageInfo.totalCount, 2, 'total record count reported')
+  t.notOk(resp.pageInfo.hasNextPage, 'single page of results')
+
+  // ASSERT: export is paged
+  const events = await exportAll(observation, 'economic_event', 'export_economic_events', 1)
+  t.equal(events.pages, 2, 'export split into pages')
+  t.equal(events.graph.length, 2, 'all events exported across pages')
+  t.notEqual(events.graph[0]['@id'], events.graph[1]['@id'], 'pages do not overlap')
+  t.equal(events.lastPage.pageInfo.pageLimit, 1, 'page limit reported')
+
+  try {
+    await observation.call('economic_event', 'export_economic_events', { after: 'not-a-cursor' })
+    t.fail('unknown cursor accepted')
+  } catch (e) {
+    t.ok(e.toString().match(/INVALID_FIELD_VALUE/), 'unknown cursor rejected')
+  }
+
+  // ASSERT: records are serialised with ValueFlows terms
+  const produce = events.graph.find(n => n.action['@id'] === 'vf:produce')
+  const consume = events.graph.find(n => n.action['@id'] === 'vf:consume')
+  t.equal(produce['@type'], 'EconomicEvent', 'event type exported')
+  t.ok(produce['@id'].match(/^hrea:/), 'records identified by IRI')
+  t.equal(produce.hasPointInTime, '2019-11-19T04:29:55.056+00:00', 'dates exported as ISO8601')
+  t.equal(produce.resourceQuantity.hasNumericalValue, 10, 'quantities exported')
+  t.ok(produce.provider['@id'].match(/^hrea:/), 'agents referenced by IRI')
+
+  // ASSERT: cross-record references resolve to exported records
+  const resources = await exportAll(observation, 'economic_resource', 'export_economic_resources')
+  t.equal(resources.graph.length, 1, 'resources exported')
+  t.equal(consume.resourceInventoriedAs['@id'], resources.graph[0]['@id'], 'resource reference is IRI of exported resource')
+  t.equal(resources.graph[0].accountingQuantity.hasNumericalValue, 8, 'resource state exported')
+
+  const processes = await exportAll(observation, 'process', 'export_processes')
+  t.equal(processes.graph.length, 1, 'processes exported')
+  t.equal(produce.outputOf['@id'], processes.graph[0]['@id'], 'process reference is IRI of exported process')
+  t.equal(processes.graph[0].hasBeginning, '2019-11-19T04:00:00+00:00', 'timestamps exported as RFC 3339')
+
+  const specs = await exportAll(specification, 'resource_specification', 'export_resource_specifications')
+  t.equal(specs.graph[0].name, 'apples', 'resource specifications exported')
+  t.equal(produce.resourceConformsTo['@id'], specs.graph[0]['@id'], 'cross-DNA reference is IRI of exported record')
+
+  const units = await exportAll(specification, 'unit', 'export_units', 1)
+  t.equal(units.graph.length, 1, 'units exported, excluding anchor indexes')
+  t.equal(units.graph[0]['@type'], 'om2:Unit', 'units exported as OM2 units')
+  t.equal(units.graph[0].symbol, 'kg', 'unit symbol exported')
+  t.equal(produce.resourceQuantity.hasUnit['@id'], units.graph[0]['@id'], 'unit reference is IRI of exported unit')
+})
+
+runner.registerScenario('deleted records are omitted from exports', async (s, t) => {
+  const { cells: [observation, specification] } = await buildPlayer(s, config, ['observation', 'specification'])
+
+  // SCENARIO: create records, then delete some of them
+  let resp = await observation.call('process', 'create_process', { process: { name: 'kept process' } })
+  await s.consistency()
+  resp = await observation.call('process', 'create_process', { process: { name: 'deleted process' } })
+  await s.consistency()
+  await observation.call('process', 'delete_process', { address: resp.process.revisionId })
+  await s.consistency()
+
+  resp = await specification.call('unit', 'create_unit', { unit: { label: 'kilograms', symbol: 'kg' } })
+  await s.consistency()
+  resp = await specification.call('unit', 'create_unit', { unit: { label: 'grams', symbol: 'g' } })
+  await s.consistency()
+  await specification.call('unit', 'delete_unit', { address: resp.unit.revisionId })
+  await s.consistency()
+
+  // ASSERT: deleted records do not fail the export, and are not included in it
+  const processes = await exportAll(observation, 'process', 'export_processes')
+  t.equal(processes.graph.length, 1, 'deleted processes omitted from export')
+  t.equal(processes.graph[0].name, 'kept process', 'remaining processes exported')
+
+  const units = await exportAll(specification, 'unit', 'export_units')
+  t.equal(units.graph.length, 1, 'deleted anchored records omitted from export')
+  t.equal(units.graph[0].symbol, 'kg', 'remaining units exported')
+})
+
+runner.run()
@@ -0,0 +1,216 @@
+const {
+  buildConfig,
+  buildRunner,
+  buildPlayer,
+  mockAgentId,
+} = require('../init')
+
+const { importValueFlows } = require('@valueflows/vf-graphql-holochain')
+
+const runner = buildRunner()
+
+const config = buildConfig()
+
+async function exportPages (cell, zome, method) {
+  const pages = []
+  let after = null
+  let resp
+  do {
+    resp = await cell.call(zome, method, { after, limit: 2 })
+    pages.push(resp)
+    after = resp.pageInfo.endCursor
+  } while (resp.pageInfo.hasNextPage)
+  return pages
+}
+
+runner.registerScenario('ValueFlows JSON-LD exported from one network can be imported into another', async (s, t) => {
+  const { cells: [observation, specification] } = await buildPlayer(s, config, ['observation', 'specification'])
+  const { graphQL, connection } = await buildPlayer(s, config, ['observation', 'specification'])
+
+  // SCENARIO: create & export records in the source network
+  let resp = await specification.call('unit', 'create_unit', { unit: { label: 'kilograms', symbol: 'kg' } })
+  await s.consistency()
+  const unitId = resp.unit.id
+
+  resp = await specification.call('resource_specification', 'create_resource_specification', { resourceSpecification: { name: 'apples' } })
+  await s.consistency()
+  const specId = resp.resourceSpecification.id
+
+  resp = await observation.call('process', 'create_process', { process: { name: 'orchard harvest' } })
+  await s.consistency()
+  const processId = resp.process.id
+
+  resp = await observation.call('economic_event', 'create_economic_event', {
+    event: {
+      action: 'produce',
+      outputOf: processId,
+      resourceConformsTo: specId,
+      resourceQuantity: { hasNumericalValue: 10, hasUnit: unitId },
+      provider: mockAgentId(false),
+      receiver: mockAgentId(false),
+      hasPointInTime: '2019-11-19T04:29:55.056Z',
+    },
+    newInventoriedResource: { conformsTo: specId, trackingIdentifier: 'crate-1' },
+  })
+  await s.consistency()
+
+  resp = await observation.call('economic_event', 'create_economic_event', {
+    event: {
+      action: 'consume',
+      resourceInventoriedAs: resp.economicResource.id,
+      resourceQuantity: { hasNumericalValue: 2, hasUnit: unitId },
+      provider: mockAgentId(false),
+      receiver: mockAgentId(false),
+      hasPointInTime: '2019-11-20T04:29:55.056Z',
+    },
+  })
+  await s.consistency()
+
+  // events first, so that import ordering is not simply document ordering
+  const docs = [].concat(
+    await exportPages(observation, 'economic_event', 'export_economic_events'),
+    await exportPages(observation, 'economic_resource', 'export_economic_resources'),
+    await exportPages(observation, 'process', 'export_processes'),
+    await exportPages(specification, 'resource_specification', 'export_resource_specifications'),
+    await exportPages(specification, 'unit', 'export_units'),
+  )
+  const nodes = docs.reduce((r, d) => r.concat(d['@graph']), [])
+  const iriOf = (test) => nodes.find(test)['@id']
+  const produceIri = iriOf(n => n.action && n.action['@id'] === 'vf:produce')
+  const consumeIri = iriOf(n => n.action && n.action['@id'] === 'vf:consume')
+  const resourceIri = iriOf(n => n['@type'] === 'EconomicResource')
+  const processIri = iriOf(n => n['@type'] === 'Process')
+  const specIri = iriOf(n => n['@type'] === 'ResourceSpecification')
+  const unitIri = iriOf(n => n['@type'] === 'om2:Unit')
+
+  // ACT: import into the destination network
+  const report = await importValueFlows(docs, { ...connection, defaultAgent: mockAgentId() })
+  await s.consistency()
+
+  // ASSERT: all records mapped to new identifiers
+  t.deepEqual(report.failed, [], 'no records failed to import')
+  t.deepEqual(report.warnings, [], 'no warnings raised')
+  t.equal(report.created.length, 6, 'all records created')
+  ;[produceIri, consumeIri, resourceIri, processIri, specIri, unitIri].forEach(iri => {
+    t.ok(report.mapping[iri], `${iri} mapped to new record`)
+  })
+  t.ok(report.mapping[unitIri].match(/^kg:/), 'unit mapped by symbol')
+
+  // ASSERT: records created in dependency order
+  const order = report.created.map(r => r.iri)
+  t.ok(order.indexOf(unitIri) < order.indexOf(specIri), 'units created before specifications')
+  t.ok(order.indexOf(specIri) < order.indexOf(processIri), 'specifications created before processes')
+  t.ok(order.indexOf(processIri) < order.indexOf(produceIri), 'processes created before events')
+  t.ok(order.indexOf(produceIri) < order.indexOf(consumeIri), 'events created in order of occurrence')
+  t.equal(report.created.find(r => r.iri === resourceIri).type, 'EconomicResource', 'resource created as inventory effect')
+
+  // ASSERT: references remapped & inventory effects applied
+  resp = await graphQL(`{
+    economicEvent(id: "${report.mapping[produceIri]}") {
+      outputOf { id }
+      resourceConformsTo { id }
+      resourceInventoriedAs {
+        id
+        trackingIdentifier
+        accountingQuantity { hasNumericalValue hasUnit { id } }
+      }
+    }
+  }`)
+  const event = resp.data.economicEvent
+  t.equal(event.outputOf.id, report.mapping[processIri], 'process reference remapped')
+  t.equal(event.resourceConformsTo.id, report.mapping[specIri], 'specification reference remapped')
+  t.equal(event.resourceInventoriedAs.id, report.mapping[resourceIri], 'resource reference remapped')
+  t.equal(event.resourceInventoriedAs.trackingIdentifier, 'crate-1', 'resource fields imported')
+  t.equal(event.resourceInventoriedAs.accountingQuantity.hasNumericalValue, 8, 'inventory effects of all events applied')
+  t.equal(event.resourceInventoriedAs.accountingQuantity.hasUnit.id, report.mapping[unitIri], 'unit reference remapped')
+})
+
+runner.registerScenario('ValueFlows Turtle can be imported, reporting unresolvable records', async (s, t) => {
+  const { graphQL, connection } = await buildPlayer(s, config, ['observation', 'specification'])
+  const agent = mockAgentId()
+
+  const turtle = `
+    @prefix vf: <https://w3id.org/valueflows#> .
+    @prefix om2: <http://www.ontology-of-units-of-measure.org/resource/om-2/> .
+    @prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+    @prefix ex: <https://example.com/> .
+
+    ex:transfer a vf:EconomicEvent ;
+      vf:action vf:transfer ;
+      vf:provider ex:alice ;
+      vf:receiver ex:bob ;
+      vf:resourceInventoriedAs ex:stock ;
+      vf:resourceQuantity [ om2:hasNumericalValue 1 ; om2:hasUnit ex:each ] ;
+      vf:hasPointInTime "2020-02-01T00:00:00Z" .
+
+    ex:raise a vf:EconomicEvent ;
+      vf:action vf:raise ;
+      vf:provider ex:alice ;
+      vf:receiver ex:alice ;
+      vf:resourceInventoriedAs ex:stock ;
+      vf:resourceConformsTo ex:widget ;
+      vf:resourceQuantity [ om2:hasNumericalValue 5 ; om2:hasUnit om2:litre ] ;
+      vf:hasPointInTime "2020-01-01T00:00:00Z" .
+
+    ex:stock a vf:EconomicResource ;
+      vf:conformsTo ex:widget ;
+      vf:accountingQuantity [ om2:hasNumericalValue 5 ; om2:hasUnit om2:litre ] .
+
+    ex:widget a vf:ResourceSpecification ;
+      vf:name "widget" .
+
+    ex:each a om2:Unit ;
+      rdfs:label "each" ;
+      om2:symbol "each" .
+  `
+
+  const report = await importValueFlows(turtle, { ...connection, agents: { 'https://example.com/alice': agent } })
+  await s.consistency()
+
+  // ASSERT: records with resolvable references imported
+  const order = report.created.map(r => r.iri)
+  t.ok(report.mapping['https://example.com/widget'], 'specification imported')
+  t.ok(report.mapping['https://example.com/each'], 'unit imported')
+  t.ok(report.mapping['https://example.com/raise'], 'event imported')
+  t.ok(report.mapping['https://example.com/stock'], 'resource created by earliest event')
+  t.ok(order.indexOf('https://example.com/widget') < order.indexOf('https://example.com/raise'), 'specification created before event')
+
+  // ASSERT: units referenced only by IRI created on demand
+  t.ok(report.mapping[`http://www.ontology-of-units-of-measure.org/resource/om-2/litre`].match(/^litre:/), 'external unit created from IRI')
+  t.ok(report.warnings.find(w => w.message.match(/om-2\/litre/)), 'unit creation reported')
+
+  // ASSERT: units referenced by IRI reuse existing units with the same symbol
+  const reimport = await importValueFlows(`
+    @prefix vf: <https://w3id.org/valueflows#> .
+    @prefix om2: <http://www.ontology-of-units-of-measure.org/resource/om-2/> .
+    @prefix ex: <https://example.com/> .
+
+    ex:spill a vf:EconomicEvent ;
+      vf:action vf:raise ;
+      vf:provider ex:alice ;
+      vf:receiver ex:alice ;
+      vf:resourceQuantity [ om2:hasNumericalValue 1 ; om2:hasUnit om2:litre ] ;
+      vf:hasPointInTime "2020-03-01T00:00:00Z" .
+  `, { ...connection, agents: { 'https://example.com/alice': agent } })
+  await s.consistency()
+  const litre = 'http://www.ontology-of-units-of-measure.org/resource/om-2/litre'
+  t.equal(reimport.mapping[litre], report.mapping[litre], 'existing unit reused')
+  t.notOk(reimport.created.find(r => r.iri === litre), 'no duplicate unit created')
+
+  // ASSERT: records with unmapped agents reported
+  t.equal(report.failed.length, 1, 'failed records reported')
+  t.equal(report.failed[0].iri, 'https://example.com/transfer', 'failed record identified')
+  t.equal(report.failed[0].code, 'UNMAPPED_AGENT', 'failure reason coded')
+
+  // ASSERT: inventory reflects imported events
+  const resp = await graphQL(`{
+    economicResource(id: "${report.mapping['https://example.com/stock']}") {
+      accountingQuantity { hasNumericalValue }
+      conformsTo { id }
+    }
+  }`)
+  t.equal(resp.data.economicResource.accountingQuantity.hasNumericalValue, 5, 'inventory effects applied')
+  t.equal(resp.data.economicResource.conformsTo.id, report.mapping['https://example.com/widget'], 'resource references remapped')
+})
+
+runner.run()
@@ -0,0 +1,187 @@
+const {
+  buildConfig,
+  buildRunner,
+  buildPlayer,
+  mockIdentifier,
+  mockAgentId,
+} = require('../init')
+
+const runner = buildRunner()
+
+const config = buildConfig()
+
+const testCommitmentProps = {
+  action: 'produce',
+  resourceClassifiedAs: ['some-resource-type'],
+  resourceQuantity: { hasNumericalValue: 1, hasUnit: mockIdentifier() },
+  due: '2019-11-19T04:29:55.056Z',
+}
+
+runner.registerScenario('records may only be modified by their author or authorised agents', async (s, t) => {
+  const { cells: [alicePlanning] } = await buildPlayer(s, config, ['planning'])
+  const { cells: [bobPlanning] } = await buildPlayer(s, config, ['planning'])
+  const bob = bobPlanning.cellId
+
+  // SCENARIO: alice records a commitment between two other agents
+  let resp = await alicePlanning.call('commitment', 'create_commitment', { commitment: {
+    note: 'private commitment',
+    provider: mockAgentId(),
+    receiver: mockAgentId(),
+    ...testCommitmentProps,
+  } })
+  await s.consistency()
+  t.ok(resp.commitment && resp.commitment.id, 'record created successfully')
+  const privateCommitment = resp.commitment
+
+  // ASSERT: unrelated agents cannot modify the record
+  try {
+    await bobPlanning.call('commitment', 'update_commitment', { commitment: {
+      revisionId: privateCommitment.revisionId,
+      note: 'tampered',
+    } })
+    t.fail('update by unauthorised agent accepted')
+  } catch (e) {
+    t.ok(e.toString().match(/not authorised to modify this record/), 'update by unauthorised agent rejected')
+  }
+  try {
+    await bobPlanning.call('commitment', 'delete_commitment', { address: privateCommitment.revisionId })
+    t.fail('delete by unauthorised agent accepted')
+  } catch (e) {
+    t.ok(e.toString().match(/not authorised to modify this record/), 'delete by unauthorised agent rejected')
+  }
+
+  // ASSERT: the original author can modify the record
+  resp = await alicePlanning.call('commitment', 'update_commitment', { commitment: {
+    revisionId: privateCommitment.revisionId,
+    note: 'updated by author',
+  } })
+  await s.consistency()
+  t.equal(resp.commitment.note, 'updated by author', 'update by original author accepted')
+
+  // SCENARIO: alice records a commitment with bob as the receiver
+  resp = await alicePlanning.call('commitment', 'create_commitment', { commitment: {
+    note: 'shared commitment',
+    provider: mockAgentId(),
+    receiver: bob,
+    ...testCommitmentProps,
+  } })
+  await s.consistency()
+  const sharedCommitment = resp.commitment
+
+  // ASSERT: agents in a role on the record can modify it
+  resp = await bobPlanning.call('commitment', 'update_commitment', { commitment: {
+    revisionId: sharedCommitment.revisionId,
+    note: 'updated by receiver',
+  } })
+  await s.consistency()
+  t.equal(resp.commitment.note, 'updated by receiver', 'update by receiver accepted')
+
+  resp = await bobPlanning.call('commitment', 'delete_commitment', { address: resp.commitment.revisionId })
+  t.ok(resp, 'delete by receiver accepted')
+})
+
+runner.registerScenario('resources may be modified by their primary accountable agent', async (s, t) => {
+  const { cells: [aliceObservation] } = await buildPlayer(s, config, ['observation'])
+  const { cells: [bobObservation] } = await buildPlayer(s, config, ['observation'])
+  const { cells: [carolObservation] } = await buildPlayer(s, config, ['observation'])
+  const bob = bobObservation.cellId
+
+  // SCENARIO: alice records a resource raised into bob's inventory
+  let resp = await aliceObservation.call('economic_event', 'create_economic_event', {
+    event: {
+      action: 'raise',
+      resourceQuantity: { hasNumericalValue: 1, hasUnit: mockIdentifier() },
+      provider: bob,
+      receiver: bob,
+      hasPointInTime: '2019-11-19T04:29:55.056Z',
+    },
+    new_inventoried_resource: { note: 'bob\'s resource' },
+  })
+  await s.consistency()
+  const resource = resp.economicResource
+  t.deepEqual(resource.primaryAccountable, bob, 'receiver becomes primary accountable agent')
+
+  // ASSERT: unrelated agents cannot modify the resource
+  try {
+    await carolObservation.call('economic_resource', 'update_economic_resource', { resource: {
+      revisionId: resource.revisionId,
+      note: 'tampered',
+    } })
+    t.fail('update by unauthorised agent accepted')
+  } catch (e) {
+    t.ok(e.toString().match(/not authorised to modify this record/), 'update by unauthorised agent rejected')
+  }
+
+  // ASSERT: the primary accountable agent can modify the resource
+  resp = await bobObservation.call('economic_resource', 'update_economic_resource', { resource: {
+    revisionId: resource.revisionId,
+    note: 'updated by primary accountable',
+  } })
+  await s.consistency()
+  t.equal(resp.economicResource.note, 'updated by primary accountable', 'update by primary accountable agent accepted')
+})
+
+runner.registerScenario('commitment parties cannot be rewritten by the counterparty', async (s, t) => {
+  const { cells: [alicePlanning] } = await buildPlayer(s, config, ['planning'])
+  const { cells: [bobPlanning] } = await buildPlayer(s, config, ['planning'])
+  const alice = alicePlanning.cellId
+  const bob = bobPlanning.cellId
+
+  let resp = await alicePlanning.call('commitment', 'create_commitment', { commitment: {
+    provider: alice,
+    receiver: bob,
+    ...testCommitmentProps,
+  } })
+  await s.consistency()
+  const commitment = resp.commitment
+
+  // ASSERT: the receiver cannot reassign the provider
+  try {
+    await bobPlanning.call('commitment', 'update_commitment', { commitment: {
+      revisionId: commitment.revisionId,
+      provider: bob,
+    } })
+    t.fail('provider reassigned by counterparty')
+  } catch (e) {
+    t.ok(e.toString().match(/provider cannot be changed/), 'provider reassignment rejected')
+  }
+
+  // ASSERT: the provider cannot reassign the receiver
+  try {
+    await alicePlanning.call('commitment', 'update_commitment', { commitment: {
+      revisionId: commitment.revisionId,
+      receiver: alice,
+    } })
+    t.fail('receiver reassigned by counterparty')
+  } catch (e) {
+    t.ok(e.toString().match(/receiver cannot be changed/), 'receiver reassignment rejected')
+  }
+})
+
+runner.registerScenario('proposal addressees may only be deleted by their author', async (s, t) => {
+  const { cells: [aliceProposal] } = await buildPlayer(s, config, ['proposal'])
+  const { cells: [bobProposal] } = await buildPlayer(s, config, ['proposal'])
+
+  let resp = await aliceProposal.call('proposal', 'create_proposal', { proposal: { name: 'offer' } })
+  await s.consistency()
+  resp = await aliceProposal.call('proposed_to', 'create_proposed_to', { proposed_to: {
+    proposed: resp.proposal.id,
+    proposedTo: mockAgentId(),
+  } })
+  await s.consistency()
+  const proposedTo = resp.proposedTo
+
+  // ASSERT: unrelated agents cannot delete the record
+  try {
+    await bobProposal.call('proposed_to', 'delete_proposed_to', { address: proposedTo.revisionId })
+    t.fail('delete by unauthorised agent accepted')
+  } catch (e) {
+    t.ok(e.toString().match(/not authorised to modify this record/), 'delete by unauthorised agent rejected')
+  }
+
+  // ASSERT: the author can delete the record
+  resp = await aliceProposal.call('proposed_to', 'delete_proposed_to', { address: proposedTo.revisionId })
+  t.ok(resp, 'delete by author accepted')
+})
+
+runner.run()
@@ -0,0 +1,64 @@
+const {
+  buildConfig,
+  buildRunner,
+  buildPlayer,
+  mockAgentId,
+  mockIdentifier,
+} = require('../init')
+
+const runner = buildRunner()
+
+const config = buildConfig()
+
+const testEventProps = {
+  action: 'raise',
+  resourceClassifiedAs: ['some-resource-type'],
+  resourceQuantity: { hasNumericalValue: 1, hasUnit: mockIdentifier(false) },
+  provider: mockAgentId(false),
+  receiver: mockAgentId(false),
+  hasPointInTime: '2019-11-19T04:29:55.056Z',
+}
+
+runner.registerScenario('updates to superseded revisions are rejected', async (s, t) => {
+  const { cells: [observation] } = await buildPlayer(s, config, ['observation'])
+
+  // SCENARIO: create & update a record
+  const createResp = await observation.call('economic_event', 'create_economic_event', { event: { note: 'event v1', ...testEventProps } })
+  await s.consistency()
+  const eventId = createResp.economicEvent.id
+  const firstRevisionId = createResp.economicEvent.revisionId
+
+  let resp = await observation.call('economic_event', 'get_economic_event_revision_heads', { address: eventId })
+  t.deepEqual(resp, [firstRevisionId], 'initial revision is sole head')
+
+  const updateResp = await observation.call('economic_event', 'update_economic_event', { event: { revisionId: firstRevisionId, note: 'event v2' } })
+  await s.consistency()
+  const secondRevisionId = updateResp.economicEvent.revisionId
+
+  resp = await observation.call('economic_event', 'get_economic_event_revision_heads', { address: eventId })
+  t.deepEqual(resp, [secondRevisionId], 'updated revision becomes sole head')
+
+  // ASSERT: stale update rejected
+  try {
+    await observation.call('economic_event', 'update_economic_event', { event: { revisionId: firstRevisionId, note: 'conflicting v2' } })
+    t.fail('update to superseded revision accepted')
+  } catch (e) {
+    t.ok(e.toString().match(/not the latest revision/), 'update to superseded revision rejected with conflict error')
+  }
+
+  resp = await observation.call('economic_event', 'get_economic_event', { address: eventId })
+  t.equal(resp.economicEvent.note, 'event v2', 'record unchanged after rejected update')
+
+  // ASSERT: merges must account for all current heads
+  try {
+    await observation.call('economic_event', 'merge_economic_event_revisions', { revisionId: firstRevisionId, supersededRevisions: [] })
+    t.fail('merge of superseded revision accepted')
+  } catch (e) {
+    t.ok(e.toString().match(/not the latest revision/), 'merge of superseded revision rejected')
+  }
+
+  resp = await observation.call('economic_event', 'merge_economic_event_revisions', { revisionId: secondRevisionId, supersededRevisions: [] })
+  t.deepEqual(resp.economicEvent.revisionId, secondRevisionId, 'merge of sole head is a no-op')
+})
+
+runner.run()
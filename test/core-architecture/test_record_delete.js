@@ -84,5 +84,43 @@ runner2.registerScenario('Cannot delete records of a different type via zome API
   }
 })
 
+const runner3 = buildRunner()
+
+runner3.registerScenario('Records cannot be deleted while other records reference them', async (s, t) => {
+  const { cells: [observation] } = await buildPlayer(s, config, ['observation'])
+
+  // SCENARIO: write records
+  const processResp = await observation.call('process', 'create_process', { process: { name: 'referenced process' } })
+  t.ok(processResp.process && processResp.process.id, 'process created successfully')
+  await s.consistency()
+
+  const eventResp = await observation.call('economic_event', 'create_economic_event', { event: {
+    action: 'consume',
+    inputOf: processResp.process.id,
+    resourceClassifiedAs: ['some-resource-type'],
+    resourceQuantity: { hasNumericalValue: 1, hasUnit: mockIdentifier(false) },
+    provider: mockAgentId(false),
+    receiver: mockAgentId(false),
+    hasPointInTime: '2019-11-19T04:29:55.056Z',
+  } })
+  t.ok(eventResp.economicEvent && eventResp.economicEvent.id, 'referencing event created successfully')
+  await s.consistency()
+
+  // ASSERT: referenced process cannot be deleted
+  try {
+    await observation.call('process', 'delete_process', { address: processResp.process.revisionId })
+    t.fail('referenced process deleted')
+  } catch (err) {
+    t.ok(err.data.data.includes('cannot be deleted while it is referenced by 1 economic event(s)'), 'deletion of referenced record refused')
+  }
+
+  // ASSERT: process can be deleted once no longer referenced
+  await observation.call('economic_event', 'delete_economic_event', { address: eventResp.economicEvent.revisionId })
+  await s.consistency()
+  const delResp = await observation.call('process', 'delete_process', { address: processResp.process.revisionId })
+  t.ok(delResp, 'unreferenced process deleted successfully')
+})
+
 runner.run()
 runner2.run()
+runner3.run()
@@ -0,0 +1,78 @@
+const {
+  buildConfig,
+  buildRunner,
+  buildPlayer,
+  mockAgentId,
+  mockIdentifier,
+} = require('../init')
+
+const runner = buildRunner()
+
+const config = buildConfig()
+
+const testEventProps = {
+  action: 'raise',
+  resourceClassifiedAs: ['some-resource-type'],
+  resourceQuantity: { hasNumericalValue: 1, hasUnit: mockIdentifier(false) },
+  provider: mockAgentId(false),
+  receiver: mockAgentId(false),
+  hasPointInTime: '2019-11-19T04:29:55.056Z',
+}
+
+runner.registerScenario('record revision history and point-in-time reads', async (s, t) => {
+  const { cells: [observation] } = await buildPlayer(s, config, ['observation'])
+
+  // SCENARIO: create & update a record
+  const createResp = await observation.call('economic_event', 'create_economic_event', { event: { note: 'event v1', ...testEventProps } })
+  await s.consistency()
+  const eventId = createResp.economicEvent.id
+  const firstRevisionId = createResp.economicEvent.revisionId
+
+  const updateResp = await observation.call('economic_event', 'update_economic_event', { event: { revisionId: firstRevisionId, note: 'event v2' } })
+  await s.consistency()
+  const secondRevisionId = updateResp.economicEvent.revisionId
+
+  // ASSERT: history lists all revisions in order
+  const history = await observation.call('economic_event', 'get_economic_event_history', { address: eventId })
+  t.equal(history.length, 2, 'history contains all revisions')
+  t.deepEqual(history[0].revisionId, firstRevisionId, 'initial revision listed first')
+  t.deepEqual(history[1].revisionId, secondRevisionId, 'updated revision listed second')
+  t.equal(history[0].previousRevisionId, null, 'initial revision has no predecessor')
+  t.deepEqual(history[1].previousRevisionId, firstRevisionId, 'updated revision references its predecessor')
+  t.ok(history[0].author && history[0].timestamp, 'revision author & timestamp recorded')
+  t.notOk(history[1].deleted, 'revision not marked as deleted')
+
+  // ASSERT: point-in-time reads
+  let resp = await observation.call('economic_event', 'get_economic_event_at_revision', { address: firstRevisionId })
+  t.equal(resp.economicEvent.note, 'event v1', 'prior revision readable by revision ID')
+
+  resp = await observation.call('economic_event', 'get_economic_event_as_of', { address: eventId, asOf: history[0].timestamp })
+  t.equal(resp.economicEvent.note, 'event v1', 'record readable as at time of prior revision')
+
+  resp = await observation.call('economic_event', 'get_economic_event_as_of', { address: eventId, asOf: history[1].timestamp })
+  t.equal(resp.economicEvent.note, 'event v2', 'record readable as at time of latest revision')
+
+  // ASSERT: revision diffs
+  const diff = await observation.call('economic_event', 'diff_economic_event_revisions', { fromRevisionId: firstRevisionId, toRevisionId: secondRevisionId })
+  const noteChange = diff.changes.find(c => c.field === 'note')
+  t.ok(noteChange, 'changed field included in diff')
+  t.equal(noteChange.previousValue, 'event v1', 'diff includes previous value')
+  t.equal(noteChange.newValue, 'event v2', 'diff includes new value')
+
+  // SCENARIO: delete the record
+  await observation.call('economic_event', 'delete_economic_event', { address: secondRevisionId })
+  await s.consistency()
+
+  // ASSERT: point-in-time reads honour deletion
+  resp = await observation.call('economic_event', 'get_economic_event_as_of', { address: eventId, asOf: history[1].timestamp })
+  t.equal(resp.economicEvent.note, 'event v2', 'record readable as at time prior to deletion')
+
+  try {
+    await observation.call('economic_event', 'get_economic_event_as_of', { address: eventId, asOf: Date.now() * 1000 })  // Holochain timestamps are in microseconds
+    t.fail('deleted record readable as at time after deletion')
+  } catch (e) {
+    t.ok(e.toString().match(/No entry at this address/), 'record not found as at time after deletion')
+  }
+})
+
+runner.run()
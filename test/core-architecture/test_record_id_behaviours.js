@@ -80,5 +80,30 @@ runner2.registerScenario('records can be updated multiple times with appropriate
   t.deepEqual(createResp.economicEvent.id, updateResp2.economicEvent.id, 'ID consistency after subsequent update')
 })
 
+const runner3 = buildRunner()
+
+runner3.registerScenario('records with identical field data are assigned distinct IDs', async (s, t) => {
+  const { cells: [observation] } = await buildPlayer(s, config, ['observation'])
+
+  const event = {
+    note: 'identical work event',
+    action: 'work',
+    ...testEventProps,
+  }
+
+  const createResp1 = await observation.call('economic_event', 'create_economic_event', { event })
+  const createResp2 = await observation.call('economic_event', 'create_economic_event', { event })
+  await s.consistency()
+
+  t.ok(createResp1.economicEvent && createResp2.economicEvent, 'both records created successfully')
+  t.notDeepEqual(createResp1.economicEvent.id, createResp2.economicEvent.id, 'IDs do not collide')
+
+  const readResp1 = await observation.call('economic_event', 'get_economic_event', { address: createResp1.economicEvent.id })
+  const readResp2 = await observation.call('economic_event', 'get_economic_event', { address: createResp2.economicEvent.id })
+  t.deepEqual(readResp1.economicEvent.id, createResp1.economicEvent.id, 'first record readable by ID')
+  t.deepEqual(readResp2.economicEvent.id, createResp2.economicEvent.id, 'second record readable by ID')
+})
+
 runner.run()
 runner2.run()
+runner3.run()
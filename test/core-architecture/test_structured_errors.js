@@ -0,0 +1,93 @@
+const {
+  buildConfig,
+  buildRunner,
+  buildPlayer,
+  mockIdentifier,
+  mockAgentId,
+} = require('../init')
+const { decodeErrorDetails } = require('@valueflows/vf-graphql-holochain')
+
+const runner = buildRunner()
+
+const config = buildConfig()
+
+const testCommitmentProps = {
+  action: 'produce',
+  resourceClassifiedAs: ['some-resource-type'],
+  provider: mockAgentId(),
+  receiver: mockAgentId(),
+  due: '2019-11-19T04:29:55.056Z',
+}
+
+runner.registerScenario('zome errors are returned with machine-readable details', async (s, t) => {
+  const { cells: [planning, observation], graphQL } = await buildPlayer(s, config, ['planning', 'observation'])
+
+  // ASSERT: validation errors identify the offending field
+  try {
+    await planning.call('commitment', 'create_commitment', { commitment: testCommitmentProps })
+    t.fail('commitment without quantity accepted')
+  } catch (e) {
+    const err = decodeErrorDetails(e.toString())
+    t.ok(err, 'error details returned')
+    t.equal(err.code, 'MISSING_REQUIRED_FIELD', 'error code returned')
+    t.equal(err.field, 'resourceQuantity', 'invalid field returned')
+    t.equal(err.details.alternatives, 'resourceQuantity,effortQuantity', 'alternative fields returned')
+    t.ok(err.message.match(/resource quantity or an effort quantity/), 'error message returned')
+  }
+
+  try {
+    await observation.call('economic_event', 'create_economic_event', { event: {
+      action: 'consume',
+      resourceClassifiedAs: ['some-resource-type'],
+      resourceQuantity: { hasNumericalValue: 1, hasUnit: mockIdentifier() },
+      provider: mockAgentId(),
+      receiver: mockAgentId(),
+      hasPointInTime: '2019-11-19T04:29:55.056Z',
+    } })
+    t.fail('input event without process accepted')
+  } catch (e) {
+    const err = decodeErrorDetails(e.toString())
+    t.equal(err.code, 'MISSING_REQUIRED_FIELD', 'action validation error code returned')
+    t.equal(err.field, 'inputOf', 'action validation field returned')
+    t.equal(err.details.action, 'consume', 'action returned in error details')
+  }
+
+  // ASSERT: storage errors are also reported with codes
+  const resp = await planning.call('commitment', 'create_commitment', { commitment: {
+    ...testCommitmentProps,
+    resourceQuantity: { hasNumericalValue: 1, hasUnit: mockIdentifier() },
+  } })
+  await s.consistency()
+  const { revisionId } = resp.commitment
+  await planning.call('commitment', 'update_commitment', { commitment: { revisionId, note: 'first update' } })
+  await s.consistency()
+
+  try {
+    await planning.call('commitment', 'update_commitment', { commitment: { revisionId, note: 'stale update' } })
+    t.fail('stale update accepted')
+  } catch (e) {
+    const err = decodeErrorDetails(e.toString())
+    t.equal(err.code, 'UPDATE_CONFLICT', 'conflict error code returned')
+    t.equal(err.field, 'revisionId', 'conflicting field returned')
+    t.equal(err.details.revisionId, revisionId, 'stale revision returned in error details')
+  }
+
+  // ASSERT: error details are exposed to GraphQL clients as extensions
+  const result = await graphQL(`
+    mutation($rs: CommitmentCreateParams!) {
+      res: createCommitment(commitment: $rs) {
+        commitment {
+          id
+        }
+      }
+    }
+  `, {
+    rs: testCommitmentProps,
+  })
+  t.equal(result.errors.length, 1, 'GraphQL error returned')
+  t.equal(result.errors[0].extensions.code, 'MISSING_REQUIRED_FIELD', 'GraphQL error code returned')
+  t.equal(result.errors[0].extensions.field, 'resourceQuantity', 'GraphQL error field returned')
+  t.ok(result.errors[0].message.match(/resource quantity or an effort quantity/), 'GraphQL error message is human-readable')
+})
+
+runner.run()
@@ -0,0 +1,146 @@
+const {
+  buildConfig,
+  buildRunner,
+  buildPlayer,
+  mockAgentId,
+  mockIdentifier,
+  mockAddress,
+} = require('../init')
+
+const runner = buildRunner()
+
+const hours = mockIdentifier(false)
+const minutes = mockIdentifier(false)
+const currency = mockIdentifier(false)
+
+const DESIGN = 'http://example.com/skills/design'
+const ASSEMBLY = 'http://example.com/skills/assembly'
+
+const testEventProps = {
+  receiver: mockAgentId(false),
+  hasPointInTime: '2019-11-19T12:12:42.739+01:00',
+}
+
+runner.registerScenario('EconomicEvent income distribution via value equations', async (s, t) => {
+  const { cells: [observation] } = await buildPlayer(s, buildConfig({ playerName: 'alice' }), ['observation'])
+
+  const designer = mockAgentId(false)
+  const assembler = mockAgentId(false)
+  const agentRoles = [
+    { agent: designer, roles: [DESIGN] },
+    { agent: assembler, roles: [ASSEMBLY] },
+  ]
+
+  // SCENARIO: two processes contributing to a product which is later sold
+  let resp = await observation.call('process', 'create_process', { process: { name: 'design' } })
+  await s.consistency()
+  const designProcessId = resp.process.id
+
+  resp = await observation.call('process', 'create_process', { process: { name: 'assembly' } })
+  await s.consistency()
+  const assemblyProcessId = resp.process.id
+
+  resp = await observation.call('economic_event', 'create_economic_event', {
+    event: { action: 'work', inputOf: designProcessId, provider: designer, effortQuantity: { hasNumericalValue: 4, hasUnit: hours }, ...testEventProps },
+  })
+  const designWorkId = resp.economicEvent.id
+  resp = await observation.call('economic_event', 'create_economic_event', {
+    event: { action: 'produce', outputOf: designProcessId, provider: designer, resourceClassifiedAs: ['design-document'], resourceQuantity: { hasNumericalValue: 1 }, ...testEventProps },
+    new_inventoried_resource: { conformsTo: mockAddress(false) },
+  })
+  await s.consistency()
+  const designDocId = resp.economicResource.id
+
+  resp = await observation.call('economic_event', 'create_economic_event', {
+    event: { action: 'cite', inputOf: assemblyProcessId, provider: designer, resourceInventoriedAs: designDocId, resourceQuantity: { hasNumericalValue: 1 }, ...testEventProps },
+  })
+  await observation.call('economic_event', 'create_economic_event', {
+    event: { action: 'work', inputOf: assemblyProcessId, provider: assembler, effortQuantity: { hasNumericalValue: 2, hasUnit: hours }, ...testEventProps },
+  })
+  resp = await observation.call('economic_event', 'create_economic_event', {
+    event: { action: 'produce', outputOf: assemblyProcessId, provider: assembler, resourceClassifiedAs: ['product'], resourceQuantity: { hasNumericalValue: 1 }, ...testEventProps },
+    new_inventoried_resource: { conformsTo: mockAddress(false) },
+  })
+  await s.consistency()
+  const productId = resp.economicResource.id
+
+  resp = await observation.call('economic_event', 'create_economic_event', {
+    event: { action: 'transfer', provider: assembler, resourceInventoriedAs: productId, resourceQuantity: { hasNumericalValue: 1 }, ...testEventProps },
+  })
+  await s.consistency()
+  const saleId = resp.economicEvent.id
+
+  // ASSERT: income distributed according to value equation
+  const valueEquation = [
+    { action: 'work', providerRole: DESIGN, weight: 2 },
+    { action: 'work', weight: 1 },
+    { action: 'cite', weight: 2 },
+  ]
+  resp = await observation.call('economic_event', 'get_income_distribution', {
+    event: saleId,
+    income: { hasNumericalValue: 120, hasUnit: currency },
+    valueEquation,
+    agentRoles,
+  })
+  t.equal(resp.distributions.length, 2, 'all contributors included')
+
+  const designerShare = resp.distributions.find(d => d.agent.toString() === designer.toString())
+  const assemblerShare = resp.distributions.find(d => d.agent.toString() === assembler.toString())
+  t.equal(designerShare.score, 10, 'upstream work & citations scored recursively')
+  t.equal(assemblerShare.score, 2, 'direct work scored')
+  t.equal(designerShare.share.hasNumericalValue, 100, 'income shared in proportion to score')
+  t.equal(assemblerShare.share.hasNumericalValue, 20, 'income shared in proportion to score')
+  t.ok(designerShare.contributions.find(c => c.toString() === designWorkId.toString()), 'contributing events listed')
+
+  // ASSERT: unmatched contributions are not scored
+  resp = await observation.call('economic_event', 'get_income_distribution', {
+    event: saleId,
+    income: { hasNumericalValue: 120, hasUnit: currency },
+    valueEquation: [{ action: 'work', providerRole: ASSEMBLY, weight: 1 }],
+    agentRoles,
+  })
+  t.equal(resp.distributions.length, 1, 'only matching contributions distributed')
+  t.equal(resp.distributions[0].share.hasNumericalValue, 120, 'full income allocated to sole contributor')
+
+  // ASSERT: roles are taken from the roles agents play, not from event classifications
+  resp = await observation.call('economic_event', 'get_income_distribution', {
+    event: saleId,
+    income: { hasNumericalValue: 120, hasUnit: currency },
+    valueEquation: [{ action: 'work', providerRole: ASSEMBLY, weight: 1 }],
+  })
+  t.equal(resp.distributions.length, 0, 'contributors without assigned roles not matched')
+
+  // SCENARIO: further work on assembly, measured in a different unit
+  await observation.call('economic_event', 'create_economic_event', {
+    event: { action: 'work', inputOf: assemblyProcessId, provider: assembler, effortQuantity: { hasNumericalValue: 60, hasUnit: minutes }, ...testEventProps },
+  })
+  await s.consistency()
+
+  // ASSERT: contributions in differing units are not summed by the same rule
+  try {
+    await observation.call('economic_event', 'get_income_distribution', {
+      event: saleId,
+      income: { hasNumericalValue: 120, hasUnit: currency },
+      valueEquation: [{ action: 'work', weight: 1 }],
+    })
+    t.fail('contributions in differing units scored together')
+  } catch (e) {
+    t.ok(e.toString().match(/differing units/), 'mixed unit contributions rejected')
+  }
+
+  // ASSERT: rules for each unit convert their quantities to comparable scores
+  resp = await observation.call('economic_event', 'get_income_distribution', {
+    event: saleId,
+    income: { hasNumericalValue: 120, hasUnit: currency },
+    valueEquation: [
+      { action: 'work', unit: hours, weight: 1 },
+      { action: 'work', unit: minutes, weight: 1 / 60 },
+    ],
+  })
+  const assemblerScore = resp.distributions.find(d => d.agent.toString() === assembler.toString()).score
+  const designerScore = resp.distributions.find(d => d.agent.toString() === designer.toString()).score
+  t.ok(Math.abs(assemblerScore - 3) < 1e-9, 'work in minutes converted by unit rule')
+  t.equal(designerScore, 4, 'work in hours weighted by unit rule')
+})
+
+runner.run()
@@ -0,0 +1,137 @@
+const {
+  buildConfig,
+  buildRunner,
+  buildPlayer,
+  mockIdentifier,
+} = require('../init')
+
+const runner = buildRunner()
+
+const config = buildConfig()
+
+const testUnit = mockIdentifier()
+
+runner.registerScenario('transfers countersigned by provider and receiver', async (s, t) => {
+  const { cells: [aliceObs] } = await buildPlayer(s, config, ['observation'])
+  const { cells: [bobObs] } = await buildPlayer(s, config, ['observation'])
+  const alice = aliceObs.cellId
+  const bob = bobObs.cellId
+
+  // SCENARIO: each party holds their own inventory
+  let resp = await aliceObs.call('economic_event', 'create_economic_event', {
+    event: {
+      action: 'raise',
+      provider: alice,
+      receiver: alice,
+      resourceQuantity: { hasNumericalValue: 10, hasUnit: testUnit },
+      hasPointInTime: '2019-11-19T04:29:55.056Z',
+    },
+    new_inventoried_resource: { name: 'alice apples' },
+  })
+  const aliceResourceId = resp.economicResource.id
+  resp = await bobObs.call('economic_event', 'create_economic_event', {
+    event: {
+      action: 'raise',
+      provider: bob,
+      receiver: bob,
+      resourceQuantity: { hasNumericalValue: 0, hasUnit: testUnit },
+      hasPointInTime: '2019-11-19T04:29:55.056Z',
+    },
+    new_inventoried_resource: { name: 'bob apples' },
+  })
+  const bobResourceId = resp.economicResource.id
+  await s.consistency()
+
+  // ASSERT: non-transfer events cannot be countersigned
+  try {
+    await aliceObs.call('economic_event', 'create_countersigned_economic_event', { event: {
+      action: 'consume',
+      provider: alice,
+      receiver: bob,
+      resourceInventoriedAs: aliceResourceId,
+      resourceQuantity: { hasNumericalValue: 1, hasUnit: testUnit },
+      hasPointInTime: '2019-11-19T04:29:55.056Z',
+    } })
+    t.fail('countersigned consume event accepted')
+  } catch (e) {
+    t.ok(e.toString().match(/cannot be countersigned/), 'only transfers may be countersigned')
+  }
+
+  // SCENARIO: alice proposes a transfer to bob
+  resp = await aliceObs.call('economic_event', 'create_countersigned_economic_event', { event: {
+    action: 'transfer',
+    provider: alice,
+    receiver: bob,
+    resourceInventoriedAs: aliceResourceId,
+    toResourceInventoriedAs: bobResourceId,
+    resourceQuantity: { hasNumericalValue: 4, hasUnit: testUnit },
+    hasPointInTime: '2019-11-19T04:29:55.056Z',
+  } })
+  await s.consistency()
+  const eventId = resp.id
+  t.ok(eventId, 'transfer proposed')
+
+  // ASSERT: nothing is signed until the counterparty approves
+  resp = await bobObs.call('economic_event', 'get_pending_transfers', null)
+  t.equal(resp.length, 1, 'proposal received by counterparty')
+  t.deepEqual(resp[0].id, eventId, 'proposal identified by the address of the event to be signed')
+  t.deepEqual(resp[0].proposer, alice, 'proposer recorded')
+  try {
+    await aliceObs.call('economic_event', 'get_economic_event', { address: eventId })
+    t.fail('event written before approval')
+  } catch (e) {
+    t.ok(e, 'event not written before approval')
+  }
+  try {
+    await aliceObs.call('economic_event', 'approve_countersigned_transfer', { address: eventId })
+    t.fail('proposer approved their own transfer')
+  } catch (e) {
+    t.ok(e.toString().match(/approved by the counterparty/), 'proposer cannot approve their own transfer')
+  }
+
+  // SCENARIO: bob approves the transfer, both sign the event
+  resp = await bobObs.call('economic_event', 'approve_countersigned_transfer', { address: eventId })
+  await s.consistency()
+  t.ok(resp.economicEvent, 'countersigned event created')
+  t.deepEqual(resp.economicEvent.id, eventId, 'approved event matches proposal')
+  resp = await bobObs.call('economic_event', 'get_pending_transfers', null)
+  t.equal(resp.length, 0, 'proposal no longer pending once signed')
+
+  // ASSERT: signed transfers are tracked until applied to each party's inventory
+  resp = await aliceObs.call('economic_event', 'get_transfer_applications', null)
+  t.equal(resp.length, 1, 'signed transfer tracked for provider')
+  t.deepEqual(resp[0].economicEvent, eventId, 'tracked transfer identified by event address')
+  resp = await bobObs.call('economic_event', 'get_transfer_applications', null)
+  t.equal(resp.length, 1, 'signed transfer tracked for receiver')
+
+  // SCENARIO: outstanding transfers are applied without further action by either party.
+  // Retries are triggered here rather than awaiting the scheduled attempt.
+  await aliceObs.call('economic_event', 'retry_transfer_applications', null)
+  await bobObs.call('economic_event', 'retry_transfer_applications', null)
+  await s.consistency()
+
+  resp = await aliceObs.call('economic_event', 'get_transfer_applications', null)
+  t.ok(resp[0].applied, 'provider side of transfer applied')
+  t.notOk(resp[0].lastError, 'provider application succeeded')
+  resp = await bobObs.call('economic_event', 'get_transfer_applications', null)
+  t.ok(resp[0].applied, 'receiver side of transfer applied')
+  resp = await aliceObs.call('economic_event', 'retry_transfer_applications', null)
+  t.equal(resp.length, 0, 'applied transfers are not retried')
+
+  resp = await aliceObs.call('economic_resource', 'get_economic_resource', { address: aliceResourceId })
+  t.equal(resp.economicResource.accountingQuantity.hasNumericalValue, 6, 'provider inventory decremented')
+  resp = await bobObs.call('economic_resource', 'get_economic_resource', { address: bobResourceId })
+  t.equal(resp.economicResource.accountingQuantity.hasNumericalValue, 4, 'receiver inventory incremented')
+
+  // ASSERT: transfers cannot be applied twice
+  try {
+    await aliceObs.call('economic_event', 'apply_countersigned_transfer', { address: eventId })
+    t.fail('transfer applied twice')
+  } catch (e) {
+    t.ok(e.toString().match(/already been applied/), 'repeated application of transfer rejected')
+  }
+  resp = await aliceObs.call('economic_resource', 'get_economic_resource', { address: aliceResourceId })
+  t.equal(resp.economicResource.accountingQuantity.hasNumericalValue, 6, 'provider inventory unchanged by repeated application')
+})
+
+runner.run()
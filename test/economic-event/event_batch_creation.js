@@ -0,0 +1,182 @@
+const {
+  buildConfig,
+  buildRunner,
+  buildPlayer,
+  mockIdentifier,
+  mockAgentId,
+} = require('../init')
+const { decodeErrorDetails } = require('@valueflows/vf-graphql-holochain')
+
+const runner = buildRunner()
+
+const config = buildConfig()
+
+const testUnit = mockIdentifier(false)
+const testEventProps = {
+  provider: mockAgentId(false),
+  receiver: mockAgentId(false),
+  hasPointInTime: '2019-11-19T04:29:55.056Z',
+}
+
+runner.registerScenario('events and resources can be created in atomic batches', async (s, t) => {
+  const { cells: [observation] } = await buildPlayer(s, config, ['observation'])
+
+  // SCENARIO: create a resource, then consume from it and record a triggered event, all in one batch
+  let resp = await observation.call('economic_event', 'apply_economic_event_batch', { operations: [
+    { createEconomicEvent: {
+      ref: 'harvest',
+      resourceRef: 'apples',
+      event: {
+        action: 'raise',
+        resourceQuantity: { hasNumericalValue: 10, hasUnit: testUnit },
+        ...testEventProps,
+      },
+      newInventoriedResource: { name: 'apples' },
+    } },
+    { createEconomicEvent: {
+      ref: 'eaten',
+      event: {
+        action: 'consume',
+        resourceQuantity: { hasNumericalValue: 3, hasUnit: testUnit },
+        ...testEventProps,
+      },
+      references: { resourceInventoriedAs: 'apples', triggeredBy: 'harvest' },
+    } },
+  ] })
+  await s.consistency()
+
+  // ASSERT: all operations applied
+  t.equal(resp.length, 2, 'all operations applied')
+  t.ok(resp[0].economicEvent, 'first event created')
+  t.ok(resp[0].economicResource, 'resource created')
+  t.ok(resp[1].economicEvent, 'second event created')
+  t.notOk(resp[1].economicResource, 'no resource created by second event')
+  const resourceId = resp[0].economicResource
+  const harvestId = resp[0].economicEvent
+  const eatenId = resp[1].economicEvent
+
+  // ASSERT: references to records created earlier in the batch were resolved
+  resp = await observation.call('economic_event', 'get_economic_event', { address: eatenId })
+  t.deepEqual(resp.economicEvent.resourceInventoriedAs, resourceId, 'resource reference resolved')
+  t.deepEqual(resp.economicEvent.triggeredBy, harvestId, 'event reference resolved')
+
+  resp = await observation.call('economic_resource', 'get_economic_resource', { address: resourceId })
+  t.equal(resp.economicResource.accountingQuantity.hasNumericalValue, 7, 'inventory updated by events in the batch')
+
+  resp = await observation.call('economic_event', 'get_all_economic_events', null)
+  const eventCount = resp.edges.length
+  t.equal(eventCount, 2, 'events readable after batch')
+
+  // SCENARIO: a batch fails partway through
+  try {
+    await observation.call('economic_event', 'apply_economic_event_batch', { operations: [
+      { createEconomicEvent: {
+        event: {
+          action: 'raise',
+          resourceQuantity: { hasNumericalValue: 1, hasUnit: testUnit },
+          resourceInventoriedAs: resourceId,
+          ...testEventProps,
+        },
+      } },
+      { createEconomicEvent: {
+        event: {
+          action: 'consume',
+          resourceQuantity: { hasNumericalValue: 1, hasUnit: testUnit },
+          ...testEventProps,
+        },
+        references: { resourceInventoriedAs: 'pears' },
+      } },
+    ] })
+    t.fail('batch with unresolvable reference applied')
+  } catch (e) {
+    const err = decodeErrorDetails(e.toString())
+    t.equal(err.code, 'REFERENCE_NOT_FOUND', 'unresolvable reference reported')
+    t.equal(err.field, 'operations[1].references.resourceInventoriedAs', 'failing operation and field reported')
+  }
+  await s.consistency()
+
+  // ASSERT: no operations in the failed batch were applied
+  resp = await observation.call('economic_event', 'get_all_economic_events', null)
+  t.equal(resp.edges.length, eventCount, 'no events written by failed batch')
+  resp = await observation.call('economic_resource', 'get_economic_resource', { address: resourceId })
+  t.equal(resp.economicResource.accountingQuantity.hasNumericalValue, 7, 'inventory unaffected by failed batch')
+
+  // ASSERT: resource references cannot be assigned without creating a resource
+  try {
+    await observation.call('economic_event', 'apply_economic_event_batch', { operations: [
+      { createEconomicEvent: {
+        resourceRef: 'pears',
+        event: {
+          action: 'raise',
+          resourceQuantity: { hasNumericalValue: 1, hasUnit: testUnit },
+          resourceInventoriedAs: resourceId,
+          ...testEventProps,
+        },
+      } },
+    ] })
+    t.fail('resource reference assigned without a new resource')
+  } catch (e) {
+    const err = decodeErrorDetails(e.toString())
+    t.equal(err.code, 'INVALID_FIELD_VALUE', 'dangling resource reference rejected')
+    t.equal(err.field, 'operations[0].resourceRef', 'dangling resource reference field reported')
+  }
+})
+
+runner.registerScenario('failed batches do not write indexes in other DNAs', async (s, t) => {
+  const { cells: [observation, agreement] } = await buildPlayer(s, config, ['observation', 'agreement'])
+
+  let resp = await agreement.call('agreement', 'create_agreement', { agreement: { name: 'test agreement' } })
+  await s.consistency()
+  const agreementId = resp.agreement.id
+
+  // SCENARIO: the first operation of a failing batch realizes an agreement in another DNA
+  try {
+    await observation.call('economic_event', 'apply_economic_event_batch', { operations: [
+      { createEconomicEvent: {
+        event: {
+          action: 'raise',
+          resourceQuantity: { hasNumericalValue: 1, hasUnit: testUnit },
+          realizationOf: agreementId,
+          ...testEventProps,
+        },
+      } },
+      { createEconomicEvent: {
+        event: {
+          action: 'consume',
+          resourceQuantity: { hasNumericalValue: 1, hasUnit: testUnit },
+          ...testEventProps,
+        },
+        references: { resourceInventoriedAs: 'pears' },
+      } },
+    ] })
+    t.fail('batch with unresolvable reference applied')
+  } catch (e) {
+    t.ok(e.toString().match(/REFERENCE_NOT_FOUND/), 'batch failed')
+  }
+  await s.consistency()
+
+  // ASSERT: the remote agreement was not linked to the discarded event
+  resp = await agreement.call('agreement', 'get_agreement', { address: agreementId })
+  t.notOk(resp.agreement.economicEvents, 'no realizations indexed by failed batch')
+
+  // SCENARIO: the same operation in a successful batch
+  resp = await observation.call('economic_event', 'apply_economic_event_batch', { operations: [
+    { createEconomicEvent: {
+      event: {
+        action: 'raise',
+        resourceQuantity: { hasNumericalValue: 1, hasUnit: testUnit },
+        realizationOf: agreementId,
+        ...testEventProps,
+      },
+    } },
+  ] })
+  await s.consistency()
+  const eventId = resp[0].economicEvent
+
+  // ASSERT: remote indexes written once the batch has succeeded
+  resp = await agreement.call('agreement', 'get_agreement', { address: agreementId })
+  t.equal(resp.agreement.economicEvents.length, 1, 'realization indexed by successful batch')
+  t.deepEqual(resp.agreement.economicEvents[0], eventId, 'realization index references created event')
+})
+
+runner.run()
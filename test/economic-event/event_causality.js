@@ -0,0 +1,62 @@
+const {
+  buildConfig,
+  buildRunner,
+  buildPlayer,
+  mockAgentId,
+  mockIdentifier,
+} = require('../init')
+
+const runner = buildRunner()
+
+const testEventProps = {
+  action: 'work',
+  provider: mockAgentId(false),
+  receiver: mockAgentId(false),
+  hasPointInTime: '2019-11-19T12:12:42.739+01:00',
+  effortQuantity: { hasNumericalValue: 1, hasUnit: mockIdentifier(false) },
+}
+
+runner.registerScenario('EconomicEvent triggeredBy indexes & causal chains', async (s, t) => {
+  const { cells: [observation] } = await buildPlayer(s, buildConfig({ playerName: 'alice' }), ['observation'])
+
+  // SCENARIO: create a chain of events
+  let resp = await observation.call('economic_event', 'create_economic_event', { event: { note: 'root event', ...testEventProps } })
+  await s.consistency()
+  const rootId = resp.economicEvent.id
+
+  resp = await observation.call('economic_event', 'create_economic_event', { event: { note: 'child event', triggeredBy: rootId, ...testEventProps } })
+  await s.consistency()
+  const childId = resp.economicEvent.id
+
+  resp = await observation.call('economic_event', 'create_economic_event', { event: { note: 'grandchild event', triggeredBy: childId, ...testEventProps } })
+  await s.consistency()
+  const grandchildId = resp.economicEvent.id
+
+  // ASSERT: check reciprocal index
+  resp = await observation.call('economic_event', 'get_economic_event', { address: rootId })
+  t.deepEqual(resp.economicEvent.triggers, [childId], 'EconomicEvent.triggers index created')
+
+  resp = await observation.call('economic_event_index', 'query_economic_events', { params: { triggeredBy: childId } })
+  t.equal(resp.results.length, 1, 'triggeredBy query index present')
+  t.deepEqual(resp.results[0].economicEvent.id, grandchildId, 'triggeredBy query returns triggered event')
+
+  // ASSERT: check causal chain
+  resp = await observation.call('economic_event', 'get_economic_event_causal_chain', { address: childId })
+  t.deepEqual(resp.triggeredBy, [rootId], 'upstream causes returned')
+  t.deepEqual(resp.triggers, [grandchildId], 'downstream effects returned')
+
+  resp = await observation.call('economic_event', 'get_economic_event_causal_chain', { address: rootId })
+  t.deepEqual(resp.triggers, [childId, grandchildId], 'indirect effects returned in breadth-first order')
+
+  // SCENARIO: re-point an event's trigger
+  resp = await observation.call('economic_event', 'get_economic_event', { address: grandchildId })
+  await observation.call('economic_event', 'update_economic_event', { event: { revisionId: resp.economicEvent.revisionId, triggeredBy: rootId } })
+  await s.consistency()
+
+  resp = await observation.call('economic_event', 'get_economic_event', { address: childId })
+  t.notOk(resp.economicEvent.triggers, 'stale triggers index removed on update')
+  resp = await observation.call('economic_event', 'get_economic_event', { address: rootId })
+  t.equal(resp.economicEvent.triggers.length, 2, 'new triggers index created on update')
+})
+
+runner.run()
@@ -9,6 +9,8 @@ const runner = buildRunner()
 
 const config = buildConfig()
 
+const scopeId = mockAgentId(false)
+
 runner.registerScenario('create simplest event', async (s, t) => {
   const { cells: [alice] } = await buildPlayer(s, config, ['observation'])
 
@@ -20,14 +22,14 @@ runner.registerScenario('create simplest event', async (s, t) => {
     hasPointInTime: '2019-11-19T12:12:42.739+01:00',
     resourceClassifiedAs: ['some-resource-type'],
     resourceQuantity: { hasNumericalValue: 1 },
-    inScopeOf: ['some-accounting-scope'],
+    inScopeOf: [scopeId],
   }
 
   const createEventResponse = await alice.call('economic_event', 'create_economic_event', { event })
   await s.consistency()
 
   t.ok(createEventResponse.economicEvent, 'event created')
-  t.deepEqual(createEventResponse.economicEvent.inScopeOf, ['some-accounting-scope'], 'event inScopeOf saved')
+  t.deepEqual(createEventResponse.economicEvent.inScopeOf, [scopeId], 'event inScopeOf saved')
 })
 
 runner.run()
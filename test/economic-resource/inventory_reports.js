@@ -0,0 +1,79 @@
+const {
+  buildConfig,
+  buildRunner,
+  buildPlayer,
+  mockAgentId,
+  mockIdentifier,
+  mockAddress,
+} = require('../init')
+
+const runner = buildRunner()
+
+const config = buildConfig()
+
+const testEventProps = {
+  action: 'raise',
+  provider: mockAgentId(false),
+  receiver: mockAgentId(false),
+  hasPointInTime: '2019-11-19T04:29:55.056Z',
+  resourceClassifiedAs: ['todo-this-shouldnt-be-needed'],
+}
+
+runner.registerScenario('EconomicResource inventory summary reports', async (s, t) => {
+  const { cells: [alice] } = await buildPlayer(s, config, ['observation'])
+
+  // SCENARIO: resources of two specifications, in mixed units
+  const specA = mockAddress(false)
+  const specB = mockAddress(false)
+  const kg = mockIdentifier(false)
+  const lb = mockIdentifier(false)
+
+  const resources = [
+    [specA, 5, kg],
+    [specA, 3, kg],
+    [specA, 2, lb],
+    [specB, 7, kg],
+  ]
+  for (const [conformsTo, hasNumericalValue, hasUnit] of resources) {
+    await alice.call('economic_event', 'create_economic_event', {
+      event: { resourceQuantity: { hasNumericalValue, hasUnit }, ...testEventProps },
+      new_inventoried_resource: { conformsTo },
+    })
+  }
+  await s.consistency()
+
+  // ASSERT: mixed units totalled separately within each group
+  let resp = await alice.call('economic_resource', 'get_inventory_report', { groupBy: ['conformsTo'] })
+  t.equal(resp.groups.length, 2, 'one group per specification')
+
+  const groupA = resp.groups.find(g => g.conformsTo.toString() === specA.toString())
+  const totalIn = (totals, unit) => totals.find(q => q.hasUnit.toString() === unit.toString())
+  t.equal(groupA.resourceCount, 3, 'resources counted per group')
+  t.equal(groupA.accountingQuantity.length, 2, 'one total per unit within group')
+  t.equal(totalIn(groupA.accountingQuantity, kg).hasNumericalValue, 8, 'accounting quantities summed per unit')
+  t.equal(totalIn(groupA.onhandQuantity, lb).hasNumericalValue, 2, 'onhand quantities summed per unit')
+
+  // ASSERT: grouped by specification & unit
+  resp = await alice.call('economic_resource', 'get_inventory_report', { groupBy: ['conformsTo', 'unit'] })
+  t.equal(resp.groups.length, 3, 'one group per specification & unit')
+
+  const groupAkg = resp.groups.find(g => g.conformsTo.toString() === specA.toString() && g.unit.toString() === kg.toString())
+  const groupAlb = resp.groups.find(g => g.conformsTo.toString() === specA.toString() && g.unit.toString() === lb.toString())
+  t.equal(groupAkg.resourceCount, 2, 'resources counted per unit group')
+  t.equal(groupAkg.accountingQuantity[0].hasNumericalValue, 8, 'accounting quantities summed per unit group')
+  t.equal(groupAlb.onhandQuantity[0].hasNumericalValue, 2, 'onhand quantities summed per unit group')
+
+  // ASSERT: filtered via specification index
+  resp = await alice.call('economic_resource', 'get_inventory_report', { conformsTo: [specB], groupBy: ['conformsTo'] })
+  t.equal(resp.groups.length, 1, 'report restricted to requested specifications')
+  t.equal(resp.groups[0].accountingQuantity[0].hasNumericalValue, 7, 'filtered group totalled')
+
+  // ASSERT: grouped by owner
+  resp = await alice.call('economic_resource', 'get_inventory_report', { groupBy: ['owner'] })
+  t.equal(resp.groups.length, 1, 'resources grouped by owner')
+  t.equal(resp.groups[0].owner.toString(), testEventProps.receiver.toString(), 'primary accountable agent returned as owner')
+  t.equal(resp.groups[0].resourceCount, 4, 'all resources included')
+  t.equal(totalIn(resp.groups[0].accountingQuantity, kg).hasNumericalValue, 15, 'totals span specifications')
+})
+
+runner.run()
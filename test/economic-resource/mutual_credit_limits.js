@@ -0,0 +1,145 @@
+const {
+  buildConfig,
+  buildRunner,
+  buildPlayer,
+  mockAgentId,
+  mockIdentifier,
+} = require('../init')
+
+const runner = buildRunner()
+
+const config = buildConfig()
+
+const testEventProps = {
+  provider: mockAgentId(),
+  receiver: mockAgentId(),
+  hasPointInTime: '2019-11-19T04:29:55.056Z',
+}
+const creditUnit = mockIdentifier()
+
+runner.registerScenario('mutual credit balances cannot exceed their credit limits', async (s, t) => {
+  const { cells: [observation, specification] } = await buildPlayer(s, config, ['observation', 'specification'])
+
+  // SCENARIO: currency specification in ledger mode
+  let resp = await specification.call('resource_specification', 'create_resource_specification', { resource_specification: {
+    name: 'community credits',
+    ledgerMode: true,
+    defaultCreditLimit: { hasNumericalValue: 5, hasUnit: creditUnit },
+  } })
+  await s.consistency()
+  t.ok(resp.resourceSpecification.ledgerMode, 'ledger mode enabled for specification')
+  const currencyId = resp.resourceSpecification.id
+
+  // SCENARIO: zero balances for two members
+  const openAccount = async (note) => {
+    const r = await observation.call('economic_event', 'create_economic_event', {
+      event: {
+        action: 'raise',
+        resourceQuantity: { hasNumericalValue: 0, hasUnit: creditUnit },
+        ...testEventProps,
+      },
+      new_inventoried_resource: { note, conformsTo: currencyId },
+    })
+    await s.consistency()
+    return r.economicResource
+  }
+  const aliceAccount = await openAccount('alice balance')
+  const bobAccount = await openAccount('bob balance')
+  t.deepEqual(aliceAccount.creditLimit, { hasNumericalValue: 5, hasUnit: creditUnit }, 'default credit limit assigned to new balance')
+
+  const transfer = (qty) => observation.call('economic_event', 'create_economic_event', { event: {
+    action: 'transfer',
+    resourceInventoriedAs: aliceAccount.id,
+    toResourceInventoriedAs: bobAccount.id,
+    resourceQuantity: { hasNumericalValue: qty, hasUnit: creditUnit },
+    ...testEventProps,
+  } })
+
+  // ASSERT: spending into credit within the limit is OK
+  resp = await transfer(3)
+  await s.consistency()
+  t.ok(resp.economicEvent, 'transfer within credit limit accepted')
+
+  resp = await observation.call('economic_resource', 'get_economic_resource', { address: aliceAccount.id })
+  t.deepEqual(resp.economicResource.accountingQuantity, { hasNumericalValue: -3, hasUnit: creditUnit }, 'balance allowed to go negative')
+
+  // ASSERT: spending beyond the limit is rejected
+  try {
+    await transfer(4)
+    t.fail('transfer beyond credit limit accepted')
+  } catch (e) {
+    t.ok(e.toString().match(/credit limit/), 'transfer beyond credit limit rejected')
+  }
+
+  resp = await observation.call('economic_resource', 'get_economic_resource', { address: aliceAccount.id })
+  t.deepEqual(resp.economicResource.accountingQuantity, { hasNumericalValue: -3, hasUnit: creditUnit }, 'rejected transfer leaves balance unchanged')
+
+  // ASSERT: per-agent limits can be raised
+  resp = await observation.call('economic_resource', 'update_economic_resource', { resource: {
+    revisionId: resp.economicResource.revisionId,
+    creditLimit: { hasNumericalValue: 10, hasUnit: creditUnit },
+  } })
+  await s.consistency()
+  t.deepEqual(resp.economicResource.creditLimit, { hasNumericalValue: 10, hasUnit: creditUnit }, 'credit limit updated')
+
+  resp = await transfer(4)
+  await s.consistency()
+  t.ok(resp.economicEvent, 'transfer within raised credit limit accepted')
+})
+
+runner.registerScenario('credit limits may only be changed by the issuer of a balance', async (s, t) => {
+  const { cells: [issuerObservation, specification] } = await buildPlayer(s, config, ['observation', 'specification'])
+  const { cells: [holderObservation] } = await buildPlayer(s, config, ['observation'])
+  const holder = holderObservation.cellId
+
+  let resp = await specification.call('resource_specification', 'create_resource_specification', { resource_specification: {
+    name: 'community credits',
+    ledgerMode: true,
+    defaultCreditLimit: { hasNumericalValue: 5, hasUnit: creditUnit },
+  } })
+  await s.consistency()
+  const currencyId = resp.resourceSpecification.id
+
+  // SCENARIO: issuer opens a balance held by another agent
+  resp = await issuerObservation.call('economic_event', 'create_economic_event', {
+    event: {
+      action: 'raise',
+      resourceQuantity: { hasNumericalValue: 0, hasUnit: creditUnit },
+      provider: holder,
+      receiver: holder,
+      hasPointInTime: '2019-11-19T04:29:55.056Z',
+    },
+    new_inventoried_resource: { note: 'holder balance', conformsTo: currencyId },
+  })
+  await s.consistency()
+  const account = resp.economicResource
+
+  // ASSERT: the holder cannot raise their own limit
+  try {
+    await holderObservation.call('economic_resource', 'update_economic_resource', { resource: {
+      revisionId: account.revisionId,
+      creditLimit: { hasNumericalValue: 1000, hasUnit: creditUnit },
+    } })
+    t.fail('credit limit change by holder accepted')
+  } catch (e) {
+    t.ok(e.toString().match(/can only be changed by the issuer/), 'credit limit change by holder rejected')
+  }
+
+  // ASSERT: the holder may still update other fields of their balance
+  resp = await holderObservation.call('economic_resource', 'update_economic_resource', { resource: {
+    revisionId: account.revisionId,
+    note: 'my balance',
+  } })
+  await s.consistency()
+  t.equal(resp.economicResource.note, 'my balance', 'holder may update balance details')
+
+  // ASSERT: the issuer can change the limit
+  resp = await issuerObservation.call('economic_resource', 'update_economic_resource', { resource: {
+    revisionId: resp.economicResource.revisionId,
+    creditLimit: { hasNumericalValue: 10, hasUnit: creditUnit },
+  } })
+  await s.consistency()
+  t.deepEqual(resp.economicResource.creditLimit, { hasNumericalValue: 10, hasUnit: creditUnit }, 'credit limit change by issuer accepted')
+})
+
+runner.run()
@@ -0,0 +1,97 @@
+const {
+  buildConfig,
+  buildRunner,
+  buildPlayer,
+  mockAgentId,
+  mockIdentifier,
+} = require('../init')
+
+const runner = buildRunner()
+
+const config = buildConfig()
+
+const testEventProps = {
+  provider: mockAgentId(),
+  receiver: mockAgentId(),
+  hasPointInTime: '2019-11-19T04:29:55.056Z',
+}
+const kilograms = mockIdentifier()
+
+runner.registerScenario('negative inventory policy', async (s, t) => {
+  const { cells: [observation, specification] } = await buildPlayer(s, config, ['observation', 'specification'])
+
+  let lastSpec
+  const createStock = async (policy) => {
+    const specResp = await specification.call('resource_specification', 'create_resource_specification', { resource_specification: {
+      name: `flour (${policy || 'DNA default'})`,
+      negativeInventoryPolicy: policy,
+    } })
+    await s.consistency()
+    lastSpec = specResp.resourceSpecification
+    const resp = await observation.call('economic_event', 'create_economic_event', {
+      event: {
+        action: 'raise',
+        resourceQuantity: { hasNumericalValue: 3, hasUnit: kilograms },
+        ...testEventProps,
+      },
+      new_inventoried_resource: { conformsTo: specResp.resourceSpecification.id },
+    })
+    await s.consistency()
+    return resp.economicResource.id
+  }
+  const consume = (resourceId, qty) => observation.call('economic_event', 'create_economic_event', { event: {
+    action: 'consume',
+    resourceInventoriedAs: resourceId,
+    resourceQuantity: { hasNumericalValue: qty, hasUnit: kilograms },
+    ...testEventProps,
+  } })
+
+  // ASSERT: DNA default allows negative inventory
+  const allowedId = await createStock()
+  let resp = await consume(allowedId, 10)
+  await s.consistency()
+  t.ok(resp.economicEvent, 'overdrawing event accepted under default policy')
+  resp = await observation.call('economic_resource', 'get_economic_resource', { address: allowedId })
+  t.equal(resp.economicResource.accountingQuantity.hasNumericalValue, -7, 'inventory allowed to go negative')
+
+  // ASSERT: warn policy accepts the event
+  const warnedId = await createStock('warn')
+  resp = await consume(warnedId, 10)
+  await s.consistency()
+  t.ok(resp.economicEvent, 'overdrawing event accepted under warn policy')
+  t.equal(resp.warnings.length, 1, 'warning returned with the event')
+  t.equal(resp.warnings[0].code, 'INSUFFICIENT_INVENTORY', 'warning is typed')
+  t.equal(resp.warnings[0].details.available, '3', 'available quantity reported in warning')
+
+  // ASSERT: reject policy refuses the event and reports available stock
+  const rejectedId = await createStock('reject')
+  try {
+    await consume(rejectedId, 10)
+    t.fail('overdrawing event accepted under reject policy')
+  } catch (e) {
+    t.ok(e.toString().match(/Insufficient inventory/), 'overdrawing event rejected')
+    t.ok(e.toString().match(/3 available, 10 requested/), 'available quantity reported')
+  }
+
+  resp = await consume(rejectedId, 3)
+  await s.consistency()
+  t.ok(resp.economicEvent, 'event within available stock accepted under reject policy')
+  resp = await observation.call('economic_resource', 'get_economic_resource', { address: rejectedId })
+  t.equal(resp.economicResource.accountingQuantity.hasNumericalValue, 0, 'inventory drawn down to zero')
+
+  // ASSERT: the policy currently set by the specification applies, rather than that at resource creation
+  const changedId = await createStock('allow')
+  await specification.call('resource_specification', 'update_resource_specification', { resource_specification: {
+    revisionId: lastSpec.revisionId,
+    negativeInventoryPolicy: 'reject',
+  } })
+  await s.consistency()
+  try {
+    await consume(changedId, 10)
+    t.fail('overdrawing event accepted after policy changed to reject')
+  } catch (e) {
+    t.ok(e.toString().match(/Insufficient inventory/), 'updated specification policy applied')
+  }
+})
+
+runner.run()
@@ -0,0 +1,87 @@
+const {
+  buildConfig,
+  buildRunner,
+  buildPlayer,
+  mockAgentId,
+  mockIdentifier,
+  mockAddress,
+} = require('../init')
+
+const runner = buildRunner()
+
+const config = buildConfig()
+
+const resourceUnitId = mockIdentifier(false)
+
+const testEventProps = {
+  provider: mockAgentId(false),
+  receiver: mockAgentId(false),
+  resourceClassifiedAs: ['todo-this-shouldnt-be-needed'],
+}
+
+runner.registerScenario('EconomicResource historic balances', async (s, t) => {
+  const { cells: [alice] } = await buildPlayer(s, config, ['observation'])
+
+  // SCENARIO: resource affected by events over time
+  let resp = await alice.call('economic_event', 'create_economic_event', {
+    event: { action: 'raise', resourceQuantity: { hasNumericalValue: 10, hasUnit: resourceUnitId }, hasPointInTime: '2020-01-01T00:00:00.000Z', ...testEventProps },
+    new_inventoried_resource: { conformsTo: mockAddress(false) },
+  })
+  await s.consistency()
+  const resourceId = resp.economicResource.id
+
+  // events recorded out of chronological order, to check replay ordering
+  resp = await alice.call('economic_event', 'create_economic_event', {
+    event: { action: 'lower', resourceInventoriedAs: resourceId, resourceQuantity: { hasNumericalValue: 4, hasUnit: resourceUnitId }, hasPointInTime: '2020-03-01T00:00:00.000Z', ...testEventProps },
+  })
+  await s.consistency()
+  resp = await alice.call('economic_event', 'create_economic_event', {
+    event: { action: 'raise', resourceInventoriedAs: resourceId, resourceQuantity: { hasNumericalValue: 2, hasUnit: resourceUnitId }, hasPointInTime: '2020-02-01T00:00:00.000Z', ...testEventProps },
+  })
+  await s.consistency()
+  const backdatedEventId = resp.economicEvent.id
+
+  // ASSERT: point-in-time balances
+  resp = await alice.call('economic_resource', 'get_economic_resource_balance', { address: resourceId, asOf: '2020-01-15T00:00:00.000Z' })
+  t.equal(resp.accountingQuantity.hasNumericalValue, 10, 'accounting quantity as at initial event')
+  t.equal(resp.onhandQuantity.hasNumericalValue, 10, 'onhand quantity as at initial event')
+  t.ok(resp.custodian, 'custodian returned')
+
+  resp = await alice.call('economic_resource', 'get_economic_resource_balance', { address: resourceId, asOf: '2020-02-15T00:00:00.000Z' })
+  t.equal(resp.accountingQuantity.hasNumericalValue, 12, 'backdated events included in historic balance')
+  t.deepEqual(resp.lastAffectedBy, backdatedEventId, 'most recent event to affect balance returned')
+
+  resp = await alice.call('economic_resource', 'get_economic_resource_balance', { address: resourceId, asOf: '2021-01-01T00:00:00.000Z' })
+  t.equal(resp.accountingQuantity.hasNumericalValue, 8, 'balance after all events')
+
+  try {
+    await alice.call('economic_resource', 'get_economic_resource_balance', { address: resourceId, asOf: '2019-01-01T00:00:00.000Z' })
+    t.fail('balance returned for resource prior to its creation')
+  } catch (e) {
+    t.ok(e, 'balance query prior to resource creation errors')
+  }
+
+  // ASSERT: time series
+  resp = await alice.call('economic_resource', 'get_economic_resource_balance_series', { address: resourceId })
+  t.deepEqual(resp.map(b => b.accountingQuantity.hasNumericalValue), [10, 12, 8], 'full balance series in chronological order')
+
+  resp = await alice.call('economic_resource', 'get_economic_resource_balance_series', { address: resourceId, from: '2020-01-15T00:00:00.000Z', until: '2020-02-15T00:00:00.000Z' })
+  t.deepEqual(resp.map(b => b.accountingQuantity.hasNumericalValue), [12], 'balance series bounded by date range')
+
+  // SCENARIO: rights to the resource passed to another agent
+  const newOwner = mockAgentId(false)
+  await alice.call('economic_event', 'create_economic_event', {
+    event: { action: 'transfer-all-rights', resourceInventoriedAs: resourceId, resourceQuantity: { hasNumericalValue: 8, hasUnit: resourceUnitId }, hasPointInTime: '2020-04-01T00:00:00.000Z', ...testEventProps, receiver: newOwner },
+  })
+  await s.consistency()
+
+  // ASSERT: historic balances reflect the primary accountable agent at the time
+  resp = await alice.call('economic_resource', 'get_economic_resource_balance', { address: resourceId, asOf: '2020-03-15T00:00:00.000Z' })
+  t.deepEqual(resp.primaryAccountable, testEventProps.receiver, 'primary accountable agent prior to transfer of rights')
+
+  resp = await alice.call('economic_resource', 'get_economic_resource_balance', { address: resourceId, asOf: '2021-01-01T00:00:00.000Z' })
+  t.deepEqual(resp.primaryAccountable, newOwner, 'primary accountable agent following transfer of rights')
+  t.equal(resp.accountingQuantity.hasNumericalValue, 0, 'transfer of rights reflected in balance')
+})
+
+runner.run()
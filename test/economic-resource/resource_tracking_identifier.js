@@ -0,0 +1,63 @@
+const {
+  buildConfig,
+  buildRunner,
+  buildPlayer,
+  mockAgentId,
+  mockIdentifier,
+  mockAddress,
+} = require('../init')
+
+const runner = buildRunner()
+
+const config = buildConfig()
+
+const testEventProps = {
+  action: 'raise',
+  provider: mockAgentId(false),
+  receiver: mockAgentId(false),
+  hasPointInTime: '2019-11-19T04:29:55.056Z',
+  resourceClassifiedAs: ['todo-this-shouldnt-be-needed'],
+  resourceQuantity: { hasNumericalValue: 1, hasUnit: mockIdentifier(false) },
+}
+
+runner.registerScenario('EconomicResource lookup by tracking identifier', async (s, t) => {
+  const { cells: [alice] } = await buildPlayer(s, config, ['observation'])
+
+  // SCENARIO: create tracked resource
+  const resourceSpecificationId = mockAddress(false)
+  let resp = await alice.call('economic_event', 'create_economic_event', {
+    event: { note: 'tagged resource instantiation', ...testEventProps },
+    new_inventoried_resource: { conformsTo: resourceSpecificationId, trackingIdentifier: 'RFID-0001' },
+  })
+  await s.consistency()
+  t.ok(resp.economicResource && resp.economicResource.id, 'tracked resource created successfully')
+  const resourceId = resp.economicResource.id
+  const eventId = resp.economicEvent.id
+
+  // ASSERT: resolve tag to resource
+  resp = await alice.call('economic_resource', 'get_economic_resource_by_tracking_identifier', { trackingIdentifier: 'RFID-0001' })
+  t.deepEqual(resp.economicResource.id, resourceId, 'resource resolved by tracking identifier')
+  t.equal(resp.economicResource.trackingIdentifier, 'RFID-0001', 'tracking identifier returned')
+  t.deepEqual(resp.affectedBy, [eventId], 'resource event history returned')
+
+  // ASSERT: collisions rejected
+  try {
+    await alice.call('economic_event', 'create_economic_event', {
+      event: { note: 'duplicate tag', ...testEventProps },
+      new_inventoried_resource: { conformsTo: resourceSpecificationId, trackingIdentifier: 'RFID-0001' },
+    })
+    t.fail('duplicate tracking identifier accepted')
+  } catch (e) {
+    t.ok(e.toString().match(/already in use/), 'duplicate tracking identifier rejected')
+  }
+
+  // ASSERT: unknown tags error
+  try {
+    await alice.call('economic_resource', 'get_economic_resource_by_tracking_identifier', { trackingIdentifier: 'RFID-9999' })
+    t.fail('unknown tracking identifier resolved')
+  } catch (e) {
+    t.ok(e, 'unknown tracking identifier errors')
+  }
+})
+
+runner.run()
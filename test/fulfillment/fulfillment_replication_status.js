@@ -0,0 +1,104 @@
+const {
+  buildConfig,
+  buildRunner,
+  buildPlayer,
+  mockAddress,
+  mockIdentifier,
+  mockAgentId,
+} = require('../init')
+
+const runner = buildRunner()
+
+const config = buildConfig()
+
+const testEventProps = {
+  action: 'raise',
+  resourceClassifiedAs: ['some-resource-type'],
+  resourceQuantity: { hasNumericalValue: 1, hasUnit: mockIdentifier(false) },
+  provider: mockAgentId(false),
+  receiver: mockAgentId(false),
+  hasPointInTime: '2019-11-19T04:29:55.056Z',
+}
+
+runner.registerScenario('undeliverable writes to the observation DNA are retained for retry', async (s, t) => {
+  const { cells: [planning] } = await buildPlayer(s, config, ['planning'])
+
+  let resp = await planning.call('commitment', 'create_commitment', { commitment: {
+    note: 'a commitment to provide something',
+    ...testEventProps,
+  } })
+  await s.consistency()
+  const commitmentId = resp.commitment.id
+
+  // SCENARIO: fulfill the commitment with an event in an unreachable DNA
+  resp = await planning.call('fulfillment', 'create_fulfillment', { fulfillment: {
+    fulfills: commitmentId,
+    fulfilledBy: mockAddress(false),
+    note: 'fulfillment of an event in another network',
+  } })
+  await s.consistency()
+  t.ok(resp.fulfillment && resp.fulfillment.id, 'fulfillment created despite failed replication')
+  const fulfillmentId = resp.fulfillment.id
+
+  // ASSERT: the failed write is reported to the caller
+  t.equal(resp.replication.length, 1, 'replication status returned with record')
+  t.equal(resp.replication[0].delivered, false, 'replication marked as undelivered')
+  t.equal(resp.replication[0].attempts, 1, 'delivery attempted once')
+  t.ok(resp.replication[0].lastError, 'delivery error reported')
+
+  // ASSERT: the failed write can be queried later
+  resp = await planning.call('fulfillment', 'get_fulfillment_replication_status', { address: fulfillmentId })
+  t.equal(resp.length, 1, 'replication status queryable by record')
+  t.equal(resp[0].method, 'create_fulfillment', 'replicated method recorded')
+  t.equal(resp[0].delivered, false, 'replication remains undelivered')
+
+  // ASSERT: the failed write is retried
+  resp = await planning.call('fulfillment', 'retry_fulfillment_replications', null)
+  t.equal(resp.length, 1, 'undelivered write retried')
+  t.equal(resp[0].attempts, 2, 'retry attempt recorded')
+
+  resp = await planning.call('fulfillment', 'retry_fulfillment_replications', null)
+  t.equal(resp[0].attempts, 3, 'attempt count accumulates across retries')
+  t.ok(resp[0].lastError, 'latest delivery error reported')
+
+  resp = await planning.call('fulfillment', 'get_fulfillment_replication_status', {})
+  t.equal(resp.length, 1, 'replication status queryable for all records')
+})
+
+runner.registerScenario('delivered writes are reported as such', async (s, t) => {
+  const { cells: [planning, observation] } = await buildPlayer(s, config, ['planning', 'observation'])
+
+  let resp = await planning.call('commitment', 'create_commitment', { commitment: {
+    note: 'a commitment to provide something',
+    ...testEventProps,
+  } })
+  const commitmentId = resp.commitment.id
+  resp = await observation.call('economic_event', 'create_economic_event', { event: {
+    note: 'test event which is fulfilling a commitment',
+    ...testEventProps,
+  } })
+  await s.consistency()
+  const eventId = resp.economicEvent.id
+
+  resp = await planning.call('fulfillment', 'create_fulfillment', { fulfillment: {
+    fulfills: commitmentId,
+    fulfilledBy: eventId,
+    note: 'fulfillment indicating the relationship',
+  } })
+  await s.consistency()
+  const fulfillmentId = resp.fulfillment.id
+
+  // ASSERT: successful delivery is reported
+  t.equal(resp.replication.length, 1, 'replication status returned with record')
+  t.equal(resp.replication[0].delivered, true, 'replication delivered')
+  t.notOk(resp.replication[0].lastError, 'no delivery error reported')
+
+  // ASSERT: delivered writes are not retried
+  resp = await planning.call('fulfillment', 'retry_fulfillment_replications', null)
+  t.equal(resp.length, 0, 'nothing left to retry')
+
+  resp = await observation.call('fulfillment', 'get_fulfillment', { address: fulfillmentId })
+  t.deepEqual(resp.fulfillment.fulfilledBy, eventId, 'fulfillment replicated to observation DNA')
+})
+
+runner.run()
@@ -50,19 +50,28 @@ const buildRunner = () => new Orchestrator({
 })
 
 /**
- * Create per-agent interfaces to the DNA
+ * Determine connection options for binding client-side APIs to a player's DNAs
  */
-const buildGraphQL = async (player, apiOptions, appCellIds) => {
+const buildConnectionOptions = async (player, appCellIds) => {
   const appCells = await player.adminWs().listCellIds()
   const appCellMapping = appCells.reduce((r, cell, idx) => {
     r[appCellIds[idx]] = cell
     return r
   }, {})
 
-  const tester = new GQLTester(schema, resolverLoggerMiddleware()(await generateResolvers({
-    ...apiOptions,
+  return {
     conductorUri: player.appWs().client.socket._url,
     dnaConfig: appCellMapping,
+  }
+}
+
+/**
+ * Create per-agent interfaces to the DNA
+ */
+const buildGraphQL = async (player, apiOptions, appCellIds) => {
+  const tester = new GQLTester(schema, resolverLoggerMiddleware()(await generateResolvers({
+    ...apiOptions,
+    ...(await buildConnectionOptions(player, appCellIds)),
     traceAppSignals: (signal) => {
       console.info('App signal received:', signal)
     },
@@ -104,6 +113,8 @@ const buildPlayer = async (scenario, config, agentDNAs, graphQLAPIOptions) => {
   return {
     // :TODO: is it possible to derive GraphQL DNA binding config from underlying Tryorama `config`?
     graphQL: await buildGraphQL(player, graphQLAPIOptions, appCellIds),
+    // connection options for binding other client-side APIs to the same DNAs
+    connection: await buildConnectionOptions(player, appCellIds),
     cells: firstHapp.cells,
     player,
   }
@@ -0,0 +1,152 @@
+const {
+  buildConfig,
+  buildRunner,
+  buildPlayer,
+  mockAgentId,
+  mockIdentifier,
+} = require('../init')
+
+const runner = buildRunner()
+
+const testUnit = mockIdentifier(false)
+const currencyUnit = mockIdentifier(false)
+const testFlowProps = {
+  provider: mockAgentId(false),
+  receiver: mockAgentId(false),
+  hasPointInTime: '2019-11-19T12:12:42.739+01:00',
+  resourceClassifiedAs: ['resource-type-uri'],
+}
+
+runner.registerScenario('process cost roll-up from resource specification prices', async (s, t) => {
+  const { cells: [observation, specification] } = await buildPlayer(s, buildConfig({ playerName: 'alice' }), ['observation', 'specification'])
+
+  // SCENARIO: write records
+  const rsResp = await specification.call('resource_specification', 'create_resource_specification', { resource_specification: {
+    name: 'flour',
+    unitPrice: { hasNumericalValue: 2, hasUnit: currencyUnit },
+  } })
+  await s.consistency()
+  t.ok(rsResp.resourceSpecification && rsResp.resourceSpecification.id, 'resource spec created successfully')
+  t.equal(rsResp.resourceSpecification.unitPrice.hasNumericalValue, 2, 'resource spec unit price stored')
+  const specId = rsResp.resourceSpecification.id
+
+  const pResp = await observation.call('process', 'create_process', { process: { name: 'baking' } })
+  await s.consistency()
+  t.ok(pResp.process && pResp.process.id, 'process created successfully')
+  const processId = pResp.process.id
+
+  const ieResp = await observation.call('economic_event', 'create_economic_event', { event: {
+    action: 'consume',
+    inputOf: processId,
+    resourceConformsTo: specId,
+    resourceQuantity: { hasNumericalValue: 5, hasUnit: testUnit },
+    ...testFlowProps,
+  } })
+  await s.consistency()
+  t.ok(ieResp.economicEvent && ieResp.economicEvent.id, 'input event created successfully')
+  const iEventId = ieResp.economicEvent.id
+
+  const oe1Resp = await observation.call('economic_event', 'create_economic_event', { event: {
+    action: 'produce',
+    outputOf: processId,
+    resourceQuantity: { hasNumericalValue: 3, hasUnit: testUnit },
+    ...testFlowProps,
+  } })
+  const oe2Resp = await observation.call('economic_event', 'create_economic_event', { event: {
+    action: 'produce',
+    outputOf: processId,
+    resourceQuantity: { hasNumericalValue: 2, hasUnit: testUnit },
+    ...testFlowProps,
+  } })
+  await s.consistency()
+  t.ok(oe1Resp.economicEvent && oe2Resp.economicEvent, 'output events created successfully')
+
+  // ASSERT: check input costing
+  const { processCost } = await observation.call('process', 'get_process_cost', { address: processId })
+  t.deepEqual(processCost.process, processId, 'cost calculated for correct process')
+  t.equal(processCost.inputs.length, 1, 'input costed')
+  t.deepEqual(processCost.inputs[0].economicEvent, iEventId, 'input cost references event')
+  t.equal(processCost.inputs[0].priceSource, 'resourceSpecification', 'input priced from resource specification')
+  t.equal(processCost.inputs[0].cost.hasNumericalValue, 10, 'input cost is quantity multiplied by unit price')
+  t.equal(processCost.totalCost.length, 1, 'total cost kept in a single currency')
+  t.equal(processCost.totalCost[0].hasNumericalValue, 10, 'total cost summed')
+
+  // ASSERT: check allocation to outputs
+  t.equal(processCost.outputs.length, 2, 'outputs costed')
+  t.equal(processCost.outputs[0].cost[0].hasNumericalValue, 6, 'cost allocated proportionally to first output')
+  t.equal(processCost.outputs[1].cost[0].hasNumericalValue, 4, 'cost allocated proportionally to second output')
+  t.equal(processCost.outputs[0].unitCost[0].hasNumericalValue, 2, 'unit cost of output calculated')
+})
+
+runner.registerScenario('process costs are shared by all downstream paths through the process graph', async (s, t) => {
+  const { cells: [observation, specification] } = await buildPlayer(s, buildConfig({ playerName: 'alice' }), ['observation', 'specification'])
+
+  const rsResp = await specification.call('resource_specification', 'create_resource_specification', { resource_specification: {
+    name: 'wheat',
+    unitPrice: { hasNumericalValue: 2, hasUnit: currencyUnit },
+  } })
+  await s.consistency()
+  const specId = rsResp.resourceSpecification.id
+
+  const createProcess = async (name) => {
+    const resp = await observation.call('process', 'create_process', { process: { name } })
+    await s.consistency()
+    return resp.process.id
+  }
+  const consume = async (processId, resourceId, qty) => {
+    await observation.call('economic_event', 'create_economic_event', { event: {
+      action: 'consume',
+      inputOf: processId,
+      resourceInventoriedAs: resourceId,
+      resourceQuantity: { hasNumericalValue: qty, hasUnit: testUnit },
+      ...testFlowProps,
+    } })
+    await s.consistency()
+  }
+  const produce = async (processId, name, qty) => {
+    const resp = await observation.call('economic_event', 'create_economic_event', {
+      event: {
+        action: 'produce',
+        outputOf: processId,
+        resourceQuantity: { hasNumericalValue: qty, hasUnit: testUnit },
+        ...testFlowProps,
+      },
+      newInventoriedResource: { name },
+    })
+    await s.consistency()
+    return resp.economicResource.id
+  }
+
+  // SCENARIO: a 'diamond' of processes, where two intermediate processes draw from the same source process
+  const harvest = await createProcess('harvest')
+  await observation.call('economic_event', 'create_economic_event', { event: {
+    action: 'consume',
+    inputOf: harvest,
+    resourceConformsTo: specId,
+    resourceQuantity: { hasNumericalValue: 4, hasUnit: testUnit },
+    ...testFlowProps,
+  } })
+  await s.consistency()
+  const grain = await produce(harvest, 'grain', 4)
+
+  const milling = await createProcess('milling')
+  await consume(milling, grain, 2)
+  const flour = await produce(milling, 'flour', 2)
+
+  const malting = await createProcess('malting')
+  await consume(malting, grain, 2)
+  const malt = await produce(malting, 'malt', 2)
+
+  const baking = await createProcess('baking')
+  await consume(baking, flour, 1)
+  await consume(baking, malt, 1)
+
+  // ASSERT: both paths to the source process are priced from it
+  const { processCost } = await observation.call('process', 'get_process_cost', { address: baking })
+  t.equal(processCost.inputs.length, 2, 'both inputs costed')
+  t.ok(processCost.inputs.every(i => i.priceSource === 'upstreamProcess'), 'both inputs priced from their upstream processes')
+  t.ok(processCost.inputs.every(i => i.cost && i.cost.hasNumericalValue === 2), 'both inputs carry the cost of the shared source process')
+  t.equal(processCost.totalCost[0].hasNumericalValue, 4, 'total cost includes both paths')
+})
+
+runner.run()
@@ -0,0 +1,82 @@
+const {
+  buildConfig,
+  buildRunner,
+  buildPlayer,
+  mockAgentId,
+  mockIdentifier,
+} = require('../init')
+
+const runner = buildRunner()
+
+const testUnit = mockIdentifier(false)
+const testFlowProps = {
+  provider: mockAgentId(false),
+  receiver: mockAgentId(false),
+  hasPointInTime: '2019-11-19T12:12:42.739+01:00',
+  resourceClassifiedAs: ['resource-type-uri'],
+}
+
+runner.registerScenario('process plan vs actual variance report', async (s, t) => {
+  const { cells: [observation, planning] } = await buildPlayer(s, buildConfig({ playerName: 'alice' }), ['observation', 'planning'])
+
+  // SCENARIO: write records
+  const pResp = await observation.call('process', 'create_process', { process: { name: 'test process for reporting' } })
+  t.ok(pResp.process && pResp.process.id, 'process created successfully')
+  await s.consistency()
+  const processId = pResp.process.id
+
+  const icResp = await planning.call('commitment', 'create_commitment', { commitment: {
+    note: 'planned input',
+    action: 'consume',
+    inputOf: processId,
+    resourceQuantity: { hasNumericalValue: 10, hasUnit: testUnit },
+    ...testFlowProps,
+  } })
+  t.ok(icResp.commitment && icResp.commitment.id, 'input commitment created successfully')
+  await s.consistency()
+  const iCommitmentId = icResp.commitment.id
+
+  const ieResp = await observation.call('economic_event', 'create_economic_event', { event: {
+    note: 'observed input',
+    action: 'consume',
+    inputOf: processId,
+    resourceQuantity: { hasNumericalValue: 7, hasUnit: testUnit },
+    ...testFlowProps,
+  } })
+  t.ok(ieResp.economicEvent && ieResp.economicEvent.id, 'input event created successfully')
+  await s.consistency()
+  const iEventId = ieResp.economicEvent.id
+
+  const oeResp = await observation.call('economic_event', 'create_economic_event', { event: {
+    note: 'unplanned output',
+    action: 'produce',
+    outputOf: processId,
+    resourceQuantity: { hasNumericalValue: 1, hasUnit: testUnit },
+    ...testFlowProps,
+  } })
+  t.ok(oeResp.economicEvent && oeResp.economicEvent.id, 'output event created successfully')
+  await s.consistency()
+  const oEventId = oeResp.economicEvent.id
+
+  // ASSERT: check report groupings
+  const { processReport } = await observation.call('process', 'get_process_report', { address: processId })
+  t.deepEqual(processReport.process, processId, 'report generated for correct process')
+
+  t.equal(processReport.inputs.length, 1, 'input flows grouped by action & resource specification')
+  t.equal(processReport.inputs[0].action, 'consume', 'input group action OK')
+  t.deepEqual(processReport.inputs[0].commitments, [iCommitmentId], 'input group references commitment')
+  t.deepEqual(processReport.inputs[0].economicEvents, [iEventId], 'input group references event')
+  t.equal(processReport.inputs[0].plannedResourceQuantity.hasNumericalValue, 10, 'planned input quantity totalled')
+  t.equal(processReport.inputs[0].observedResourceQuantity.hasNumericalValue, 7, 'observed input quantity totalled')
+  t.equal(processReport.inputs[0].resourceQuantityVariance.hasNumericalValue, -3, 'input variance calculated')
+
+  t.equal(processReport.outputs.length, 1, 'output flows grouped')
+  t.notOk(processReport.outputs[0].plannedResourceQuantity, 'unplanned output has no planned quantity')
+  t.equal(processReport.outputs[0].resourceQuantityVariance.hasNumericalValue, 1, 'unplanned output variance is observed quantity')
+
+  // ASSERT: check fulfillment status
+  t.deepEqual(processReport.unplannedEconomicEvents, [iEventId, oEventId], 'events without fulfillments reported as unplanned')
+  t.deepEqual(processReport.unfulfilledCommitments, [iCommitmentId], 'unfulfilled commitment reported')
+})
+
+runner.run()
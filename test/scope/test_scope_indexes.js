@@ -0,0 +1,69 @@
+const {
+  buildConfig,
+  buildRunner,
+  buildPlayer,
+  mockAgentId,
+  mockIdentifier,
+} = require('../init')
+
+const runner = buildRunner()
+
+const orgA = mockAgentId(false)
+const orgB = mockAgentId(false)
+
+const testFlowProps = {
+  action: 'transfer-custody',
+  provider: mockAgentId(false),
+  receiver: mockAgentId(false),
+  hasPointInTime: '2019-11-19T12:12:42.739+01:00',
+  resourceClassifiedAs: ['resource-type-uri'],
+  resourceQuantity: { hasNumericalValue: 1, hasUnit: mockIdentifier(false) },
+}
+
+runner.registerScenario('records indexed by inScopeOf', async (s, t) => {
+  const { cells: [observation, planning, proposal] } = await buildPlayer(s, buildConfig({ playerName: 'alice' }), ['observation', 'planning', 'proposal'])
+
+  // SCENARIO: write records
+  let resp = await observation.call('process', 'create_process', { process: { name: 'scoped process', inScopeOf: [orgA] } })
+  const processId = resp.process.id
+  const processRevisionId = resp.process.revisionId
+
+  resp = await observation.call('economic_event', 'create_economic_event', { event: { inScopeOf: [orgA, orgB], ...testFlowProps } })
+  const eventId = resp.economicEvent.id
+
+  resp = await planning.call('commitment', 'create_commitment', { commitment: { inScopeOf: [orgB], ...testFlowProps } })
+  const commitmentId = resp.commitment.id
+
+  resp = await planning.call('intent', 'create_intent', { intent: { inScopeOf: [orgA], ...testFlowProps } })
+  const intentId = resp.intent.id
+
+  resp = await proposal.call('proposal', 'create_proposal', { proposal: { name: 'scoped proposal', inScopeOf: [orgA] } })
+  const proposalId = resp.proposal.id
+  await s.consistency()
+
+  // ASSERT: scope queries
+  resp = await observation.call('process_index', 'query_processes', { params: { inScopeOf: orgA } })
+  t.deepEqual(resp.results.map(r => r.process.id), [processId], 'processes queryable by scope')
+  resp = await observation.call('economic_event_index', 'query_economic_events', { params: { inScopeOf: orgB } })
+  t.deepEqual(resp.results.map(r => r.economicEvent.id), [eventId], 'events queryable by any of multiple scopes')
+  resp = await planning.call('commitment_index', 'query_commitments', { params: { inScopeOf: orgB } })
+  t.deepEqual(resp.results.map(r => r.commitment.id), [commitmentId], 'commitments queryable by scope')
+  resp = await planning.call('commitment_index', 'query_commitments', { params: { inScopeOf: orgA } })
+  t.equal(resp.results.length, 0, 'commitments outside of scope not returned')
+  resp = await planning.call('intent_index', 'query_intents', { params: { inScopeOf: orgA } })
+  t.deepEqual(resp.results.map(r => r.intent.id), [intentId], 'intents queryable by scope')
+  resp = await proposal.call('proposal_index', 'query_proposals', { params: { inScopeOf: orgA } })
+  t.deepEqual(resp.results.map(r => r.proposal.id), [proposalId], 'proposals queryable by scope')
+
+  // SCENARIO: move process to another scope
+  await observation.call('process', 'update_process', { process: { revisionId: processRevisionId, inScopeOf: [orgB] } })
+  await s.consistency()
+
+  // ASSERT: stale scope indexes removed
+  resp = await observation.call('process_index', 'query_processes', { params: { inScopeOf: orgA } })
+  t.equal(resp.results.length, 0, 'process scope index removed on update')
+  resp = await observation.call('process_index', 'query_processes', { params: { inScopeOf: orgB } })
+  t.deepEqual(resp.results.map(r => r.process.id), [processId], 'process scope index added on update')
+})
+
+runner.run()
@@ -0,0 +1,66 @@
+const {
+  buildConfig,
+  buildRunner,
+  buildPlayer,
+} = require('../init')
+
+const runner = buildRunner()
+
+const config = buildConfig()
+
+runner.registerScenario('Unit symbol uniqueness', async (s, t) => {
+  const { cells: [alice] } = await buildPlayer(s, config, ['specification'])
+
+  // SCENARIO: create units
+  let resp = await alice.call('unit', 'create_unit', { unit: { label: 'kilograms', symbol: 'kg' } })
+  await s.consistency()
+  t.ok(resp.unit && resp.unit.id, 'unit created successfully')
+  const kgRevisionId = resp.unit.revisionId
+
+  resp = await alice.call('unit', 'create_unit', { unit: { label: 'grams', symbol: 'g' } })
+  await s.consistency()
+  const gRevisionId = resp.unit.revisionId
+
+  // ASSERT: duplicate symbols rejected on create
+  try {
+    await alice.call('unit', 'create_unit', { unit: { label: 'kilos', symbol: 'kg' } })
+    t.fail('duplicate unit symbol accepted')
+  } catch (e) {
+    t.ok(e.toString().match(/already in use/), 'duplicate unit symbol rejected on create')
+  }
+
+  // ASSERT: duplicate symbols rejected on update
+  try {
+    await alice.call('unit', 'update_unit', { unit: { revisionId: gRevisionId, label: 'grams', symbol: 'kg' } })
+    t.fail('duplicate unit symbol accepted on update')
+  } catch (e) {
+    t.ok(e.toString().match(/already in use/), 'duplicate unit symbol rejected on update')
+  }
+
+  // ASSERT: renamed symbols are released
+  resp = await alice.call('unit', 'update_unit', { unit: { revisionId: kgRevisionId, label: 'kilograms', symbol: 'kgs' } })
+  await s.consistency()
+  t.equal(resp.unit.symbol, 'kgs', 'unit symbol updated')
+  const kgsId = resp.unit.id
+  const kgsRevisionId = resp.unit.revisionId
+
+  resp = await alice.call('unit', 'get_unit', { id: kgsId })
+  t.equal(resp.unit.label, 'kilograms', 'renamed unit readable via new symbol')
+
+  resp = await alice.call('unit', 'create_unit', { unit: { label: 'kilograms (old)', symbol: 'kg' } })
+  await s.consistency()
+  t.equal(resp.unit.symbol, 'kg', 'previous symbol reusable after rename')
+
+  // ASSERT: deleted symbols are released
+  await alice.call('unit', 'delete_unit', { address: kgsRevisionId })
+  await s.consistency()
+
+  resp = await alice.call('unit', 'create_unit', { unit: { label: 'kilograms (new)', symbol: 'kgs' } })
+  await s.consistency()
+  t.equal(resp.unit.symbol, 'kgs', 'symbol reusable after delete')
+
+  resp = await alice.call('unit', 'get_unit', { id: kgsId })
+  t.equal(resp.unit.label, 'kilograms (new)', 'reused symbol resolves to new record')
+})
+
+runner.run()
@@ -8,6 +8,7 @@
  */
 use hdk::prelude::*;
 
+use hdk_rpc_errors::{ ErrorCode, ErrorDetails };
 use vf_attributes_hdk::{
     ActionId,
 };
@@ -26,7 +27,11 @@ struct ById {
 fn get_action(ById { id }: ById) -> ExternResult<Action> {
     match get_builtin_action(id.as_ref()) {
         Some(action) => Ok(action),
-        None => Err(WasmError::Guest(format!("No action with ID '{}' available", id.as_ref()))),
+        None => Err(WasmError::Guest(
+            ErrorDetails::new(ErrorCode::EntryNotFound, format!("No action with ID '{}' available", id.as_ref()))
+                .at_field("id")
+                .encode()
+        )),
     }
 }
 
@@ -12,10 +12,23 @@ use hdk_records::{
     records::{
         create_record,
         read_record_entry,
+        read_record_entry_by_header,
         update_record,
+        read_record_revision_heads,
+        merge_record_revisions,
         delete_record,
     },
+    history::{
+        Timestamp,
+        read_record_revision_history,
+        read_record_entry_as_of,
+        diff_record_revisions,
+    },
+    local_indexes::query_root_index_page,
 };
+pub use hdk_records::history::{RevisionMeta, RevisionDiff};
+pub use vf_jsonld::{ExportParams, ExportPage};
+use vf_jsonld::{JsonLdNode, build_export_page};
 use hdk_semantic_indexes_client_lib::*;
 
 use hc_zome_rea_agreement_storage::*;
@@ -45,10 +58,61 @@ pub fn handle_update_agreement<S>(entry_def_id: S, agreement: UpdateRequest) ->
     construct_response(&identity_address, revision_id, &entry, get_link_fields(&identity_address)?)
 }
 
+pub fn handle_get_agreement_revision_heads<S>(entry_def_id: S, address: AgreementAddress) -> RecordAPIResult<Vec<RevisionHash>>
+    where S: AsRef<str>
+{
+    read_record_revision_heads::<AgreementAddress, _>(&entry_def_id, address.as_ref())
+}
+
+pub fn handle_merge_agreement_revisions<S>(entry_def_id: S, revision_id: RevisionHash, superseded_revisions: Vec<RevisionHash>) -> RecordAPIResult<ResponseData>
+    where S: AsRef<str>
+{
+    let (_revision, base_address, _entry, _superseded): (_, AgreementAddress, EntryData, _) = merge_record_revisions::<EntryData, EntryStorage, _>(&revision_id, superseded_revisions.as_slice())?;
+    handle_get_agreement(entry_def_id, base_address)
+}
+
+pub fn handle_get_agreement_history<S>(entry_def_id: S, address: AgreementAddress) -> RecordAPIResult<Vec<RevisionMeta>>
+    where S: AsRef<str>
+{
+    read_record_revision_history::<AgreementAddress, _>(&entry_def_id, address.as_ref())
+}
+
+pub fn handle_get_agreement_at_revision(revision_id: RevisionHash) -> RecordAPIResult<ResponseData>
+{
+    let (base_address, entry) = read_record_entry_by_header::<EntryData, EntryStorage, _>(&revision_id)?;
+    construct_response(&base_address, revision_id, &entry, get_link_fields(&base_address)?)
+}
+
+pub fn handle_get_agreement_as_of<S>(entry_def_id: S, address: AgreementAddress, as_of: Timestamp) -> RecordAPIResult<ResponseData>
+    where S: AsRef<str>
+{
+    let (revision, base_address, entry) = read_record_entry_as_of::<EntryData, EntryStorage, _,_>(&entry_def_id, address.as_ref(), &as_of)?;
+    // link fields are omitted, since indexes do not retain their history
+    construct_response(&base_address, revision, &entry, Default::default())
+}
+
+pub fn handle_diff_agreement_revisions(from_revision_id: RevisionHash, to_revision_id: RevisionHash) -> RecordAPIResult<RevisionDiff>
+{
+    diff_record_revisions::<EntryData, EntryStorage, AgreementAddress>(&from_revision_id, &to_revision_id)
+}
+
 pub fn handle_delete_agreement(address: RevisionHash) -> RecordAPIResult<bool> {
     delete_record::<EntryData, RevisionHash>(&address)
 }
 
+/// Export a page of all agreements in the DNA as ValueFlows JSON-LD
+///
+pub fn handle_export_agreements<S>(entry_def_id: S, params: ExportParams) -> RecordAPIResult<ExportPage>
+    where S: AsRef<str>
+{
+    let limit = params.get_limit();
+    let page = query_root_index_page::<EntryData, EntryStorage, _,_>(&entry_def_id, &params.after, limit)?;
+    build_export_page(page, limit, |(revision, base_address, entry)| {
+        let link_fields = get_link_fields(&base_address)?;
+        Ok(construct_jsonld(&construct_response(&base_address, revision, &entry, link_fields)?.agreement))
+    })
+}
+
 /// Create response from input DHT primitives
 fn construct_response<'a>(
     address: &AgreementAddress, revision: RevisionHash, e: &EntryData, (
@@ -72,6 +136,18 @@ fn construct_response<'a>(
     })
 }
 
+/// Create a ValueFlows JSON-LD node from an output record
+///
+/// Inverse relationships (`commitments` & `economicEvents`) are omitted, as they
+/// are expressed by the records on the other side of the relationship.
+///
+pub fn construct_jsonld(r: &Response) -> JsonLdNode {
+    JsonLdNode::new("Agreement", &r.id)
+        .field("name", &r.name)
+        .field("created", &r.created)
+        .field("note", &r.note)
+}
+
 //---------------- READ ----------------
 
 /// Properties accessor for zome config
@@ -11,6 +11,26 @@ use hdk::prelude::*;
 
 use hc_zome_rea_agreement_rpc::*;
 use hc_zome_rea_agreement_lib::*;
+use hc_zome_rea_agreement_storage::EntryStorage;
+use hdk_records::validation::{get_modified_revision, validate_modification_author};
+
+#[hdk_extern]
+fn validate(validation_data: ValidateData) -> ExternResult<ValidateCallbackResult> {
+    let element = validation_data.element;
+    let (signed_header, _entry) = element.into_inner();
+    let header = signed_header.header();
+
+    // agreements may only be updated or deleted by their original author
+    if let Some(modified) = get_modified_revision(header)? {
+        if EntryStorage::try_from(&modified.entry).is_ok() {
+            if let Err(e) = validate_modification_author(header, &modified, &[]) {
+                return Ok(ValidateCallbackResult::Invalid(e.encode()));
+            }
+        }
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
 
 #[hdk_extern]
 fn entry_defs(_: ()) -> ExternResult<EntryDefsCallbackResult> {
@@ -56,6 +76,36 @@ fn update_agreement(UpdateParams { agreement }: UpdateParams) -> ExternResult<Re
     Ok(handle_update_agreement(AGREEMENT_ENTRY_TYPE, agreement)?)
 }
 
+#[hdk_extern]
+fn get_agreement_revision_heads(ReadParams { address }: ReadParams) -> ExternResult<Vec<RevisionHash>> {
+    Ok(handle_get_agreement_revision_heads(AGREEMENT_ENTRY_TYPE, address)?)
+}
+
+#[hdk_extern]
+fn merge_agreement_revisions(MergeRevisions { revision_id, superseded_revisions }: MergeRevisions) -> ExternResult<ResponseData> {
+    Ok(handle_merge_agreement_revisions(AGREEMENT_ENTRY_TYPE, revision_id, superseded_revisions)?)
+}
+
+#[hdk_extern]
+fn get_agreement_history(ReadParams { address }: ReadParams) -> ExternResult<Vec<RevisionMeta>> {
+    Ok(handle_get_agreement_history(AGREEMENT_ENTRY_TYPE, address)?)
+}
+
+#[hdk_extern]
+fn get_agreement_at_revision(ByHeader { address }: ByHeader) -> ExternResult<ResponseData> {
+    Ok(handle_get_agreement_at_revision(address)?)
+}
+
+#[hdk_extern]
+fn get_agreement_as_of(ByAddressAsOf { address, as_of }: ByAddressAsOf<AgreementAddress>) -> ExternResult<ResponseData> {
+    Ok(handle_get_agreement_as_of(AGREEMENT_ENTRY_TYPE, address, as_of)?)
+}
+
+#[hdk_extern]
+fn diff_agreement_revisions(ByRevisionPair { from_revision_id, to_revision_id }: ByRevisionPair) -> ExternResult<RevisionDiff> {
+    Ok(handle_diff_agreement_revisions(from_revision_id, to_revision_id)?)
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct DeleteParams {
     pub address: RevisionHash,
@@ -65,3 +115,8 @@ struct DeleteParams {
 fn delete_agreement(DeleteParams { address }: DeleteParams) -> ExternResult<bool> {
     Ok(handle_delete_agreement(address)?)
 }
+
+#[hdk_extern]
+fn export_agreements(params: ExportParams) -> ExternResult<ExportPage> {
+    Ok(handle_export_agreements(AGREEMENT_ENTRY_TYPE, params)?)
+}
@@ -8,16 +8,30 @@
  */
 use paste::paste;
 use hdk_records::{
-    RecordAPIResult, MaybeUndefined,
+    RecordAPIResult, DataIntegrityError, MaybeUndefined, EntryHash,
     records::{
         create_record,
         read_record_entry,
         read_record_entry_by_header,
         update_record,
+        read_record_revision_heads,
+        merge_record_revisions,
+        get_index_changes,
         delete_record,
     },
+    history::{
+        Timestamp,
+        read_record_revision_history,
+        read_record_entry_as_of,
+        diff_record_revisions,
+    },
+    local_indexes::query_root_index_page,
 };
+pub use hdk_records::history::{RevisionMeta, RevisionDiff};
+pub use vf_jsonld::{ExportParams, ExportPage};
+use vf_jsonld::{JsonLdNode, build_export_page};
 use hdk_semantic_indexes_client_lib::*;
+use vf_attributes_hdk::{get_scope_agents, get_scoped_agents, get_involved_agents};
 
 use hc_zome_rea_commitment_storage::*;
 use hc_zome_rea_commitment_rpc::*;
@@ -41,6 +55,21 @@ pub fn handle_create_commitment<S>(entry_def_id: S, commitment: CreateRequest) -
         create_index!(Remote(commitment.clause_of(clause_of), agreement.commitments(&base_address)))?;
     };
 
+    // index involved agents
+    let provider: AgentRecordAddress = entry_resp.provider.to_owned().into();
+    let receiver: AgentRecordAddress = entry_resp.receiver.to_owned().into();
+    create_index!(Self(commitment(&base_address).provider(&provider)))?;
+    create_index!(Self(commitment(&base_address).receiver(&receiver)))?;
+    update_index!(Self(commitment(&base_address).involved_agent(
+        get_involved_agents(&Some(entry_resp.provider.to_owned()), &Some(entry_resp.receiver.to_owned())).as_slice()
+    )))?;
+
+    // index scope
+    update_index!(Self(commitment(&base_address).in_scope_of(get_scope_agents(&entry_resp.in_scope_of).as_slice())))?;
+
+    // index classifications
+    update_index!(Keyed(commitment(&base_address).classified_as(entry_resp.resource_classified_as.to_owned().unwrap_or_default().as_slice())))?;
+
     // :TODO: pass results from link creation rather than re-reading
     construct_response(&base_address, &header_addr, &entry_resp, get_link_fields(&base_address)?)
 }
@@ -57,40 +86,118 @@ pub fn handle_update_commitment<S>(entry_def_id: S, commitment: UpdateRequest) -
 {
     let address = commitment.get_revision_id().to_owned();
     let (revision_id, base_address, new_entry, prev_entry): (_, CommitmentAddress, EntryData, EntryData) = update_record(&entry_def_id, &address, commitment.to_owned())?;
+    update_commitment_indexes(&base_address, &new_entry, &prev_entry, None)?;
+
+    construct_response(&base_address, &revision_id, &new_entry, get_link_fields(&base_address)?)
+}
 
+/// Update the index links of a commitment as it moves from `prev_entry` to `new_entry`.
+/// `parent_entry` is given where `prev_entry` is a superseded revision being merged.
+///
+/// @see hdk_records::records::get_index_changes
+///
+fn update_commitment_indexes(
+    base_address: &CommitmentAddress, new_entry: &EntryData, prev_entry: &EntryData, parent_entry: Option<&EntryData>,
+) -> RecordAPIResult<()> {
     // handle link fields
-    if new_entry.input_of != prev_entry.input_of {
-        let new_value = match &new_entry.input_of { Some(val) => vec![val.to_owned()], None => vec![] };
-        let prev_value = match &prev_entry.input_of { Some(val) => vec![val.to_owned()], None => vec![] };
+    let (added, removed) = get_index_changes(new_entry, prev_entry, parent_entry, |e| { e.input_of.iter().cloned().collect() });
+    if !(added.is_empty() && removed.is_empty()) {
         update_index!(Remote(
             commitment
-                .input_of(new_value.as_slice())
-                .not(prev_value.as_slice()),
-            process.committed_inputs(&base_address)
+                .input_of(added.as_slice())
+                .not(removed.as_slice()),
+            process.committed_inputs(base_address)
         ))?;
     }
-    if new_entry.output_of != prev_entry.output_of {
-        let new_value = match &new_entry.output_of { Some(val) => vec![val.to_owned()], None => vec![] };
-        let prev_value = match &prev_entry.output_of { Some(val) => vec![val.to_owned()], None => vec![] };
+    let (added, removed) = get_index_changes(new_entry, prev_entry, parent_entry, |e| { e.output_of.iter().cloned().collect() });
+    if !(added.is_empty() && removed.is_empty()) {
         update_index!(Remote(
             commitment
-                .output_of(new_value.as_slice())
-                .not(prev_value.as_slice()),
-            process.committed_outputs(&base_address)
+                .output_of(added.as_slice())
+                .not(removed.as_slice()),
+            process.committed_outputs(base_address)
         ))?;
     }
-    if new_entry.clause_of != prev_entry.clause_of {
-        let new_value = match &new_entry.clause_of { Some(val) => vec![val.to_owned()], None => vec![] };
-        let prev_value = match &prev_entry.clause_of { Some(val) => vec![val.to_owned()], None => vec![] };
+    let (added, removed) = get_index_changes(new_entry, prev_entry, parent_entry, |e| { e.clause_of.iter().cloned().collect() });
+    if !(added.is_empty() && removed.is_empty()) {
         update_index!(Remote(
             commitment
-                .clause_of(new_value.as_slice())
-                .not(prev_value.as_slice()),
-            agreement.commitments(&base_address)
+                .clause_of(added.as_slice())
+                .not(removed.as_slice()),
+            agreement.commitments(base_address)
         ))?;
     }
 
-    construct_response(&base_address, &revision_id, &new_entry, get_link_fields(&base_address)?)
+    // index involved agents
+    let (added, removed): (Vec<AgentRecordAddress>, _) = get_index_changes(new_entry, prev_entry, parent_entry, |e| { vec![e.provider.to_owned().into()] });
+    if !(added.is_empty() && removed.is_empty()) {
+        update_index!(Self(commitment(base_address).provider(added.as_slice()).not(removed.as_slice())))?;
+    }
+    let (added, removed): (Vec<AgentRecordAddress>, _) = get_index_changes(new_entry, prev_entry, parent_entry, |e| { vec![e.receiver.to_owned().into()] });
+    if !(added.is_empty() && removed.is_empty()) {
+        update_index!(Self(commitment(base_address).receiver(added.as_slice()).not(removed.as_slice())))?;
+    }
+    let (added, removed) = get_index_changes(new_entry, prev_entry, parent_entry, |e| {
+        get_involved_agents(&Some(e.provider.to_owned()), &Some(e.receiver.to_owned()))
+    });
+    if !(added.is_empty() && removed.is_empty()) {
+        update_index!(Self(commitment(base_address).involved_agent(added.as_slice()).not(removed.as_slice())))?;
+    }
+
+    // index scope
+    let (added, removed) = get_index_changes(new_entry, prev_entry, parent_entry, |e| { get_scope_agents(&e.in_scope_of) });
+    if !(added.is_empty() && removed.is_empty()) {
+        update_index!(Self(commitment(base_address).in_scope_of(added.as_slice()).not(removed.as_slice())))?;
+    }
+
+    // index classifications
+    let (added, removed): (Vec<ExternalURL>, _) = get_index_changes(new_entry, prev_entry, parent_entry, |e| { e.resource_classified_as.to_owned().unwrap_or_default() });
+    if !(added.is_empty() && removed.is_empty()) {
+        update_index!(Keyed(commitment(base_address).classified_as(added.as_slice()).not(removed.as_slice())))?;
+    }
+
+    Ok(())
+}
+
+pub fn handle_get_commitment_revision_heads<S>(entry_def_id: S, address: CommitmentAddress) -> RecordAPIResult<Vec<RevisionHash>>
+    where S: AsRef<str>
+{
+    read_record_revision_heads::<CommitmentAddress, _>(&entry_def_id, address.as_ref())
+}
+
+pub fn handle_merge_commitment_revisions<S>(entry_def_id: S, revision_id: RevisionHash, superseded_revisions: Vec<RevisionHash>) -> RecordAPIResult<ResponseData>
+    where S: AsRef<str>
+{
+    let (_revision, base_address, entry, superseded): (_, CommitmentAddress, EntryData, _) = merge_record_revisions::<EntryData, EntryStorage, _>(&revision_id, superseded_revisions.as_slice())?;
+    for (superseded_entry, parent_entry) in superseded.iter() {
+        update_commitment_indexes(&base_address, &entry, superseded_entry, Some(parent_entry))?;
+    }
+    handle_get_commitment(entry_def_id, base_address)
+}
+
+pub fn handle_get_commitment_history<S>(entry_def_id: S, address: CommitmentAddress) -> RecordAPIResult<Vec<RevisionMeta>>
+    where S: AsRef<str>
+{
+    read_record_revision_history::<CommitmentAddress, _>(&entry_def_id, address.as_ref())
+}
+
+pub fn handle_get_commitment_at_revision(revision_id: RevisionHash) -> RecordAPIResult<ResponseData>
+{
+    let (base_address, entry) = read_record_entry_by_header::<EntryData, EntryStorage, _>(&revision_id)?;
+    construct_response(&base_address, &revision_id, &entry, get_link_fields(&base_address)?)
+}
+
+pub fn handle_get_commitment_as_of<S>(entry_def_id: S, address: CommitmentAddress, as_of: Timestamp) -> RecordAPIResult<ResponseData>
+    where S: AsRef<str>
+{
+    let (revision, base_address, entry) = read_record_entry_as_of::<EntryData, EntryStorage, _,_>(&entry_def_id, address.as_ref(), &as_of)?;
+    // link fields are omitted, since indexes do not retain their history
+    construct_response(&base_address, &revision, &entry, Default::default())
+}
+
+pub fn handle_diff_commitment_revisions(from_revision_id: RevisionHash, to_revision_id: RevisionHash) -> RecordAPIResult<RevisionDiff>
+{
+    diff_record_revisions::<EntryData, EntryStorage, CommitmentAddress>(&from_revision_id, &to_revision_id)
 }
 
 pub fn handle_delete_commitment(revision_id: RevisionHash) -> RecordAPIResult<bool>
@@ -98,7 +205,26 @@ pub fn handle_delete_commitment(revision_id: RevisionHash) -> RecordAPIResult<bo
     // load the record to ensure it is of the correct type
     let (base_address, entry) = read_record_entry_by_header::<EntryData, EntryStorage, _>(&revision_id)?;
 
+    // refuse to delete commitments which other records still reference
+    let (fulfillments, satisfactions, _involved_agents) = get_link_fields(&base_address)?;
+    if !fulfillments.is_empty() || !satisfactions.is_empty() {
+        let identity_hash: &EntryHash = base_address.as_ref();
+        return Err(DataIntegrityError::RecordInUse(
+            identity_hash.to_owned(),
+            format!("{} fulfillment(s) and {} satisfaction(s)", fulfillments.len(), satisfactions.len()),
+        ));
+    }
+
     // handle link fields
+    let provider: AgentRecordAddress = entry.provider.to_owned().into();
+    let receiver: AgentRecordAddress = entry.receiver.to_owned().into();
+    update_index!(Self(commitment(&base_address).provider.not(&vec![provider])))?;
+    update_index!(Self(commitment(&base_address).receiver.not(&vec![receiver])))?;
+    update_index!(Self(commitment(&base_address).involved_agent.not(
+        get_involved_agents(&Some(entry.provider.to_owned()), &Some(entry.receiver.to_owned())).as_slice()
+    )))?;
+    update_index!(Self(commitment(&base_address).in_scope_of.not(get_scope_agents(&entry.in_scope_of).as_slice())))?;
+    update_index!(Keyed(commitment(&base_address).classified_as.not(entry.resource_classified_as.to_owned().unwrap_or_default().as_slice())))?;
     if let Some(process_address) = entry.input_of {
         update_index!(Remote(commitment.input_of.not(&vec![process_address]), process.committed_inputs(&base_address)))?;
     }
@@ -113,6 +239,19 @@ pub fn handle_delete_commitment(revision_id: RevisionHash) -> RecordAPIResult<bo
     delete_record::<EntryStorage, _>(&revision_id)
 }
 
+/// Export a page of all commitments in the DNA as ValueFlows JSON-LD
+///
+pub fn handle_export_commitments<S>(entry_def_id: S, params: ExportParams) -> RecordAPIResult<ExportPage>
+    where S: AsRef<str>
+{
+    let limit = params.get_limit();
+    let page = query_root_index_page::<EntryData, EntryStorage, _,_>(&entry_def_id, &params.after, limit)?;
+    build_export_page(page, limit, |(revision, base_address, entry)| {
+        let link_fields = get_link_fields(&base_address)?;
+        Ok(construct_jsonld(&construct_response(&base_address, &revision, &entry, link_fields)?.commitment))
+    })
+}
+
 /// Create response from input DHT primitives
 fn construct_response<'a>(
     address: &CommitmentAddress, revision_id: &RevisionHash, e: &EntryData, (
@@ -150,7 +289,7 @@ fn construct_response<'a>(
             clause_of: e.clause_of.to_owned(),
             independent_demand_of: e.independent_demand_of.to_owned(),
             finished: e.finished.to_owned(),
-            in_scope_of: e.in_scope_of.to_owned(),
+            in_scope_of: get_scoped_agents(&e.in_scope_of),
             fulfilled_by: fulfillments.to_owned(),
             satisfies: satisfactions.to_owned(),
             involved_agents: involved_agents.to_owned(),
@@ -158,6 +297,37 @@ fn construct_response<'a>(
     })
 }
 
+/// Create a ValueFlows JSON-LD node from an output record
+///
+/// Inverse relationships (eg. `fulfilledBy`) are omitted, as they are
+/// expressed by the records on the other side of the relationship.
+///
+pub fn construct_jsonld(r: &Response) -> JsonLdNode {
+    JsonLdNode::new("Commitment", &r.id)
+        .action("action", &r.action)
+        .reference("inputOf", &r.input_of)
+        .reference("outputOf", &r.output_of)
+        .reference("provider", &Some(r.provider.to_owned()))
+        .reference("receiver", &Some(r.receiver.to_owned()))
+        .reference("resourceInventoriedAs", &r.resource_inventoried_as)
+        .reference_list("resourceClassifiedAs", &r.resource_classified_as)
+        .reference("resourceConformsTo", &r.resource_conforms_to)
+        .quantity("resourceQuantity", &r.resource_quantity)
+        .quantity("effortQuantity", &r.effort_quantity)
+        .field("hasBeginning", &r.has_beginning)
+        .field("hasEnd", &r.has_end)
+        .field("hasPointInTime", &r.has_point_in_time)
+        .field("due", &r.due)
+        .reference("atLocation", &r.at_location)
+        .reference("plannedWithin", &r.plan)
+        .reference("independentDemandOf", &r.independent_demand_of)
+        .reference("agreedIn", &r.agreed_in)
+        .reference("clauseOf", &r.clause_of)
+        .reference_list("inScopeOf", &r.in_scope_of)
+        .literal("finished", &r.finished)
+        .field("note", &r.note)
+}
+
 //---------------- READ ----------------
 
 /// Properties accessor for zome config
@@ -171,9 +341,11 @@ fn get_link_fields(commitment: &CommitmentAddress) -> RecordAPIResult<(
     Vec<SatisfactionAddress>,
     Vec<AgentAddress>,
 )> {
+    let involved_agents: Vec<AgentRecordAddress> = read_index!(commitment(commitment).involved_agent)?;
+
     Ok((
         read_index!(commitment(commitment).fulfilled_by)?,
         read_index!(commitment(commitment).satisfies)?,
-        vec![],   // :TODO:
+        involved_agents.into_iter().map(|agent| { agent.into() }).collect(),
     ))
 }
@@ -11,12 +11,13 @@ use holochain_serialized_bytes::prelude::*;
 use serde_maybe_undefined::{MaybeUndefined, default_false};
 use vf_measurement::QuantityValue;
 pub use vf_attributes_hdk::{
-    RevisionHash,
+    RevisionHash, MergeRevisions, ByAddressAsOf, ByRevisionPair,
     ActionId,
     DateTime, FixedOffset,
     ExternalURL,
     LocationAddress,
     AgentAddress,
+    AgentRecordAddress,
     EconomicResourceAddress,
     ProcessAddress,
     ResourceSpecificationAddress,
@@ -70,7 +71,7 @@ pub struct Response {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub plan: Option<PlanAddress>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub in_scope_of: Option<Vec<String>>,
+    pub in_scope_of: Option<Vec<AgentAddress>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub agreed_in: Option<ExternalURL>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -149,7 +150,7 @@ pub struct CreateRequest {
     #[serde(default = "default_false")]
     pub finished: MaybeUndefined<bool>,
     #[serde(default)]
-    pub in_scope_of: MaybeUndefined<Vec<String>>,
+    pub in_scope_of: MaybeUndefined<Vec<AgentAddress>>,
 }
 
 impl<'a> CreateRequest {
@@ -207,7 +208,7 @@ pub struct UpdateRequest {
     #[serde(default)]
     pub finished: MaybeUndefined<bool>,
     #[serde(default)]
-    pub in_scope_of: MaybeUndefined<Vec<String>>,
+    pub in_scope_of: MaybeUndefined<Vec<AgentAddress>>,
 }
 
 impl<'a> UpdateRequest {
@@ -228,4 +229,9 @@ pub struct QueryParams {
     pub fulfilled_by: Option<FulfillmentAddress>,
     pub satisfies: Option<SatisfactionAddress>,
     pub clause_of: Option<AgreementAddress>,
+    pub provider: Option<AgentRecordAddress>,
+    pub receiver: Option<AgentRecordAddress>,
+    pub involved_agent: Option<AgentRecordAddress>,
+    pub in_scope_of: Option<AgentRecordAddress>,
+    pub classified_as: Option<ExternalURL>,
 }
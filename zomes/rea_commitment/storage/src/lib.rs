@@ -9,9 +9,11 @@
 use hdk::prelude::*;
 
 use hdk_records::{
-    MaybeUndefined,
+    RecordAPIResult, MaybeUndefined,
     record_interface::Updateable,
     generate_record_entry,
+    ErrorCode, ErrorDetails,
+    validation::{must_get_local_references, missing_alternative_fields},
 };
 use vf_measurement::QuantityValue;
 
@@ -29,6 +31,7 @@ pub use vf_attributes_hdk::{
     AgreementAddress,
     PlanAddress,
 };
+use vf_attributes_hdk::{ScopeReference, get_scoped_agents, update_scope_references};
 
 use vf_actions::{ validate_flow_action };
 
@@ -74,27 +77,72 @@ pub struct EntryData {
     pub independent_demand_of: Option<PlanAddress>,
     pub plan: Option<PlanAddress>,
     pub finished: bool,
-    pub in_scope_of: Option<Vec<String>>,
+    #[serde(default)]
+    pub in_scope_of: Option<Vec<ScopeReference>>,
     pub note: Option<String>,
 }
 
 impl EntryData {
-    pub fn validate_action(&self) -> Result<(), String> {
+    pub fn validate_action(&self) -> Result<(), ErrorDetails> {
         validate_flow_action(self.action.to_owned(), self.input_of.to_owned(), self.output_of.to_owned())
     }
 
-    pub fn validate_or_fields(&self) -> Result<(), String> {
+    pub fn validate_or_fields(&self) -> Result<(), ErrorDetails> {
         if !(self.resource_inventoried_as.is_some() || self.resource_classified_as.is_some() || self.resource_conforms_to.is_some()) {
-            return Err("Commitment must reference an inventoried resource, resource specification or resource classification".into());
+            return Err(missing_alternative_fields(
+                "Commitment must reference an inventoried resource, resource specification or resource classification",
+                &["resourceInventoriedAs", "resourceConformsTo", "resourceClassifiedAs"],
+            ));
         }
         if !(self.resource_quantity.is_some() || self.effort_quantity.is_some()) {
-            return Err("Commmitment must include either a resource quantity or an effort quantity".into());
+            return Err(missing_alternative_fields(
+                "Commmitment must include either a resource quantity or an effort quantity",
+                &["resourceQuantity", "effortQuantity"],
+            ));
         }
         if !(self.has_beginning.is_some() || self.has_end.is_some() || self.has_point_in_time.is_some() || self.due.is_some()) {
-            return Err("Commmitment must have a beginning, end, exact time or due date".into());
+            return Err(missing_alternative_fields(
+                "Commmitment must have a beginning, end, exact time or due date",
+                &["hasPointInTime", "hasBeginning", "hasEnd", "due"],
+            ));
         }
         Ok(())
     }
+
+    /// Ensure referenced processes, agreements, plans & resources in this DNA exist.
+    ///
+    pub fn must_get_references(&self) -> RecordAPIResult<()> {
+        must_get_local_references(vec![&self.input_of, &self.output_of])?;
+        must_get_local_references(vec![&self.resource_inventoried_as])?;
+        must_get_local_references(vec![&self.resource_conforms_to])?;
+        must_get_local_references(vec![&self.clause_of])?;
+        must_get_local_references(vec![&self.independent_demand_of, &self.plan])
+    }
+
+    /// The action of a commitment may not be changed once it is recorded, since any fulfillments of it depend upon it.
+    /// Nor may its provider or receiver, since either party may update the commitment.
+    ///
+    pub fn validate_immutable_fields(&self, previous: &EntryData) -> Result<(), ErrorDetails> {
+        if self.action != previous.action {
+            return Err(ErrorDetails::new(ErrorCode::ImmutableField, "Commitment action cannot be changed").at_field("action"));
+        }
+        if self.provider != previous.provider {
+            return Err(ErrorDetails::new(ErrorCode::ImmutableField, "Commitment provider cannot be changed").at_field("provider"));
+        }
+        if self.receiver != previous.receiver {
+            return Err(ErrorDetails::new(ErrorCode::ImmutableField, "Commitment receiver cannot be changed").at_field("receiver"));
+        }
+        Ok(())
+    }
+
+    /// Agents other than the original author who may update or delete this commitment:
+    /// its provider & receiver, and any agents it is in the scope of.
+    ///
+    pub fn get_authorised_agents(&self) -> Vec<AgentPubKey> {
+        let mut agents = vec![self.provider.1.to_owned(), self.receiver.1.to_owned()];
+        agents.extend(get_scoped_agents(&self.in_scope_of).iter().flatten().map(|a| { a.1.to_owned() }));
+        agents
+    }
 }
 
 generate_record_entry!(EntryData, CommitmentAddress, EntryStorage);
@@ -126,7 +174,7 @@ impl From<CreateRequest> for EntryData {
             clause_of: e.clause_of.into(),
             independent_demand_of: e.independent_demand_of.into(),
             finished: e.finished.to_option().unwrap(),  // :NOTE: unsafe, would crash if not for "default_false" binding via Serde
-            in_scope_of: e.in_scope_of.into(),
+            in_scope_of: update_scope_references(&None, e.in_scope_of.into()),
         }
     }
 }
@@ -157,7 +205,7 @@ impl Updateable<UpdateRequest> for EntryData {
             clause_of: if e.clause_of == MaybeUndefined::Undefined { self.clause_of.clone() } else { e.clause_of.clone().into() },
             independent_demand_of: if e.independent_demand_of == MaybeUndefined::Undefined { self.independent_demand_of.clone() } else { e.independent_demand_of.clone().into() },
             finished: if e.finished == MaybeUndefined::Undefined { self.finished.clone() } else { e.finished.clone().to_option().unwrap() },
-            in_scope_of: if e.in_scope_of== MaybeUndefined::Undefined { self.in_scope_of.clone() } else { e.in_scope_of.clone().into() },
+            in_scope_of: if e.in_scope_of == MaybeUndefined::Undefined { self.in_scope_of.to_owned() } else { update_scope_references(&self.in_scope_of, e.in_scope_of.to_owned().into()) },
             note: if e.note== MaybeUndefined::Undefined { self.note.clone() } else { e.note.clone().into() },
         }
     }
@@ -17,11 +17,29 @@ use hc_zome_rea_commitment_rpc::*;
 use hc_zome_rea_commitment_lib::*;
 use hc_zome_rea_commitment_storage::*;
 use hc_zome_rea_commitment_storage_consts::*;
+use hdk_records::validation::{get_modified_revision, validate_modification_author};
 
 #[hdk_extern]
 fn validate(validation_data: ValidateData) -> ExternResult<ValidateCallbackResult> {
     let element = validation_data.element;
-    let entry = element.into_inner().1;
+    let (signed_header, entry) = element.into_inner();
+    let header = signed_header.header();
+
+    // updates & deletes may only be made by the original author or agents authorised by the previous revision
+    let previous = match get_modified_revision(header)? {
+        Some(modified) => match EntryStorage::try_from(&modified.entry) {
+            Ok(previous_storage) => {
+                let previous = previous_storage.entry();
+                if let Err(e) = validate_modification_author(header, &modified, &previous.get_authorised_agents()) {
+                    return Ok(ValidateCallbackResult::Invalid(e.encode()));
+                }
+                Some(previous)
+            },
+            _ => None,
+        },
+        None => None,
+    };
+
     let entry = match entry {
         ElementEntry::Present(e) => e,
         _ => return Ok(ValidateCallbackResult::Valid),
@@ -30,10 +48,12 @@ fn validate(validation_data: ValidateData) -> ExternResult<ValidateCallbackResul
     match EntryStorage::try_from(&entry) {
         Ok(event_storage) => {
             let record = event_storage.entry();
+            record.must_get_references()?;
             record.validate_or_fields()
                 .and_then(|()| { record.validate_action() })
+                .and_then(|()| { previous.as_ref().map_or(Ok(()), |p| { record.validate_immutable_fields(p) }) })
                 .and_then(|()| { Ok(ValidateCallbackResult::Valid) })
-                .or_else(|e| { Ok(ValidateCallbackResult::Invalid(e)) })
+                .or_else(|e| { Ok(ValidateCallbackResult::Invalid(e.encode())) })
         },
         _ => Ok(ValidateCallbackResult::Valid),
     }
@@ -90,6 +110,36 @@ fn update_commitment(UpdateParams { commitment }: UpdateParams) -> ExternResult<
     Ok(handle_update_commitment(COMMITMENT_ENTRY_TYPE, commitment)?)
 }
 
+#[hdk_extern]
+fn get_commitment_revision_heads(ByAddress { address }: ByAddress) -> ExternResult<Vec<RevisionHash>> {
+    Ok(handle_get_commitment_revision_heads(COMMITMENT_ENTRY_TYPE, address)?)
+}
+
+#[hdk_extern]
+fn merge_commitment_revisions(MergeRevisions { revision_id, superseded_revisions }: MergeRevisions) -> ExternResult<ResponseData> {
+    Ok(handle_merge_commitment_revisions(COMMITMENT_ENTRY_TYPE, revision_id, superseded_revisions)?)
+}
+
+#[hdk_extern]
+fn get_commitment_history(ByAddress { address }: ByAddress) -> ExternResult<Vec<RevisionMeta>> {
+    Ok(handle_get_commitment_history(COMMITMENT_ENTRY_TYPE, address)?)
+}
+
+#[hdk_extern]
+fn get_commitment_at_revision(ByHeader { address }: ByHeader) -> ExternResult<ResponseData> {
+    Ok(handle_get_commitment_at_revision(address)?)
+}
+
+#[hdk_extern]
+fn get_commitment_as_of(ByAddressAsOf { address, as_of }: ByAddressAsOf<CommitmentAddress>) -> ExternResult<ResponseData> {
+    Ok(handle_get_commitment_as_of(COMMITMENT_ENTRY_TYPE, address, as_of)?)
+}
+
+#[hdk_extern]
+fn diff_commitment_revisions(ByRevisionPair { from_revision_id, to_revision_id }: ByRevisionPair) -> ExternResult<RevisionDiff> {
+    Ok(handle_diff_commitment_revisions(from_revision_id, to_revision_id)?)
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct ByHeader {
     pub address: RevisionHash,
@@ -99,3 +149,8 @@ struct ByHeader {
 fn delete_commitment(ByHeader { address }: ByHeader) -> ExternResult<bool> {
     Ok(handle_delete_commitment(address)?)
 }
+
+#[hdk_extern]
+fn export_commitments(params: ExportParams) -> ExternResult<ExportPage> {
+    Ok(handle_export_commitments(COMMITMENT_ENTRY_TYPE, params)?)
+}
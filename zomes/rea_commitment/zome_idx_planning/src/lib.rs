@@ -14,4 +14,9 @@ struct Commitment {
     input_of: Local<process, committed_inputs>,
     output_of: Local<process, committed_outputs>,
     clause_of: Local<agreement, commitments>,
+    provider: Local<agent_record, commitments_as_provider>,
+    receiver: Local<agent_record, commitments_as_receiver>,
+    involved_agent: Local<agent_record, commitments_involving>,
+    in_scope_of: Local<agent_record, commitments_in_scope>,
+    classified_as: Keyed<classification, commitments>,
 }
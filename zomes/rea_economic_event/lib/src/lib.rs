@@ -6,25 +6,67 @@
  *
  * @package Holo-REA
  */
+use std::collections::{HashSet, HashMap, BTreeMap};
+use std::convert::TryFrom;
 use paste::paste;
+use hdk::prelude::{
+    warn, query, create_entry, update_entry, call_info,
+    Entry, EntryHash, HeaderHash, DnaHash, PreflightResponse, ZomeName, AgentPubKey,
+    ChainQueryFilter, HeaderType, Header,
+    must_get_valid_element,
+};
 use hdk_records::{
-    RecordAPIResult, OtherCellResult, MaybeUndefined,
+    RecordAPIResult, DataIntegrityError, OtherCellResult, MaybeUndefined,
+    ErrorCode, ErrorDetails,
+    DnaAddressable, agent_info, dna_info, hash_entry,
+    record_interface::{Identifiable, Updateable},
+    entries::get_entry_by_address,
+    countersigning::{
+        build_preflight_request,
+        accept_preflight_request,
+        create_countersigned_entry,
+        get_countersigning_agents,
+        grant_countersigning_access,
+    },
     local_indexes::{
         query_root_index,
+        query_root_index_page,
     },
     rpc::{
-        call_local_zome_method,
+        read_local_zome_name,
+        call_configured_zome_method,
+        call_agent_zome_method,
+    },
+    batch::{
+        run_batch,
+        BatchReferences,
     },
     records::{
         create_record,
+        create_record_identity,
+        get_latest_header_hash,
         read_record_entry,
         read_record_entry_by_header,
         update_record,
+        read_record_revision_heads,
+        merge_record_revisions,
+        get_index_changes,
         delete_record,
     },
+    history::{
+        Timestamp,
+        RevisionMeta,
+        RevisionDiff,
+        read_record_revision_history,
+        read_record_entry_as_of,
+        diff_record_revisions,
+    },
 };
 use hdk_semantic_indexes_client_lib::*;
 use hdk_relay_pagination::PageInfo;
+use vf_attributes_hdk::{NegativeInventoryPolicy, get_scope_agents, get_scoped_agents, get_involved_agents};
+use vf_measurement::QuantityValue;
+use vf_jsonld::{JsonLdNode, build_export_page};
 
 pub use hc_zome_rea_economic_event_storage_consts::*;
 
@@ -38,14 +80,19 @@ use hc_zome_rea_economic_event_rpc::{
 };
 use hc_zome_rea_economic_resource_rpc::{ CreationPayload as ResourceCreationPayload };
 
+use hc_zome_rea_economic_resource_storage_consts::RESOURCE_ENTRY_TYPE;
 use hc_zome_rea_economic_resource_storage::{
     EntryData as EconomicResourceData,
+    EntryStorage as EconomicResourceStorage,
+    read_current_negative_inventory_policy,
 };
 use hc_zome_rea_economic_resource_lib::{
     construct_response_record as construct_resource_response,
     get_link_fields as get_resource_link_fields,
 };
 
+/// Time allowed for the counterparty to a transfer to accept and sign the event
+const COUNTERSIGNING_SESSION_DURATION_MS: u64 = 30000;
 
 /// Properties accessor for zome config.
 fn read_economic_resource_index_zome(conf: DnaConfigSlice) -> Option<String> {
@@ -64,39 +111,13 @@ impl API for EconomicEventZomePermissableDefault {
         entry_def_id: Self::S, process_entry_def_id: Self::S,
         event: EconomicEventCreateRequest, new_inventoried_resource: Option<ResourceCreateRequest>
     ) -> RecordAPIResult<ResponseData> {
-        let mut resources_affected: Vec<(RevisionHash, EconomicResourceAddress, EconomicResourceData, EconomicResourceData)> = vec![];
-        let mut resource_created: Option<(RevisionHash, EconomicResourceAddress, EconomicResourceData)> = None;
-
-        // if the event observes a new resource, create that resource & return it in the response
-        if let Some(economic_resource) = new_inventoried_resource {
-            let new_resource = handle_create_inventory_from_event(
-                &economic_resource, &event,
-            )?;
-            resource_created = Some(new_resource.clone());
-            resources_affected.push((new_resource.0, new_resource.1, new_resource.2.clone(), new_resource.2));
-        }
-
-        // update any linked resources affected by the event
-        resources_affected.append(&mut handle_update_resource_inventory(&event)?);
-
-        // Now that the resource updates have succeeded, write the event.
-        // Note we ignore the revision ID because events can't be edited (only underwritten by subsequent events)
-        // :TODO: rethinking this, it's probably the event that should be written first, and the resource
-        // validation should eventually depend on an event already having been authored.
-        let (revision_id, event_address, event_entry) = handle_create_economic_event_record(
-            &entry_def_id,
-            &event, match &resource_created {
-                Some(data) => Some(data.1.to_owned()),
-                None => None,
-            },
+        let resource_zome = read_local_zome_name(read_resource_zome, INVENTORY_UPDATE_API_METHOD)?;
+        let (revision_id, event_address, event_entry, resource_created, mut warnings) = handle_create_economic_event(
+            &entry_def_id, &resource_zome, event.to_owned(), new_inventoried_resource,
         )?;
+        warnings.append(&mut index_economic_event_remotes(&event_address, &event)?);
 
-        // Link any affected resources to this event so that we can pull all the events which affect any resource
-        for resource_data in resources_affected.iter() {
-            create_index!(Local(economic_event.affects(&(resource_data.1)), economic_resource.affected_by(&event_address)))?;
-        }
-
-        match resource_created {
+        let response = match resource_created {
             Some((resource_revision_id, resource_addr, resource_entry)) => {
                 construct_response_with_resource(
                     &event_address, &revision_id, &event_entry, get_link_fields(&event_address)?,
@@ -109,7 +130,56 @@ impl API for EconomicEventZomePermissableDefault {
                 // :TODO: pass results from link creation rather than re-reading
                 construct_response(&event_address, &revision_id, &event_entry, get_link_fields(&event_address)?)
             },
+        }?;
+
+        Ok(ResponseData { warnings, ..response })
+    }
+
+    fn apply_economic_event_batch(entry_def_id: Self::S, operations: Vec<BatchOperation>) -> RecordAPIResult<Vec<BatchOperationResult>> {
+        // configuration is read once for the whole batch
+        let resource_zome = read_local_zome_name(read_resource_zome, INVENTORY_UPDATE_API_METHOD)?;
+        let mut remote_indexes = vec![];
+
+        let mut results = run_batch(operations, |references, operation| {
+            match operation {
+                BatchOperation::CreateEconomicEvent(BatchCreateRequest {
+                    event, new_inventoried_resource, event_ref, resource_ref, references: reference_fields,
+                }) => {
+                    if resource_ref.is_some() && new_inventoried_resource.is_none() {
+                        return Err(DataIntegrityError::Detailed(
+                            ErrorDetails::new(ErrorCode::InvalidFieldValue, "resourceRef can only be assigned by operations which create a newInventoriedResource")
+                                .at_field("resourceRef")
+                        ));
+                    }
+
+                    let event = resolve_batch_references(&entry_def_id, event, &reference_fields, references)?;
+                    let (revision_id, event_address, _event_entry, resource_created, warnings) = handle_create_economic_event(
+                        &entry_def_id, &resource_zome, event.to_owned(), new_inventoried_resource,
+                    )?;
+                    remote_indexes.push((event_address.to_owned(), event));
+
+                    references.insert(&event_ref, &entry_def_id, &event_address)?;
+                    if let Some((_revision, resource_address, _entry)) = &resource_created {
+                        references.insert(&resource_ref, RESOURCE_ENTRY_TYPE, resource_address)?;
+                    }
+
+                    Ok(BatchOperationResult {
+                        economic_event: event_address,
+                        revision_id,
+                        economic_resource_revision_id: resource_created.as_ref().map(|r| { r.0.to_owned() }),
+                        economic_resource: resource_created.map(|r| { r.1 }),
+                        warnings,
+                    })
+                },
+            }
+        })?;
+
+        // indexes in other DNAs are not reverted if the batch fails, so are only written once all operations have succeeded
+        for (result, (event_address, event)) in results.iter_mut().zip(remote_indexes.iter()) {
+            result.warnings.append(&mut index_economic_event_remotes(event_address, event)?);
         }
+
+        Ok(results)
     }
 
     fn get_economic_event(entry_def_id: Self::S, address: EconomicEventAddress) -> RecordAPIResult<ResponseData> {
@@ -119,17 +189,72 @@ impl API for EconomicEventZomePermissableDefault {
 
     fn update_economic_event(entry_def_id: Self::S, event: EconomicEventUpdateRequest) -> RecordAPIResult<ResponseData> {
         let address = event.get_revision_id().to_owned();
-        let (revision_id, identity_address, new_entry, _prev_entry): (_, EconomicEventAddress, EntryData, EntryData) = update_record(&entry_def_id, &address, event)?;
+        let (revision_id, identity_address, new_entry, prev_entry): (_, EconomicEventAddress, EntryData, EntryData) = update_record(&entry_def_id, &address, event)?;
+
+        update_economic_event_indexes(&identity_address, &new_entry, &prev_entry, None)?;
 
         // :TODO: optimise this- should pass results from `replace_direct_index` instead of retrieving from `get_link_fields` where updates
         construct_response(&identity_address, &revision_id, &new_entry, get_link_fields(&identity_address)?)
     }
 
+    fn get_economic_event_revision_heads(entry_def_id: Self::S, address: EconomicEventAddress) -> RecordAPIResult<Vec<RevisionHash>> {
+        read_record_revision_heads::<EconomicEventAddress, _>(&entry_def_id, address.as_ref())
+    }
+
+    fn merge_economic_event_revisions(entry_def_id: Self::S, revision_id: RevisionHash, superseded_revisions: Vec<RevisionHash>) -> RecordAPIResult<ResponseData> {
+        let (_revision, base_address, entry, superseded): (_, EconomicEventAddress, EntryData, _) = merge_record_revisions::<EntryData, EntryStorage, _>(&revision_id, superseded_revisions.as_slice())?;
+        for (superseded_entry, parent_entry) in superseded.iter() {
+            update_economic_event_indexes(&base_address, &entry, superseded_entry, Some(parent_entry))?;
+        }
+        Self::get_economic_event(entry_def_id, base_address)
+    }
+
+    fn get_economic_event_history(entry_def_id: Self::S, address: EconomicEventAddress) -> RecordAPIResult<Vec<RevisionMeta>> {
+        read_record_revision_history::<EconomicEventAddress, _>(&entry_def_id, address.as_ref())
+    }
+
+    fn get_economic_event_at_revision(revision_id: RevisionHash) -> RecordAPIResult<ResponseData> {
+        let (base_address, entry) = read_record_entry_by_header::<EntryData, EntryStorage, _>(&revision_id)?;
+        construct_response(&base_address, &revision_id, &entry, get_link_fields(&base_address)?)
+    }
+
+    fn get_economic_event_as_of(entry_def_id: Self::S, address: EconomicEventAddress, as_of: Timestamp) -> RecordAPIResult<ResponseData> {
+        let (revision, base_address, entry) = read_record_entry_as_of::<EntryData, EntryStorage, _,_>(&entry_def_id, address.as_ref(), &as_of)?;
+        // link fields are omitted, since indexes do not retain their history
+        construct_response(&base_address, &revision, &entry, Default::default())
+    }
+
+    fn diff_economic_event_revisions(from_revision_id: RevisionHash, to_revision_id: RevisionHash) -> RecordAPIResult<RevisionDiff> {
+        diff_record_revisions::<EntryData, EntryStorage, EconomicEventAddress>(&from_revision_id, &to_revision_id)
+    }
+
     fn delete_economic_event(revision_id: RevisionHash) -> RecordAPIResult<bool> {
         // read any referencing indexes
         let (base_address, entry) = read_record_entry_by_header::<EntryData, EntryStorage, _>(&revision_id)?;
 
+        // refuse to delete events which other records still reference
+        let (fulfillments, satisfactions, triggered_events) = get_link_fields(&base_address)?;
+        if !(fulfillments.is_empty() && satisfactions.is_empty() && triggered_events.is_empty()) {
+            let identity_hash: &EntryHash = base_address.as_ref();
+            return Err(DataIntegrityError::RecordInUse(
+                identity_hash.to_owned(),
+                format!(
+                    "{} fulfillment(s), {} satisfaction(s) and {} triggered event(s)",
+                    fulfillments.len(), satisfactions.len(), triggered_events.len(),
+                ),
+            ));
+        }
+
         // handle link fields
+        let provider: AgentRecordAddress = entry.provider.to_owned().into();
+        let receiver: AgentRecordAddress = entry.receiver.to_owned().into();
+        update_index!(Self(economic_event(&base_address).provider.not(&vec![provider])))?;
+        update_index!(Self(economic_event(&base_address).receiver.not(&vec![receiver])))?;
+        update_index!(Self(economic_event(&base_address).involved_agent.not(
+            get_involved_agents(&Some(entry.provider.to_owned()), &Some(entry.receiver.to_owned())).as_slice()
+        )))?;
+        update_index!(Self(economic_event(&base_address).in_scope_of.not(get_scope_agents(&entry.in_scope_of).as_slice())))?;
+        update_index!(Keyed(economic_event(&base_address).classified_as.not(entry.resource_classified_as.to_owned().unwrap_or_default().as_slice())))?;
         if let Some(process_address) = entry.input_of {
             update_index!(Local(economic_event.input_of.not(&vec![process_address.to_owned()]), process.inputs(&base_address)))?;
         }
@@ -139,9 +264,9 @@ impl API for EconomicEventZomePermissableDefault {
         if let Some(agreement_address) = entry.realization_of {
             let _ = update_index!(Remote(economic_event.realization_of.not(&vec![agreement_address.to_owned()]), agreement.economic_events(&base_address)));
         }
-
-        // :TODO: handle cleanup of foreign key fields? (fulfillment, satisfaction)
-        // May not be needed due to cross-record deletion validation logic.
+        if let Some(triggering_event) = entry.triggered_by {
+            update_index!(Self(economic_event(&base_address).triggered_by.not(&vec![triggering_event])))?;
+        }
 
         // delete entry last as it must be present in order for links to be removed
         delete_record::<EntryStorage, RevisionHash>(&revision_id)
@@ -151,6 +276,530 @@ impl API for EconomicEventZomePermissableDefault {
         let entries_result = query_root_index::<EntryData, EntryStorage, _,_>(&entry_def_id)?;
         handle_list_output(entries_result)
     }
+
+    fn export_economic_events(entry_def_id: Self::S, params: ExportParams) -> RecordAPIResult<ExportPage> {
+        let limit = params.get_limit();
+        let page = query_root_index_page::<EntryData, EntryStorage, _,_>(&entry_def_id, &params.after, limit)?;
+        build_export_page(page, limit, |(revision_id, base_address, entry)| {
+            let link_fields = get_link_fields(&base_address)?;
+            Ok(construct_jsonld(&construct_response(&base_address, &revision_id, &entry, link_fields)?.economic_event))
+        })
+    }
+
+    fn get_economic_event_causal_chain(entry_def_id: Self::S, address: EconomicEventAddress) -> RecordAPIResult<CausalChainResponse> {
+        // load the record to ensure it is of the correct type
+        let (_revision, base_address, entry) = read_record_entry::<EntryData, EntryStorage, _,_>(&entry_def_id, address.as_ref())?;
+
+        // track all visited events so that cyclic `triggered_by` references cannot cause infinite recursion
+        let mut seen: HashSet<EconomicEventAddress> = HashSet::new();
+        seen.insert(base_address.to_owned());
+
+        // walk upstream to the originating event
+        let mut triggered_by = vec![];
+        let mut next_cause = entry.triggered_by;
+        while let Some(cause) = next_cause {
+            if !seen.insert(cause.to_owned()) {
+                break;
+            }
+            let (_revision, _cause_address, cause_entry) = read_record_entry::<EntryData, EntryStorage, _,_>(&entry_def_id, cause.as_ref())?;
+            next_cause = cause_entry.triggered_by;
+            triggered_by.push(cause);
+        }
+
+        // walk downstream through all effects
+        let mut triggers = vec![];
+        let mut pending = vec![base_address.to_owned()];
+        while !pending.is_empty() {
+            let mut next_pending = vec![];
+            for event in pending.iter() {
+                let effects: Vec<EconomicEventAddress> = read_index!(economic_event(event).triggers)?;
+                for effect in effects {
+                    if seen.insert(effect.to_owned()) {
+                        triggers.push(effect.to_owned());
+                        next_pending.push(effect);
+                    }
+                }
+            }
+            pending = next_pending;
+        }
+
+        Ok(CausalChainResponse {
+            economic_event: base_address,
+            triggered_by,
+            triggers,
+        })
+    }
+
+    fn get_income_distribution(entry_def_id: Self::S, params: IncomeDistributionParams) -> RecordAPIResult<IncomeDistributionResponse> {
+        let (_revision, base_address, entry) = read_record_entry::<EntryData, EntryStorage, _,_>(&entry_def_id, params.event.as_ref())?;
+
+        let contributions = trace_contributions(&entry_def_id, &entry, params.value_equation.as_slice(), params.agent_roles.as_slice())?;
+        let total_score: f64 = contributions.iter().map(|(_agent, score, _events)| { score }).sum();
+        let income_value = params.income.get_numerical_value();
+        let income_unit = params.income.get_unit();
+
+        Ok(IncomeDistributionResponse {
+            economic_event: base_address,
+            distributions: contributions.into_iter()
+                .map(|(agent, score, contributions)| AgentDistribution {
+                    agent,
+                    score,
+                    share: QuantityValue::new(
+                        if total_score > 0.0 { income_value * score / total_score } else { 0.0 },
+                        income_unit.to_owned(),
+                    ),
+                    contributions,
+                })
+                .collect(),
+            income: params.income,
+        })
+    }
+
+    /// Propose a transfer to the other party, to be countersigned by both provider and receiver.
+    ///
+    /// Nothing is signed until the counterparty approves the transfer via `approve_countersigned_transfer`.
+    /// They are granted access to drive the countersigning session with the current agent for this transfer only.
+    ///
+    fn create_countersigned_economic_event(event: EconomicEventCreateRequest) -> RecordAPIResult<PendingTransferResponse> {
+        if !is_transfer_action(&event.action) {
+            return Err(DataIntegrityError::CountersigningError(format!("{} events cannot be countersigned", event.action.as_ref())));
+        }
+        let me = agent_info()?.agent_initial_pubkey;
+        let entry_data: EntryData = event.into();
+        let counterparty = get_transfer_counterparty(&entry_data, &me)?;
+
+        let entry = entry_data.with_new_identity()?;
+        let cap_secret = grant_countersigning_access(&[
+            EVENT_TRANSFER_PREFLIGHT_API_METHOD,
+            EVENT_TRANSFER_COMMIT_API_METHOD,
+        ], vec![counterparty.to_owned()])?;
+
+        // record our own consent to the transfer, checked once the counterparty begins the session
+        let pending = PendingTransfer { entry: entry.to_owned(), proposer: me, cap_secret: None };
+        create_entry(&pending)?;
+
+        let _: () = call_agent_zome_method(&counterparty, EVENT_TRANSFER_PROPOSAL_API_METHOD, None, CountersignedTransferProposal {
+            entry,
+            cap_secret,
+        })?;
+
+        construct_pending_transfer_response(&pending)
+    }
+
+    /// Record a transfer proposed by the counterparty, for later approval. Called remotely by the proposer.
+    ///
+    fn receive_transfer_proposal(proposal: CountersignedTransferProposal) -> RecordAPIResult<()> {
+        let event = proposal.entry.entry();
+        let me = agent_info()?.agent_initial_pubkey;
+        let proposer = call_info()?.provenance;
+
+        if !is_transfer_action(&event.action) {
+            return Err(DataIntegrityError::CountersigningError(format!("{} events cannot be countersigned", event.action.as_ref())));
+        }
+        if get_transfer_counterparty(&event, &proposer)? != me {
+            return Err(DataIntegrityError::CountersigningError("agent is not a party to the transfer".to_string()));
+        }
+
+        create_entry(&PendingTransfer {
+            entry: proposal.entry,
+            proposer,
+            cap_secret: Some(proposal.cap_secret),
+        })?;
+        Ok(())
+    }
+
+    fn get_pending_transfers() -> RecordAPIResult<Vec<PendingTransferResponse>> {
+        read_pending_transfers()?.iter()
+            .map(construct_pending_transfer_response)
+            .collect()
+    }
+
+    /// Approve a transfer proposed by the counterparty, and drive the countersigning session in which
+    /// both provider and receiver sign the event entry.
+    ///
+    /// The event is written without indexes or inventory effects, since countersigned entries must be
+    /// committed alone. Each party's side of the transfer is applied to their own inventory by the
+    /// scheduled `retry_transfer_applications`, or immediately via `apply_countersigned_transfer`.
+    ///
+    fn approve_countersigned_transfer(entry_def_id: Self::S, address: EconomicEventAddress) -> RecordAPIResult<ResponseData> {
+        let pending = read_pending_transfer(address.as_ref())?;
+        let cap_secret = pending.cap_secret
+            .ok_or_else(|| { DataIntegrityError::CountersigningError("transfers may only be approved by the counterparty".to_string()) })?;
+        let entry_data = pending.entry.entry();
+
+        let request = build_preflight_request(
+            hdk::entry_type!(EntryStorage)?, pending.entry.to_owned(),
+            vec![entry_data.provider.1.to_owned(), entry_data.receiver.1.to_owned()], COUNTERSIGNING_SESSION_DURATION_MS,
+        )?;
+
+        let mut responses = vec![accept_preflight_request(request.to_owned())?];
+        responses.push(call_agent_zome_method(&pending.proposer, EVENT_TRANSFER_PREFLIGHT_API_METHOD, Some(cap_secret), CountersignedTransferPreflight {
+            entry: pending.entry.to_owned(),
+            request,
+        })?);
+
+        let _counterparty_revision: RevisionHash = call_agent_zome_method(&pending.proposer, EVENT_TRANSFER_COMMIT_API_METHOD, Some(cap_secret), CountersignedTransferCommit {
+            entry: pending.entry.to_owned(),
+            responses: responses.to_owned(),
+        })?;
+        let (revision_id, entry_hash) = create_countersigned_entry(&entry_def_id, pending.entry, responses)?;
+
+        construct_response(&EconomicEventAddress::new(dna_info()?.hash, entry_hash), &revision_id, &entry_data, (vec![], vec![], vec![]))
+    }
+
+    /// Accept the countersigning session for a transfer we proposed. Called remotely by the approving counterparty.
+    ///
+    fn accept_transfer_preflight(preflight: CountersignedTransferPreflight) -> RecordAPIResult<PreflightResponse> {
+        let entry_hash = hash_entry(preflight.entry.to_owned())?;
+        if entry_hash != *preflight.request.app_entry_hash() {
+            return Err(DataIntegrityError::CountersigningError("transfer does not match the requested entry".to_string()));
+        }
+        check_transfer_proposed_to_caller(&entry_hash)?;
+
+        accept_preflight_request(preflight.request)
+    }
+
+    /// Write the countersigned event to the proposer's source chain. Called remotely by the approving counterparty.
+    ///
+    fn commit_countersigned_transfer(entry_def_id: Self::S, commit: CountersignedTransferCommit) -> RecordAPIResult<RevisionHash> {
+        check_transfer_proposed_to_caller(&hash_entry(commit.entry.to_owned())?)?;
+
+        let (revision_id, _entry_hash) = create_countersigned_entry(&entry_def_id, commit.entry, commit.responses)?;
+        Ok(revision_id)
+    }
+
+    /// Apply the calling agent's side of a countersigned transfer to their own inventory immediately,
+    /// rather than awaiting its automatic application.
+    ///
+    /// @see apply_transfer
+    ///
+    fn apply_countersigned_transfer(entry_def_id: Self::S, address: EconomicEventAddress) -> RecordAPIResult<ResponseData> {
+        let latest_attempt = read_transfer_applications()?
+            .into_iter()
+            .find(|(event, _)| { event == address.as_ref() })
+            .and_then(|(_, latest_attempt)| { latest_attempt });
+        if is_transfer_applied(&latest_attempt) {
+            return Err(DataIntegrityError::CountersigningError("transfer has already been applied to this agent's inventory".to_string()));
+        }
+
+        let response = apply_transfer(&entry_def_id, &address)?;
+        record_transfer_application(address.as_ref(), &latest_attempt, None)?;
+        Ok(response)
+    }
+
+    fn get_transfer_applications() -> RecordAPIResult<Vec<TransferApplicationStatus>> {
+        let dna_hash = dna_info()?.hash;
+        Ok(read_transfer_applications()?.iter()
+            .map(|(event, latest_attempt)| { construct_transfer_application_status(&dna_hash, event, latest_attempt) })
+            .collect())
+    }
+
+    /// Attempt to apply all countersigned transfers not yet applied to the calling agent's inventory,
+    /// in the order they were signed. Where a transfer fails, later transfers are held back, since
+    /// transfers must be applied to inventory in order.
+    ///
+    /// Returns the status of every transfer which was pending prior to the call.
+    ///
+    fn retry_transfer_applications(entry_def_id: Self::S) -> RecordAPIResult<Vec<TransferApplicationStatus>> {
+        let dna_hash = dna_info()?.hash;
+        let mut blocked = false;
+        let mut results = vec![];
+
+        for (event, mut latest_attempt) in read_transfer_applications()? {
+            if is_transfer_applied(&latest_attempt) {
+                continue;
+            }
+            if !blocked {
+                let address = EconomicEventAddress::new(dna_hash.to_owned(), event.to_owned());
+                let error = apply_transfer(&entry_def_id, &address).err().map(|e| { e.to_string() });
+                latest_attempt = Some(record_transfer_application(&event, &latest_attempt, error)?);
+            }
+            blocked = blocked || !is_transfer_applied(&latest_attempt);
+            results.push(construct_transfer_application_status(&dna_hash, &event, &latest_attempt));
+        }
+
+        Ok(results)
+    }
+}
+
+//---------------- COUNTERSIGNED TRANSFERS ----------------
+
+/// Apply the calling agent's side of a countersigned transfer to their own inventory,
+/// and index the event. The provider also indexes the event's other relationships.
+///
+/// The update to inventory is determined entirely by the event, and validators reject
+/// any resource revision which does not reflect it or which applies it more than once.
+///
+fn apply_transfer<S>(entry_def_id: S, address: &EconomicEventAddress) -> RecordAPIResult<ResponseData>
+    where S: AsRef<str>,
+{
+    let storage: EntryStorage = get_entry_by_address(address.as_ref())?;
+    let entry = storage.entry();
+    let _identity: EconomicEventAddress = create_record_identity(&entry_def_id, address.as_ref())?;
+
+    let me = agent_info()?.agent_initial_pubkey;
+    let event: EconomicEventCreateRequest = entry.to_owned().into();
+    let mut warnings = if entry.provider.1 == me {
+        check_inventory_levels(&event)?
+    } else if entry.receiver.1 == me {
+        vec![]
+    } else {
+        return Err(DataIntegrityError::CountersigningError("agent is not a party to the transfer".to_string()));
+    };
+
+    let resource_zome = read_local_zome_name(read_resource_zome, INVENTORY_TRANSFER_API_METHOD)?;
+    let (_revision_id, resource_address, _new_entry, _prev_entry): (RevisionHash, EconomicResourceAddress, EconomicResourceData, EconomicResourceData) = call_configured_zome_method(
+        &resource_zome,
+        INVENTORY_TRANSFER_API_METHOD.to_string(),
+        ByAddress { address: address.to_owned() },
+    )?;
+    create_index!(Local(economic_event.affects(&resource_address), economic_resource.affected_by(address)))?;
+    if entry.provider.1 == me {
+        index_economic_event(address, &event, &entry)?;
+        warnings.append(&mut index_economic_event_remotes(address, &event)?);
+    }
+
+    let revision_id = get_latest_header_hash(address.as_ref().to_owned())?;
+    let response = construct_response(address, &revision_id, &entry, get_link_fields(address)?)?;
+    Ok(ResponseData { warnings, ..response })
+}
+
+/// Load all countersigned transfers on the agent's source chain in the order they were
+/// signed, along with the latest attempt to apply each to the agent's inventory.
+///
+fn read_transfer_applications() -> RecordAPIResult<Vec<(EntryHash, Option<(HeaderHash, TransferApplication)>)>> {
+    let transfers = query(ChainQueryFilter::new()
+        .entry_type(hdk::entry_type!(EntryStorage)?)
+        .header_type(HeaderType::Create)
+        .include_entries(true))?;
+    // includes both creates & updates, in chain order
+    let attempts = query(ChainQueryFilter::new()
+        .entry_type(hdk::entry_type!(TransferApplication)?)
+        .include_entries(true))?;
+
+    let mut latest_attempts: HashMap<EntryHash, (HeaderHash, TransferApplication)> = HashMap::new();
+    for element in attempts {
+        if let Some(attempt) = element.entry().to_app_option::<TransferApplication>()? {
+            latest_attempts.insert(attempt.event.to_owned(), (element.header_address().to_owned(), attempt));
+        }
+    }
+
+    Ok(transfers.iter()
+        .filter(|element| { matches!(element.entry().as_option(), Some(Entry::CounterSign(_, _))) })
+        .filter_map(|element| { element.header().entry_hash().cloned() })
+        .map(|event| {
+            let latest_attempt = latest_attempts.remove(&event);
+            (event, latest_attempt)
+        })
+        .collect())
+}
+
+/// Record the outcome of an attempt to apply a transfer, updating the record of any previous attempt.
+///
+fn record_transfer_application(
+    event: &EntryHash,
+    latest_attempt: &Option<(HeaderHash, TransferApplication)>,
+    error: Option<String>,
+) -> RecordAPIResult<(HeaderHash, TransferApplication)> {
+    let attempt = TransferApplication {
+        event: event.to_owned(),
+        attempts: latest_attempt.as_ref().map_or(0, |(_, a)| { a.attempts }) + 1,
+        error,
+    };
+    let attempt_id = match latest_attempt {
+        Some((prev_attempt_id, _)) => update_entry(prev_attempt_id.to_owned(), &attempt)?,
+        None => create_entry(&attempt)?,
+    };
+    Ok((attempt_id, attempt))
+}
+
+fn is_transfer_applied(latest_attempt: &Option<(HeaderHash, TransferApplication)>) -> bool {
+    latest_attempt.as_ref().map_or(false, |(_, a)| { a.error.is_none() })
+}
+
+fn construct_transfer_application_status(
+    dna_hash: &DnaHash, event: &EntryHash, latest_attempt: &Option<(HeaderHash, TransferApplication)>,
+) -> TransferApplicationStatus {
+    TransferApplicationStatus {
+        economic_event: EconomicEventAddress::new(dna_hash.to_owned(), event.to_owned()),
+        applied: is_transfer_applied(latest_attempt),
+        attempts: latest_attempt.as_ref().map_or(0, |(_, a)| { a.attempts }),
+        last_error: latest_attempt.as_ref().and_then(|(_, a)| { a.error.to_owned() }),
+    }
+}
+
+/// Determine the other party to a transfer involving `agent`.
+///
+fn get_transfer_counterparty(event: &EntryData, agent: &AgentPubKey) -> RecordAPIResult<AgentPubKey> {
+    if event.provider.1 == event.receiver.1 {
+        return Err(DataIntegrityError::CountersigningError("countersigned transfers must be between two different agents".to_string()));
+    }
+    if event.provider.1 == *agent {
+        Ok(event.receiver.1.to_owned())
+    } else if event.receiver.1 == *agent {
+        Ok(event.provider.1.to_owned())
+    } else {
+        Err(DataIntegrityError::CountersigningError("agent is not a party to the transfer".to_string()))
+    }
+}
+
+/// Load all transfers proposed by or to the current agent which have not yet been countersigned.
+///
+fn read_pending_transfers() -> RecordAPIResult<Vec<PendingTransfer>> {
+    let signed: HashSet<EntryHash> = query(ChainQueryFilter::new()
+        .entry_type(hdk::entry_type!(EntryStorage)?)
+        .header_type(HeaderType::Create))?
+        .iter()
+        .filter_map(|element| { element.header().entry_hash().cloned() })
+        .collect();
+
+    let mut pending = vec![];
+    for element in query(ChainQueryFilter::new()
+        .entry_type(hdk::entry_type!(PendingTransfer)?)
+        .header_type(HeaderType::Create)
+        .include_entries(true))?
+    {
+        if let Some(transfer) = element.entry().to_app_option::<PendingTransfer>()? {
+            if !signed.contains(&hash_entry(transfer.entry.to_owned())?) {
+                pending.push(transfer);
+            }
+        }
+    }
+    Ok(pending)
+}
+
+fn read_pending_transfer(entry_hash: &EntryHash) -> RecordAPIResult<PendingTransfer> {
+    for transfer in read_pending_transfers()? {
+        if hash_entry(transfer.entry.to_owned())? == *entry_hash {
+            return Ok(transfer);
+        }
+    }
+    Err(DataIntegrityError::CountersigningError("no pending transfer matches the request".to_string()))
+}
+
+/// Ensure the current agent proposed the given transfer to the agent calling them, and that it is yet to be signed.
+///
+fn check_transfer_proposed_to_caller(entry_hash: &EntryHash) -> RecordAPIResult<()> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let caller = call_info()?.provenance;
+    let pending = read_pending_transfer(entry_hash)?;
+
+    if pending.proposer != me || get_transfer_counterparty(&pending.entry.entry(), &me)? != caller {
+        return Err(DataIntegrityError::CountersigningError("transfer was not proposed to this agent".to_string()));
+    }
+    Ok(())
+}
+
+fn construct_pending_transfer_response(pending: &PendingTransfer) -> RecordAPIResult<PendingTransferResponse> {
+    let dna = dna_info()?.hash;
+    Ok(PendingTransferResponse {
+        id: EconomicEventAddress::new(dna.to_owned(), hash_entry(pending.entry.to_owned())?),
+        proposer: AgentAddress::new(dna, pending.proposer.to_owned()),
+        economic_event: pending.entry.entry().into(),
+    })
+}
+
+//---------------- CONTRIBUTION ACCOUNTING ----------------
+
+/// Trace all contributions upstream of an event, by recursively following the inputs of the
+/// processes which produced the event's resource (and the resources consumed by those processes).
+///
+/// Each contribution is scored as its effort (or resource) quantity multiplied by the weight of the
+/// first matching rule in `value_equation`. Contributions matching no rule are not scored.
+/// Scores are summed per contributing agent, in order of first contribution.
+///
+/// Errors if any rule without a `unit` matches contributions measured in differing units.
+///
+fn trace_contributions<S>(
+    entry_def_id: S,
+    event: &EntryData,
+    value_equation: &[ValueRule],
+    agent_roles: &[AgentRoles],
+) -> RecordAPIResult<Vec<(AgentAddress, f64, Vec<EconomicEventAddress>)>>
+    where S: AsRef<str>,
+{
+    let mut contributions: Vec<(AgentAddress, f64, Vec<EconomicEventAddress>)> = vec![];
+
+    // unit of the first contribution weighted by each rule, to ensure like-for-like scoring
+    let mut rule_units: Vec<Option<Option<UnitId>>> = vec![None; value_equation.len()];
+
+    // track visited records so that cyclic flows cannot cause infinite recursion
+    let mut seen_resources: HashSet<EconomicResourceAddress> = HashSet::new();
+    let mut seen_processes: HashSet<ProcessAddress> = HashSet::new();
+
+    let mut pending_resources: Vec<EconomicResourceAddress> = event.resource_inventoried_as.iter().cloned().collect();
+    let mut pending_processes: Vec<ProcessAddress> = event.output_of.iter().cloned().collect();
+
+    while !(pending_resources.is_empty() && pending_processes.is_empty()) {
+        // locate the processes which produced each resource
+        while let Some(resource_address) = pending_resources.pop() {
+            if !seen_resources.insert(resource_address.to_owned()) {
+                continue;
+            }
+            let affecting_events: Vec<EconomicEventAddress> = read_index!(economic_resource(&resource_address).affected_by)?;
+            for event_address in affecting_events {
+                let (_revision, _address, affecting) = read_record_entry::<EntryData, EntryStorage, _,_>(&entry_def_id, event_address.as_ref())?;
+                if let Some(process_address) = affecting.output_of {
+                    pending_processes.push(process_address);
+                }
+            }
+        }
+
+        // score the inputs of each process, and continue tracing through any resources they consumed
+        while let Some(process_address) = pending_processes.pop() {
+            if !seen_processes.insert(process_address.to_owned()) {
+                continue;
+            }
+            let inputs: Vec<EconomicEventAddress> = read_index!(process(&process_address).inputs)?;
+            for input_address in inputs {
+                let (_revision, _address, input) = read_record_entry::<EntryData, EntryStorage, _,_>(&entry_def_id, input_address.as_ref())?;
+
+                let quantity = input.effort_quantity.as_ref().or(input.resource_quantity.as_ref());
+                let unit = quantity.and_then(|q| { q.get_unit() });
+
+                if let Some(rule_idx) = value_equation.iter().position(|rule| { value_rule_matches(rule, &input, &unit, agent_roles) }) {
+                    match &rule_units[rule_idx] {
+                        None => rule_units[rule_idx] = Some(unit.to_owned()),
+                        Some(rule_unit) => if *rule_unit != unit {
+                            return Err(DataIntegrityError::Detailed(
+                                ErrorDetails::new(ErrorCode::InvalidFieldValue, "Contributions being scored by the same value rule are measured in differing units. Specify the unit of each rule to weight them separately.")
+                                    .at_field("valueEquation")
+                                    .with_detail("rule", rule_idx)
+                            ));
+                        },
+                    }
+                    let score = quantity.map_or(1.0, |q| { q.get_numerical_value() }) * value_equation[rule_idx].weight;
+
+                    match contributions.iter().position(|(agent, _score, _events)| { *agent == input.provider }) {
+                        Some(idx) => {
+                            contributions[idx].1 += score;
+                            contributions[idx].2.push(input_address.to_owned());
+                        },
+                        None => contributions.push((input.provider.to_owned(), score, vec![input_address.to_owned()])),
+                    }
+                }
+
+                if let Some(resource_address) = input.resource_inventoried_as {
+                    pending_resources.push(resource_address);
+                }
+            }
+        }
+    }
+
+    Ok(contributions)
+}
+
+/// Determine whether a contribution event meets all the criteria of a `ValueRule`
+fn value_rule_matches(rule: &ValueRule, event: &EntryData, unit: &Option<UnitId>, agent_roles: &[AgentRoles]) -> bool {
+    rule.action == event.action
+        && rule.resource_conforms_to.as_ref().map_or(true, |spec| { event.resource_conforms_to.as_ref() == Some(spec) })
+        && rule.unit.as_ref().map_or(true, |rule_unit| { unit.as_ref() == Some(rule_unit) })
+        && rule.provider_role.as_ref().map_or(true, |role| { agent_plays_role(&event.provider, role, agent_roles) })
+        && rule.receiver_role.as_ref().map_or(true, |role| { agent_plays_role(&event.receiver, role, agent_roles) })
+}
+
+fn agent_plays_role(agent: &AgentAddress, role: &ExternalURL, agent_roles: &[AgentRoles]) -> bool {
+    agent_roles.iter().any(|assigned| { assigned.agent == *agent && assigned.roles.contains(role) })
 }
 
 // API logic handlers
@@ -167,18 +816,127 @@ fn read_process_index_zome(conf: DnaConfigSlice) -> Option<String> {
     conf.economic_event.process_index_zome
 }
 
-fn handle_create_economic_event_record<S>(entry_def_id: S, event: &EconomicEventCreateRequest, resource_address: Option<EconomicResourceAddress>,
+/// Write an event along with any new resource it observes, updating the inventory of
+/// existing resources affected by the event.
+///
+fn handle_create_economic_event<S>(
+    entry_def_id: S, resource_zome: &ZomeName,
+    event: EconomicEventCreateRequest, new_inventoried_resource: Option<ResourceCreateRequest>,
+) -> RecordAPIResult<(RevisionHash, EconomicEventAddress, EntryData, Option<(RevisionHash, EconomicResourceAddress, EconomicResourceData)>, Vec<ErrorDetails>)>
+    where S: AsRef<str>
+{
+    let mut resources_affected: Vec<(RevisionHash, EconomicResourceAddress, EconomicResourceData, EconomicResourceData)> = vec![];
+    let mut resource_created: Option<(RevisionHash, EconomicResourceAddress, EconomicResourceData)> = None;
+
+    // if the event observes a new resource, create that resource & return it in the response
+    if let Some(economic_resource) = new_inventoried_resource {
+        let new_resource = handle_create_inventory_from_event(
+            resource_zome, &economic_resource, &event,
+        )?;
+        resource_created = Some(new_resource.clone());
+        resources_affected.push((new_resource.0, new_resource.1, new_resource.2.clone(), new_resource.2));
+    }
+
+    // update any linked resources affected by the event
+    let warnings = check_inventory_levels(&event)?;
+    resources_affected.append(&mut handle_update_resource_inventory(resource_zome, &event)?);
+
+    // Now that the resource updates have succeeded, write the event.
+    // Note we ignore the revision ID because events can't be edited (only underwritten by subsequent events)
+    // :TODO: rethinking this, it's probably the event that should be written first, and the resource
+    // validation should eventually depend on an event already having been authored.
+    let provider_inventory_revision = match &event.resource_inventoried_as {
+        MaybeUndefined::Some(provider_inventory) => resources_affected.iter()
+            .filter(|(_revision, address, _new, _prev)| { address == provider_inventory })
+            .map(|(revision, _address, _new, _prev)| { revision.to_owned() })
+            .last(),
+        _ => None,
+    };
+    let (revision_id, event_address, event_entry) = handle_create_economic_event_record(
+        &entry_def_id,
+        &event, match &resource_created {
+            Some(data) => Some(data.1.to_owned()),
+            None => None,
+        },
+        provider_inventory_revision,
+    )?;
+
+    // Link any affected resources to this event so that we can pull all the events which affect any resource
+    for resource_data in resources_affected.iter() {
+        create_index!(Local(economic_event.affects(&(resource_data.1)), economic_resource.affected_by(&event_address)))?;
+    }
+
+    Ok((revision_id, event_address, event_entry, resource_created, warnings))
+}
+
+/// Populate the fields of an event which reference records created earlier in a batch.
+///
+fn resolve_batch_references<S>(
+    entry_def_id: S, event: EconomicEventCreateRequest,
+    reference_fields: &BTreeMap<String, String>, references: &BatchReferences,
+) -> RecordAPIResult<EconomicEventCreateRequest>
+    where S: AsRef<str>
+{
+    let mut event = event;
+    for (field, name) in reference_fields.iter() {
+        let at_field = |e: DataIntegrityError| { DataIntegrityError::Detailed(e.details().at_field(format!("references.{}", field))) };
+        match field.as_str() {
+            "resourceInventoriedAs" =>
+                event.resource_inventoried_as = MaybeUndefined::Some(references.resolve(name, RESOURCE_ENTRY_TYPE).map_err(at_field)?),
+            "toResourceInventoriedAs" =>
+                event.to_resource_inventoried_as = MaybeUndefined::Some(references.resolve(name, RESOURCE_ENTRY_TYPE).map_err(at_field)?),
+            "triggeredBy" =>
+                event.triggered_by = MaybeUndefined::Some(references.resolve(name, &entry_def_id).map_err(at_field)?),
+            _ => return Err(at_field(DataIntegrityError::Detailed(
+                ErrorDetails::new(ErrorCode::InvalidFieldValue, format!("Field {} cannot reference records created in a batch", field))
+            ))),
+        }
+    }
+    Ok(event)
+}
+
+/// Update the index links of an economic event as it moves from `prev_entry` to `new_entry`.
+/// `parent_entry` is given where `prev_entry` is a superseded revision being merged.
+///
+/// @see hdk_records::records::get_index_changes
+///
+fn update_economic_event_indexes(
+    identity_address: &EconomicEventAddress, new_entry: &EntryData, prev_entry: &EntryData, parent_entry: Option<&EntryData>,
+) -> RecordAPIResult<()> {
+    let (added, removed) = get_index_changes(new_entry, prev_entry, parent_entry, |e| { e.triggered_by.iter().cloned().collect() });
+    if !(added.is_empty() && removed.is_empty()) {
+        update_index!(Self(economic_event(identity_address).triggered_by(added.as_slice()).not(removed.as_slice())))?;
+    }
+    let (added, removed) = get_index_changes(new_entry, prev_entry, parent_entry, |e| { get_scope_agents(&e.in_scope_of) });
+    if !(added.is_empty() && removed.is_empty()) {
+        update_index!(Self(economic_event(identity_address).in_scope_of(added.as_slice()).not(removed.as_slice())))?;
+    }
+
+    Ok(())
+}
+
+fn handle_create_economic_event_record<S>(
+    entry_def_id: S, event: &EconomicEventCreateRequest, resource_address: Option<EconomicResourceAddress>,
+    provider_inventory_revision: Option<RevisionHash>,
 ) -> RecordAPIResult<(RevisionHash, EconomicEventAddress, EntryData)>
     where S: AsRef<str>
 {
+    let entry: EntryData = match resource_address {
+        Some(addr) => event.with_inventoried_resource(&addr),
+        None => event.to_owned(),
+    }.into();
     let (revision_id, base_address, entry_resp): (_, EconomicEventAddress, EntryData) = create_record(
         &entry_def_id,
-        match resource_address {
-            Some(addr) => event.with_inventoried_resource(&addr),
-            None => event.to_owned(),
-        }
+        entry.with_provider_inventory_revision(provider_inventory_revision),
     )?;
 
+    index_economic_event(&base_address, event, &entry_resp)?;
+
+    Ok((revision_id, base_address, entry_resp))
+}
+
+fn index_economic_event(base_address: &EconomicEventAddress, event: &EconomicEventCreateRequest, entry_resp: &EntryData) -> RecordAPIResult<()>
+{
     // handle link fields
     // :TODO: propagate errors
     if let EconomicEventCreateRequest { input_of: MaybeUndefined::Some(input_of), .. } = event {
@@ -187,11 +945,50 @@ fn handle_create_economic_event_record<S>(entry_def_id: S, event: &EconomicEvent
     if let EconomicEventCreateRequest { output_of: MaybeUndefined::Some(output_of), .. } = event {
         create_index!(Local(economic_event.output_of(output_of), process.outputs(&base_address)))?;
     };
+    if let EconomicEventCreateRequest { triggered_by: MaybeUndefined::Some(triggered_by), .. } = event {
+        create_index!(Self(economic_event(&base_address).triggered_by(triggered_by)))?;
+    };
+
+    // index involved agents
+    let provider: AgentRecordAddress = event.provider.to_owned().into();
+    let receiver: AgentRecordAddress = event.receiver.to_owned().into();
+    create_index!(Self(economic_event(&base_address).provider(&provider)))?;
+    create_index!(Self(economic_event(&base_address).receiver(&receiver)))?;
+    update_index!(Self(economic_event(&base_address).involved_agent(
+        get_involved_agents(&Some(event.provider.to_owned()), &Some(event.receiver.to_owned())).as_slice()
+    )))?;
+
+    // index scope
+    update_index!(Self(economic_event(&base_address).in_scope_of(get_scope_agents(&entry_resp.in_scope_of).as_slice())))?;
+
+    // index classifications
+    update_index!(Keyed(economic_event(&base_address).classified_as(entry_resp.resource_classified_as.to_owned().unwrap_or_default().as_slice())))?;
+
+    Ok(())
+}
+
+/// Write the indexes of an event which reside in other DNAs. Writes to other cells are not reverted
+/// if the zome call subsequently fails, so this must only be called once all local writes have succeeded.
+///
+/// Failed writes are returned as warnings, since the event itself has been recorded.
+///
+fn index_economic_event_remotes(base_address: &EconomicEventAddress, event: &EconomicEventCreateRequest) -> RecordAPIResult<Vec<ErrorDetails>>
+{
+    let mut warnings = vec![];
     if let EconomicEventCreateRequest { realization_of: MaybeUndefined::Some(realization_of), .. } = event {
-        create_index!(Remote(economic_event.realization_of(realization_of), agreement.realized(&base_address)))?;
+        let results = create_index!(Remote(economic_event.realization_of(realization_of), agreement.realized(&base_address)))?;
+        warnings.extend(results.iter()
+            .filter_map(|r| { r.as_ref().err() })
+            .map(|e| { e.details().at_field("realizationOf") }));
     };
+    Ok(warnings)
+}
 
-    Ok((revision_id, base_address, entry_resp))
+fn is_transfer_action(action: &ActionId) -> bool {
+    match action.as_ref().as_str() {
+        "transfer" | "transfer-all-rights" | "transfer-custody" => true,
+        _ => false,
+    }
 }
 
 /// Properties accessor for zome config.
@@ -205,11 +1002,11 @@ fn read_resource_zome(conf: DnaConfigSlice) -> Option<String> {
 /// Handle creation of new resources via events + resource metadata
 ///
 fn handle_create_inventory_from_event(
-    economic_resource: &ResourceCreateRequest, event: &CreateRequest,
+    resource_zome: &ZomeName, economic_resource: &ResourceCreateRequest, event: &CreateRequest,
 ) -> OtherCellResult<(RevisionHash, EconomicResourceAddress, EconomicResourceData)>
 {
-    Ok(call_local_zome_method(
-        read_resource_zome,
+    Ok(call_configured_zome_method(
+        resource_zome,
         INVENTORY_CREATION_API_METHOD.to_string(),
         resource_creation(&event, &economic_resource),
     )?)
@@ -222,14 +1019,61 @@ fn resource_creation(event: &CreateRequest, resource: &ResourceCreateRequest) ->
     }
 }
 
+/// Apply the negative inventory policy currently configured for the providing resource
+/// to an event, prior to any inventory being updated.
+///
+/// Returns the details of any inventory taken below zero under a `Warn` policy, for
+/// reporting alongside the created event.
+///
+fn check_inventory_levels(event: &EconomicEventCreateRequest) -> RecordAPIResult<Vec<ErrorDetails>> {
+    let provider_inventory = match &event.resource_inventoried_as {
+        MaybeUndefined::Some(r) => r,
+        _ => return Ok(vec![]),
+    };
+    let event_quantity = match &event.resource_quantity {
+        MaybeUndefined::Some(q) => q.get_numerical_value(),
+        _ => return Ok(vec![]),
+    };
+
+    let (_revision, _address, resource) = read_record_entry::<EconomicResourceData, EconomicResourceStorage, _,_>(&RESOURCE_ENTRY_TYPE, provider_inventory.as_ref())?;
+    let policy = read_current_negative_inventory_policy(&resource);
+    if policy == NegativeInventoryPolicy::Allow || resource.credit_limit.is_some() {
+        return Ok(vec![]);
+    }
+
+    let updated = resource.update_with(event.with_inventory_type(ResourceInventoryType::ProvidingInventory));
+    let levels = [
+        (&resource.accounting_quantity, &updated.accounting_quantity),
+        (&resource.onhand_quantity, &updated.onhand_quantity),
+    ];
+    let mut warnings = vec![];
+    for (before, after) in levels.iter() {
+        if let (Some(available), Some(remaining)) = (before, after) {
+            if remaining.get_numerical_value() >= 0.0 {
+                continue;
+            }
+            let insufficient = DataIntegrityError::InsufficientInventory(
+                provider_inventory.as_ref().to_owned(), available.get_numerical_value(), event_quantity,
+            );
+            if policy == NegativeInventoryPolicy::Reject {
+                return Err(insufficient);
+            }
+            warnings.push(insufficient.details());
+        }
+    }
+    warnings.dedup();
+
+    Ok(warnings)
+}
+
 /// Handle alteration of existing resources via events
 ///
 fn handle_update_resource_inventory(
-    event: &EconomicEventCreateRequest,
+    resource_zome: &ZomeName, event: &EconomicEventCreateRequest,
 ) -> RecordAPIResult<Vec<(RevisionHash, EconomicResourceAddress, EconomicResourceData, EconomicResourceData)>>
 {
-    Ok(call_local_zome_method(
-        read_resource_zome,
+    Ok(call_configured_zome_method(
+        resource_zome,
         INVENTORY_UPDATE_API_METHOD.to_string(),
         event,
     )?)
@@ -275,9 +1119,11 @@ pub fn construct_response_with_resource<'a>(
     event: &EntryData, (
         fulfillments,
         satisfactions,
+        triggers,
     ): (
         Vec<FulfillmentAddress>,
         Vec<SatisfactionAddress>,
+        Vec<EconomicEventAddress>,
     ),
     resource_address: Option<EconomicResourceAddress>,
     resource_revision_id: &RevisionHash,
@@ -316,14 +1162,16 @@ pub fn construct_response_with_resource<'a>(
             agreed_in: event.agreed_in.to_owned(),
             triggered_by: event.triggered_by.to_owned(),
             realization_of: event.realization_of.to_owned(),
-            in_scope_of: event.in_scope_of.to_owned(),
+            in_scope_of: get_scoped_agents(&event.in_scope_of),
             fulfills: fulfillments.to_owned(),
             satisfies: satisfactions.to_owned(),
+            triggers: triggers.to_owned(),
         },
         economic_resource: match resource_address {
             Some(addr) => Some(construct_resource_response(&addr, &resource_revision_id, &resource, (contained_in, stage, state, contains))?),
             None => None,
         },
+        warnings: vec![],
     })
 }
 
@@ -332,9 +1180,11 @@ pub fn construct_response<'a>(
     address: &EconomicEventAddress, revision_id: &RevisionHash, e: &EntryData, (
         fulfillments,
         satisfactions,
+        triggers,
     ): (
         Vec<FulfillmentAddress>,
         Vec<SatisfactionAddress>,
+        Vec<EconomicEventAddress>,
     )
 ) -> RecordAPIResult<ResponseData> {
     Ok(ResponseData {
@@ -360,11 +1210,13 @@ pub fn construct_response<'a>(
             agreed_in: e.agreed_in.to_owned(),
             triggered_by: e.triggered_by.to_owned(),
             realization_of: e.realization_of.to_owned(),
-            in_scope_of: e.in_scope_of.to_owned(),
+            in_scope_of: get_scoped_agents(&e.in_scope_of),
             fulfills: fulfillments.to_owned(),
             satisfies: satisfactions.to_owned(),
+            triggers: triggers.to_owned(),
         },
         economic_resource: None,
+        warnings: vec![],
     })
 }
 
@@ -372,27 +1224,62 @@ pub fn construct_list_response<'a>(
     address: &EconomicEventAddress, revision_id: &RevisionHash, e: &EntryData, (
         fulfillments,
         satisfactions,
+        triggers,
     ): (
         Vec<FulfillmentAddress>,
         Vec<SatisfactionAddress>,
+        Vec<EconomicEventAddress>,
     )
 ) -> RecordAPIResult<Edge> {
     let record_cursor: Vec<u8> = address.to_owned().into();
     Ok(Edge {
-        node: construct_response(address, revision_id, e, (fulfillments, satisfactions))?.economic_event,
+        node: construct_response(address, revision_id, e, (fulfillments, satisfactions, triggers))?.economic_event,
         // :TODO: use HoloHashb64 once API stabilises
         cursor: String::from_utf8(record_cursor).unwrap_or("".to_string())
     })
 }
 
+/// Create a ValueFlows JSON-LD node from an output record
+///
+/// Inverse relationships (eg. `triggers`) are omitted, as they are expressed
+/// by the records on the other side of the relationship.
+///
+pub fn construct_jsonld(r: &Response) -> JsonLdNode {
+    JsonLdNode::new("EconomicEvent", &r.id)
+        .action("action", &r.action)
+        .reference("inputOf", &r.input_of)
+        .reference("outputOf", &r.output_of)
+        .reference("provider", &Some(r.provider.to_owned()))
+        .reference("receiver", &Some(r.receiver.to_owned()))
+        .reference("resourceInventoriedAs", &r.resource_inventoried_as)
+        .reference("toResourceInventoriedAs", &r.to_resource_inventoried_as)
+        .reference_list("resourceClassifiedAs", &r.resource_classified_as)
+        .reference("resourceConformsTo", &r.resource_conforms_to)
+        .quantity("resourceQuantity", &r.resource_quantity)
+        .quantity("effortQuantity", &r.effort_quantity)
+        .field("hasBeginning", &r.has_beginning)
+        .field("hasEnd", &r.has_end)
+        .field("hasPointInTime", &r.has_point_in_time)
+        .reference("atLocation", &r.at_location)
+        .reference("agreedIn", &r.agreed_in)
+        .reference("realizationOf", &r.realization_of)
+        .reference("triggeredBy", &r.triggered_by)
+        .references("fulfills", r.fulfills.as_slice())
+        .references("satisfies", r.satisfies.as_slice())
+        .reference_list("inScopeOf", &r.in_scope_of)
+        .field("note", &r.note)
+}
+
 // @see construct_response
 pub fn get_link_fields(event: &EconomicEventAddress) -> RecordAPIResult<(
     Vec<FulfillmentAddress>,
     Vec<SatisfactionAddress>,
+    Vec<EconomicEventAddress>,
 )> {
     Ok((
         read_index!(economic_event(event).fulfills)?,
         read_index!(economic_event(event).satisfies)?,
+        read_index!(economic_event(event).triggers)?,
     ))
 }
 
@@ -408,3 +1295,86 @@ pub fn get_link_fields(event: &EconomicEventAddress) -> RecordAPIResult<(
 
     // :TODO: unit tests for type conversions... though maybe these should be macro tests, not tests for every single record type
 // }
+
+//---------------- VALIDATION ----------------
+
+/// Ensure that countersigned events are signed by both the provider and receiver.
+/// Events authored by a single agent are not checked.
+///
+pub fn validate_countersignatures(event: &EntryData, entry: &Entry) -> Result<(), ErrorDetails> {
+    let signers = get_countersigning_agents(entry);
+    if signers.is_empty() {
+        return Ok(());
+    }
+    if !signers.contains(&event.provider.1) {
+        return Err(ErrorDetails::new(ErrorCode::InvalidCountersignature, "Countersigned event must be signed by the provider").at_field("provider"));
+    }
+    if !signers.contains(&event.receiver.1) {
+        return Err(ErrorDetails::new(ErrorCode::InvalidCountersignature, "Countersigned event must be signed by the receiver").at_field("receiver"));
+    }
+    Ok(())
+}
+
+/// Whether an event draws down the providing resource's balance, and so must respect its credit limit.
+///
+fn draws_provider_balance(event: &EntryData) -> bool {
+    let action: &str = event.action.as_ref();
+    (action == "transfer" || action == "transfer-all-rights") && event.resource_inventoried_as.is_some()
+}
+
+/// Load the revision of the providing resource referenced by a transfer event, for validating its credit limit.
+///
+/// A missing revision defers validation until it can be retrieved.
+///
+pub fn must_get_provider_inventory(event: &EntryData) -> RecordAPIResult<Option<(Header, EconomicResourceAddress, EconomicResourceData)>> {
+    let revision = match (draws_provider_balance(event), &event.provider_inventory_revision) {
+        (true, Some(revision)) => revision,
+        _ => return Ok(None),
+    };
+    let element = must_get_valid_element(revision.1.to_owned())?;
+    let (signed_header, entry) = element.into_inner();
+    let resource = match entry.into_option().map(|e| { EconomicResourceStorage::try_from(&e) }) {
+        Some(Ok(resource)) => resource,
+        _ => return Err(DataIntegrityError::Detailed(
+            ErrorDetails::new(ErrorCode::InvalidFieldValue, "EconomicEvent must reference a revision of its providing resource")
+                .at_field("providerInventoryRevision")
+        )),
+    };
+    Ok(Some((signed_header.header().to_owned(), resource.identity()?, resource.entry())))
+}
+
+/// Reject transfers which draw a mutual credit balance down beyond its credit limit.
+///
+/// Inventory updates are written prior to the event which caused them, and the event references
+/// the resulting revision of the provider's resource (loaded via `must_get_provider_inventory()`).
+/// That revision must belong to the providing resource and precede the event in the author's source chain.
+/// Countersigned transfers are exempt, since their inventory effects are applied (and validated
+/// by the resource zome) only once the event has been committed.
+///
+pub fn validate_credit_limit(
+    event: &EntryData, header: &Header, countersigned: bool,
+    provider_inventory: Option<&(Header, EconomicResourceAddress, EconomicResourceData)>,
+) -> Result<(), ErrorDetails> {
+    if !draws_provider_balance(event) || countersigned {
+        return Ok(());
+    }
+    let (revision_header, resource_address, resource) = match provider_inventory {
+        Some(p) => p,
+        None => return Err(ErrorDetails::new(ErrorCode::MissingRequiredField, "EconomicEvent transferring from an inventoried resource must reference the resulting revision of that resource")
+            .at_field("providerInventoryRevision")),
+    };
+    if Some(resource_address) != event.resource_inventoried_as.as_ref() {
+        return Err(ErrorDetails::new(ErrorCode::InvalidFieldValue, "EconomicEvent must reference a revision of its providing resource")
+            .at_field("providerInventoryRevision"));
+    }
+    if revision_header.author() != header.author() || revision_header.header_seq() >= header.header_seq() {
+        return Err(ErrorDetails::new(ErrorCode::InvalidFieldValue, "EconomicEvent must reference a resource revision written earlier by its author")
+            .at_field("providerInventoryRevision"));
+    }
+
+    resource.validate_credit_limit()
+        .map_err(|e| {
+            let resource_hash: &EntryHash = resource_address.as_ref();
+            e.at_field("resourceQuantity").with_detail("resource", resource_hash)
+        })
+}
@@ -6,19 +6,22 @@
  *
  * @package Holo-REA
  */
+use std::collections::BTreeMap;
 use holochain_serialized_bytes::prelude::*;
 
 use serde_maybe_undefined::MaybeUndefined;
 use vf_measurement::QuantityValue;
 use hdk_relay_pagination::PageInfo;
+pub use hdk_rpc_errors::ErrorDetails;
 pub use vf_attributes_hdk::{
-    RevisionHash, ByAddress, ByHeader,
+    RevisionHash, ByAddress, ByHeader, MergeRevisions, ByAddressAsOf, ByRevisionPair,
     EconomicEventAddress,
     EconomicResourceAddress,
     ActionId,
     ExternalURL,
     LocationAddress,
     AgentAddress,
+    AgentRecordAddress,
     ProcessAddress,
     ResourceSpecificationAddress,
     ProcessSpecificationAddress,
@@ -76,7 +79,7 @@ pub struct Response {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub triggered_by: Option<EconomicEventAddress>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub in_scope_of: Option<Vec<String>>,
+    pub in_scope_of: Option<Vec<AgentAddress>>,
 
     // LINK FIELDS
     #[serde(default)]
@@ -85,6 +88,9 @@ pub struct Response {
     #[serde(default)]
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub satisfies: Vec<SatisfactionAddress>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub triggers: Vec<EconomicEventAddress>,
 }
 
 /// I/O struct to describe EconomicResources, including all managed link fields
@@ -121,6 +127,11 @@ pub struct ResourceResponse {
     pub current_location: Option<LocationAddress>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub note: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub primary_accountable: Option<AgentAddress>,
+    // for mutual credit balances, how far below zero the balance may be drawn
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub credit_limit: Option<QuantityValue>,
 
     // query edges
     #[serde(default)]
@@ -139,6 +150,9 @@ pub struct ResponseData {
     pub economic_event: Response,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub economic_resource: Option<ResourceResponse>,
+    /// Problems which did not prevent the event from being recorded, eg. inventory taken below zero under a `Warn` policy
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<ErrorDetails>,
 }
 
 /// I/O struct to describe what is returned outside the gateway
@@ -158,6 +172,81 @@ pub struct EventResponseEdge {
     pub cursor: String,
 }
 
+/// I/O struct to describe all events related to an event via `triggered_by`
+#[derive(Clone, Serialize, Deserialize, SerializedBytes, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CausalChainResponse {
+    pub economic_event: EconomicEventAddress,
+    // events which directly or indirectly triggered the event, nearest first
+    pub triggered_by: Vec<EconomicEventAddress>,
+    // events directly or indirectly triggered by the event, in breadth-first order
+    pub triggers: Vec<EconomicEventAddress>,
+}
+
+//---------------- CONTRIBUTION ACCOUNTING ----------------
+
+/// A single rule of a value equation, weighting contributions which match its criteria.
+/// Unspecified criteria match any contribution.
+///
+/// `weight` is the score given per unit of the contribution's quantity. Rules without a `unit`
+/// may only match contributions measured in a single unit, since scores in differing units
+/// cannot be summed meaningfully.
+///
+#[derive(Clone, Serialize, Deserialize, SerializedBytes, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ValueRule {
+    pub action: ActionId,
+    #[serde(default)]
+    pub resource_conforms_to: Option<ResourceSpecificationAddress>,
+    // unit which the contribution's effort (or resource) quantity must be measured in
+    #[serde(default)]
+    pub unit: Option<UnitId>,
+    // role which the event's provider must play, as assigned in `IncomeDistributionParams.agent_roles`
+    #[serde(default)]
+    pub provider_role: Option<ExternalURL>,
+    // role which the event's receiver must play, as assigned in `IncomeDistributionParams.agent_roles`
+    #[serde(default)]
+    pub receiver_role: Option<ExternalURL>,
+    pub weight: f64,
+}
+
+/// Roles played by an agent within the network, for matching against `ValueRule` roles
+#[derive(Clone, Serialize, Deserialize, SerializedBytes, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentRoles {
+    pub agent: AgentAddress,
+    pub roles: Vec<ExternalURL>,
+}
+
+/// Parameters for distributing income amongst the contributors to whatever was sold in `event`
+#[derive(Clone, Serialize, Deserialize, SerializedBytes, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct IncomeDistributionParams {
+    pub event: EconomicEventAddress,
+    pub income: QuantityValue,
+    pub value_equation: Vec<ValueRule>,
+    #[serde(default)]
+    pub agent_roles: Vec<AgentRoles>,
+}
+
+/// Portion of income allocated to a single contributor
+#[derive(Clone, Serialize, Deserialize, SerializedBytes, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentDistribution {
+    pub agent: AgentAddress,
+    pub score: f64,
+    pub share: QuantityValue,
+    pub contributions: Vec<EconomicEventAddress>,
+}
+
+#[derive(Clone, Serialize, Deserialize, SerializedBytes, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct IncomeDistributionResponse {
+    pub economic_event: EconomicEventAddress,
+    pub income: QuantityValue,
+    pub distributions: Vec<AgentDistribution>,
+}
+
 /// I/O struct to describe what is returned outside the gateway
 #[derive(Clone, Serialize, Deserialize, SerializedBytes, Debug)]
 #[serde(rename_all = "camelCase")]
@@ -230,7 +319,7 @@ pub struct CreateRequest {
     #[serde(default)]
     pub triggered_by: MaybeUndefined<EconomicEventAddress>,
     #[serde(default)]
-    pub in_scope_of: MaybeUndefined<Vec<String>>,
+    pub in_scope_of: MaybeUndefined<Vec<AgentAddress>>,
 
     // :SHONK: internal field used in updating linked resource quantities
     #[serde(default)]
@@ -299,6 +388,34 @@ pub struct CreateParams {
     pub new_inventoried_resource: Option<ResourceCreateRequest>,
 }
 
+/// Countersigned transfers move existing inventory only, so do not accept resource creation parameters
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CountersignedCreateParams {
+    pub event: CreateRequest,
+}
+
+/// A countersigned transfer awaiting approval by the counterparty.
+/// `id` is the address the event will have once both parties have signed it.
+#[derive(Clone, Serialize, Deserialize, SerializedBytes, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingTransferResponse {
+    pub id: EconomicEventAddress,
+    pub proposer: AgentAddress,
+    pub economic_event: CreateRequest,
+}
+
+/// Whether the current agent's side of a countersigned transfer has been applied to their inventory.
+/// Transfers are applied automatically once signed, and retried periodically until they succeed.
+#[derive(Clone, Serialize, Deserialize, SerializedBytes, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferApplicationStatus {
+    pub economic_event: EconomicEventAddress,
+    pub applied: bool,
+    pub attempts: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
 //---------------- UPDATE REQUEST ----------------
 
 /// I/O struct to describe the complete input record, including all managed links
@@ -315,7 +432,7 @@ pub struct UpdateRequest {
     #[serde(default)]
     pub triggered_by: MaybeUndefined<EconomicEventAddress>,
     #[serde(default)]
-    pub in_scope_of: MaybeUndefined<Vec<String>>,
+    pub in_scope_of: MaybeUndefined<Vec<AgentAddress>>,
 }
 
 impl<'a> UpdateRequest {
@@ -331,6 +448,56 @@ pub struct UpdateParams {
     pub event: UpdateRequest,
 }
 
+//---------------- BATCH REQUEST ----------------
+
+/// An operation to perform as part of a batch
+#[derive(Clone, Serialize, Deserialize, SerializedBytes, Debug)]
+#[serde(rename_all = "camelCase")]
+pub enum BatchOperation {
+    CreateEconomicEvent(BatchCreateRequest),
+}
+
+/// Creation of an event (and optionally a new inventoried resource) within a batch.
+///
+/// `references` maps fields of `event` to the `ref` or `resourceRef` names of records
+/// created by earlier operations in the same batch, eg. `{ "resourceInventoriedAs": "apples" }`.
+/// Supported fields are `resourceInventoriedAs`, `toResourceInventoriedAs` & `triggeredBy`.
+#[derive(Clone, Serialize, Deserialize, SerializedBytes, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchCreateRequest {
+    pub event: CreateRequest,
+    #[serde(default)]
+    pub new_inventoried_resource: Option<ResourceCreateRequest>,
+    #[serde(default, rename = "ref")]
+    pub event_ref: Option<String>,
+    // may only be given alongside `new_inventoried_resource`
+    #[serde(default)]
+    pub resource_ref: Option<String>,
+    #[serde(default)]
+    pub references: BTreeMap<String, String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, SerializedBytes, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchParams {
+    pub operations: Vec<BatchOperation>,
+}
+
+/// Records written by a batch operation. Full records are not returned, to avoid
+/// reading back the link fields of every record written.
+#[derive(Clone, Serialize, Deserialize, SerializedBytes, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchOperationResult {
+    pub economic_event: EconomicEventAddress,
+    pub revision_id: RevisionHash,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub economic_resource: Option<EconomicResourceAddress>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub economic_resource_revision_id: Option<RevisionHash>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<ErrorDetails>,
+}
+
 //---------------- QUERY FILTER REQUEST ----------------
 
 #[derive(Clone, Serialize, Deserialize, SerializedBytes, Debug)]
@@ -341,5 +508,12 @@ pub struct QueryParams {
     pub satisfies: Option<IntentAddress>,
     pub fulfills: Option<CommitmentAddress>,
     pub realization_of: Option<AgreementAddress>,
+    pub triggered_by: Option<EconomicEventAddress>,
+    pub triggers: Option<EconomicEventAddress>,
+    pub provider: Option<AgentRecordAddress>,
+    pub receiver: Option<AgentRecordAddress>,
+    pub involved_agent: Option<AgentRecordAddress>,
     pub affects: Option<EconomicResourceAddress>,
+    pub in_scope_of: Option<AgentRecordAddress>,
+    pub classified_as: Option<ExternalURL>,
 }
@@ -10,8 +10,10 @@ use hdk::prelude::*;
 
 use hdk_records::{
     generate_record_entry,
-    MaybeUndefined,
+    RecordAPIResult, MaybeUndefined, RevisionHash,
     record_interface::Updateable,
+    ErrorCode, ErrorDetails,
+    validation::{must_get_local_references, missing_alternative_fields},
 };
 
 use vf_measurement::QuantityValue;
@@ -21,6 +23,9 @@ use vf_attributes_hdk::{
     ExternalURL,
     LocationAddress,
     AgentAddress,
+    ScopeReference,
+    get_scoped_agents,
+    update_scope_references,
     EconomicResourceAddress,
     ProcessAddress,
     ResourceSpecificationAddress,
@@ -72,12 +77,25 @@ pub struct EntryData {
     pub agreed_in: Option<ExternalURL>,
     pub realization_of: Option<AgreementAddress>,
     pub triggered_by: Option<EconomicEventAddress>,
-    pub in_scope_of: Option<Vec<String>>,
+    #[serde(default)]
+    pub in_scope_of: Option<Vec<ScopeReference>>,
     pub note: Option<String>,
+    // revision of the providing resource which resulted from this event, written prior to the event
+    #[serde(default)]
+    pub provider_inventory_revision: Option<RevisionHash>,
 }
 
 impl EntryData {
-    pub fn validate_action(&self) -> Result<(), String> {
+    /// Reference the revision of the providing resource which resulted from this event.
+    ///
+    pub fn with_provider_inventory_revision(&self, revision: Option<RevisionHash>) -> EntryData {
+        EntryData {
+            provider_inventory_revision: revision,
+            ..self.to_owned()
+        }
+    }
+
+    pub fn validate_action(&self) -> Result<(), ErrorDetails> {
         let result = validate_flow_action(self.action.to_owned(), self.input_of.to_owned(), self.output_of.to_owned());
         if result.is_ok() && self.action.as_ref() == "move" {
             return validate_move_inventories(self.resource_inventoried_as.to_owned(), self.to_resource_inventoried_as.to_owned());
@@ -85,18 +103,69 @@ impl EntryData {
         return result;
     }
 
-    pub fn validate_or_fields(&self) -> Result<(), String> {
+    pub fn validate_or_fields(&self) -> Result<(), ErrorDetails> {
         if !(self.resource_inventoried_as.is_some() || self.resource_classified_as.is_some() || self.resource_conforms_to.is_some()) {
-            return Err("EconomicEvent must reference an inventoried resource, resource specification or resource classification".into());
+            return Err(missing_alternative_fields(
+                "EconomicEvent must reference an inventoried resource, resource specification or resource classification",
+                &["resourceInventoriedAs", "resourceConformsTo", "resourceClassifiedAs"],
+            ));
         }
         if !(self.resource_quantity.is_some() || self.effort_quantity.is_some()) {
-            return Err("EconomicEvent must include either a resource quantity or an effort quantity".into());
+            return Err(missing_alternative_fields(
+                "EconomicEvent must include either a resource quantity or an effort quantity",
+                &["resourceQuantity", "effortQuantity"],
+            ));
         }
         if !(self.has_beginning.is_some() || self.has_end.is_some() || self.has_point_in_time.is_some()) {
-            return Err("EconomicEvent must have a beginning, end or exact time".into());
+            return Err(missing_alternative_fields(
+                "EconomicEvent must have a beginning, end or exact time",
+                &["hasPointInTime", "hasBeginning", "hasEnd"],
+            ));
         }
         Ok(())
     }
+
+    /// Ensure referenced processes, resources & events in this DNA exist.
+    ///
+    pub fn must_get_references(&self) -> RecordAPIResult<()> {
+        must_get_local_references(vec![&self.input_of, &self.output_of])?;
+        must_get_local_references(vec![&self.resource_inventoried_as, &self.to_resource_inventoried_as])?;
+        must_get_local_references(vec![&self.resource_conforms_to])?;
+        must_get_local_references(vec![&self.realization_of])?;
+        must_get_local_references(vec![&self.triggered_by])
+    }
+
+    /// Fields which determine the inventory effects of an event may not be changed once it is recorded.
+    ///
+    pub fn validate_immutable_fields(&self, previous: &EntryData) -> Result<(), ErrorDetails> {
+        if self.action != previous.action {
+            return Err(ErrorDetails::new(ErrorCode::ImmutableField, "EconomicEvent action cannot be changed").at_field("action"));
+        }
+        if self.provider != previous.provider {
+            return Err(ErrorDetails::new(ErrorCode::ImmutableField, "EconomicEvent provider cannot be changed").at_field("provider"));
+        }
+        if self.receiver != previous.receiver {
+            return Err(ErrorDetails::new(ErrorCode::ImmutableField, "EconomicEvent receiver cannot be changed").at_field("receiver"));
+        }
+        if self.resource_inventoried_as != previous.resource_inventoried_as
+            || self.to_resource_inventoried_as != previous.to_resource_inventoried_as
+            || self.provider_inventory_revision != previous.provider_inventory_revision {
+            return Err(ErrorDetails::new(ErrorCode::ImmutableField, "EconomicEvent affected resources cannot be changed").at_field("resourceInventoriedAs"));
+        }
+        if self.resource_quantity != previous.resource_quantity {
+            return Err(ErrorDetails::new(ErrorCode::ImmutableField, "EconomicEvent resource quantity cannot be changed").at_field("resourceQuantity"));
+        }
+        Ok(())
+    }
+
+    /// Agents other than the original author who may update or delete this event:
+    /// its provider & receiver, and any agents it is in the scope of.
+    ///
+    pub fn get_authorised_agents(&self) -> Vec<AgentPubKey> {
+        let mut agents = vec![self.provider.1.to_owned(), self.receiver.1.to_owned()];
+        agents.extend(get_scoped_agents(&self.in_scope_of).iter().flatten().map(|a| { a.1.to_owned() }));
+        agents
+    }
 }
 
 generate_record_entry!(EntryData, EconomicEventAddress, EntryStorage);
@@ -128,11 +197,90 @@ impl From<CreateRequest> for EntryData {
             realization_of: e.realization_of.into(),
             triggered_by: e.triggered_by.into(),
             at_location: e.at_location.into(),
-            in_scope_of: e.in_scope_of.into(),
+            in_scope_of: update_scope_references(&None, e.in_scope_of.into()),
+            provider_inventory_revision: None,
+        }
+    }
+}
+
+/// Reconstruct the creation parameters of a stored event, for replaying its effects upon inventory
+impl From<EntryData> for CreateRequest {
+    fn from(e: EntryData) -> CreateRequest {
+        CreateRequest {
+            action: e.action,
+            note: e.note.into(),
+            input_of: e.input_of.into(),
+            output_of: e.output_of.into(),
+            provider: e.provider,
+            receiver: e.receiver,
+            resource_inventoried_as: e.resource_inventoried_as.into(),
+            to_resource_inventoried_as: e.to_resource_inventoried_as.into(),
+            resource_classified_as: e.resource_classified_as.into(),
+            resource_conforms_to: e.resource_conforms_to.into(),
+            resource_quantity: e.resource_quantity.into(),
+            effort_quantity: e.effort_quantity.into(),
+            has_beginning: e.has_beginning.into(),
+            has_end: e.has_end.into(),
+            has_point_in_time: e.has_point_in_time.into(),
+            at_location: e.at_location.into(),
+            agreed_in: e.agreed_in.into(),
+            realization_of: e.realization_of.into(),
+            triggered_by: e.triggered_by.into(),
+            in_scope_of: get_scoped_agents(&e.in_scope_of).into(),
+            target_inventory_type: None,
         }
     }
 }
 
+//---------------- COUNTERSIGNING ----------------
+
+/// A countersigned transfer awaiting approval by the counterparty, held privately by both parties.
+///
+/// The proposer's copy records their consent to the transfer. The counterparty's copy
+/// holds the secret granting them access to drive the countersigning session, once approved.
+///
+#[hdk_entry(id = "pending_transfer", visibility = "private")]
+#[derive(Clone)]
+pub struct PendingTransfer {
+    pub entry: EntryStorage,
+    pub proposer: AgentPubKey,
+    pub cap_secret: Option<CapSecret>,
+}
+
+/// The outcome of the latest attempt to apply the current agent's side of a countersigned
+/// transfer to their inventory. `error` is empty once the transfer has been applied.
+///
+/// A single entry is created for each transfer upon its first attempt, and updated upon each retry.
+///
+#[hdk_entry(id = "transfer_application", visibility = "private")]
+#[derive(Clone)]
+pub struct TransferApplication {
+    pub event: EntryHash,
+    pub attempts: u32,
+    pub error: Option<String>,
+}
+
+/// Sent by the proposer of a countersigned transfer to the counterparty, for their approval
+#[derive(Clone, Serialize, Deserialize, SerializedBytes, Debug)]
+pub struct CountersignedTransferProposal {
+    pub entry: EntryStorage,
+    pub cap_secret: CapSecret,
+}
+
+/// Sent by the approver of a countersigned transfer to request the proposer's acceptance
+#[derive(Clone, Serialize, Deserialize, SerializedBytes, Debug)]
+pub struct CountersignedTransferPreflight {
+    pub entry: EntryStorage,
+    pub request: PreflightRequest,
+}
+
+/// Sent by the approver of a countersigned transfer once all parties have accepted
+#[derive(Clone, Serialize, Deserialize, SerializedBytes, Debug)]
+pub struct CountersignedTransferCommit {
+    pub entry: EntryStorage,
+    pub responses: Vec<PreflightResponse>,
+}
+
 //---------------- UPDATE ----------------
 
 /// Handles update operations by merging any newly provided fields into
@@ -154,11 +302,12 @@ impl Updateable<UpdateRequest> for EntryData {
             has_end: self.has_end.to_owned(),
             has_point_in_time: self.has_point_in_time.to_owned(),
             agreed_in: self.agreed_in.to_owned(),
-            triggered_by: self.triggered_by.to_owned(),
+            triggered_by: if e.triggered_by == MaybeUndefined::Undefined { self.triggered_by.to_owned() } else { e.triggered_by.to_owned().into() },
             realization_of: self.realization_of.to_owned(),
             at_location: self.at_location.to_owned(),
-            in_scope_of: if e.in_scope_of== MaybeUndefined::Undefined { self.in_scope_of.to_owned() } else { e.in_scope_of.to_owned().into() },
+            in_scope_of: if e.in_scope_of == MaybeUndefined::Undefined { self.in_scope_of.to_owned() } else { update_scope_references(&self.in_scope_of, e.in_scope_of.to_owned().into()) },
             note: if e.note== MaybeUndefined::Undefined { self.note.to_owned() } else { e.note.to_owned().into() },
+            provider_inventory_revision: self.provider_inventory_revision.to_owned(),
         }
     }
 }
@@ -16,3 +16,8 @@ pub const EVENT_AFFECTS_RESOURCE_LINK_TAG: &str = "affects";
 
 pub const INVENTORY_CREATION_API_METHOD: &str = "_internal_create_inventory";
 pub const INVENTORY_UPDATE_API_METHOD: &str = "_internal_update_inventory";
+pub const INVENTORY_TRANSFER_API_METHOD: &str = "_internal_apply_transfer";
+
+pub const EVENT_TRANSFER_PROPOSAL_API_METHOD: &str = "receive_transfer_proposal";
+pub const EVENT_TRANSFER_PREFLIGHT_API_METHOD: &str = "accept_transfer_preflight";
+pub const EVENT_TRANSFER_COMMIT_API_METHOD: &str = "commit_countersigned_transfer";
@@ -24,7 +24,9 @@ fn entry_defs(_: ()) -> ExternResult<EntryDefsCallbackResult> {
             crdt_type: CrdtType,
             required_validations: 2.into(),
             required_validation_type: RequiredValidationType::default(),
-        }
+        },
+        PendingTransfer::entry_def(),
+        TransferApplication::entry_def(),
     ]))
 }
 
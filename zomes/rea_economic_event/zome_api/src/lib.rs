@@ -1,5 +1,14 @@
-use hdk_records::{RecordAPIResult};
+use hdk::prelude::PreflightResponse;
+use hdk_records::{
+    RecordAPIResult,
+    history::Timestamp,
+};
+pub use hdk_records::history::{RevisionMeta, RevisionDiff};
+pub use hdk_records::countersigning::{grant_countersigning_proposal_access, get_countersigning_agents};
+pub use hdk_records::validation::{get_modified_revision, validate_modification_author};
 pub use hc_zome_rea_economic_event_rpc::*;
+pub use vf_jsonld::{ExportParams, ExportPage};
+use hc_zome_rea_economic_event_storage::{CountersignedTransferProposal, CountersignedTransferPreflight, CountersignedTransferCommit};
 
 /// API interface for the external zome gateway
 pub trait API {
@@ -8,12 +17,35 @@ pub trait API {
     fn create_economic_event(entry_def_id: Self::S, process_entry_def_id: Self::S,
         event: CreateRequest, new_inventoried_resource: Option<ResourceCreateRequest>
     ) -> RecordAPIResult<ResponseData>;
+    fn apply_economic_event_batch(entry_def_id: Self::S, operations: Vec<BatchOperation>) -> RecordAPIResult<Vec<BatchOperationResult>>;
     fn get_economic_event(entry_def_id: Self::S, address: EconomicEventAddress) -> RecordAPIResult<ResponseData>;
     fn update_economic_event(entry_def_id: Self::S, event: UpdateRequest) -> RecordAPIResult<ResponseData>;
+    fn get_economic_event_revision_heads(entry_def_id: Self::S, address: EconomicEventAddress) -> RecordAPIResult<Vec<RevisionHash>>;
+    fn merge_economic_event_revisions(entry_def_id: Self::S, revision_id: RevisionHash, superseded_revisions: Vec<RevisionHash>) -> RecordAPIResult<ResponseData>;
+    fn get_economic_event_history(entry_def_id: Self::S, address: EconomicEventAddress) -> RecordAPIResult<Vec<RevisionMeta>>;
+    fn get_economic_event_at_revision(revision_id: RevisionHash) -> RecordAPIResult<ResponseData>;
+    fn get_economic_event_as_of(entry_def_id: Self::S, address: EconomicEventAddress, as_of: Timestamp) -> RecordAPIResult<ResponseData>;
+    fn diff_economic_event_revisions(from_revision_id: RevisionHash, to_revision_id: RevisionHash) -> RecordAPIResult<RevisionDiff>;
     fn delete_economic_event(revision_id: RevisionHash) -> RecordAPIResult<bool>;
     fn get_all_economic_events(entry_def_id: Self::S) -> RecordAPIResult<EventResponseCollection>;
+    fn export_economic_events(entry_def_id: Self::S, params: ExportParams) -> RecordAPIResult<ExportPage>;
+    fn get_economic_event_causal_chain(entry_def_id: Self::S, address: EconomicEventAddress) -> RecordAPIResult<CausalChainResponse>;
+    fn get_income_distribution(entry_def_id: Self::S, params: IncomeDistributionParams) -> RecordAPIResult<IncomeDistributionResponse>;
+
+    fn create_countersigned_economic_event(event: CreateRequest) -> RecordAPIResult<PendingTransferResponse>;
+    fn receive_transfer_proposal(proposal: CountersignedTransferProposal) -> RecordAPIResult<()>;
+    fn get_pending_transfers() -> RecordAPIResult<Vec<PendingTransferResponse>>;
+    fn approve_countersigned_transfer(entry_def_id: Self::S, address: EconomicEventAddress) -> RecordAPIResult<ResponseData>;
+    fn accept_transfer_preflight(preflight: CountersignedTransferPreflight) -> RecordAPIResult<PreflightResponse>;
+    fn commit_countersigned_transfer(entry_def_id: Self::S, commit: CountersignedTransferCommit) -> RecordAPIResult<RevisionHash>;
+    fn apply_countersigned_transfer(entry_def_id: Self::S, address: EconomicEventAddress) -> RecordAPIResult<ResponseData>;
+    fn get_transfer_applications() -> RecordAPIResult<Vec<TransferApplicationStatus>>;
+    fn retry_transfer_applications(entry_def_id: Self::S) -> RecordAPIResult<Vec<TransferApplicationStatus>>;
 }
 
+/// Interval between attempts to apply countersigned transfers which have not yet been applied to inventory
+pub const TRANSFER_APPLICATION_RETRY_INTERVAL_SECS: u64 = 60;
+
 /// Macro to programatically and predictably bind an `API` implementation to a
 /// ValueFlows-compatibile module storage API.
 ///
@@ -31,6 +63,11 @@ macro_rules! declare_economic_event_zome_api {
             )?)
         }
 
+        #[hdk_extern]
+        fn apply_economic_event_batch(BatchParams { operations }: BatchParams) -> ExternResult<Vec<BatchOperationResult>> {
+            Ok(<$zome_api>::apply_economic_event_batch(EVENT_ENTRY_TYPE, operations)?)
+        }
+
         #[hdk_extern]
         fn get_economic_event(ByAddress { address }: ByAddress<EconomicEventAddress>) -> ExternResult<ResponseData> {
             Ok(<$zome_api>::get_economic_event(EVENT_ENTRY_TYPE, address)?)
@@ -41,6 +78,36 @@ macro_rules! declare_economic_event_zome_api {
             Ok(<$zome_api>::update_economic_event(EVENT_ENTRY_TYPE, event)?)
         }
 
+        #[hdk_extern]
+        fn get_economic_event_revision_heads(ByAddress { address }: ByAddress<EconomicEventAddress>) -> ExternResult<Vec<RevisionHash>> {
+            Ok(<$zome_api>::get_economic_event_revision_heads(EVENT_ENTRY_TYPE, address)?)
+        }
+
+        #[hdk_extern]
+        fn merge_economic_event_revisions(MergeRevisions { revision_id, superseded_revisions }: MergeRevisions) -> ExternResult<ResponseData> {
+            Ok(<$zome_api>::merge_economic_event_revisions(EVENT_ENTRY_TYPE, revision_id, superseded_revisions)?)
+        }
+
+        #[hdk_extern]
+        fn get_economic_event_history(ByAddress { address }: ByAddress<EconomicEventAddress>) -> ExternResult<Vec<RevisionMeta>> {
+            Ok(<$zome_api>::get_economic_event_history(EVENT_ENTRY_TYPE, address)?)
+        }
+
+        #[hdk_extern]
+        fn get_economic_event_at_revision(ByHeader { address }: ByHeader) -> ExternResult<ResponseData> {
+            Ok(<$zome_api>::get_economic_event_at_revision(address)?)
+        }
+
+        #[hdk_extern]
+        fn get_economic_event_as_of(ByAddressAsOf { address, as_of }: ByAddressAsOf<EconomicEventAddress>) -> ExternResult<ResponseData> {
+            Ok(<$zome_api>::get_economic_event_as_of(EVENT_ENTRY_TYPE, address, as_of)?)
+        }
+
+        #[hdk_extern]
+        fn diff_economic_event_revisions(ByRevisionPair { from_revision_id, to_revision_id }: ByRevisionPair) -> ExternResult<RevisionDiff> {
+            Ok(<$zome_api>::diff_economic_event_revisions(from_revision_id, to_revision_id)?)
+        }
+
         #[hdk_extern]
         fn delete_economic_event(ByHeader { address }: ByHeader) -> ExternResult<bool> {
             Ok(<$zome_api>::delete_economic_event(address)?)
@@ -50,6 +117,87 @@ macro_rules! declare_economic_event_zome_api {
         fn get_all_economic_events(_: ()) -> ExternResult<EventResponseCollection> {
             Ok(<$zome_api>::get_all_economic_events(EVENT_ENTRY_TYPE)?)
         }
+
+        #[hdk_extern]
+        fn export_economic_events(params: ExportParams) -> ExternResult<ExportPage> {
+            Ok(<$zome_api>::export_economic_events(EVENT_ENTRY_TYPE, params)?)
+        }
+
+        #[hdk_extern]
+        fn get_economic_event_causal_chain(ByAddress { address }: ByAddress<EconomicEventAddress>) -> ExternResult<CausalChainResponse> {
+            Ok(<$zome_api>::get_economic_event_causal_chain(EVENT_ENTRY_TYPE, address)?)
+        }
+
+        #[hdk_extern]
+        fn get_income_distribution(params: IncomeDistributionParams) -> ExternResult<IncomeDistributionResponse> {
+            Ok(<$zome_api>::get_income_distribution(EVENT_ENTRY_TYPE, params)?)
+        }
+
+        // counterparties to transfers must be able to propose them. Access to countersigning
+        // sessions themselves is granted per-transfer, once proposed.
+        #[hdk_extern]
+        fn init(_: ()) -> ExternResult<InitCallbackResult> {
+            $crate::grant_countersigning_proposal_access(&[
+                EVENT_TRANSFER_PROPOSAL_API_METHOD,
+            ])?;
+            schedule("scheduled_transfer_application")?;
+            Ok(InitCallbackResult::Pass)
+        }
+
+        #[hdk_extern]
+        fn create_countersigned_economic_event(CountersignedCreateParams { event }: CountersignedCreateParams) -> ExternResult<PendingTransferResponse> {
+            Ok(<$zome_api>::create_countersigned_economic_event(event)?)
+        }
+
+        #[hdk_extern]
+        fn receive_transfer_proposal(proposal: CountersignedTransferProposal) -> ExternResult<()> {
+            Ok(<$zome_api>::receive_transfer_proposal(proposal)?)
+        }
+
+        #[hdk_extern]
+        fn get_pending_transfers(_: ()) -> ExternResult<Vec<PendingTransferResponse>> {
+            Ok(<$zome_api>::get_pending_transfers()?)
+        }
+
+        #[hdk_extern]
+        fn approve_countersigned_transfer(ByAddress { address }: ByAddress<EconomicEventAddress>) -> ExternResult<ResponseData> {
+            Ok(<$zome_api>::approve_countersigned_transfer(EVENT_ENTRY_TYPE, address)?)
+        }
+
+        #[hdk_extern]
+        fn accept_transfer_preflight(preflight: CountersignedTransferPreflight) -> ExternResult<PreflightResponse> {
+            Ok(<$zome_api>::accept_transfer_preflight(preflight)?)
+        }
+
+        #[hdk_extern]
+        fn commit_countersigned_transfer(commit: CountersignedTransferCommit) -> ExternResult<RevisionHash> {
+            Ok(<$zome_api>::commit_countersigned_transfer(EVENT_ENTRY_TYPE, commit)?)
+        }
+
+        #[hdk_extern]
+        fn apply_countersigned_transfer(ByAddress { address }: ByAddress<EconomicEventAddress>) -> ExternResult<ResponseData> {
+            Ok(<$zome_api>::apply_countersigned_transfer(EVENT_ENTRY_TYPE, address)?)
+        }
+
+        #[hdk_extern]
+        fn get_transfer_applications(_: ()) -> ExternResult<Vec<TransferApplicationStatus>> {
+            Ok(<$zome_api>::get_transfer_applications()?)
+        }
+
+        #[hdk_extern]
+        fn retry_transfer_applications(_: ()) -> ExternResult<Vec<TransferApplicationStatus>> {
+            Ok(<$zome_api>::retry_transfer_applications(EVENT_ENTRY_TYPE)?)
+        }
+
+        // countersigned entries must be committed alone, so each party's side of a transfer is
+        // applied to their inventory by a later call
+        #[hdk_extern(infallible)]
+        fn scheduled_transfer_application(_: Option<Schedule>) -> Option<Schedule> {
+            if let Err(e) = <$zome_api>::retry_transfer_applications(EVENT_ENTRY_TYPE) {
+                warn!("transfer application failed: {}", e);
+            }
+            Some(Schedule::Ephemeral(std::time::Duration::from_secs($crate::TRANSFER_APPLICATION_RETRY_INTERVAL_SECS)))
+        }
     };
 }
 
@@ -58,7 +206,16 @@ macro_rules! declare_economic_event_zome_api {
 /// This is the minimum validation required by any zome, and regardless of other
 /// validation rules being implemented it is critical that
 /// `record.validate_or_fields()` and `record.validate_action()` be peformed upon
-/// `EntryStorage` creation.
+/// `EntryStorage` creation. `validate_countersignatures()` ensures countersigned
+/// transfers were signed by both parties. `validate_credit_limit()` enforces the
+/// limits of mutual credit balances against the provider's resource revision loaded by
+/// `must_get_provider_inventory()`, and may be omitted where ledger mode is not used.
+/// `record.must_get_references()` defers validation until referenced records in
+/// the same DNA are available.
+///
+/// Updates & deletes are restricted to the event's original author, its provider &
+/// receiver and any agents it is in the scope of. Updates may not alter the fields
+/// checked by `record.validate_immutable_fields()`.
 ///
 /// Use this method as a reference, and always call the below logic before any
 /// application-specific validation in custom validation rules.
@@ -69,7 +226,24 @@ macro_rules! declare_economic_event_zome_validation_defaults {
         #[hdk_extern]
         fn validate(validation_data: ValidateData) -> ExternResult<ValidateCallbackResult> {
             let element = validation_data.element;
-            let entry = element.into_inner().1;
+            let (signed_header, entry) = element.into_inner();
+            let header = signed_header.header();
+
+            // updates & deletes may only be made by the original author or agents authorised by the previous revision
+            let previous = match $crate::get_modified_revision(header)? {
+                Some(modified) => match EntryStorage::try_from(&modified.entry) {
+                    Ok(previous_storage) => {
+                        let previous = previous_storage.entry();
+                        if let Err(e) = $crate::validate_modification_author(header, &modified, &previous.get_authorised_agents()) {
+                            return Ok(ValidateCallbackResult::Invalid(e.encode()));
+                        }
+                        Some(previous)
+                    },
+                    _ => None,
+                },
+                None => None,
+            };
+
             let entry = match entry {
                 ElementEntry::Present(e) => e,
                 _ => return Ok(ValidateCallbackResult::Valid),
@@ -78,10 +252,23 @@ macro_rules! declare_economic_event_zome_validation_defaults {
             match EntryStorage::try_from(&entry) {
                 Ok(event_storage) => {
                     let record = event_storage.entry();
+                    record.must_get_references()?;
+                    // credit limits are checked upon creation only, since the referenced resource revision cannot be updated
+                    let provider_inventory = match &previous {
+                        None => must_get_provider_inventory(&record)?,
+                        Some(_) => None,
+                    };
+                    let countersigned = !$crate::get_countersigning_agents(&entry).is_empty();
                     record.validate_or_fields()
                         .and_then(|()| { record.validate_action() })
+                        .and_then(|()| { previous.as_ref().map_or(Ok(()), |p| { record.validate_immutable_fields(p) }) })
+                        .and_then(|()| { validate_countersignatures(&record, &entry) })
+                        .and_then(|()| { match &previous {
+                            None => validate_credit_limit(&record, header, countersigned, provider_inventory.as_ref()),
+                            Some(_) => Ok(()),
+                        } })
                         .and_then(|()| { Ok(ValidateCallbackResult::Valid) })
-                        .or_else(|e| { Ok(ValidateCallbackResult::Invalid(e)) })
+                        .or_else(|e| { Ok(ValidateCallbackResult::Invalid(e.encode())) })
                 },
                 _ => Ok(ValidateCallbackResult::Valid),
             }
@@ -9,11 +9,22 @@ use hc_zome_rea_economic_event_rpc::*;
 
 #[index_zome]
 struct EconomicEvent {
+    #[source_field]
     input_of: Local<process, inputs>,
+    #[source_field]
     output_of: Local<process, outputs>,
+    #[source_field]
     realization_of: Remote<agreement, realized_by>,
     satisfies: Remote<satisfaction, satisfied_by>,
     fulfills: Remote<fulfillment, fulfilled_by>,
+    #[source_field]
+    triggered_by: Local<economic_event, triggers>,
+    triggers: Local<economic_event, triggered_by>,
+    provider: Local<agent_record, economic_events_as_provider>,
+    receiver: Local<agent_record, economic_events_as_receiver>,
+    involved_agent: Local<agent_record, economic_events_involving>,
+    in_scope_of: Local<agent_record, economic_events_in_scope>,
+    classified_as: Keyed<classification, economic_events>,
 
     // internal indexes (not part of REA spec)
     affects: Local<economic_resource, affected_by>,
@@ -9,15 +9,36 @@
 use paste::paste;
 use hdk_records::{
     DataIntegrityError, RecordAPIResult, MaybeUndefined,
+    agent_info,
+    entries::get_entry_by_address,
     local_indexes::{
         query_root_index,
+        query_root_index_page,
     },
     records::{
         get_latest_header_hash,
         create_record,
         read_record_entry,
+        read_record_entry_by_header,
         update_record,
+        read_record_revision_heads,
+        merge_record_revisions,
+        get_index_changes,
     },
+    history::{
+        Timestamp,
+        RevisionMeta,
+        RevisionDiff,
+        read_record_revision_history,
+        read_record_entry_as_of,
+        diff_record_revisions,
+    },
+    records_anchored::{
+        anchor_index_exists,
+        create_anchor_index,
+        read_anchor_index,
+    },
+    rpc::call_local_zome_method,
     EntryHash,
 };
 use hdk_semantic_indexes_client_lib::*;
@@ -28,7 +49,13 @@ use vf_attributes_hdk::{
     EconomicEventAddress,
     ActionId,
     ProcessSpecificationAddress,
+    ResourceSpecificationAddress,
+    LocationAddress,
+    ExternalURL,
+    DateTime, FixedOffset,
 };
+use vf_measurement::{QuantityValue, add};
+use vf_jsonld::{JsonLdNode, build_export_page};
 
 pub use hc_zome_rea_economic_resource_storage_consts::*;
 pub use hc_zome_rea_economic_event_storage_consts::{EVENT_ENTRY_TYPE};
@@ -80,6 +107,13 @@ impl API for EconomicResourceZomePermissableDefault {
         let resource_params = params.get_resource_params().clone();
         let resource_spec = params.get_resource_specification_id();
 
+        // ensure tracking identifier is not already in use before writing anything
+        if let MaybeUndefined::Some(tracking_identifier) = &resource_params.tracking_identifier {
+            if anchor_index_exists(&RESOURCE_TRACKING_IDENTIFIER_ANCHOR, tracking_identifier)? {
+                return Err(DataIntegrityError::DuplicateAnchorKey(tracking_identifier.to_owned()));
+            }
+        }
+
         let (revision_id, base_address, entry_resp): (_, EconomicResourceAddress, EntryData) = create_record(
             &resource_entry_def_id,
             params.with_inventory_type(ResourceInventoryType::ProvidingInventory),  // inventories can only be inited by their owners initially
@@ -93,6 +127,13 @@ impl API for EconomicResourceZomePermissableDefault {
             create_index!(Self(economic_resource(&base_address).contained_in(&contained_in)))?;
         };
 
+        if let Some(tracking_identifier) = &entry_resp.tracking_identifier {
+            create_anchor_index(&resource_entry_def_id, &base_address, &RESOURCE_TRACKING_IDENTIFIER_ANCHOR, tracking_identifier)?;
+        }
+
+        // index classifications
+        update_index!(Keyed(economic_resource(&base_address).classified_as(entry_resp.classified_as.to_owned().unwrap_or_default().as_slice())))?;
+
         Ok((revision_id, base_address, entry_resp))
     }
 
@@ -102,6 +143,17 @@ impl API for EconomicResourceZomePermissableDefault {
         construct_response(&base_address, &revision, &entry, get_link_fields(&event_entry_def_id, &process_entry_def_id, &address)?)
     }
 
+    fn get_economic_resource_by_tracking_identifier(entry_def_id: Self::S, event_entry_def_id: Self::S, process_entry_def_id: Self::S, tracking_identifier: String) -> RecordAPIResult<TrackedResourceResponseData>
+    {
+        let address: EconomicResourceAddress = read_anchor_index(&RESOURCE_TRACKING_IDENTIFIER_ANCHOR, &tracking_identifier)?;
+        let (revision, base_address, entry) = read_record_entry::<EntryData, EntryStorage, _,_>(&entry_def_id, address.as_ref())?;
+
+        Ok(TrackedResourceResponseData {
+            economic_resource: construct_response_record(&base_address, &revision, &entry, get_link_fields(&event_entry_def_id, &process_entry_def_id, &base_address)?)?,
+            affected_by: get_affecting_events(&base_address)?,
+        })
+    }
+
     /// Handle update of resources by iterative reduction of event records over time.
     ///
     fn update_inventory_from_event(
@@ -130,29 +182,160 @@ impl API for EconomicResourceZomePermissableDefault {
             )?);
         }
 
+        // events may append to the classifications of affected resources
+        for (_revision_id, identity_address, new_entry, prev_entry) in resources_affected.iter() {
+            update_economic_resource_indexes(identity_address, new_entry, prev_entry, None)?;
+        }
+
         Ok(resources_affected)
     }
 
+    /// Apply the current agent's side of a countersigned transfer to their inventory.
+    /// The same checks are made by validators, see `EntryData::validate_applied_transfer`.
+    ///
+    fn apply_transfer_to_inventory(
+        resource_entry_def_id: Self::S,
+        transfer: EconomicEventAddress,
+    ) -> RecordAPIResult<(RevisionHash, EconomicResourceAddress, EntryData, EntryData)>
+    {
+        let me = agent_info()?.agent_initial_pubkey;
+        let event = get_entry_by_address::<EventStorage>(transfer.as_ref())?.entry();
+        let resource = if event.provider.1 == me {
+            event.resource_inventoried_as.to_owned()
+        } else if event.receiver.1 == me {
+            event.to_resource_inventoried_as.to_owned()
+        } else {
+            None
+        }.ok_or_else(|| { DataIntegrityError::CountersigningError("agent holds no inventory affected by the transfer".to_string()) })?;
+
+        let (revision_id, identity_address, prev_entry) = read_record_entry::<EntryData, EntryStorage, _,_>(&resource_entry_def_id, resource.as_ref())?;
+        let entry = prev_entry.with_transfer(&identity_address, &transfer, &event, &me)?;
+        entry.validate_applied_transfer(&identity_address, &prev_entry, &me, entry.must_get_applied_transfers(&prev_entry)?.as_ref())?;
+
+        Ok(update_record(&resource_entry_def_id, &revision_id, TransferEffect(entry))?)
+    }
+
     fn update_economic_resource(entry_def_id: Self::S, event_entry_def_id: Self::S, process_entry_def_id: Self::S, resource: UpdateRequest) -> RecordAPIResult<ResponseData>
     {
         let address = resource.get_revision_id().clone();
         let (revision_id, identity_address, entry, prev_entry): (_,_, EntryData, EntryData) = update_record(&entry_def_id, &address, resource)?;
-
-        // :TODO: this may eventually be moved to an EconomicEvent update, see https://lab.allmende.io/valueflows/valueflows/-/issues/637
-        let now_contained = if let Some(contained) = &entry.contained_in { vec![contained.clone()] } else { vec![] };
-        let prev_contained = if let Some(contained) = &prev_entry.contained_in { vec![contained.clone()] } else { vec![] };
-        update_index!(Self(economic_resource(&identity_address).contained_in(now_contained.as_slice()).not(prev_contained.as_slice())))?;
+        update_economic_resource_indexes(&identity_address, &entry, &prev_entry, None)?;
 
         // :TODO: optimise this- should pass results from `replace_direct_index` instead of retrieving from `get_link_fields` where updates
         construct_response(&identity_address, &revision_id, &entry, get_link_fields(&event_entry_def_id, &process_entry_def_id, &identity_address)?)
     }
 
+    fn get_economic_resource_revision_heads(entry_def_id: Self::S, address: EconomicResourceAddress) -> RecordAPIResult<Vec<RevisionHash>>
+    {
+        read_record_revision_heads::<EconomicResourceAddress, _>(&entry_def_id, address.as_ref())
+    }
+
+    fn merge_economic_resource_revisions(entry_def_id: Self::S, event_entry_def_id: Self::S, process_entry_def_id: Self::S, revision_id: RevisionHash, superseded_revisions: Vec<RevisionHash>) -> RecordAPIResult<ResponseData>
+    {
+        let (_revision, base_address, entry, superseded): (_, EconomicResourceAddress, EntryData, _) = merge_record_revisions::<EntryData, EntryStorage, _>(&revision_id, superseded_revisions.as_slice())?;
+        for (superseded_entry, parent_entry) in superseded.iter() {
+            update_economic_resource_indexes(&base_address, &entry, superseded_entry, Some(parent_entry))?;
+        }
+        Self::get_economic_resource(entry_def_id, event_entry_def_id, process_entry_def_id, base_address)
+    }
+
+    fn get_economic_resource_history(entry_def_id: Self::S, address: EconomicResourceAddress) -> RecordAPIResult<Vec<RevisionMeta>>
+    {
+        read_record_revision_history::<EconomicResourceAddress, _>(&entry_def_id, address.as_ref())
+    }
+
+    fn get_economic_resource_at_revision(event_entry_def_id: Self::S, process_entry_def_id: Self::S, revision_id: RevisionHash) -> RecordAPIResult<ResponseData>
+    {
+        let (base_address, entry) = read_record_entry_by_header::<EntryData, EntryStorage, _>(&revision_id)?;
+        construct_response(&base_address, &revision_id, &entry, get_link_fields(&event_entry_def_id, &process_entry_def_id, &base_address)?)
+    }
+
+    fn get_economic_resource_as_of(entry_def_id: Self::S, _event_entry_def_id: Self::S, _process_entry_def_id: Self::S, address: EconomicResourceAddress, as_of: Timestamp) -> RecordAPIResult<ResponseData>
+    {
+        let (revision, base_address, entry) = read_record_entry_as_of::<EntryData, EntryStorage, _,_>(&entry_def_id, address.as_ref(), &as_of)?;
+        // link fields are omitted, since indexes (and the events determining resource stage & state) do not retain their history
+        construct_response(&base_address, &revision, &entry, Default::default())
+    }
+
+    fn diff_economic_resource_revisions(from_revision_id: RevisionHash, to_revision_id: RevisionHash) -> RecordAPIResult<RevisionDiff>
+    {
+        diff_record_revisions::<EntryData, EntryStorage, EconomicResourceAddress>(&from_revision_id, &to_revision_id)
+    }
+
+    fn get_economic_resource_balance(entry_def_id: Self::S, event_entry_def_id: Self::S, address: EconomicResourceAddress, as_of: DateTime<FixedOffset>) -> RecordAPIResult<ResourceBalance>
+    {
+        replay_inventory_events(&entry_def_id, &event_entry_def_id, &address, Some(&as_of))?
+            .pop()
+            .ok_or(DataIntegrityError::EntryNotFound)    // resource did not exist at the given time
+    }
+
+    fn get_economic_resource_balance_series(entry_def_id: Self::S, event_entry_def_id: Self::S, address: EconomicResourceAddress,
+        from: Option<DateTime<FixedOffset>>, until: Option<DateTime<FixedOffset>>,
+    ) -> RecordAPIResult<Vec<ResourceBalance>>
+    {
+        Ok(replay_inventory_events(&entry_def_id, &event_entry_def_id, &address, until.as_ref())?
+            .into_iter()
+            .filter(|balance| { from.map_or(true, |from| { balance.as_of >= from }) })
+            .collect())
+    }
+
+    fn get_inventory_report(entry_def_id: Self::S, _event_entry_def_id: Self::S, params: InventoryReportParams) -> RecordAPIResult<InventoryReport>
+    {
+        let group_by_spec = params.group_by.contains(&InventoryGrouping::ConformsTo);
+        let group_by_owner = params.group_by.contains(&InventoryGrouping::Owner);
+        let group_by_location = params.group_by.contains(&InventoryGrouping::Location);
+        let group_by_unit = params.group_by.contains(&InventoryGrouping::Unit);
+
+        let mut groups: Vec<InventorySummary> = vec![];
+
+        for item in read_inventory_items(&entry_def_id, &params.conforms_to)? {
+            let conforms_to = if group_by_spec { item.conforms_to.to_owned() } else { None };
+            let current_location = if group_by_location { item.current_location.to_owned() } else { None };
+            let owner = if group_by_owner { item.primary_accountable.to_owned() } else { None };
+            let unit = if group_by_unit {
+                item.accounting_quantity.as_ref().or(item.onhand_quantity.as_ref()).and_then(|q| { q.get_unit() })
+            } else { None };
+
+            let group_idx = match groups.iter().position(|g| {
+                g.conforms_to == conforms_to && g.owner == owner && g.current_location == current_location && g.unit == unit
+            }) {
+                Some(idx) => idx,
+                None => {
+                    groups.push(InventorySummary {
+                        conforms_to, owner, current_location, unit,
+                        resource_count: 0,
+                        accounting_quantity: vec![],
+                        onhand_quantity: vec![],
+                    });
+                    groups.len() - 1
+                },
+            };
+
+            let group = &mut groups[group_idx];
+            group.resource_count += 1;
+            add_to_totals(&mut group.accounting_quantity, &item.accounting_quantity);
+            add_to_totals(&mut group.onhand_quantity, &item.onhand_quantity);
+        }
+
+        Ok(InventoryReport { groups })
+    }
+
     fn get_all_economic_resources(entry_def_id: Self::S, event_entry_def_id: Self::S, process_entry_def_id: Self::S) -> RecordAPIResult<Collection>
     {
         let entries_result = query_root_index::<EntryData, EntryStorage, _,_>(&entry_def_id)?;
 
         handle_list_output(event_entry_def_id, process_entry_def_id, entries_result)
     }
+
+    fn export_economic_resources(entry_def_id: Self::S, event_entry_def_id: Self::S, process_entry_def_id: Self::S, params: ExportParams) -> RecordAPIResult<ExportPage>
+    {
+        let limit = params.get_limit();
+        let page = query_root_index_page::<EntryData, EntryStorage, _,_>(&entry_def_id, &params.after, limit)?;
+        build_export_page(page, limit, |(revision_id, base_address, entry)| {
+            let link_fields = get_link_fields(&event_entry_def_id, &process_entry_def_id, &base_address)?;
+            Ok(construct_jsonld(&construct_response_record(&base_address, &revision_id, &entry, link_fields)?))
+        })
+    }
 }
 
 /// Properties accessor for zome config
@@ -160,6 +343,103 @@ fn read_economic_resource_index_zome(conf: DnaConfigSlice) -> Option<String> {
     Some(conf.economic_resource.index_zome)
 }
 
+/// Update the index links of a resource as it moves from `prev_entry` to `new_entry`.
+/// `parent_entry` is given where `prev_entry` is a superseded revision being merged.
+///
+/// @see hdk_records::records::get_index_changes
+///
+fn update_economic_resource_indexes(
+    identity_address: &EconomicResourceAddress, new_entry: &EntryData, prev_entry: &EntryData, parent_entry: Option<&EntryData>,
+) -> RecordAPIResult<()> {
+    // :TODO: this may eventually be moved to an EconomicEvent update, see https://lab.allmende.io/valueflows/valueflows/-/issues/637
+    let (added, removed) = get_index_changes(new_entry, prev_entry, parent_entry, |e| { e.contained_in.iter().cloned().collect() });
+    if !(added.is_empty() && removed.is_empty()) {
+        update_index!(Self(economic_resource(identity_address).contained_in(added.as_slice()).not(removed.as_slice())))?;
+    }
+
+    // sync `classified_as` index with any classifications added or removed
+    let (added, removed): (Vec<ExternalURL>, _) = get_index_changes(new_entry, prev_entry, parent_entry, |e| { e.classified_as.to_owned().unwrap_or_default() });
+    if !(added.is_empty() && removed.is_empty()) {
+        update_index!(Keyed(economic_resource(identity_address).classified_as(added.as_slice()).not(removed.as_slice())))?;
+    }
+
+    Ok(())
+}
+
+//---------------- REPORTING ----------------
+
+/// Inventory-related fields of a resource, as considered by inventory reports
+struct InventoryItem {
+    primary_accountable: Option<AgentAddress>,
+    conforms_to: Option<ResourceSpecificationAddress>,
+    accounting_quantity: Option<QuantityValue>,
+    onhand_quantity: Option<QuantityValue>,
+    current_location: Option<LocationAddress>,
+}
+
+/// Read the resources to include in an inventory report. Where `conforms_to` is provided,
+/// resources are located via the `conforming_resources` index of each specification.
+///
+fn read_inventory_items<S>(entry_def_id: S, conforms_to: &Option<Vec<ResourceSpecificationAddress>>) -> RecordAPIResult<Vec<InventoryItem>>
+    where S: AsRef<str>
+{
+    match conforms_to {
+        Some(specifications) => {
+            let mut items = vec![];
+            for specification in specifications {
+                let resp: ResourceQueryResults = call_local_zome_method(
+                    read_economic_resource_index_zome,
+                    RESOURCE_QUERY_API_METHOD,
+                    ResourceQueryInputs { params: QueryParams {
+                        conforms_to: Some(specification.to_owned()),
+                        contains: None,
+                        contained_in: None,
+                        affected_by: None,
+                        classified_as: None,
+                    } },
+                )?;
+                items.extend(resp.results.into_iter().map(|r| {
+                    let resource = r.economic_resource;
+                    InventoryItem {
+                        primary_accountable: resource.primary_accountable,
+                        conforms_to: resource.conforms_to,
+                        accounting_quantity: resource.accounting_quantity,
+                        onhand_quantity: resource.onhand_quantity,
+                        current_location: resource.current_location,
+                    }
+                }));
+            }
+            Ok(items)
+        },
+        None => Ok(query_root_index::<EntryData, EntryStorage, _,_>(&entry_def_id)?
+            .into_iter()
+            .filter_map(Result::ok)
+            .map(|(_revision_id, _address, entry): (RevisionHash, EconomicResourceAddress, EntryData)| {
+                InventoryItem {
+                    primary_accountable: entry.primary_accountable,
+                    conforms_to: entry.conforms_to,
+                    accounting_quantity: entry.accounting_quantity,
+                    onhand_quantity: entry.onhand_quantity,
+                    current_location: entry.current_location,
+                }
+            })
+            .collect()),
+    }
+}
+
+/// Accumulate a quantity into a set of totals, keeping a separate total for each unit
+///
+/// :TODO: unit conversions, so that totals in compatible units can be combined.
+///
+fn add_to_totals(totals: &mut Vec<QuantityValue>, quantity: &Option<QuantityValue>) {
+    if let Some(qty) = quantity {
+        match totals.iter().position(|total| { total.get_unit() == qty.get_unit() }) {
+            Some(idx) => totals[idx] = add(totals[idx].to_owned(), qty.to_owned()),
+            None => totals.push(qty.to_owned()),
+        }
+    }
+}
+
 fn handle_update_inventory_resource<S>(
     resource_entry_def_id: S,
     resource_addr: &RevisionHash,
@@ -167,7 +447,11 @@ fn handle_update_inventory_resource<S>(
 ) -> RecordAPIResult<(RevisionHash, EconomicResourceAddress, EntryData, EntryData)>
     where S: AsRef<str>,
 {
-    Ok(update_record(&resource_entry_def_id, resource_addr, event)?)
+    // record the negative inventory policy currently in effect, for validators to check inventory levels against
+    let (_identity, resource): (EconomicResourceAddress, EntryData) = read_record_entry_by_header::<EntryData, EntryStorage, _>(resource_addr)?;
+    let policy = read_specification_negative_inventory_policy(&resource);
+
+    Ok(update_record(&resource_entry_def_id, resource_addr, InventoryEffect(event, policy))?)
 }
 
 fn handle_list_output<S>(event_entry_def_id: S, process_entry_def_id: S, entries_result: Vec<RecordAPIResult<(RevisionHash, EconomicResourceAddress, EntryData)>>) -> RecordAPIResult<Collection>
@@ -250,6 +534,8 @@ pub fn construct_response_record<'a>(
         state: state.to_owned(),
         current_location: e.current_location.to_owned(),
         note: e.note.to_owned(),
+        primary_accountable: e.primary_accountable.to_owned(),
+        credit_limit: e.credit_limit.to_owned(),
 
         // link fields
         contained_in: contained_in.to_owned(),
@@ -278,6 +564,32 @@ pub fn construct_list_response<'a>(
     })
 }
 
+/// Create a ValueFlows JSON-LD node from an output record
+///
+/// Inverse relationships (eg. `contains`) are omitted, as they are expressed
+/// by the records on the other side of the relationship.
+///
+pub fn construct_jsonld(r: &Response) -> JsonLdNode {
+    let node = JsonLdNode::new("EconomicResource", &r.id)
+        .reference("conformsTo", &r.conforms_to)
+        .reference_list("classifiedAs", &r.classified_as)
+        .field("trackingIdentifier", &r.tracking_identifier)
+        .reference("lot", &r.lot)
+        .reference("image", &r.image)
+        .quantity("accountingQuantity", &r.accounting_quantity)
+        .quantity("onhandQuantity", &r.onhand_quantity)
+        .reference("unitOfEffort", &r.unit_of_effort)
+        .reference("containedIn", &r.contained_in)
+        .reference("stage", &r.stage)
+        .reference("currentLocation", &r.current_location)
+        .reference("primaryAccountable", &r.primary_accountable)
+        .field("note", &r.note);
+    match &r.state {
+        Some(state) => node.action("state", state),
+        None => node,
+    }
+}
+
 // field list retrieval internals
 // @see construct_response
 pub fn get_link_fields<'a, S>(event_entry_def_id: S, process_entry_def_id: S, resource: &EconomicResourceAddress) -> RecordAPIResult<(
@@ -365,6 +677,67 @@ fn get_resource_stage<S>(event_entry_def_id: S, process_entry_def_id: S, resourc
     )
 }
 
+/// Determine the inventory state of a resource after each of the events affecting it,
+/// in chronological order of event time. Events occurring after `until` are ignored.
+///
+/// :NOTE: events are ordered by their observed time rather than the time they were recorded,
+/// so backdated events will be reflected in historic balances even where they were recorded later.
+///
+fn replay_inventory_events<S>(
+    entry_def_id: S,
+    event_entry_def_id: S,
+    resource: &EconomicResourceAddress,
+    until: Option<&DateTime<FixedOffset>>,
+) -> RecordAPIResult<Vec<ResourceBalance>>
+    where S: AsRef<str>
+{
+    // resource attributes are not recorded on the creating event, retrieve them from the first resource revision
+    let initial_revision = read_record_revision_history::<EconomicResourceAddress, _>(&entry_def_id, resource.as_ref())?
+        .first().cloned()
+        .ok_or(DataIntegrityError::EntryNotFound)?;
+    let (_, initial_entry): (EconomicResourceAddress, EntryData) = read_record_entry_by_header::<EntryData, EntryStorage, _>(&initial_revision.revision_id)?;
+
+    let mut events = get_affecting_events(resource)?
+        .iter()
+        .map(|event_address| {
+            let (_, base_address, entry) = read_record_entry::<EventData, EventStorage, _,_>(&event_entry_def_id, event_address.as_ref())?;
+            let event_time = get_event_time(&entry).ok_or(DataIntegrityError::EntryNotFound)?;
+            Ok((event_time, base_address, entry))
+        })
+        .collect::<RecordAPIResult<Vec<(DateTime<FixedOffset>, EconomicEventAddress, EventData)>>>()?;
+    events.sort_by(|(a, _, _), (b, _, _)| { a.cmp(b) });
+
+    let mut state: Option<InventoryState> = None;
+    Ok(events.iter()
+        .filter(|(event_time, _, _)| { until.map_or(true, |until| { event_time <= until }) })
+        .map(|(event_time, event_address, event)| {
+            let next_state = match &state {
+                None => InventoryState::from_creating_event(resource, event, &initial_entry),
+                Some(prev_state) => prev_state.with_event(resource, event),
+            };
+            state = Some(next_state.to_owned());
+
+            ResourceBalance {
+                as_of: event_time.to_owned(),
+                last_affected_by: event_address.to_owned(),
+                accounting_quantity: next_state.resource.accounting_quantity,
+                onhand_quantity: next_state.resource.onhand_quantity,
+                current_location: next_state.resource.current_location,
+                primary_accountable: next_state.resource.primary_accountable,
+                custodian: next_state.custodian,
+            }
+        })
+        .collect())
+}
+
+/// Determine the time at which an event occurred, for the purposes of ordering
+fn get_event_time(event: &EventData) -> Option<DateTime<FixedOffset>>
+{
+    event.has_point_in_time.to_owned()
+        .or(event.has_end.to_owned())
+        .or(event.has_beginning.to_owned())
+}
+
 /// Read all the EconomicEvents affecting a given EconomicResource
 fn get_affecting_events(resource: &EconomicResourceAddress) -> RecordAPIResult<Vec<EconomicEventAddress>>
 {
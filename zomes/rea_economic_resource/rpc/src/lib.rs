@@ -9,8 +9,9 @@
 use holochain_serialized_bytes::prelude::*;
 
 use serde_maybe_undefined::MaybeUndefined;
+use vf_measurement::QuantityValue;
 pub use vf_attributes_hdk::{
-    RevisionHash, ByAddress,
+    RevisionHash, ByAddress, ByHeader, MergeRevisions, ByAddressAsOf, ByRevisionPair,
     EconomicResourceAddress,
     EconomicEventAddress,
     ExternalURL,
@@ -18,11 +19,15 @@ pub use vf_attributes_hdk::{
     ResourceSpecificationAddress,
     UnitId,
     ProductBatchAddress,
+    AgentAddress,
+    DateTime, FixedOffset,
 };
 
 use hc_zome_rea_economic_event_rpc::{
     CreateRequest as EventCreateRequest,
     ResourceCreateRequest as CreateRequest,
+    ResourceResponse as Response,
+    ResourceResponseData as ResponseData,
     ResourceInventoryType,
 };
 
@@ -74,6 +79,8 @@ pub struct UpdateRequest {
     pub unit_of_effort: MaybeUndefined<UnitId>,
     #[serde(default)]
     pub note: MaybeUndefined<String>,
+    #[serde(default)]
+    pub credit_limit: MaybeUndefined<QuantityValue>,
 }
 
 impl<'a> UpdateRequest {
@@ -91,6 +98,114 @@ pub struct UpdateParams {
     pub resource: UpdateRequest,
 }
 
+//---------------- TRACKING IDENTIFIER LOOKUP ----------------
+
+/// Locate a resource by its unique `tracking_identifier` (eg. serial number or RFID tag)
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ByTrackingIdentifier {
+    pub tracking_identifier: String,
+}
+
+/// Resource record resolved via its tracking identifier, along with the events
+/// which have affected it over time
+#[derive(Clone, Serialize, Deserialize, SerializedBytes, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TrackedResourceResponseData {
+    pub economic_resource: Response,
+    pub affected_by: Vec<EconomicEventAddress>,
+}
+
+//---------------- HISTORIC BALANCE QUERIES ----------------
+
+/// Query the inventory state of a resource as at some date
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BalanceAsOfParams {
+    pub address: EconomicResourceAddress,
+    pub as_of: DateTime<FixedOffset>,
+}
+
+/// Query the inventory state of a resource over time, optionally bounded by date
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BalanceSeriesParams {
+    pub address: EconomicResourceAddress,
+    #[serde(default)]
+    pub from: Option<DateTime<FixedOffset>>,
+    #[serde(default)]
+    pub until: Option<DateTime<FixedOffset>>,
+}
+
+/// Inventory state of a resource following the most recent event at or before `as_of`
+#[derive(Clone, Serialize, Deserialize, SerializedBytes, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceBalance {
+    pub as_of: DateTime<FixedOffset>,
+    pub last_affected_by: EconomicEventAddress,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accounting_quantity: Option<QuantityValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub onhand_quantity: Option<QuantityValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current_location: Option<LocationAddress>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub primary_accountable: Option<AgentAddress>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custodian: Option<AgentAddress>,
+}
+
+//---------------- INVENTORY REPORTS ----------------
+
+/// Attributes by which resource inventory can be aggregated
+#[derive(Clone, Serialize, Deserialize, SerializedBytes, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum InventoryGrouping {
+    ConformsTo,
+    Owner,
+    Location,
+    Unit,
+}
+
+/// Request an aggregate summary of resource inventory.
+/// If `conforms_to` is provided, only resources conforming to the given specifications are included.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InventoryReportParams {
+    #[serde(default)]
+    pub conforms_to: Option<Vec<ResourceSpecificationAddress>>,
+    #[serde(default)]
+    pub group_by: Vec<InventoryGrouping>,
+}
+
+/// Aggregate quantities for a group of resources. Only the attributes being grouped by are populated.
+///
+/// `owner` is the primary accountable agent of the grouped resources. Totals are reported
+/// separately for each unit of measure encountered, since resources recorded in different
+/// units cannot be meaningfully summed. Grouping by `unit` places each unit in its own group.
+///
+#[derive(Clone, Serialize, Deserialize, SerializedBytes, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct InventorySummary {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub conforms_to: Option<ResourceSpecificationAddress>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<AgentAddress>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current_location: Option<LocationAddress>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unit: Option<UnitId>,
+    pub resource_count: u64,
+    pub accounting_quantity: Vec<QuantityValue>,
+    pub onhand_quantity: Vec<QuantityValue>,
+}
+
+#[derive(Clone, Serialize, Deserialize, SerializedBytes, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct InventoryReport {
+    pub groups: Vec<InventorySummary>,
+}
+
 //---------------- QUERY FILTER REQUEST ----------------
 
 #[derive(Clone, Serialize, Deserialize, SerializedBytes, Debug)]
@@ -100,4 +215,18 @@ pub struct QueryParams {
     pub contained_in: Option<EconomicResourceAddress>,
     pub conforms_to: Option<ResourceSpecificationAddress>,
     pub affected_by: Option<EconomicEventAddress>,
+    pub classified_as: Option<ExternalURL>,
+}
+
+/// Wraps `QueryParams` for calls to the associated index zome's query API
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResourceQueryInputs {
+    pub params: QueryParams,
+}
+
+/// Results returned from the associated index zome's query API
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResourceQueryResults {
+    #[serde(default)]
+    pub results: Vec<ResponseData>,
 }
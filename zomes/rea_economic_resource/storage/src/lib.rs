@@ -9,30 +9,43 @@
 use hdk::prelude::*;
 
 use hdk_records::{
-    MaybeUndefined, OtherCellResult,
+    RecordAPIResult, MaybeUndefined, OtherCellResult,
     generate_record_entry,
     record_interface::Updateable,
     rpc::call_zome_method,
+    countersigning::{get_countersigning_agents, get_countersigning_session_start},
+    ErrorCode, ErrorDetails,
+    validation::{must_get_local_references, missing_alternative_fields},
 };
 
 use vf_measurement::*;
 use vf_attributes_hdk::{
     EconomicResourceAddress,
+    EconomicEventAddress,
     ExternalURL,
     LocationAddress,
     ResourceSpecificationAddress,
     UnitId,
     ProductBatchAddress,
     ActionId,
+    AgentAddress,
+    NegativeInventoryPolicy,
 };
 use vf_actions::{ ActionEffect, ActionInventoryEffect, get_builtin_action };
-use hc_zome_rea_resource_specification_rpc::{ResponseData as ResourceSpecificationResponse};
+use hc_zome_rea_resource_specification_rpc::{
+    Response as ResourceSpecification,
+    ResponseData as ResourceSpecificationResponse,
+};
 
 use hc_zome_rea_economic_resource_rpc::*;
 use hc_zome_rea_economic_event_rpc::{
     CreateRequest as EventCreateRequest,
     ResourceInventoryType,
 };
+use hc_zome_rea_economic_event_storage::{
+    EntryData as EventData,
+    EntryStorage as EventStorage,
+};
 
 // :SHONK: needed as re-export in zome logic to allow validation logic to parse entries
 pub use hdk_records::record_interface::Identified;
@@ -48,6 +61,9 @@ pub struct DnaConfigSlice {
 #[derive(Clone, Serialize, Deserialize, SerializedBytes, PartialEq, Debug)]
 pub struct EconomicResourceZomeConfig {
     pub index_zome: String,
+    // default handling of events which would take inventory below zero, where not set by the `ResourceSpecification`
+    #[serde(default)]
+    pub negative_inventory_policy: NegativeInventoryPolicy,
 }
 
 //---------------- RECORD INTERNALS & VALIDATION ----------------
@@ -65,17 +81,252 @@ pub struct EntryData {
     pub current_location: Option<LocationAddress>,
     pub contained_in: Option<EconomicResourceAddress>,
     pub note: Option<String>,
+    #[serde(default)]
+    pub primary_accountable: Option<AgentAddress>,
+    #[serde(default)]
+    pub credit_limit: Option<QuantityValue>,
+    #[serde(default)]
+    pub negative_inventory_policy: Option<NegativeInventoryPolicy>,
+    #[serde(default)]
+    pub last_applied_transfer: Option<EconomicEventAddress>,
 }
 
 impl EntryData {
-    pub fn validate(&self) -> Result<(), String> {
+    pub fn validate(&self) -> Result<(), ErrorDetails> {
         if !(self.classified_as.is_some() || self.conforms_to.is_some()) {
-            return Err("EconomicResource must have either a specification or classification".into());
+            return Err(missing_alternative_fields(
+                "EconomicResource must have either a specification or classification",
+                &["conformsTo", "classifiedAs"],
+            ));
+        }
+        self.validate_credit_limit()
+    }
+
+    /// Ensure referenced specifications & containing resources in this DNA exist.
+    ///
+    pub fn must_get_references(&self) -> RecordAPIResult<()> {
+        must_get_local_references(vec![&self.conforms_to])?;
+        must_get_local_references(vec![&self.contained_in])
+    }
+
+    /// Agents other than the original author who may update or delete this resource:
+    /// the agent primarily accountable for it.
+    ///
+    pub fn get_authorised_agents(&self) -> Vec<AgentPubKey> {
+        self.primary_accountable.iter().map(|a| { a.1.to_owned() }).collect()
+    }
+
+    /// The specification a resource conforms to may not be changed once it is created.
+    ///
+    pub fn validate_immutable_fields(&self, previous: &EntryData) -> Result<(), ErrorDetails> {
+        if self.conforms_to != previous.conforms_to {
+            return Err(ErrorDetails::new(ErrorCode::ImmutableField, "EconomicResource specification cannot be changed").at_field("conformsTo"));
+        }
+        Ok(())
+    }
+
+    /// Mutual credit balances (those with a `credit_limit`) may not be drawn
+    /// down further than their limit below zero.
+    ///
+    pub fn validate_credit_limit(&self) -> Result<(), ErrorDetails> {
+        let (limit, balance) = match (&self.credit_limit, &self.accounting_quantity) {
+            (Some(l), Some(b)) => (l, b),
+            _ => return Ok(()),
+        };
+        if limit.get_unit() != balance.get_unit() {
+            return Err(ErrorDetails::new(ErrorCode::InvalidFieldValue, "EconomicResource credit limit must be measured in the same unit as its balance")
+                .at_field("creditLimit"));
+        }
+        if balance.get_numerical_value() < -limit.get_numerical_value() {
+            return Err(ErrorDetails::new(ErrorCode::CreditLimitExceeded, format!(
+                "EconomicResource balance of {} would exceed its credit limit of {}",
+                balance.get_numerical_value(), limit.get_numerical_value(),
+            ))
+                .at_field("accountingQuantity")
+                .with_detail("balance", balance.get_numerical_value())
+                .with_detail("creditLimit", limit.get_numerical_value()));
+        }
+        Ok(())
+    }
+
+    /// Credit limits may only be changed by the issuer of a balance (the agent who originally
+    /// recorded it), and never by the agent primarily accountable for the balance.
+    ///
+    pub fn validate_credit_limit_change(&self, previous: &EntryData, author: &AgentPubKey, issuer: &AgentPubKey) -> Result<(), ErrorDetails> {
+        if self.credit_limit == previous.credit_limit {
+            return Ok(());
+        }
+        let is_holder = previous.primary_accountable.as_ref().map_or(false, |a| { a.1 == *author });
+        if author != issuer || is_holder {
+            return Err(ErrorDetails::new(ErrorCode::Unauthorized, "EconomicResource credit limit can only be changed by the issuer of the balance")
+                .at_field("creditLimit")
+                .with_detail("agent", author));
+        }
+        Ok(())
+    }
+
+    /// Resources under a `Reject` policy may not hold negative inventory.
+    /// Mutual credit balances are exempt, being bounded by their credit limit instead.
+    ///
+    pub fn validate_inventory_levels(&self, policy: NegativeInventoryPolicy) -> Result<(), ErrorDetails> {
+        if policy != NegativeInventoryPolicy::Reject || self.credit_limit.is_some() {
+            return Ok(());
+        }
+        let quantities = self.accounting_quantity.iter().map(|q| ("accountingQuantity", q))
+            .chain(self.onhand_quantity.iter().map(|q| ("onhandQuantity", q)));
+        for (field, quantity) in quantities {
+            if quantity.get_numerical_value() < 0.0 {
+                return Err(ErrorDetails::new(ErrorCode::InsufficientInventory, format!("EconomicResource inventory of {} cannot be negative", quantity.get_numerical_value()))
+                    .at_field(field)
+                    .with_detail("available", quantity.get_numerical_value()));
+            }
+        }
+        Ok(())
+    }
+}
+
+//---------------- COUNTERSIGNED TRANSFERS ----------------
+
+impl EntryData {
+    /// Apply one side of a countersigned transfer to this resource, on behalf of `agent`.
+    /// The result depends only upon the resource and the event, so that it can be checked
+    /// by validators via `validate_applied_transfer`.
+    ///
+    pub fn with_transfer(
+        &self,
+        resource: &EconomicResourceAddress,
+        transfer: &EconomicEventAddress,
+        event: &EventData,
+        agent: &AgentPubKey,
+    ) -> Result<EntryData, ErrorDetails> {
+        let inventory_type = if event.provider.1 == *agent && event.resource_inventoried_as.as_ref() == Some(resource) {
+            ResourceInventoryType::ProvidingInventory
+        } else if event.receiver.1 == *agent && event.to_resource_inventoried_as.as_ref() == Some(resource) {
+            ResourceInventoryType::ReceivingInventory
+        } else {
+            return Err(ErrorDetails::new(ErrorCode::Unauthorized, "Transfers may only be applied to a resource by the party holding it")
+                .with_detail("agent", agent));
+        };
+
+        let update: EventCreateRequest = event.to_owned().into();
+        Ok(EntryData {
+            last_applied_transfer: Some(transfer.to_owned()),
+            ..self.update_with(update.with_inventory_type(inventory_type))
+        })
+    }
+
+    /// Load the countersigned transfer events applied by this revision of a resource and by
+    /// its `previous` revision, where this revision applies a new transfer.
+    /// Validation is deferred until both are available.
+    ///
+    pub fn must_get_applied_transfers(&self, previous: &EntryData) -> RecordAPIResult<Option<(Entry, Option<Entry>)>> {
+        if self.last_applied_transfer == previous.last_applied_transfer {
+            return Ok(None);
+        }
+        let transfer = match &self.last_applied_transfer {
+            Some(t) => must_get_entry(AsRef::<EntryHash>::as_ref(t).to_owned())?.into_content(),
+            None => return Ok(None),
+        };
+        let previous_transfer = match &previous.last_applied_transfer {
+            Some(t) => Some(must_get_entry(AsRef::<EntryHash>::as_ref(t).to_owned())?.into_content()),
+            None => None,
+        };
+        Ok(Some((transfer, previous_transfer)))
+    }
+
+    /// Ensure that a revision applying a countersigned transfer reflects exactly the effect
+    /// of that transfer upon the `previous` revision, and that transfers are applied once only
+    /// and in the order they were signed.
+    ///
+    /// `transfers` are as loaded by `must_get_applied_transfers`.
+    ///
+    pub fn validate_applied_transfer(
+        &self,
+        resource: &EconomicResourceAddress,
+        previous: &EntryData,
+        author: &AgentPubKey,
+        transfers: Option<&(Entry, Option<Entry>)>,
+    ) -> Result<(), ErrorDetails> {
+        if self.last_applied_transfer == previous.last_applied_transfer {
+            return Ok(());
+        }
+        let (address, (transfer, previous_transfer)) = match (&self.last_applied_transfer, transfers) {
+            (Some(a), Some(t)) => (a, t),
+            _ => return Err(ErrorDetails::new(ErrorCode::ImmutableField, "Transfers applied to an EconomicResource cannot be removed")
+                .at_field("lastAppliedTransfer")),
+        };
+        let invalid_transfer = |message: &str| {
+            let transfer_hash: &EntryHash = address.as_ref();
+            ErrorDetails::new(ErrorCode::InvalidCountersignature, message)
+                .at_field("lastAppliedTransfer")
+                .with_detail("economicEvent", transfer_hash)
+        };
+
+        let signed_at = get_countersigning_session_start(transfer)
+            .ok_or_else(|| invalid_transfer("Only countersigned transfers may be applied to an EconomicResource"))?;
+        if !get_countersigning_agents(transfer).contains(author) {
+            return Err(invalid_transfer("Transfers may only be applied by a party to the transfer"));
+        }
+        if let Some(previously_signed_at) = previous_transfer.as_ref().and_then(get_countersigning_session_start) {
+            if previously_signed_at >= signed_at {
+                return Err(invalid_transfer("Transfer has already been applied, or was signed before the last applied transfer"));
+            }
+        }
+
+        let event = EventStorage::try_from(transfer)
+            .map_err(|_| { invalid_transfer("Applied transfer is not an EconomicEvent") })?
+            .entry();
+        let expected = previous.with_transfer(resource, address, &event, author)?;
+        if SerializedBytes::try_from(expected).ok() != SerializedBytes::try_from(self.to_owned()).ok() {
+            return Err(invalid_transfer("EconomicResource does not reflect the effect of the applied transfer"));
         }
         Ok(())
     }
 }
 
+/// Determine the policy for events drawing a resource below zero, as recorded upon the resource.
+/// The policy of the resource's `ResourceSpecification` takes precedence over that configured for the DNA.
+///
+/// Validators check inventory levels against this recorded policy, which is refreshed whenever
+/// inventory is updated by an event. @see read_current_negative_inventory_policy
+///
+pub fn get_negative_inventory_policy(resource: &EntryData) -> NegativeInventoryPolicy {
+    resource.negative_inventory_policy.unwrap_or_else(get_dna_negative_inventory_policy)
+}
+
+/// Determine the policy for events drawing a resource below zero, as currently configured.
+///
+/// Reads the present policy of the resource's `ResourceSpecification` from the specification DNA,
+/// and so may not be used in validation callbacks.
+///
+pub fn read_current_negative_inventory_policy(resource: &EntryData) -> NegativeInventoryPolicy {
+    read_specification_negative_inventory_policy(resource).unwrap_or_else(get_dna_negative_inventory_policy)
+}
+
+/// Read the policy currently set by the `ResourceSpecification` of a resource, if any.
+/// Where the specification cannot be read, the policy last recorded upon the resource is retained.
+///
+pub fn read_specification_negative_inventory_policy(resource: &EntryData) -> Option<NegativeInventoryPolicy> {
+    match &resource.conforms_to {
+        Some(conforms_to) => match read_specification(conforms_to) {
+            Some(spec) => spec.negative_inventory_policy,
+            None => resource.negative_inventory_policy,
+        },
+        None => None,
+    }
+}
+
+fn get_dna_negative_inventory_policy() -> NegativeInventoryPolicy {
+    let conf: Result<DnaConfigSlice, _> = dna_info()
+        .map_err(|_| ())
+        .and_then(|info| { info.properties.try_into().map_err(|_| ()) });
+
+    match conf {
+        Ok(c) => c.economic_resource.negative_inventory_policy,
+        Err(_) => NegativeInventoryPolicy::default(),
+    }
+}
+
 generate_record_entry!(EntryData, EconomicResourceAddress, EntryStorage);
 
 //---------------- CREATE ----------------
@@ -86,6 +337,10 @@ impl From<CreationPayload> for EntryData
 {
     fn from(t: CreationPayload) -> EntryData {
         let conforming = t.get_resource_specification_id();
+        let specification = match &conforming {
+            Some(conforms_to_spec) => read_specification(conforms_to_spec),
+            None => None,
+        };
         let r = t.resource;
         let e = t.event;
         EntryData {
@@ -120,13 +375,20 @@ impl From<CreationPayload> for EntryData
                 ),
                 _ => None,
             },
-            unit_of_effort: match conforming {
-                Some(conforms_to_spec) => get_default_unit_for_specification(conforms_to_spec),
-                None => None,
-            },
+            unit_of_effort: specification.as_ref().and_then(|spec| { spec.default_unit_of_effort.to_owned() }),
             current_location: if r.current_location == MaybeUndefined::Undefined { None } else { r.current_location.to_owned().to_option() },
             contained_in: if r.contained_in == MaybeUndefined::Undefined { None } else { r.contained_in.to_owned().to_option() },
             note: if r.note == MaybeUndefined::Undefined { None } else { r.note.clone().into() },
+            primary_accountable: Some(e.receiver.to_owned()),
+            credit_limit: match (&specification, e.resource_quantity.to_owned()) {
+                (Some(spec), MaybeUndefined::Some(resource_quantity)) if spec.ledger_mode => Some(
+                    spec.default_credit_limit.to_owned()
+                        .unwrap_or(QuantityValue::new(0.0, resource_quantity.get_unit()))
+                ),
+                _ => None,
+            },
+            negative_inventory_policy: specification.as_ref().and_then(|spec| { spec.negative_inventory_policy }),
+            last_applied_transfer: None,
         }
     }
 }
@@ -138,15 +400,15 @@ pub struct GetSpecificationRequest {
     pub address: ResourceSpecificationAddress,
 }
 
-fn get_default_unit_for_specification(specification_id: ResourceSpecificationAddress) -> Option<UnitId> {
+fn read_specification(specification_id: &ResourceSpecificationAddress) -> Option<ResourceSpecification> {
     let spec_data: OtherCellResult<ResourceSpecificationResponse> = call_zome_method(
-        &specification_id,
+        specification_id,
         &String::from("read_resource_specification"),
         GetSpecificationRequest { address: specification_id.to_owned() },
     );
 
     match spec_data {
-        Ok(spec_response) => spec_response.resource_specification.default_unit_of_effort,
+        Ok(spec_response) => Some(spec_response.resource_specification),
         Err(_) => None,     // :TODO: error handling
     }
 }
@@ -168,6 +430,10 @@ impl Updateable<UpdateRequest> for EntryData {
             current_location: self.current_location.to_owned(),
             contained_in: if e.contained_in == MaybeUndefined::Undefined { self.contained_in.to_owned() } else { e.contained_in.to_owned().to_option() },
             note: if e.note == MaybeUndefined::Undefined { self.note.to_owned() } else { e.note.to_owned().to_option() },
+            primary_accountable: self.primary_accountable.to_owned(),
+            credit_limit: if e.credit_limit == MaybeUndefined::Undefined { self.credit_limit.to_owned() } else { e.credit_limit.to_owned().to_option() },
+            negative_inventory_policy: self.negative_inventory_policy,
+            last_applied_transfer: self.last_applied_transfer.to_owned(),
         }
     }
 }
@@ -231,10 +497,104 @@ impl Updateable<EventCreateRequest> for EntryData {
             } else { self.current_location.to_owned() },
             contained_in: self.contained_in.to_owned(),
             note: self.note.to_owned(),
+            primary_accountable: if transfers_rights(&e) { Some(e.receiver.to_owned()) } else { self.primary_accountable.to_owned() },
+            credit_limit: self.credit_limit.to_owned(),
+            negative_inventory_policy: self.negative_inventory_policy,
+            last_applied_transfer: self.last_applied_transfer.to_owned(),
+        }
+    }
+}
+
+/// Handle update operations by countersigned transfers, the effect of which
+/// has already been determined via `EntryData::with_transfer`
+///
+#[derive(Clone, Debug)]
+pub struct TransferEffect(pub EntryData);
+
+impl Updateable<TransferEffect> for EntryData {
+    fn update_with(&self, e: TransferEffect) -> EntryData {
+        e.0
+    }
+}
+
+/// Handle update operations by observed events, recording the negative inventory policy
+/// in effect at the time the event was applied
+///
+#[derive(Clone, Debug)]
+pub struct InventoryEffect(pub EventCreateRequest, pub Option<NegativeInventoryPolicy>);
+
+impl Updateable<InventoryEffect> for EntryData {
+    fn update_with(&self, e: InventoryEffect) -> EntryData {
+        EntryData {
+            negative_inventory_policy: e.1,
+            ..self.update_with(e.0)
+        }
+    }
+}
+
+//---------------- HISTORIC STATE ----------------
+
+/// Inventory state of a resource at some point in time, as derived by replaying
+/// the events which have affected it.
+///
+#[derive(Clone, Debug)]
+pub struct InventoryState {
+    pub resource: EntryData,
+    pub custodian: Option<AgentAddress>,
+}
+
+impl InventoryState {
+    /// Initial state of a resource created by the given event, given the `initial_entry` it was created with.
+    /// Quantities are reset to zero prior to the event, as for `From<CreationPayload>`.
+    ///
+    pub fn from_creating_event(resource: &EconomicResourceAddress, event: &EventData, initial_entry: &EntryData) -> InventoryState {
+        let initial_quantity = event.resource_quantity.as_ref().map(|q| { QuantityValue::new(0.0, q.get_unit()) });
+
+        InventoryState {
+            resource: EntryData {
+                accounting_quantity: initial_quantity.to_owned(),
+                onhand_quantity: initial_quantity,
+                primary_accountable: Some(event.receiver.to_owned()),
+                ..initial_entry.to_owned()
+            },
+            custodian: Some(event.receiver.to_owned()),
+        }.with_event(resource, event)
+    }
+
+    /// Apply the effects of an event to this inventory state, via `Updateable<EventCreateRequest>`.
+    ///
+    pub fn with_event(&self, resource: &EconomicResourceAddress, event: &EventData) -> InventoryState {
+        let is_receiving = event.to_resource_inventoried_as.as_ref() == Some(resource);
+        let inventory_type = if is_receiving { ResourceInventoryType::ReceivingInventory } else { ResourceInventoryType::ProvidingInventory };
+        let action: &str = event.action.as_ref();
+
+        let update: EventCreateRequest = event.to_owned().into();
+        InventoryState {
+            resource: self.resource.update_with(update.with_inventory_type(inventory_type)),
+            custodian: match action {
+                "transfer" | "transfer-custody" if is_receiving => Some(event.receiver.to_owned()),
+                _ => self.custodian.to_owned(),
+            },
         }
     }
 }
 
+/// Determine whether an event passes rights over the resource being updated to the event's receiver:
+/// always so for the receiving resource of a transfer, and for the providing resource where
+/// the transfer moves the resource as a whole (rather than into another resource).
+///
+fn transfers_rights(e: &EventCreateRequest) -> bool {
+    let action: &str = e.action.as_ref();
+    if !(action == "transfer" || action == "transfer-all-rights") {
+        return false;
+    }
+    match &e.target_inventory_type {
+        Some(ResourceInventoryType::ReceivingInventory) => true,
+        Some(ResourceInventoryType::ProvidingInventory) => !matches!(e.to_resource_inventoried_as, MaybeUndefined::Some(_)),
+        None => false,
+    }
+}
+
 /// Encapsulates the logic for updating EconomicResource quantities in response to event triggers
 fn update_quantity(
     current_val: Option<QuantityValue>,
@@ -6,6 +6,7 @@
  * @package Holo-REA
  */
 pub const RESOURCE_ENTRY_TYPE: &str = "vf_economic_resource";
+pub const RESOURCE_TRACKING_IDENTIFIER_ANCHOR: &str = "vf_economic_resource_tracking_identifier";
 
 pub const RESOURCE_CONTAINS_LINK_TAG: &str = "contains";
 pub const RESOURCE_CONTAINED_IN_LINK_TAG: &str = "contained_in";
@@ -19,3 +20,4 @@ pub const RESOURCE_CONTAINEDIN_READ_API_METHOD: &str = "_internal_read_container
 pub const RESOURCE_AFFECTED_BY_READ_API_METHOD: &str = "_internal_read_affecting_events";
 pub const RESOURCE_SPECIFICATION_RESOURCES_INDEXING_API_METHOD: &str = "index_resource_specification_resources";
 pub const RESOURCE_CONFORMSTO_INDEXING_API_METHOD: &str = "_internal_reindex_resource_specifications";
+pub const RESOURCE_QUERY_API_METHOD: &str = "query_economic_resources";
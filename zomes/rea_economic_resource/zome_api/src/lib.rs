@@ -1,10 +1,17 @@
-use hdk_records::{RecordAPIResult};
+use hdk_records::{
+    RecordAPIResult,
+    history::Timestamp,
+};
+pub use hdk_records::history::{RevisionMeta, RevisionDiff};
+pub use hdk_records::validation::{get_modified_revision, validate_modification_author};
+pub use hdk_records::countersigning::get_countersigning_agents;
 use hc_zome_rea_economic_resource_rpc::*;
 pub use hc_zome_rea_economic_event_rpc::{
     CreateRequest as EventCreateRequest,
     ResourceResponseData as ResponseData,
     ResourceResponseCollection as Collection,
 };
+pub use vf_jsonld::{ExportParams, ExportPage};
 use hc_zome_rea_economic_resource_storage::{EntryData};
 
 /// API interface for the external zome gateway
@@ -16,9 +23,26 @@ pub trait API {
         resource_entry_def_id: Self::S,
         event: EventCreateRequest,
     ) -> RecordAPIResult<Vec<(RevisionHash, EconomicResourceAddress, EntryData, EntryData)>>;
+    fn apply_transfer_to_inventory(
+        resource_entry_def_id: Self::S,
+        transfer: EconomicEventAddress,
+    ) -> RecordAPIResult<(RevisionHash, EconomicResourceAddress, EntryData, EntryData)>;
     fn get_economic_resource(entry_def_id: Self::S, event_entry_def_id: Self::S, process_entry_def_id: Self::S, address: EconomicResourceAddress) -> RecordAPIResult<ResponseData>;
+    fn get_economic_resource_by_tracking_identifier(entry_def_id: Self::S, event_entry_def_id: Self::S, process_entry_def_id: Self::S, tracking_identifier: String) -> RecordAPIResult<TrackedResourceResponseData>;
     fn update_economic_resource(entry_def_id: Self::S, event_entry_def_id: Self::S, process_entry_def_id: Self::S, resource: UpdateRequest) -> RecordAPIResult<ResponseData>;
+    fn get_economic_resource_revision_heads(entry_def_id: Self::S, address: EconomicResourceAddress) -> RecordAPIResult<Vec<RevisionHash>>;
+    fn merge_economic_resource_revisions(entry_def_id: Self::S, event_entry_def_id: Self::S, process_entry_def_id: Self::S, revision_id: RevisionHash, superseded_revisions: Vec<RevisionHash>) -> RecordAPIResult<ResponseData>;
+    fn get_economic_resource_history(entry_def_id: Self::S, address: EconomicResourceAddress) -> RecordAPIResult<Vec<RevisionMeta>>;
+    fn get_economic_resource_at_revision(event_entry_def_id: Self::S, process_entry_def_id: Self::S, revision_id: RevisionHash) -> RecordAPIResult<ResponseData>;
+    fn get_economic_resource_as_of(entry_def_id: Self::S, event_entry_def_id: Self::S, process_entry_def_id: Self::S, address: EconomicResourceAddress, as_of: Timestamp) -> RecordAPIResult<ResponseData>;
+    fn diff_economic_resource_revisions(from_revision_id: RevisionHash, to_revision_id: RevisionHash) -> RecordAPIResult<RevisionDiff>;
+    fn get_economic_resource_balance(entry_def_id: Self::S, event_entry_def_id: Self::S, address: EconomicResourceAddress, as_of: DateTime<FixedOffset>) -> RecordAPIResult<ResourceBalance>;
+    fn get_economic_resource_balance_series(entry_def_id: Self::S, event_entry_def_id: Self::S, address: EconomicResourceAddress,
+        from: Option<DateTime<FixedOffset>>, until: Option<DateTime<FixedOffset>>,
+    ) -> RecordAPIResult<Vec<ResourceBalance>>;
+    fn get_inventory_report(entry_def_id: Self::S, event_entry_def_id: Self::S, params: InventoryReportParams) -> RecordAPIResult<InventoryReport>;
     fn get_all_economic_resources(entry_def_id: Self::S, event_entry_def_id: Self::S, process_entry_def_id: Self::S) -> RecordAPIResult<Collection>;
+    fn export_economic_resources(entry_def_id: Self::S, event_entry_def_id: Self::S, process_entry_def_id: Self::S, params: ExportParams) -> RecordAPIResult<ExportPage>;
 }
 
 /// Macro to programatically and predictably bind an `API` implementation to a
@@ -47,6 +71,12 @@ macro_rules! declare_economic_resource_zome_api {
             Ok(<$zome_api>::update_inventory_from_event(RESOURCE_ENTRY_TYPE, event)?)
         }
 
+        #[hdk_extern]
+        fn _internal_apply_transfer(ByAddress { address }: ByAddress<EconomicEventAddress>) -> ExternResult<(RevisionHash, EconomicResourceAddress, EntryData, EntryData)>
+        {
+            Ok(<$zome_api>::apply_transfer_to_inventory(RESOURCE_ENTRY_TYPE, address)?)
+        }
+
         #[hdk_extern]
         fn get_economic_resource(ByAddress { address }: ByAddress<EconomicResourceAddress>) -> ExternResult<$crate::ResponseData> {
             Ok(<$zome_api>::get_economic_resource(
@@ -55,6 +85,14 @@ macro_rules! declare_economic_resource_zome_api {
             )?)
         }
 
+        #[hdk_extern]
+        fn get_economic_resource_by_tracking_identifier(ByTrackingIdentifier { tracking_identifier }: ByTrackingIdentifier) -> ExternResult<TrackedResourceResponseData> {
+            Ok(<$zome_api>::get_economic_resource_by_tracking_identifier(
+                RESOURCE_ENTRY_TYPE, EVENT_ENTRY_TYPE, PROCESS_ENTRY_TYPE,
+                tracking_identifier,
+            )?)
+        }
+
         #[hdk_extern]
         fn update_economic_resource(UpdateParams { resource }: UpdateParams) -> ExternResult<$crate::ResponseData> {
             Ok(<$zome_api>::update_economic_resource(
@@ -63,10 +101,66 @@ macro_rules! declare_economic_resource_zome_api {
             )?)
         }
 
+        #[hdk_extern]
+        fn get_economic_resource_revision_heads(ByAddress { address }: ByAddress<EconomicResourceAddress>) -> ExternResult<Vec<RevisionHash>> {
+            Ok(<$zome_api>::get_economic_resource_revision_heads(RESOURCE_ENTRY_TYPE, address)?)
+        }
+
+        #[hdk_extern]
+        fn merge_economic_resource_revisions(MergeRevisions { revision_id, superseded_revisions }: MergeRevisions) -> ExternResult<$crate::ResponseData> {
+            Ok(<$zome_api>::merge_economic_resource_revisions(
+                RESOURCE_ENTRY_TYPE, EVENT_ENTRY_TYPE, PROCESS_ENTRY_TYPE,
+                revision_id, superseded_revisions,
+            )?)
+        }
+
+        #[hdk_extern]
+        fn get_economic_resource_history(ByAddress { address }: ByAddress<EconomicResourceAddress>) -> ExternResult<Vec<RevisionMeta>> {
+            Ok(<$zome_api>::get_economic_resource_history(RESOURCE_ENTRY_TYPE, address)?)
+        }
+
+        #[hdk_extern]
+        fn get_economic_resource_at_revision(ByHeader { address }: ByHeader) -> ExternResult<$crate::ResponseData> {
+            Ok(<$zome_api>::get_economic_resource_at_revision(EVENT_ENTRY_TYPE, PROCESS_ENTRY_TYPE, address)?)
+        }
+
+        #[hdk_extern]
+        fn get_economic_resource_as_of(ByAddressAsOf { address, as_of }: ByAddressAsOf<EconomicResourceAddress>) -> ExternResult<$crate::ResponseData> {
+            Ok(<$zome_api>::get_economic_resource_as_of(
+                RESOURCE_ENTRY_TYPE, EVENT_ENTRY_TYPE, PROCESS_ENTRY_TYPE,
+                address, as_of,
+            )?)
+        }
+
+        #[hdk_extern]
+        fn diff_economic_resource_revisions(ByRevisionPair { from_revision_id, to_revision_id }: ByRevisionPair) -> ExternResult<RevisionDiff> {
+            Ok(<$zome_api>::diff_economic_resource_revisions(from_revision_id, to_revision_id)?)
+        }
+
+        #[hdk_extern]
+        fn get_economic_resource_balance(BalanceAsOfParams { address, as_of }: BalanceAsOfParams) -> ExternResult<ResourceBalance> {
+            Ok(<$zome_api>::get_economic_resource_balance(RESOURCE_ENTRY_TYPE, EVENT_ENTRY_TYPE, address, as_of)?)
+        }
+
+        #[hdk_extern]
+        fn get_economic_resource_balance_series(BalanceSeriesParams { address, from, until }: BalanceSeriesParams) -> ExternResult<Vec<ResourceBalance>> {
+            Ok(<$zome_api>::get_economic_resource_balance_series(RESOURCE_ENTRY_TYPE, EVENT_ENTRY_TYPE, address, from, until)?)
+        }
+
+        #[hdk_extern]
+        fn get_inventory_report(params: InventoryReportParams) -> ExternResult<InventoryReport> {
+            Ok(<$zome_api>::get_inventory_report(RESOURCE_ENTRY_TYPE, EVENT_ENTRY_TYPE, params)?)
+        }
+
         #[hdk_extern]
         fn get_all_economic_resources(_: ()) -> ExternResult<$crate::Collection> {
             Ok(<$zome_api>::get_all_economic_resources(RESOURCE_ENTRY_TYPE, EVENT_ENTRY_TYPE, PROCESS_ENTRY_TYPE)?)
         }
+
+        #[hdk_extern]
+        fn export_economic_resources(params: $crate::ExportParams) -> ExternResult<$crate::ExportPage> {
+            Ok(<$zome_api>::export_economic_resources(RESOURCE_ENTRY_TYPE, EVENT_ENTRY_TYPE, PROCESS_ENTRY_TYPE, params)?)
+        }
     };
 }
 
@@ -74,7 +168,16 @@ macro_rules! declare_economic_resource_zome_api {
 /// records in the local zome (local field checks only).
 /// This is the minimum validation required by any zome, and regardless of other
 /// validation rules being implemented it is critical that `record.validate()`
-/// be peformed upon `EntryStorage` creation.
+/// be peformed upon `EntryStorage` creation. `record.validate_inventory_levels()`
+/// enforces the configured negative inventory policy. `record.must_get_references()`
+/// defers validation until referenced records in the same DNA are available.
+/// `record.validate_applied_transfer()` ensures countersigned transfers are applied
+/// to inventory exactly once, and with exactly the effect described by the event.
+///
+/// Updates & deletes are restricted to the resource's original author, the agent
+/// primarily accountable for it and the parties to any countersigned transfer being
+/// applied to it. Updates may not alter the fields checked by `record.validate_immutable_fields()`,
+/// and only the issuer of a balance may change its credit limit (`record.validate_credit_limit_change()`).
 ///
 /// Use this method as a reference, and always call the below logic before any
 /// application-specific validation.
@@ -84,20 +187,52 @@ macro_rules! declare_economic_resource_zome_validation_defaults {
         #[hdk_extern]
         fn validate(validation_data: ValidateData) -> ExternResult<ValidateCallbackResult> {
             let element = validation_data.element;
-            let entry = element.into_inner().1;
-            let entry = match entry {
-                ElementEntry::Present(e) => e,
-                _ => return Ok(ValidateCallbackResult::Valid),
+            let (signed_header, entry) = element.into_inner();
+            let header = signed_header.header();
+
+            let modified = $crate::get_modified_revision(header)?;
+            let previous = match &modified {
+                Some(m) => EntryStorage::try_from(&m.entry).ok().map(|p| { p.entry() }),
+                None => None,
+            };
+            let resource_storage = match &entry {
+                ElementEntry::Present(e) => EntryStorage::try_from(e).ok(),
+                _ => None,
             };
+            let transfers = match (&resource_storage, &previous) {
+                (Some(r), Some(p)) => r.entry().must_get_applied_transfers(p)?,
+                _ => None,
+            };
+
+            // updates & deletes may only be made by the original author, the agent primarily accountable
+            // for the resource, or the parties to a countersigned transfer being applied to it
+            if let (Some(modified), Some(previous)) = (&modified, &previous) {
+                let mut authorised_agents = previous.get_authorised_agents();
+                if let Some((transfer, _)) = &transfers {
+                    authorised_agents.extend($crate::get_countersigning_agents(transfer));
+                }
+                if let Err(e) = $crate::validate_modification_author(header, modified, &authorised_agents) {
+                    return Ok(ValidateCallbackResult::Invalid(e.encode()));
+                }
+            }
 
-            match EntryStorage::try_from(&entry) {
-                Ok(resource_storage) => {
+            match resource_storage {
+                Some(resource_storage) => {
                     let record = resource_storage.entry();
+                    record.must_get_references()?;
+                    let identity = resource_storage.identity()?;
                     record.validate()
+                        .and_then(|()| { previous.as_ref().map_or(Ok(()), |p| { record.validate_immutable_fields(p) }) })
+                        .and_then(|()| { match (&modified, &previous) {
+                            (Some(m), Some(p)) => record.validate_credit_limit_change(p, header.author(), &m.original_author),
+                            _ => Ok(()),
+                        } })
+                        .and_then(|()| { previous.as_ref().map_or(Ok(()), |p| { record.validate_applied_transfer(&identity, p, header.author(), transfers.as_ref()) }) })
+                        .and_then(|()| { record.validate_inventory_levels(get_negative_inventory_policy(&record)) })
                         .and_then(|()| { Ok(ValidateCallbackResult::Valid) })
-                        .or_else(|e| { Ok(ValidateCallbackResult::Invalid(e)) })
+                        .or_else(|e| { Ok(ValidateCallbackResult::Invalid(e.encode())) })
                 },
-                _ => Ok(ValidateCallbackResult::Valid),
+                None => Ok(ValidateCallbackResult::Valid),
             }
         }
     };
@@ -12,7 +12,9 @@ use hc_zome_rea_economic_event_rpc::ResourceResponseData as ResponseData;
 struct EconomicResource {
     contains: Local<economic_resource, contained_in>,
     contained_in: Local<economic_resource, contains>,
+    #[source_field]
     conforms_to: Local<resource_specification, conforming_resources>,
+    classified_as: Keyed<classification, economic_resources>,
 
     // internal indexes (not part of REA spec)
     affected_by: Local<economic_event, affects>,
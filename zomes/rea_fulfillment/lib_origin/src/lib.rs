@@ -11,16 +11,32 @@
  */
 use paste::paste;
 use hdk_records::{
-    RecordAPIResult, OtherCellResult,
+    RecordAPIResult, DataIntegrityError, EntryHash,
     records::{
         create_record,
         read_record_entry,
         read_record_entry_by_header,
         update_record,
+        read_record_revision_heads,
+        merge_record_revisions,
+        get_index_changes,
         delete_record,
     },
+    history::{
+        Timestamp,
+        read_record_revision_history,
+        read_record_entry_as_of,
+        diff_record_revisions,
+    },
     rpc::call_zome_method,
+    outbox::{
+        queue_replication,
+        deliver_replications,
+        read_replication_status,
+    },
 };
+pub use hdk_records::history::{RevisionMeta, RevisionDiff};
+pub use hdk_records::outbox::{ReplicationRequest, ReplicationAttempt};
 use hdk_semantic_indexes_client_lib::*;
 
 use hc_zome_rea_fulfillment_storage_consts::*;
@@ -37,14 +53,18 @@ pub fn handle_create_fulfillment<S>(entry_def_id: S, fulfillment: CreateRequest)
     create_index!(Local(fulfillment.fulfills(fulfillment.get_fulfills()), commitment.fulfilled_by(&fulfillment_address)))?;
 
     // update in the associated foreign DNA as well
-    let _pingback: OtherCellResult<ResponseData> = call_zome_method(
+    let identity_hash: &EntryHash = fulfillment_address.as_ref();
+    queue_replication(
+        identity_hash,
         fulfillment.get_fulfilled_by(),
         &REPLICATE_CREATE_API_METHOD,
         CreateParams { fulfillment: fulfillment.to_owned() },
-    );
-    // :TODO: report any error
+    )?;
+    let replication = replicate_fulfillments(identity_hash)?;
 
-    construct_response(&fulfillment_address, &revision_id, &entry_resp)
+    let mut response = construct_response(&fulfillment_address, &revision_id, &entry_resp)?;
+    response.replication = replication;
+    Ok(response)
 }
 
 pub fn handle_get_fulfillment<S>(entry_def_id: S, address: FulfillmentAddress) -> RecordAPIResult<ResponseData>
@@ -70,17 +90,68 @@ pub fn handle_update_fulfillment<S>(entry_def_id: S, fulfillment: UpdateRequest)
     }
 
     // update fulfillment records in remote DNA (and by proxy, event indexes in remote DNA)
+    let identity_hash: &EntryHash = base_address.as_ref();
     if new_entry.fulfilled_by != prev_entry.fulfilled_by {
-        let _pingback: OtherCellResult<ResponseData> = call_zome_method(
+        queue_replication(
+            identity_hash,
             // :TODO: update to intelligently call remote DNAs if new & old target record are not in same network
             &prev_entry.fulfilled_by,
             &REPLICATE_UPDATE_API_METHOD,
             UpdateParams { fulfillment: fulfillment.to_owned() },
-        );
-        // :TODO: report any error
+        )?;
+    }
+    let replication = replicate_fulfillments(identity_hash)?;
+
+    let mut response = construct_response(&base_address, &revision_id, &new_entry)?;
+    response.replication = replication;
+    Ok(response)
+}
+
+pub fn handle_get_fulfillment_revision_heads<S>(entry_def_id: S, address: FulfillmentAddress) -> RecordAPIResult<Vec<RevisionHash>>
+    where S: AsRef<str>
+{
+    read_record_revision_heads::<FulfillmentAddress, _>(&entry_def_id, address.as_ref())
+}
+
+pub fn handle_merge_fulfillment_revisions<S>(entry_def_id: S, revision_id: RevisionHash, superseded_revisions: Vec<RevisionHash>) -> RecordAPIResult<ResponseData>
+    where S: AsRef<str>
+{
+    let (_revision, base_address, entry, superseded): (_, FulfillmentAddress, EntryData, _) = merge_record_revisions::<EntryData, EntryStorage, _>(&revision_id, superseded_revisions.as_slice())?;
+
+    // reconcile commitment indexes in local DNA with the chosen revision
+    // :TODO: replicated copies of superseded revisions in the observation DNA are not yet reconciled
+    for (superseded_entry, parent_entry) in superseded.iter() {
+        let (added, removed) = get_index_changes(&entry, superseded_entry, Some(parent_entry), |e| { vec![e.fulfills.to_owned()] });
+        if !(added.is_empty() && removed.is_empty()) {
+            update_index!(Local(fulfillment.fulfills(&added).not(&removed), commitment.fulfilled_by(&base_address)))?;
+        }
     }
 
-    construct_response(&base_address, &revision_id, &new_entry)
+    handle_get_fulfillment(entry_def_id, base_address)
+}
+
+pub fn handle_get_fulfillment_history<S>(entry_def_id: S, address: FulfillmentAddress) -> RecordAPIResult<Vec<RevisionMeta>>
+    where S: AsRef<str>
+{
+    read_record_revision_history::<FulfillmentAddress, _>(&entry_def_id, address.as_ref())
+}
+
+pub fn handle_get_fulfillment_at_revision(revision_id: RevisionHash) -> RecordAPIResult<ResponseData>
+{
+    let (base_address, entry) = read_record_entry_by_header::<EntryData, EntryStorage, _>(&revision_id)?;
+    construct_response(&base_address, &revision_id, &entry)
+}
+
+pub fn handle_get_fulfillment_as_of<S>(entry_def_id: S, address: FulfillmentAddress, as_of: Timestamp) -> RecordAPIResult<ResponseData>
+    where S: AsRef<str>
+{
+    let (revision, base_address, entry) = read_record_entry_as_of::<EntryData, EntryStorage, _,_>(&entry_def_id, address.as_ref(), &as_of)?;
+    construct_response(&base_address, &revision, &entry)
+}
+
+pub fn handle_diff_fulfillment_revisions(from_revision_id: RevisionHash, to_revision_id: RevisionHash) -> RecordAPIResult<RevisionDiff>
+{
+    diff_record_revisions::<EntryData, EntryStorage, FulfillmentAddress>(&from_revision_id, &to_revision_id)
 }
 
 pub fn handle_delete_fulfillment(revision_id: RevisionHash) -> RecordAPIResult<bool>
@@ -91,14 +162,58 @@ pub fn handle_delete_fulfillment(revision_id: RevisionHash) -> RecordAPIResult<b
     update_index!(Local(fulfillment.fulfills.not(&vec![entry.fulfills]), commitment.fulfilled_by(&base_address)))?;
 
     // update fulfillment records in remote DNA (and by proxy, event indexes in remote DNA)
-    let _pingback: OtherCellResult<ResponseData> = call_zome_method(
+    // :NOTE: delivery failures are not returned here, query `get_fulfillment_replication_status` to inspect them
+    let identity_hash: &EntryHash = base_address.as_ref();
+    queue_replication(
+        identity_hash,
         &entry.fulfilled_by,
         &REPLICATE_DELETE_API_METHOD,
         ByHeader { address: revision_id.to_owned() },
-    );
-    // :TODO: report any error
+    )?;
+
+    let deleted = delete_record::<EntryStorage, _>(&revision_id)?;
+    replicate_fulfillments(identity_hash)?;
+    Ok(deleted)
+}
 
-    delete_record::<EntryStorage, _>(&revision_id)
+/// Read the state of writes replicated to the observation DNA, optionally only those for a single fulfillment.
+///
+pub fn handle_get_fulfillment_replication_status(address: Option<FulfillmentAddress>) -> RecordAPIResult<Vec<ReplicationStatus>>
+{
+    let identity_hash: Option<&EntryHash> = address.as_ref().map(|a| { a.as_ref() });
+    read_replication_status(identity_hash)
+}
+
+/// Retry sending any writes which could not previously be replicated to the observation DNA.
+///
+pub fn handle_retry_fulfillment_replications() -> RecordAPIResult<Vec<ReplicationStatus>>
+{
+    deliver_replications(deliver_fulfillment_replication)
+}
+
+/// Send all pending writes to the observation DNA, returning the status of those for the given record.
+///
+fn replicate_fulfillments(record: &EntryHash) -> RecordAPIResult<Vec<ReplicationStatus>> {
+    Ok(deliver_replications(deliver_fulfillment_replication)?
+        .into_iter()
+        .filter(|status| { status.record == *record })
+        .collect())
+}
+
+fn deliver_fulfillment_replication(request: &ReplicationRequest) -> RecordAPIResult<()> {
+    match request.method.as_str() {
+        REPLICATE_CREATE_API_METHOD => {
+            let _pingback: ResponseData = call_zome_method(request, &request.method, request.decode_payload::<CreateParams>()?)?;
+        },
+        REPLICATE_UPDATE_API_METHOD => {
+            let _pingback: ResponseData = call_zome_method(request, &request.method, request.decode_payload::<UpdateParams>()?)?;
+        },
+        REPLICATE_DELETE_API_METHOD => {
+            let _pingback: bool = call_zome_method(request, &request.method, request.decode_payload::<ByHeader>()?)?;
+        },
+        _ => return Err(DataIntegrityError::RemoteRequestError(request.method.to_owned())),
+    }
+    Ok(())
 }
 
 /// Properties accessor for zome config.
@@ -10,5 +10,6 @@ use hdk_semantic_indexes_zome_lib::ByAddress; // disambiguate from RPC query str
 
 #[index_zome]
 struct Fulfillment {
+    #[source_field]
     fulfilled_by: Local<economic_event, fulfills>,
 }
@@ -14,6 +14,26 @@ use hdk::prelude::*;
 use hc_zome_rea_fulfillment_lib_destination::*;
 use hc_zome_rea_fulfillment_rpc::*;
 use hc_zome_rea_fulfillment_storage_consts::*;
+use hc_zome_rea_fulfillment_storage::{EntryStorage};
+use hdk_records::validation::{get_modified_revision, validate_modification_author};
+
+#[hdk_extern]
+fn validate(validation_data: ValidateData) -> ExternResult<ValidateCallbackResult> {
+    let element = validation_data.element;
+    let (signed_header, _entry) = element.into_inner();
+    let header = signed_header.header();
+
+    // fulfillments may only be updated or deleted by their original author
+    if let Some(modified) = get_modified_revision(header)? {
+        if EntryStorage::try_from(&modified.entry).is_ok() {
+            if let Err(e) = validate_modification_author(header, &modified, &[]) {
+                return Ok(ValidateCallbackResult::Invalid(e.encode()));
+            }
+        }
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
 
 #[hdk_extern]
 fn entry_defs(_: ()) -> ExternResult<EntryDefsCallbackResult> {
@@ -29,6 +49,25 @@ fn entry_defs(_: ()) -> ExternResult<EntryDefsCallbackResult> {
     ]))
 }
 
+#[hdk_extern]
+fn validate(validation_data: ValidateData) -> ExternResult<ValidateCallbackResult> {
+    let element = validation_data.element;
+    let entry = element.into_inner().1;
+    let entry = match entry {
+        ElementEntry::Present(e) => e,
+        _ => return Ok(ValidateCallbackResult::Valid),
+    };
+
+    match EntryStorage::try_from(&entry) {
+        Ok(fulfillment_storage) => {
+            let record = fulfillment_storage.entry();
+            record.must_get_references()?;
+            Ok(ValidateCallbackResult::Valid)
+        },
+        _ => Ok(ValidateCallbackResult::Valid),
+    }
+}
+
 #[hdk_extern]
 fn fulfillment_created(CreateParams { fulfillment }: CreateParams) -> ExternResult<ResponseData> {
     Ok(handle_create_fulfillment(FULFILLMENT_ENTRY_TYPE, fulfillment)?)
@@ -8,17 +8,31 @@
  */
 use paste::paste;
 use hdk_records::{
-    RecordAPIResult,
+    RecordAPIResult, DataIntegrityError, EntryHash,
     MaybeUndefined,
     records::{
         create_record,
         read_record_entry,
         read_record_entry_by_header,
         update_record,
+        read_record_revision_heads,
+        merge_record_revisions,
+        get_index_changes,
         delete_record,
     },
+    history::{
+        Timestamp,
+        read_record_revision_history,
+        read_record_entry_as_of,
+        diff_record_revisions,
+    },
+    local_indexes::query_root_index_page,
 };
+pub use hdk_records::history::{RevisionMeta, RevisionDiff};
+pub use vf_jsonld::{ExportParams, ExportPage};
+use vf_jsonld::{JsonLdNode, build_export_page};
 use hdk_semantic_indexes_client_lib::*;
+use vf_attributes_hdk::{get_scope_agents, get_scoped_agents, get_involved_agents};
 
 use hc_zome_rea_intent_storage::*;
 use hc_zome_rea_intent_rpc::*;
@@ -39,6 +53,25 @@ pub fn handle_create_intent<S>(entry_def_id: S, intent: CreateRequest) -> Record
         create_index!(Remote(intent.output_of(output_of), process.intended_outputs(&base_address)))?;
     };
 
+    // index involved agents
+    if let Some(provider) = &entry_resp.provider {
+        let provider: AgentRecordAddress = provider.to_owned().into();
+        create_index!(Self(intent(&base_address).provider(&provider)))?;
+    }
+    if let Some(receiver) = &entry_resp.receiver {
+        let receiver: AgentRecordAddress = receiver.to_owned().into();
+        create_index!(Self(intent(&base_address).receiver(&receiver)))?;
+    }
+    update_index!(Self(intent(&base_address).involved_agent(
+        get_involved_agents(&entry_resp.provider, &entry_resp.receiver).as_slice()
+    )))?;
+
+    // index scope
+    update_index!(Self(intent(&base_address).in_scope_of(get_scope_agents(&entry_resp.in_scope_of).as_slice())))?;
+
+    // index classifications
+    update_index!(Keyed(intent(&base_address).classified_as(entry_resp.resource_classified_as.to_owned().unwrap_or_default().as_slice())))?;
+
     // return entire record structure
     construct_response(&base_address, &header_addr, &entry_resp, get_link_fields(&base_address)?)
 }
@@ -55,30 +88,106 @@ pub fn handle_update_intent<S>(entry_def_id: S, intent: UpdateRequest) -> Record
 {
     let address = intent.get_revision_id().to_owned();
     let (revision_id, base_address, new_entry, prev_entry): (_, IntentAddress, EntryData, EntryData) = update_record(&entry_def_id, &address, intent.to_owned())?;
+    update_intent_indexes(&base_address, &new_entry, &prev_entry, None)?;
 
+    construct_response(&base_address, &revision_id, &new_entry, get_link_fields(&base_address)?)
+}
+
+/// Update the index links of a intent as it moves from `prev_entry` to `new_entry`.
+/// `parent_entry` is given where `prev_entry` is a superseded revision being merged.
+///
+/// @see hdk_records::records::get_index_changes
+///
+fn update_intent_indexes(
+    base_address: &IntentAddress, new_entry: &EntryData, prev_entry: &EntryData, parent_entry: Option<&EntryData>,
+) -> RecordAPIResult<()> {
     // handle link fields
-    if new_entry.input_of != prev_entry.input_of {
-        let new_value = match &new_entry.input_of { Some(val) => vec![val.to_owned()], None => vec![] };
-        let prev_value = match &prev_entry.input_of { Some(val) => vec![val.to_owned()], None => vec![] };
+    let (added, removed) = get_index_changes(new_entry, prev_entry, parent_entry, |e| { e.input_of.iter().cloned().collect() });
+    if !(added.is_empty() && removed.is_empty()) {
         update_index!(Remote(
             intent
-                .input_of(new_value.as_slice())
-                .not(prev_value.as_slice()),
-            process.intended_inputs(&base_address)
+                .input_of(added.as_slice())
+                .not(removed.as_slice()),
+            process.intended_inputs(base_address)
         ))?;
     }
-    if new_entry.output_of != prev_entry.output_of {
-        let new_value = match &new_entry.output_of { Some(val) => vec![val.to_owned()], None => vec![] };
-        let prev_value = match &prev_entry.output_of { Some(val) => vec![val.to_owned()], None => vec![] };
+    let (added, removed) = get_index_changes(new_entry, prev_entry, parent_entry, |e| { e.output_of.iter().cloned().collect() });
+    if !(added.is_empty() && removed.is_empty()) {
         update_index!(Remote(
             intent
-                .output_of(new_value.as_slice())
-                .not(prev_value.as_slice()),
-            process.intended_outputs(&base_address)
+                .output_of(added.as_slice())
+                .not(removed.as_slice()),
+            process.intended_outputs(base_address)
         ))?;
     }
 
-    construct_response(&base_address, &revision_id, &new_entry, get_link_fields(&base_address)?)
+    // index involved agents
+    let (added, removed): (Vec<AgentRecordAddress>, _) = get_index_changes(new_entry, prev_entry, parent_entry, |e| { e.provider.iter().map(|a| { a.to_owned().into() }).collect() });
+    if !(added.is_empty() && removed.is_empty()) {
+        update_index!(Self(intent(base_address).provider(added.as_slice()).not(removed.as_slice())))?;
+    }
+    let (added, removed): (Vec<AgentRecordAddress>, _) = get_index_changes(new_entry, prev_entry, parent_entry, |e| { e.receiver.iter().map(|a| { a.to_owned().into() }).collect() });
+    if !(added.is_empty() && removed.is_empty()) {
+        update_index!(Self(intent(base_address).receiver(added.as_slice()).not(removed.as_slice())))?;
+    }
+    let (added, removed) = get_index_changes(new_entry, prev_entry, parent_entry, |e| { get_involved_agents(&e.provider, &e.receiver) });
+    if !(added.is_empty() && removed.is_empty()) {
+        update_index!(Self(intent(base_address).involved_agent(added.as_slice()).not(removed.as_slice())))?;
+    }
+
+    // index scope
+    let (added, removed) = get_index_changes(new_entry, prev_entry, parent_entry, |e| { get_scope_agents(&e.in_scope_of) });
+    if !(added.is_empty() && removed.is_empty()) {
+        update_index!(Self(intent(base_address).in_scope_of(added.as_slice()).not(removed.as_slice())))?;
+    }
+
+    // index classifications
+    let (added, removed): (Vec<ExternalURL>, _) = get_index_changes(new_entry, prev_entry, parent_entry, |e| { e.resource_classified_as.to_owned().unwrap_or_default() });
+    if !(added.is_empty() && removed.is_empty()) {
+        update_index!(Keyed(intent(base_address).classified_as(added.as_slice()).not(removed.as_slice())))?;
+    }
+    Ok(())
+}
+
+pub fn handle_get_intent_revision_heads<S>(entry_def_id: S, address: IntentAddress) -> RecordAPIResult<Vec<RevisionHash>>
+    where S: AsRef<str>
+{
+    read_record_revision_heads::<IntentAddress, _>(&entry_def_id, address.as_ref())
+}
+
+pub fn handle_merge_intent_revisions<S>(entry_def_id: S, revision_id: RevisionHash, superseded_revisions: Vec<RevisionHash>) -> RecordAPIResult<ResponseData>
+    where S: AsRef<str>
+{
+    let (_revision, base_address, entry, superseded): (_, IntentAddress, EntryData, _) = merge_record_revisions::<EntryData, EntryStorage, _>(&revision_id, superseded_revisions.as_slice())?;
+    for (superseded_entry, parent_entry) in superseded.iter() {
+        update_intent_indexes(&base_address, &entry, superseded_entry, Some(parent_entry))?;
+    }
+    handle_get_intent(entry_def_id, base_address)
+}
+
+pub fn handle_get_intent_history<S>(entry_def_id: S, address: IntentAddress) -> RecordAPIResult<Vec<RevisionMeta>>
+    where S: AsRef<str>
+{
+    read_record_revision_history::<IntentAddress, _>(&entry_def_id, address.as_ref())
+}
+
+pub fn handle_get_intent_at_revision(revision_id: RevisionHash) -> RecordAPIResult<ResponseData>
+{
+    let (base_address, entry) = read_record_entry_by_header::<EntryData, EntryStorage, _>(&revision_id)?;
+    construct_response(&base_address, &revision_id, &entry, get_link_fields(&base_address)?)
+}
+
+pub fn handle_get_intent_as_of<S>(entry_def_id: S, address: IntentAddress, as_of: Timestamp) -> RecordAPIResult<ResponseData>
+    where S: AsRef<str>
+{
+    let (revision, base_address, entry) = read_record_entry_as_of::<EntryData, EntryStorage, _,_>(&entry_def_id, address.as_ref(), &as_of)?;
+    // link fields are omitted, since indexes do not retain their history
+    construct_response(&base_address, &revision, &entry, Default::default())
+}
+
+pub fn handle_diff_intent_revisions(from_revision_id: RevisionHash, to_revision_id: RevisionHash) -> RecordAPIResult<RevisionDiff>
+{
+    diff_record_revisions::<EntryData, EntryStorage, IntentAddress>(&from_revision_id, &to_revision_id)
 }
 
 pub fn handle_delete_intent(revision_id: RevisionHash) -> RecordAPIResult<bool>
@@ -86,7 +195,26 @@ pub fn handle_delete_intent(revision_id: RevisionHash) -> RecordAPIResult<bool>
     // load the record to ensure it is of the correct type
     let (base_address, entry) = read_record_entry_by_header::<EntryData, EntryStorage, _>(&revision_id)?;
 
+    // refuse to delete intents which other records still reference
+    let (satisfactions,) = get_link_fields(&base_address)?;
+    if !satisfactions.is_empty() {
+        let identity_hash: &EntryHash = base_address.as_ref();
+        return Err(DataIntegrityError::RecordInUse(
+            identity_hash.to_owned(),
+            format!("{} satisfaction(s)", satisfactions.len()),
+        ));
+    }
+
     // handle link fields
+    let provider: Vec<AgentRecordAddress> = entry.provider.iter().map(|a| { a.to_owned().into() }).collect();
+    let receiver: Vec<AgentRecordAddress> = entry.receiver.iter().map(|a| { a.to_owned().into() }).collect();
+    update_index!(Self(intent(&base_address).provider.not(provider.as_slice())))?;
+    update_index!(Self(intent(&base_address).receiver.not(receiver.as_slice())))?;
+    update_index!(Self(intent(&base_address).involved_agent.not(
+        get_involved_agents(&entry.provider, &entry.receiver).as_slice()
+    )))?;
+    update_index!(Self(intent(&base_address).in_scope_of.not(get_scope_agents(&entry.in_scope_of).as_slice())))?;
+    update_index!(Keyed(intent(&base_address).classified_as.not(entry.resource_classified_as.to_owned().unwrap_or_default().as_slice())))?;
     if let Some(process_address) = entry.input_of {
         update_index!(Remote(intent.input_of.not(&vec![process_address]), process.intended_inputs(&base_address)))?;
     }
@@ -98,6 +226,19 @@ pub fn handle_delete_intent(revision_id: RevisionHash) -> RecordAPIResult<bool>
     delete_record::<EntryStorage, _>(&revision_id)
 }
 
+/// Export a page of all intents in the DNA as ValueFlows JSON-LD
+///
+pub fn handle_export_intents<S>(entry_def_id: S, params: ExportParams) -> RecordAPIResult<ExportPage>
+    where S: AsRef<str>
+{
+    let limit = params.get_limit();
+    let page = query_root_index_page::<EntryData, EntryStorage, _,_>(&entry_def_id, &params.after, limit)?;
+    build_export_page(page, limit, |(revision, base_address, entry)| {
+        let link_fields = get_link_fields(&base_address)?;
+        Ok(construct_jsonld(&construct_response(&base_address, &revision, &entry, link_fields)?.intent))
+    })
+}
+
 /// Create response from input DHT primitives
 pub fn construct_response<'a>(
     address: &IntentAddress, revision_id: &RevisionHash, e: &EntryData, (
@@ -132,13 +273,43 @@ pub fn construct_response<'a>(
             at_location: e.at_location.to_owned(),
             agreed_in: e.agreed_in.to_owned(),
             finished: e.finished.to_owned(),
-            in_scope_of: e.in_scope_of.to_owned(),
+            in_scope_of: get_scoped_agents(&e.in_scope_of),
             satisfied_by: satisfactions.to_owned(),
             // published_in: published_in.to_owned(),
         }
     })
 }
 
+/// Create a ValueFlows JSON-LD node from an output record
+///
+/// Inverse relationships (eg. `satisfiedBy`) are omitted, as they are
+/// expressed by the records on the other side of the relationship.
+///
+pub fn construct_jsonld(r: &Response) -> JsonLdNode {
+    JsonLdNode::new("Intent", &r.id)
+        .action("action", &r.action)
+        .reference("inputOf", &r.input_of)
+        .reference("outputOf", &r.output_of)
+        .reference("provider", &r.provider)
+        .reference("receiver", &r.receiver)
+        .reference("resourceInventoriedAs", &r.resource_inventoried_as)
+        .reference_list("resourceClassifiedAs", &r.resource_classified_as)
+        .reference("resourceConformsTo", &r.resource_conforms_to)
+        .quantity("resourceQuantity", &r.resource_quantity)
+        .quantity("effortQuantity", &r.effort_quantity)
+        .quantity("availableQuantity", &r.available_quantity)
+        .field("hasBeginning", &r.has_beginning)
+        .field("hasEnd", &r.has_end)
+        .field("hasPointInTime", &r.has_point_in_time)
+        .field("due", &r.due)
+        .reference("atLocation", &r.at_location)
+        .reference("agreedIn", &r.agreed_in)
+        .reference_list("inScopeOf", &r.in_scope_of)
+        .reference("image", &r.image)
+        .literal("finished", &r.finished)
+        .field("note", &r.note)
+}
+
 //---------------- READ ----------------
 
 /// Properties accessor for zome config
@@ -17,6 +17,7 @@ pub use vf_attributes_hdk::{
     DateTime, FixedOffset,
     ProcessAddress,
     AgentAddress,
+    AgentRecordAddress,
     EconomicResourceAddress,
     ResourceSpecificationAddress,
     SatisfactionAddress,
@@ -27,7 +28,7 @@ pub use vf_attributes_hdk::{
 //---------------- EXTERNAL RECORD STRUCTURE ----------------
 
 // Export external type interface to allow consuming zomes to easily import & define zome API
-pub use vf_attributes_hdk::{ IntentAddress };
+pub use vf_attributes_hdk::{ IntentAddress, MergeRevisions, ByAddressAsOf, ByRevisionPair };
 
 /// I/O struct to describe the complete record, including all managed link fields
 ///
@@ -74,7 +75,7 @@ pub struct Response {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub agreed_in: Option<ExternalURL>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub in_scope_of: Option<Vec<String>>,
+    pub in_scope_of: Option<Vec<AgentAddress>>,
     pub finished: bool,
     #[serde(default)]
     #[serde(skip_serializing_if = "Vec::is_empty")]
@@ -140,7 +141,7 @@ pub struct CreateRequest {
     #[serde(default = "default_false")]
     pub finished: MaybeUndefined<bool>,
     #[serde(default)]
-    pub in_scope_of: MaybeUndefined<Vec<String>>,
+    pub in_scope_of: MaybeUndefined<Vec<AgentAddress>>,
 }
 
 impl<'a> CreateRequest {
@@ -196,7 +197,7 @@ pub struct UpdateRequest {
     #[serde(default)]
     pub finished: MaybeUndefined<bool>,
     #[serde(default)]
-    pub in_scope_of: MaybeUndefined<Vec<String>>,
+    pub in_scope_of: MaybeUndefined<Vec<AgentAddress>>,
 }
 
 impl<'a> UpdateRequest {
@@ -216,4 +217,9 @@ pub struct QueryParams {
     pub output_of: Option<ProcessAddress>,
     pub satisfied_by: Option<SatisfactionAddress>,
     pub proposed_in: Option<ProposedIntentAddress>,
+    pub provider: Option<AgentRecordAddress>,
+    pub receiver: Option<AgentRecordAddress>,
+    pub involved_agent: Option<AgentRecordAddress>,
+    pub in_scope_of: Option<AgentRecordAddress>,
+    pub classified_as: Option<ExternalURL>,
 }
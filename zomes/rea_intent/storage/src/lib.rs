@@ -9,9 +9,11 @@
 use hdk::prelude::*;
 
 use hdk_records::{
-    MaybeUndefined,
+    RecordAPIResult, MaybeUndefined,
     record_interface::Updateable,
     generate_record_entry,
+    ErrorCode, ErrorDetails,
+    validation::{must_get_local_references, missing_alternative_fields},
 };
 use vf_measurement::QuantityValue;
 
@@ -27,6 +29,7 @@ pub use vf_attributes_hdk::{
     ProcessAddress,
     ResourceSpecificationAddress,
 };
+use vf_attributes_hdk::{ScopeReference, get_scoped_agents, update_scope_references};
 
 use vf_actions::{ validate_flow_action };
 
@@ -70,22 +73,59 @@ pub struct EntryData {
     pub at_location: Option<LocationAddress>,
     pub agreed_in: Option<ExternalURL>,
     pub finished: bool,
-    pub in_scope_of: Option<Vec<String>>,
+    #[serde(default)]
+    pub in_scope_of: Option<Vec<ScopeReference>>,
     pub image: Option<ExternalURL>,
     pub note: Option<String>,
 }
 
 impl EntryData {
-    pub fn validate_action(&self) -> Result<(), String> {
+    pub fn validate_action(&self) -> Result<(), ErrorDetails> {
         validate_flow_action(self.action.to_owned(), self.input_of.to_owned(), self.output_of.to_owned())
     }
 
-    pub fn validate_or_fields(&self) -> Result<(), String> {
+    pub fn validate_or_fields(&self) -> Result<(), ErrorDetails> {
         if !(self.provider.is_some() || self.receiver.is_some()) {
-            return Err("Intent must have either a provider or a receiver".into());
+            return Err(missing_alternative_fields("Intent must have either a provider or a receiver", &["provider", "receiver"]));
         }
         Ok(())
     }
+
+    /// Ensure referenced processes & resources in this DNA exist.
+    ///
+    pub fn must_get_references(&self) -> RecordAPIResult<()> {
+        must_get_local_references(vec![&self.input_of, &self.output_of])?;
+        must_get_local_references(vec![&self.resource_inventoried_as])?;
+        must_get_local_references(vec![&self.resource_conforms_to])
+    }
+
+    /// The action of a intent may not be changed once it is recorded, since any satisfactions of it depend upon it.
+    /// Nor may its provider or receiver once assigned, since either party may update the intent.
+    /// A party which was previously unassigned may be filled in.
+    ///
+    pub fn validate_immutable_fields(&self, previous: &EntryData) -> Result<(), ErrorDetails> {
+        if self.action != previous.action {
+            return Err(ErrorDetails::new(ErrorCode::ImmutableField, "Intent action cannot be changed").at_field("action"));
+        }
+        if previous.provider.is_some() && self.provider != previous.provider {
+            return Err(ErrorDetails::new(ErrorCode::ImmutableField, "Intent provider cannot be changed").at_field("provider"));
+        }
+        if previous.receiver.is_some() && self.receiver != previous.receiver {
+            return Err(ErrorDetails::new(ErrorCode::ImmutableField, "Intent receiver cannot be changed").at_field("receiver"));
+        }
+        Ok(())
+    }
+
+    /// Agents other than the original author who may update or delete this intent:
+    /// its provider or receiver, and any agents it is in the scope of.
+    ///
+    pub fn get_authorised_agents(&self) -> Vec<AgentPubKey> {
+        self.provider.iter()
+            .chain(self.receiver.iter())
+            .chain(get_scoped_agents(&self.in_scope_of).iter().flatten())
+            .map(|a| { a.1.to_owned() })
+            .collect()
+    }
 }
 
 generate_record_entry!(EntryData, IntentAddress, EntryStorage);
@@ -116,7 +156,7 @@ impl From<CreateRequest> for EntryData {
             at_location: e.at_location.to_owned().into(),
             agreed_in: e.agreed_in.to_owned().into(),
             finished: e.finished.to_option().unwrap(),  // :NOTE: unsafe, would crash if not for "default_false" binding via Serde
-            in_scope_of: e.in_scope_of.to_owned().into(),
+            in_scope_of: update_scope_references(&None, e.in_scope_of.to_owned().into()),
         }
     }
 }
@@ -145,7 +185,7 @@ impl Updateable<UpdateRequest> for EntryData {
             at_location: if e.at_location == MaybeUndefined::Undefined { self.at_location.to_owned() } else { e.at_location.to_owned().into() },
             agreed_in: if e.agreed_in == MaybeUndefined::Undefined { self.agreed_in.to_owned() } else { e.agreed_in.to_owned().into() },
             finished: if e.finished == MaybeUndefined::Undefined { self.finished.to_owned() } else { e.finished.to_owned().to_option().unwrap() },
-            in_scope_of: if e.in_scope_of== MaybeUndefined::Undefined { self.in_scope_of.to_owned() } else { e.in_scope_of.to_owned().into() },
+            in_scope_of: if e.in_scope_of == MaybeUndefined::Undefined { self.in_scope_of.to_owned() } else { update_scope_references(&self.in_scope_of, e.in_scope_of.to_owned().into()) },
             image: if e.image== MaybeUndefined::Undefined { self.image.to_owned() } else { e.image.to_owned().into() },
             note: if e.note== MaybeUndefined::Undefined { self.note.to_owned() } else { e.note.to_owned().into() },
         }
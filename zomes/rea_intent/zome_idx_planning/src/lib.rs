@@ -13,4 +13,9 @@ struct Intent {
     input_of: Local<process, intended_inputs>,
     output_of: Local<process, intended_outputs>,
     proposed_in: Remote<proposed_intent, publishes>,
+    provider: Local<agent_record, intents_as_provider>,
+    receiver: Local<agent_record, intents_as_receiver>,
+    involved_agent: Local<agent_record, intents_involving>,
+    in_scope_of: Local<agent_record, intents_in_scope>,
+    classified_as: Keyed<classification, intents>,
 }
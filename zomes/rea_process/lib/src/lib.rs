@@ -6,26 +6,72 @@
  *
  * @package Holo-REA
  */
+use std::collections::{HashSet, HashMap};
 use paste::paste;
 use hdk_records::{
-    RecordAPIResult,
+    RecordAPIResult, DataIntegrityError, EntryHash,
     records::{
         create_record,
         read_record_entry,
         read_record_entry_by_header,
         update_record,
+        read_record_revision_heads,
+        merge_record_revisions,
+        get_index_changes,
         delete_record,
     },
+    history::{
+        Timestamp,
+        read_record_revision_history,
+        read_record_entry_as_of,
+        diff_record_revisions,
+    },
+    rpc::{
+        call_zome_method,
+        call_local_zome_method,
+    },
+    local_indexes::query_root_index_page,
 };
+pub use hdk_records::history::{RevisionMeta, RevisionDiff};
+pub use vf_jsonld::{ExportParams, ExportPage};
+use vf_jsonld::{JsonLdNode, build_export_page};
 use hdk_semantic_indexes_client_lib::*;
+use vf_attributes_hdk::{get_scope_agents, get_scoped_agents};
+use vf_measurement::{ QuantityValue, add, subtract };
 
+use hc_zome_rea_process_storage_consts::*;
 use hc_zome_rea_process_storage::*;
 use hc_zome_rea_process_rpc::*;
+use hc_zome_rea_economic_event_rpc::{
+    Response as EconomicEventResponse,
+    ResponseData as EconomicEventResponseData,
+};
+use hc_zome_rea_commitment_rpc::{
+    Response as CommitmentResponse,
+    ResponseData as CommitmentResponseData,
+};
+use hc_zome_rea_intent_rpc::{
+    Response as IntentResponse,
+    ResponseData as IntentResponseData,
+};
+use hc_zome_rea_agreement_rpc::{
+    ResponseData as AgreementResponseData,
+};
+use hc_zome_rea_resource_specification_rpc::{
+    ResponseData as ResourceSpecificationResponseData,
+};
 
 pub fn handle_create_process<S>(entry_def_id: S, process: CreateRequest) -> RecordAPIResult<ResponseData>
     where S: AsRef<str>
 {
     let (header_addr, base_address, entry_resp): (_,_, EntryData) = create_record(&entry_def_id, process)?;
+
+    // index scope
+    update_index!(Self(process(&base_address).in_scope_of(get_scope_agents(&entry_resp.in_scope_of).as_slice())))?;
+
+    // index classifications
+    update_index!(Keyed(process(&base_address).classified_as(entry_resp.classified_as.to_owned().unwrap_or_default().as_slice())))?;
+
     construct_response(&base_address, &header_addr, &entry_resp, get_link_fields(&base_address)?)
 }
 
@@ -40,18 +86,175 @@ pub fn handle_update_process<S>(entry_def_id: S, process: UpdateRequest) -> Reco
     where S: AsRef<str>
 {
     let address = process.get_revision_id().clone();
-    let (revision_id, identity_address, entry, _prev_entry): (_,_, EntryData, EntryData) = update_record(&entry_def_id, &address, process)?;
-    construct_response(&identity_address, &revision_id, &entry, get_link_fields(&identity_address)?)
+    let (revision_id, base_address, new_entry, prev_entry): (_,_, EntryData, EntryData) = update_record(&entry_def_id, &address, process)?;
+    update_process_indexes(&base_address, &new_entry, &prev_entry, None)?;
+
+    construct_response(&base_address, &revision_id, &new_entry, get_link_fields(&base_address)?)
+}
+
+/// Update the index links of a process as it moves from `prev_entry` to `new_entry`.
+/// `parent_entry` is given where `prev_entry` is a superseded revision being merged.
+///
+/// @see hdk_records::records::get_index_changes
+///
+fn update_process_indexes(
+    base_address: &ProcessAddress, new_entry: &EntryData, prev_entry: &EntryData, parent_entry: Option<&EntryData>,
+) -> RecordAPIResult<()> {
+    // index scope
+    let (added, removed) = get_index_changes(new_entry, prev_entry, parent_entry, |e| { get_scope_agents(&e.in_scope_of) });
+    if !(added.is_empty() && removed.is_empty()) {
+        update_index!(Self(process(base_address).in_scope_of(added.as_slice()).not(removed.as_slice())))?;
+    }
+
+    // index classifications
+    let (added, removed): (Vec<ExternalURL>, _) = get_index_changes(new_entry, prev_entry, parent_entry, |e| { e.classified_as.to_owned().unwrap_or_default() });
+    if !(added.is_empty() && removed.is_empty()) {
+        update_index!(Keyed(process(base_address).classified_as(added.as_slice()).not(removed.as_slice())))?;
+    }
+    Ok(())
+}
+
+pub fn handle_get_process_revision_heads<S>(entry_def_id: S, address: ProcessAddress) -> RecordAPIResult<Vec<RevisionHash>>
+    where S: AsRef<str>
+{
+    read_record_revision_heads::<ProcessAddress, _>(&entry_def_id, address.as_ref())
+}
+
+pub fn handle_merge_process_revisions<S>(entry_def_id: S, revision_id: RevisionHash, superseded_revisions: Vec<RevisionHash>) -> RecordAPIResult<ResponseData>
+    where S: AsRef<str>
+{
+    let (_revision, base_address, entry, superseded): (_, ProcessAddress, EntryData, _) = merge_record_revisions::<EntryData, EntryStorage, _>(&revision_id, superseded_revisions.as_slice())?;
+    for (superseded_entry, parent_entry) in superseded.iter() {
+        update_process_indexes(&base_address, &entry, superseded_entry, Some(parent_entry))?;
+    }
+    handle_get_process(entry_def_id, base_address)
+}
+
+pub fn handle_get_process_history<S>(entry_def_id: S, address: ProcessAddress) -> RecordAPIResult<Vec<RevisionMeta>>
+    where S: AsRef<str>
+{
+    read_record_revision_history::<ProcessAddress, _>(&entry_def_id, address.as_ref())
+}
+
+pub fn handle_get_process_at_revision(revision_id: RevisionHash) -> RecordAPIResult<ResponseData>
+{
+    let (base_address, entry) = read_record_entry_by_header::<EntryData, EntryStorage, _>(&revision_id)?;
+    construct_response(&base_address, &revision_id, &entry, get_link_fields(&base_address)?)
+}
+
+pub fn handle_get_process_as_of<S>(entry_def_id: S, address: ProcessAddress, as_of: Timestamp) -> RecordAPIResult<ResponseData>
+    where S: AsRef<str>
+{
+    let (revision, base_address, entry) = read_record_entry_as_of::<EntryData, EntryStorage, _,_>(&entry_def_id, address.as_ref(), &as_of)?;
+    // link fields are omitted, since indexes do not retain their history
+    construct_response(&base_address, &revision, &entry, Default::default())
+}
+
+pub fn handle_diff_process_revisions(from_revision_id: RevisionHash, to_revision_id: RevisionHash) -> RecordAPIResult<RevisionDiff>
+{
+    diff_record_revisions::<EntryData, EntryStorage, ProcessAddress>(&from_revision_id, &to_revision_id)
 }
 
 pub fn handle_delete_process<S>(_entry_def_id: S, revision_id: RevisionHash) -> RecordAPIResult<bool>
 {
     // load the record to ensure it is of the correct type
-    let (_base_address, _entry) = read_record_entry_by_header::<EntryData, EntryStorage, _>(&revision_id)?;
+    let (base_address, entry) = read_record_entry_by_header::<EntryData, EntryStorage, _>(&revision_id)?;
+
+    // refuse to delete processes which still have flows recorded against them
+    let (
+        inputs, outputs, _unplanned_economic_events,
+        committed_inputs, committed_outputs,
+        intended_inputs, intended_outputs,
+        ..
+    ) = get_link_fields(&base_address)?;
+    let events = inputs.len() + outputs.len();
+    let commitments = committed_inputs.len() + committed_outputs.len();
+    let intents = intended_inputs.len() + intended_outputs.len();
+    if events + commitments + intents > 0 {
+        let identity_hash: &EntryHash = base_address.as_ref();
+        return Err(DataIntegrityError::RecordInUse(
+            identity_hash.to_owned(),
+            format!("{} economic event(s), {} commitment(s) and {} intent(s)", events, commitments, intents),
+        ));
+    }
 
+    // handle link fields
+    update_index!(Self(process(&base_address).in_scope_of.not(get_scope_agents(&entry.in_scope_of).as_slice())))?;
+    update_index!(Keyed(process(&base_address).classified_as.not(entry.classified_as.to_owned().unwrap_or_default().as_slice())))?;
+
+    // delete entry last, as it must be present in order for links to be removed
     delete_record::<EntryStorage, _>(&revision_id)
 }
 
+/// Export a page of all processes in the DNA as ValueFlows JSON-LD
+///
+pub fn handle_export_processes<S>(entry_def_id: S, params: ExportParams) -> RecordAPIResult<ExportPage>
+    where S: AsRef<str>
+{
+    let limit = params.get_limit();
+    let page = query_root_index_page::<EntryData, EntryStorage, _,_>(&entry_def_id, &params.after, limit)?;
+    build_export_page(page, limit, |(revision, base_address, entry)| {
+        let link_fields = get_link_fields(&base_address)?;
+        Ok(construct_jsonld(&construct_response(&base_address, &revision, &entry, link_fields)?.process))
+    })
+}
+
+/// Compare the planned flows of a process (intents & commitments) against the
+/// events actually observed, grouped by resource specification and action.
+///
+pub fn handle_get_process_report<S>(entry_def_id: S, address: ProcessAddress) -> RecordAPIResult<ReportResponseData>
+    where S: AsRef<str>
+{
+    // load the record to ensure it is of the correct type
+    let (_revision, base_address, _entry) = read_record_entry::<EntryData, EntryStorage, _,_>(&entry_def_id, address.as_ref())?;
+
+    let input_events = read_process_events(read_index!(process(&base_address).inputs)?)?;
+    let output_events = read_process_events(read_index!(process(&base_address).outputs)?)?;
+    let input_commitments = read_process_commitments(read_index!(process(&base_address).committed_inputs)?)?;
+    let output_commitments = read_process_commitments(read_index!(process(&base_address).committed_outputs)?)?;
+    let input_intents = read_process_intents(read_index!(process(&base_address).intended_inputs)?)?;
+    let output_intents = read_process_intents(read_index!(process(&base_address).intended_outputs)?)?;
+
+    let unplanned_economic_events = input_events.iter()
+        .chain(output_events.iter())
+        .filter(|e| { e.fulfills.is_empty() })
+        .map(|e| { e.id.to_owned() })
+        .collect();
+
+    let unfulfilled_commitments = input_commitments.iter()
+        .chain(output_commitments.iter())
+        .filter(|c| { c.fulfilled_by.is_empty() })
+        .map(|c| { c.id.to_owned() })
+        .collect();
+
+    Ok(ReportResponseData {
+        process_report: ProcessReport {
+            process: base_address,
+            inputs: group_flow_variances(&input_intents, &input_commitments, &input_events),
+            outputs: group_flow_variances(&output_intents, &output_commitments, &output_events),
+            unplanned_economic_events,
+            unfulfilled_commitments,
+        },
+    })
+}
+
+/// Roll up the cost of the inputs to a process and allocate it across the process's outputs.
+///
+/// Input prices are sourced from (in order of preference) the production cost of the
+/// input resource where it was output from another process; the reciprocal flows of
+/// any `Agreement` the input event realizes; or the `unit_price` of the input's
+/// `ResourceSpecification`.
+///
+pub fn handle_get_process_cost<S>(entry_def_id: S, address: ProcessAddress) -> RecordAPIResult<CostResponseData>
+    where S: AsRef<str>
+{
+    let mut visiting = HashSet::new();
+    let mut costed = HashMap::new();
+    Ok(CostResponseData {
+        process_cost: calculate_process_cost(&entry_def_id, &address, &mut visiting, &mut costed)?,
+    })
+}
+
 /// Create response from input DHT primitives
 fn construct_response<'a>(
     address: &ProcessAddress, revision_id: &RevisionHash, e: &EntryData, (
@@ -86,7 +289,7 @@ fn construct_response<'a>(
             based_on: e.based_on.to_owned(),
             planned_within: e.planned_within.to_owned(),
             note: e.note.to_owned(),
-            in_scope_of: e.in_scope_of.to_owned(),
+            in_scope_of: get_scoped_agents(&e.in_scope_of),
             finished: e.finished.to_owned(),
             deletable: true,    // :TODO:
 
@@ -107,6 +310,24 @@ fn construct_response<'a>(
     })
 }
 
+/// Create a ValueFlows JSON-LD node from an output record
+///
+/// Inverse relationships (eg. `inputs` & `outputs`) are omitted, as they are
+/// expressed by the records on the other side of the relationship.
+///
+pub fn construct_jsonld(r: &Response) -> JsonLdNode {
+    JsonLdNode::new("Process", &r.id)
+        .literal("name", &r.name)
+        .timestamp("hasBeginning", &r.has_beginning)
+        .timestamp("hasEnd", &r.has_end)
+        .reference_list("classifiedAs", &r.classified_as)
+        .reference("basedOn", &r.based_on)
+        .reference("plannedWithin", &r.planned_within)
+        .reference_list("inScopeOf", &r.in_scope_of)
+        .literal("finished", &r.finished)
+        .field("note", &r.note)
+}
+
 //---------------- READ ----------------
 
 /// Properties accessor for zome config.
@@ -114,6 +335,16 @@ fn read_process_index_zome(conf: DnaConfigSlice) -> Option<String> {
     Some(conf.process.index_zome)
 }
 
+/// Properties accessor for zome config.
+fn read_economic_event_zome(conf: DnaConfigSlice) -> Option<String> {
+    conf.process.economic_event_zome
+}
+
+/// Properties accessor for zome config.
+fn read_economic_resource_index_zome(conf: DnaConfigSlice) -> Option<String> {
+    conf.process.economic_resource_index_zome
+}
+
 // @see construct_response
 fn get_link_fields(process: &ProcessAddress) -> RecordAPIResult<(
     Vec<EconomicEventAddress>,
@@ -144,3 +375,384 @@ fn get_link_fields(process: &ProcessAddress) -> RecordAPIResult<(
         vec![], // :TODO: track
     ))
 }
+
+//---------------- REPORTING ----------------
+
+fn read_process_events(addresses: Vec<EconomicEventAddress>) -> RecordAPIResult<Vec<EconomicEventResponse>> {
+    addresses.iter()
+        .map(|address| -> RecordAPIResult<EconomicEventResponse> {
+            let resp: EconomicEventResponseData = call_local_zome_method(
+                read_economic_event_zome,
+                PROCESS_EVENT_READ_API_METHOD,
+                ByAddress { address: address.to_owned() },
+            )?;
+            Ok(resp.economic_event)
+        })
+        .collect()
+}
+
+fn read_process_commitments(addresses: Vec<CommitmentAddress>) -> RecordAPIResult<Vec<CommitmentResponse>> {
+    addresses.iter()
+        .map(|address| -> RecordAPIResult<CommitmentResponse> {
+            let resp: CommitmentResponseData = call_zome_method(
+                address,
+                &PROCESS_COMMITMENT_READ_API_METHOD,
+                ByAddress { address: address.to_owned() },
+            )?;
+            Ok(resp.commitment)
+        })
+        .collect()
+}
+
+fn read_process_intents(addresses: Vec<IntentAddress>) -> RecordAPIResult<Vec<IntentResponse>> {
+    addresses.iter()
+        .map(|address| -> RecordAPIResult<IntentResponse> {
+            let resp: IntentResponseData = call_zome_method(
+                address,
+                &PROCESS_INTENT_READ_API_METHOD,
+                ByAddress { address: address.to_owned() },
+            )?;
+            Ok(resp.intent)
+        })
+        .collect()
+}
+
+/// Bucket all flows on one side of a process by `(action, resource_conforms_to)`
+/// and total their quantities.
+///
+fn group_flow_variances(
+    intents: &Vec<IntentResponse>,
+    commitments: &Vec<CommitmentResponse>,
+    events: &Vec<EconomicEventResponse>,
+) -> Vec<FlowVariance> {
+    let mut groups: Vec<FlowVariance> = vec![];
+
+    for intent in intents {
+        let group = get_flow_group(&mut groups, &intent.action, &intent.resource_conforms_to);
+        group.intents.push(intent.id.to_owned());
+        accumulate_quantity(&mut group.intended_resource_quantity, &intent.resource_quantity, &mut group.unit_mismatch);
+        accumulate_quantity(&mut group.intended_effort_quantity, &intent.effort_quantity, &mut group.unit_mismatch);
+    }
+    for commitment in commitments {
+        let group = get_flow_group(&mut groups, &commitment.action, &commitment.resource_conforms_to);
+        group.commitments.push(commitment.id.to_owned());
+        accumulate_quantity(&mut group.planned_resource_quantity, &commitment.resource_quantity, &mut group.unit_mismatch);
+        accumulate_quantity(&mut group.planned_effort_quantity, &commitment.effort_quantity, &mut group.unit_mismatch);
+    }
+    for event in events {
+        let group = get_flow_group(&mut groups, &event.action, &event.resource_conforms_to);
+        group.economic_events.push(event.id.to_owned());
+        accumulate_quantity(&mut group.observed_resource_quantity, &event.resource_quantity, &mut group.unit_mismatch);
+        accumulate_quantity(&mut group.observed_effort_quantity, &event.effort_quantity, &mut group.unit_mismatch);
+    }
+
+    for group in groups.iter_mut() {
+        group.resource_quantity_variance = quantity_variance(&group.planned_resource_quantity, &group.observed_resource_quantity, &mut group.unit_mismatch);
+        group.effort_quantity_variance = quantity_variance(&group.planned_effort_quantity, &group.observed_effort_quantity, &mut group.unit_mismatch);
+    }
+
+    groups
+}
+
+fn get_flow_group<'a>(
+    groups: &'a mut Vec<FlowVariance>,
+    action: &ActionId,
+    resource_conforms_to: &Option<ResourceSpecificationAddress>,
+) -> &'a mut FlowVariance {
+    let existing = groups.iter().position(|g| { g.action == *action && g.resource_conforms_to == *resource_conforms_to });
+
+    match existing {
+        Some(idx) => &mut groups[idx],
+        None => {
+            groups.push(FlowVariance {
+                action: action.to_owned(),
+                resource_conforms_to: resource_conforms_to.to_owned(),
+                intended_resource_quantity: None,
+                intended_effort_quantity: None,
+                planned_resource_quantity: None,
+                planned_effort_quantity: None,
+                observed_resource_quantity: None,
+                observed_effort_quantity: None,
+                resource_quantity_variance: None,
+                effort_quantity_variance: None,
+                unit_mismatch: false,
+                intents: vec![],
+                commitments: vec![],
+                economic_events: vec![],
+            });
+            groups.last_mut().unwrap()
+        },
+    }
+}
+
+/// Add a flow quantity to a running total.
+///
+/// :TODO: unit conversions. For now, quantities in a unit differing from the
+///        running total are skipped and the group flagged as mismatched.
+///
+fn accumulate_quantity(total: &mut Option<QuantityValue>, value: &Option<QuantityValue>, unit_mismatch: &mut bool) {
+    let value = match value {
+        Some(v) => v.to_owned(),
+        None => return,
+    };
+
+    *total = match total.take() {
+        None => Some(value),
+        Some(current) => {
+            if current.get_unit() == value.get_unit() {
+                Some(add(current, value))
+            } else {
+                *unit_mismatch = true;
+                Some(current)
+            }
+        },
+    };
+}
+
+/// Determine observed minus planned quantity, where both are comparable
+fn quantity_variance(planned: &Option<QuantityValue>, observed: &Option<QuantityValue>, unit_mismatch: &mut bool) -> Option<QuantityValue> {
+    match (planned, observed) {
+        (Some(p), Some(o)) => {
+            if p.get_unit() == o.get_unit() {
+                Some(subtract(o.to_owned(), p.to_owned()))
+            } else {
+                *unit_mismatch = true;
+                None
+            }
+        },
+        (Some(p), None) => Some(subtract(QuantityValue::new(0.0, p.get_unit()), p.to_owned())),
+        (None, Some(o)) => Some(o.to_owned()),
+        (None, None) => None,
+    }
+}
+
+//---------------- COSTING ----------------
+
+/// Calculate the cost of a process and (recursively) of the processes its inputs were produced by.
+///
+/// `visiting` holds the processes along the current path through the process graph, in order to
+/// break cycles. `costed` memoises the cost of each process already calculated, so that processes
+/// reachable by multiple paths are costed consistently (and only once).
+///
+fn calculate_process_cost<S>(
+    entry_def_id: &S, address: &ProcessAddress,
+    visiting: &mut HashSet<ProcessAddress>, costed: &mut HashMap<ProcessAddress, ProcessCost>,
+) -> RecordAPIResult<ProcessCost>
+    where S: AsRef<str>
+{
+    if let Some(cost) = costed.get(address) {
+        return Ok(cost.to_owned());
+    }
+
+    // load the record to ensure it is of the correct type
+    let (_revision, base_address, _entry) = read_record_entry::<EntryData, EntryStorage, _,_>(entry_def_id, address.as_ref())?;
+    visiting.insert(base_address.to_owned());
+
+    let input_events = read_process_events(read_index!(process(&base_address).inputs)?)?;
+    let output_events = read_process_events(read_index!(process(&base_address).outputs)?)?;
+
+    let mut inputs = vec![];
+    let mut total_cost = vec![];
+    for event in input_events.iter() {
+        let input = calculate_input_cost(entry_def_id, &base_address, event, visiting, costed)?;
+        add_to_totals(&mut total_cost, &input.cost);
+        inputs.push(input);
+    }
+
+    let cost = ProcessCost {
+        process: base_address.to_owned(),
+        outputs: allocate_output_costs(&output_events, &total_cost),
+        inputs,
+        total_cost,
+    };
+
+    visiting.remove(&base_address);
+    costed.insert(base_address, cost.to_owned());
+
+    Ok(cost)
+}
+
+fn calculate_input_cost<S>(
+    entry_def_id: &S, process: &ProcessAddress, event: &EconomicEventResponse,
+    visiting: &mut HashSet<ProcessAddress>, costed: &mut HashMap<ProcessAddress, ProcessCost>,
+) -> RecordAPIResult<InputCost>
+    where S: AsRef<str>
+{
+    let quantity = event.resource_quantity.to_owned().or(event.effort_quantity.to_owned());
+
+    let mut input = InputCost {
+        economic_event: event.id.to_owned(),
+        action: event.action.to_owned(),
+        quantity: quantity.to_owned(),
+        price_source: PriceSource::Unpriced,
+        upstream_process: None,
+        unit_price: None,
+        cost: None,
+    };
+
+    if let Some((upstream, unit_price)) = get_upstream_unit_price(entry_def_id, process, event, visiting, costed)? {
+        input.price_source = PriceSource::UpstreamProcess;
+        input.upstream_process = Some(upstream);
+        input.unit_price = Some(unit_price);
+    } else if let Some(unit_price) = get_agreement_unit_price(event, &quantity)? {
+        input.price_source = PriceSource::Agreement;
+        input.unit_price = Some(unit_price);
+    } else if let Some(unit_price) = get_specification_unit_price(&event.resource_conforms_to)? {
+        input.price_source = PriceSource::ResourceSpecification;
+        input.unit_price = Some(unit_price);
+    }
+
+    input.cost = match (&input.unit_price, &quantity) {
+        (Some(price), Some(qty)) => Some(QuantityValue::new(price.get_numerical_value() * qty.get_numerical_value(), price.get_unit())),
+        _ => None,
+    };
+
+    Ok(input)
+}
+
+/// Price an input resource at the unit cost it was produced at by some other process.
+///
+/// Only usable where the upstream process was costed in a single currency.
+///
+fn get_upstream_unit_price<S>(
+    entry_def_id: &S, process: &ProcessAddress, event: &EconomicEventResponse,
+    visiting: &mut HashSet<ProcessAddress>, costed: &mut HashMap<ProcessAddress, ProcessCost>,
+) -> RecordAPIResult<Option<(ProcessAddress, QuantityValue)>>
+    where S: AsRef<str>
+{
+    let resource = match &event.resource_inventoried_as {
+        Some(r) => r,
+        None => return Ok(None),
+    };
+
+    let affecting_events = read_process_events(read_index!(economic_resource(resource).affected_by)?)?;
+    for affecting in affecting_events.iter() {
+        let upstream = match &affecting.output_of {
+            Some(p) => p,
+            None => continue,
+        };
+        // guard against cycles in the process graph
+        if upstream == process || visiting.contains(upstream) {
+            continue;
+        }
+
+        let upstream_cost = calculate_process_cost(entry_def_id, upstream, visiting, costed)?;
+        let unit_cost = upstream_cost.outputs.iter()
+            .find(|o| { o.economic_event == affecting.id })
+            .map(|o| { o.unit_cost.to_owned() })
+            .unwrap_or_default();
+
+        if unit_cost.len() == 1 {
+            return Ok(Some((upstream.to_owned(), unit_cost[0].to_owned())));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Price an input by the reciprocal flows of the `Agreement` it was exchanged under,
+/// ie. those events in the agreement flowing back from the receiver of the input.
+///
+fn get_agreement_unit_price(event: &EconomicEventResponse, quantity: &Option<QuantityValue>) -> RecordAPIResult<Option<QuantityValue>> {
+    let (agreement, qty) = match (&event.realization_of, quantity) {
+        (Some(a), Some(q)) => (a, q),
+        _ => return Ok(None),
+    };
+    if qty.get_numerical_value() == 0.0 {
+        return Ok(None);
+    }
+
+    let resp: AgreementResponseData = call_zome_method(
+        agreement,
+        &PROCESS_AGREEMENT_READ_API_METHOD,
+        ByAddress { address: agreement.to_owned() },
+    )?;
+
+    let reciprocal_events = read_process_events(resp.agreement.economic_events.iter()
+        .filter(|e| { **e != event.id })
+        .cloned()
+        .collect())?;
+
+    let mut totals = vec![];
+    for reciprocal in reciprocal_events.iter().filter(|e| { e.provider == event.receiver }) {
+        add_to_totals(&mut totals, &reciprocal.resource_quantity);
+    }
+
+    if totals.len() != 1 {
+        return Ok(None);
+    }
+    Ok(Some(QuantityValue::new(totals[0].get_numerical_value() / qty.get_numerical_value(), totals[0].get_unit())))
+}
+
+fn get_specification_unit_price(resource_conforms_to: &Option<ResourceSpecificationAddress>) -> RecordAPIResult<Option<QuantityValue>> {
+    let spec = match resource_conforms_to {
+        Some(s) => s,
+        None => return Ok(None),
+    };
+
+    let resp: ResourceSpecificationResponseData = call_zome_method(
+        spec,
+        &PROCESS_RESOURCE_SPECIFICATION_READ_API_METHOD,
+        ByAddress { address: spec.to_owned() },
+    )?;
+
+    Ok(resp.resource_specification.unit_price)
+}
+
+/// Distribute the total cost of a process across its outputs, proportionally to output quantity.
+///
+/// Where output quantities are missing or not measured in a common unit, cost is split evenly.
+///
+fn allocate_output_costs(outputs: &Vec<EconomicEventResponse>, total_cost: &Vec<QuantityValue>) -> Vec<OutputCost> {
+    let quantities: Vec<Option<QuantityValue>> = outputs.iter()
+        .map(|e| { e.resource_quantity.to_owned().or(e.effort_quantity.to_owned()) })
+        .collect();
+
+    let mut output_totals = vec![];
+    for qty in quantities.iter() {
+        add_to_totals(&mut output_totals, qty);
+    }
+    let proportional = output_totals.len() == 1
+        && output_totals[0].get_numerical_value() != 0.0
+        && quantities.iter().all(|q| { q.is_some() });
+
+    outputs.iter().zip(quantities.iter())
+        .map(|(event, qty)| {
+            let share = if proportional {
+                qty.as_ref().unwrap().get_numerical_value() / output_totals[0].get_numerical_value()
+            } else {
+                1.0 / outputs.len() as f64
+            };
+            let cost: Vec<QuantityValue> = total_cost.iter()
+                .map(|c| { QuantityValue::new(c.get_numerical_value() * share, c.get_unit()) })
+                .collect();
+            let unit_cost = match qty {
+                Some(q) if q.get_numerical_value() != 0.0 => cost.iter()
+                    .map(|c| { QuantityValue::new(c.get_numerical_value() / q.get_numerical_value(), c.get_unit()) })
+                    .collect(),
+                _ => vec![],
+            };
+
+            OutputCost {
+                economic_event: event.id.to_owned(),
+                resource_inventoried_as: event.resource_inventoried_as.to_owned(),
+                quantity: qty.to_owned(),
+                cost,
+                unit_cost,
+            }
+        })
+        .collect()
+}
+
+/// Accumulate a quantity into a set of totals, keeping a separate total for each unit
+///
+/// :DUPE: identical logic in EconomicResource zome
+///
+fn add_to_totals(totals: &mut Vec<QuantityValue>, quantity: &Option<QuantityValue>) {
+    if let Some(qty) = quantity {
+        match totals.iter().position(|total| { total.get_unit() == qty.get_unit() }) {
+            Some(idx) => totals[idx] = add(totals[idx].to_owned(), qty.to_owned()),
+            None => totals.push(qty.to_owned()),
+        }
+    }
+}
@@ -7,17 +7,22 @@ use serde_maybe_undefined::{
     MaybeUndefined,
     default_false,
 };
+use vf_measurement::QuantityValue;
 pub use vf_attributes_hdk::{
-    RevisionHash,
+    RevisionHash, ByAddress, ByHeader, MergeRevisions, ByAddressAsOf, ByRevisionPair,
+    ActionId,
+    ResourceSpecificationAddress,
     ProcessAddress,
     Timestamp,
     ExternalURL,
     ProcessSpecificationAddress,
     PlanAddress,
     EconomicEventAddress,
+    EconomicResourceAddress,
     CommitmentAddress,
     IntentAddress,
     AgentAddress,
+    AgentRecordAddress,
 };
 
 //---------------- EXTERNAL RECORD STRUCTURE ----------------
@@ -46,7 +51,7 @@ pub struct Response {
     pub finished: bool,
     pub deletable: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub in_scope_of: Option<Vec<String>>,
+    pub in_scope_of: Option<Vec<AgentAddress>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub note: Option<String>,
 
@@ -120,7 +125,7 @@ pub struct CreateRequest {
     #[serde(default = "default_false")]
     pub finished: MaybeUndefined<bool>,
     #[serde(default)]
-    pub in_scope_of: MaybeUndefined<Vec<String>>,
+    pub in_scope_of: MaybeUndefined<Vec<AgentAddress>>,
     #[serde(default)]
     pub note: MaybeUndefined<String>,
 }
@@ -155,7 +160,7 @@ pub struct UpdateRequest {
     #[serde(default)]
     pub finished: MaybeUndefined<bool>,
     #[serde(default)]
-    pub in_scope_of: MaybeUndefined<Vec<String>>,
+    pub in_scope_of: MaybeUndefined<Vec<AgentAddress>>,
     #[serde(default)]
     pub note: MaybeUndefined<String>,
 }
@@ -181,4 +186,134 @@ pub struct QueryParams {
     pub intended_inputs: Option<IntentAddress>,
     pub intended_outputs: Option<IntentAddress>,
     pub working_agents: Option<AgentAddress>,
+    pub in_scope_of: Option<AgentRecordAddress>,
+    pub classified_as: Option<ExternalURL>,
+}
+
+//---------------- PLAN VS ACTUAL REPORTING ----------------
+
+/// Planned vs observed totals for all flows of a particular `ResourceSpecification`
+/// and `Action` pairing within a `Process`.
+///
+/// Quantities are only totalled where units agree; flows with mismatched units are
+/// still listed but excluded from the totals and flagged via `unit_mismatch`.
+///
+#[derive(Clone, Serialize, Deserialize, SerializedBytes, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FlowVariance {
+    pub action: ActionId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource_conforms_to: Option<ResourceSpecificationAddress>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub intended_resource_quantity: Option<QuantityValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub intended_effort_quantity: Option<QuantityValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub planned_resource_quantity: Option<QuantityValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub planned_effort_quantity: Option<QuantityValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub observed_resource_quantity: Option<QuantityValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub observed_effort_quantity: Option<QuantityValue>,
+    // observed minus planned
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource_quantity_variance: Option<QuantityValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub effort_quantity_variance: Option<QuantityValue>,
+    pub unit_mismatch: bool,
+
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub intents: Vec<IntentAddress>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub commitments: Vec<CommitmentAddress>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub economic_events: Vec<EconomicEventAddress>,
+}
+
+/// Comparison of a `Process`' planned flows against what was actually observed
+#[derive(Clone, Serialize, Deserialize, SerializedBytes, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessReport {
+    pub process: ProcessAddress,
+    pub inputs: Vec<FlowVariance>,
+    pub outputs: Vec<FlowVariance>,
+    // events which do not fulfill any `Commitment`
+    pub unplanned_economic_events: Vec<EconomicEventAddress>,
+    // commitments which have not been fulfilled by any `EconomicEvent`
+    pub unfulfilled_commitments: Vec<CommitmentAddress>,
+}
+
+/// I/O struct to describe what is returned outside the gateway
+#[derive(Clone, Serialize, Deserialize, SerializedBytes, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportResponseData {
+    pub process_report: ProcessReport,
+}
+
+//---------------- COST ROLL-UP ----------------
+
+/// Where the unit price used to cost a process input was obtained from
+#[derive(Clone, Serialize, Deserialize, SerializedBytes, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum PriceSource {
+    // production cost of the input resource, rolled up from an upstream process
+    UpstreamProcess,
+    // reciprocal flows in the `Agreement` realized by the input event
+    Agreement,
+    // `unit_price` of the input's `ResourceSpecification`
+    ResourceSpecification,
+    // no price could be determined
+    Unpriced,
+}
+
+/// Cost attributed to a single input of a process
+#[derive(Clone, Serialize, Deserialize, SerializedBytes, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct InputCost {
+    pub economic_event: EconomicEventAddress,
+    pub action: ActionId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quantity: Option<QuantityValue>,
+    pub price_source: PriceSource,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub upstream_process: Option<ProcessAddress>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unit_price: Option<QuantityValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cost: Option<QuantityValue>,
+}
+
+/// Share of a process's total cost carried onto one of its outputs
+#[derive(Clone, Serialize, Deserialize, SerializedBytes, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputCost {
+    pub economic_event: EconomicEventAddress,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource_inventoried_as: Option<EconomicResourceAddress>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quantity: Option<QuantityValue>,
+    pub cost: Vec<QuantityValue>,
+    pub unit_cost: Vec<QuantityValue>,
+}
+
+/// Production cost of a process. Costs are totalled separately for each currency encountered.
+#[derive(Clone, Serialize, Deserialize, SerializedBytes, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessCost {
+    pub process: ProcessAddress,
+    pub inputs: Vec<InputCost>,
+    pub total_cost: Vec<QuantityValue>,
+    pub outputs: Vec<OutputCost>,
+}
+
+/// I/O struct to describe what is returned outside the gateway
+#[derive(Clone, Serialize, Deserialize, SerializedBytes, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CostResponseData {
+    pub process_cost: ProcessCost,
 }
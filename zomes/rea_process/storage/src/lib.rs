@@ -20,6 +20,9 @@ use vf_attributes_hdk::{
     ExternalURL,
     ProcessSpecificationAddress,
     PlanAddress,
+    ScopeReference,
+    get_scoped_agents,
+    update_scope_references,
 };
 
 use hc_zome_rea_process_rpc::{ CreateRequest, UpdateRequest };
@@ -35,6 +38,8 @@ pub struct DnaConfigSlice {
 #[derive(Clone, Serialize, Deserialize, SerializedBytes, PartialEq, Debug)]
 pub struct ProcessZomeConfig {
     pub index_zome: String,
+    pub economic_event_zome: Option<String>,
+    pub economic_resource_index_zome: Option<String>,
 }
 
 //---------------- RECORD INTERNALS & VALIDATION ----------------
@@ -50,10 +55,20 @@ pub struct EntryData {
     pub based_on: Option<ProcessSpecificationAddress>,
     pub planned_within: Option<PlanAddress>,
     pub finished: bool,
-    pub in_scope_of: Option<Vec<String>>,
+    #[serde(default)]
+    pub in_scope_of: Option<Vec<ScopeReference>>,
     pub note: Option<String>,
 }
 
+impl EntryData {
+    /// Agents other than the original author who may update or delete this process:
+    /// any agents it is in the scope of.
+    ///
+    pub fn get_authorised_agents(&self) -> Vec<AgentPubKey> {
+        get_scoped_agents(&self.in_scope_of).iter().flatten().map(|a| { a.1.to_owned() }).collect()
+    }
+}
+
 generate_record_entry!(EntryData, ProcessAddress, EntryStorage);
 
 //---------------- CREATE ----------------
@@ -71,7 +86,7 @@ impl From<CreateRequest> for EntryData {
             based_on: e.based_on.into(),
             planned_within: e.planned_within.into(),
             finished: e.finished.to_option().unwrap(),  // :NOTE: unsafe, would crash if not for "default_*" bindings via Serde
-            in_scope_of: e.in_scope_of.into(),
+            in_scope_of: update_scope_references(&None, e.in_scope_of.into()),
             note: e.note.into(),
         }
     }
@@ -92,7 +107,7 @@ impl Updateable<UpdateRequest> for EntryData {
             based_on: if e.based_on == MaybeUndefined::Undefined { self.based_on.to_owned() } else { e.based_on.to_owned().into() },
             planned_within: if e.planned_within == MaybeUndefined::Undefined { self.planned_within.to_owned() } else { e.planned_within.to_owned().into() },
             finished: if e.finished == MaybeUndefined::Undefined { self.finished.to_owned() } else { e.finished.to_owned().to_option().unwrap() },
-            in_scope_of: if e.in_scope_of == MaybeUndefined::Undefined { self.in_scope_of.to_owned() } else { e.in_scope_of.to_owned().into() },
+            in_scope_of: if e.in_scope_of == MaybeUndefined::Undefined { self.in_scope_of.to_owned() } else { update_scope_references(&self.in_scope_of, e.in_scope_of.to_owned().into()) },
             note: if e.note == MaybeUndefined::Undefined { self.note.to_owned() } else { e.note.to_owned().into() },
         }
     }
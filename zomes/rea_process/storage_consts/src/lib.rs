@@ -19,3 +19,9 @@ pub const PROCESS_COMMITMENT_INPUTS_READ_API_METHOD: &str = "_internal_read_proc
 pub const PROCESS_COMMITMENT_OUTPUTS_READ_API_METHOD: &str = "_internal_read_process_committed_outputs";
 pub const PROCESS_INTENT_INPUTS_READ_API_METHOD: &str = "_internal_read_process_intended_inputs";
 pub const PROCESS_INTENT_OUTPUTS_READ_API_METHOD: &str = "_internal_read_process_intended_outputs";
+
+pub const PROCESS_EVENT_READ_API_METHOD: &str = "get_economic_event";
+pub const PROCESS_COMMITMENT_READ_API_METHOD: &str = "read_commitment";
+pub const PROCESS_INTENT_READ_API_METHOD: &str = "read_intent";
+pub const PROCESS_AGREEMENT_READ_API_METHOD: &str = "read_agreement";
+pub const PROCESS_RESOURCE_SPECIFICATION_READ_API_METHOD: &str = "read_resource_specification";
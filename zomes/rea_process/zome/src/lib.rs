@@ -12,6 +12,27 @@ use hdk::prelude::*;
 use hc_zome_rea_process_storage_consts::*;
 use hc_zome_rea_process_lib::*;
 use hc_zome_rea_process_rpc::*;
+use hc_zome_rea_process_storage::EntryStorage;
+use hdk_records::record_interface::Identified;
+use hdk_records::validation::{get_modified_revision, validate_modification_author};
+
+#[hdk_extern]
+fn validate(validation_data: ValidateData) -> ExternResult<ValidateCallbackResult> {
+    let element = validation_data.element;
+    let (signed_header, _entry) = element.into_inner();
+    let header = signed_header.header();
+
+    // updates & deletes may only be made by the original author or agents the previous revision is in the scope of
+    if let Some(modified) = get_modified_revision(header)? {
+        if let Ok(previous) = EntryStorage::try_from(&modified.entry) {
+            if let Err(e) = validate_modification_author(header, &modified, &previous.entry().get_authorised_agents()) {
+                return Ok(ValidateCallbackResult::Invalid(e.encode()));
+            }
+        }
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
 
 #[hdk_extern]
 fn entry_defs(_: ()) -> ExternResult<EntryDefsCallbackResult> {
@@ -47,6 +68,16 @@ fn get_process(ReadParams { address }: ReadParams) -> ExternResult<ResponseData>
     Ok(handle_get_process(PROCESS_ENTRY_TYPE, address)?)
 }
 
+#[hdk_extern]
+fn get_process_report(ReadParams { address }: ReadParams) -> ExternResult<ReportResponseData> {
+    Ok(handle_get_process_report(PROCESS_ENTRY_TYPE, address)?)
+}
+
+#[hdk_extern]
+fn get_process_cost(ReadParams { address }: ReadParams) -> ExternResult<CostResponseData> {
+    Ok(handle_get_process_cost(PROCESS_ENTRY_TYPE, address)?)
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct UpdateParams {
     pub process: UpdateRequest,
@@ -57,6 +88,41 @@ fn update_process(UpdateParams { process }: UpdateParams) -> ExternResult<Respon
     Ok(handle_update_process(PROCESS_ENTRY_TYPE, process)?)
 }
 
+#[hdk_extern]
+fn get_process_revision_heads(ReadParams { address }: ReadParams) -> ExternResult<Vec<RevisionHash>> {
+    Ok(handle_get_process_revision_heads(PROCESS_ENTRY_TYPE, address)?)
+}
+
+#[hdk_extern]
+fn merge_process_revisions(MergeRevisions { revision_id, superseded_revisions }: MergeRevisions) -> ExternResult<ResponseData> {
+    Ok(handle_merge_process_revisions(PROCESS_ENTRY_TYPE, revision_id, superseded_revisions)?)
+}
+
+#[hdk_extern]
+fn get_process_history(ReadParams { address }: ReadParams) -> ExternResult<Vec<RevisionMeta>> {
+    Ok(handle_get_process_history(PROCESS_ENTRY_TYPE, address)?)
+}
+
+#[hdk_extern]
+fn get_process_at_revision(ByHeader { address }: ByHeader) -> ExternResult<ResponseData> {
+    Ok(handle_get_process_at_revision(address)?)
+}
+
+#[hdk_extern]
+fn get_process_as_of(ByAddressAsOf { address, as_of }: ByAddressAsOf<ProcessAddress>) -> ExternResult<ResponseData> {
+    Ok(handle_get_process_as_of(PROCESS_ENTRY_TYPE, address, as_of)?)
+}
+
+#[hdk_extern]
+fn diff_process_revisions(ByRevisionPair { from_revision_id, to_revision_id }: ByRevisionPair) -> ExternResult<RevisionDiff> {
+    Ok(handle_diff_process_revisions(from_revision_id, to_revision_id)?)
+}
+
+#[hdk_extern]
+fn export_processes(params: ExportParams) -> ExternResult<ExportPage> {
+    Ok(handle_export_processes(PROCESS_ENTRY_TYPE, params)?)
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct DeleteParams {
     pub address: RevisionHash,
@@ -14,5 +14,7 @@ struct Process {
     committed_inputs: Remote<commitment, input_of>,
     committed_outputs: Remote<commitment, output_of>,
     intended_inputs: Remote<intent, input_of>,
-    intended_outputs: Remote<intent, output_of>
+    intended_outputs: Remote<intent, output_of>,
+    in_scope_of: Local<agent_record, processes_in_scope>,
+    classified_as: Keyed<classification, processes>,
 }
@@ -11,10 +11,23 @@ use hdk_records::{
     records::{
         create_record,
         read_record_entry,
+        read_record_entry_by_header,
         update_record,
+        read_record_revision_heads,
+        merge_record_revisions,
         delete_record,
     },
+    history::{
+        Timestamp,
+        read_record_revision_history,
+        read_record_entry_as_of,
+        diff_record_revisions,
+    },
+    local_indexes::query_root_index_page,
 };
+pub use hdk_records::history::{RevisionMeta, RevisionDiff};
+pub use vf_jsonld::{ExportParams, ExportPage};
+use vf_jsonld::{JsonLdNode, build_export_page};
 
 use hc_zome_rea_process_specification_storage::*;
 use hc_zome_rea_process_specification_rpc::*;
@@ -42,11 +55,60 @@ pub fn handle_update_process_specification<S>(entry_def_id: S, process_specifica
     Ok(construct_response(&base_address, &revision_id, &new_entry))
 }
 
+pub fn handle_get_process_specification_revision_heads<S>(entry_def_id: S, address: ProcessSpecificationAddress) -> RecordAPIResult<Vec<RevisionHash>>
+    where S: AsRef<str>
+{
+    read_record_revision_heads::<ProcessSpecificationAddress, _>(&entry_def_id, address.as_ref())
+}
+
+pub fn handle_merge_process_specification_revisions<S>(entry_def_id: S, revision_id: RevisionHash, superseded_revisions: Vec<RevisionHash>) -> RecordAPIResult<ResponseData>
+    where S: AsRef<str>
+{
+    let (_revision, base_address, _entry, _superseded): (_, ProcessSpecificationAddress, EntryData, _) = merge_record_revisions::<EntryData, EntryStorage, _>(&revision_id, superseded_revisions.as_slice())?;
+    handle_get_process_specification(entry_def_id, base_address)
+}
+
+pub fn handle_get_process_specification_history<S>(entry_def_id: S, address: ProcessSpecificationAddress) -> RecordAPIResult<Vec<RevisionMeta>>
+    where S: AsRef<str>
+{
+    read_record_revision_history::<ProcessSpecificationAddress, _>(&entry_def_id, address.as_ref())
+}
+
+pub fn handle_get_process_specification_at_revision(revision_id: RevisionHash) -> RecordAPIResult<ResponseData>
+{
+    let (base_address, entry) = read_record_entry_by_header::<EntryData, EntryStorage, _>(&revision_id)?;
+    Ok(construct_response(&base_address, &revision_id, &entry))
+}
+
+pub fn handle_get_process_specification_as_of<S>(entry_def_id: S, address: ProcessSpecificationAddress, as_of: Timestamp) -> RecordAPIResult<ResponseData>
+    where S: AsRef<str>
+{
+    let (revision, base_address, entry) = read_record_entry_as_of::<EntryData, EntryStorage, _,_>(&entry_def_id, address.as_ref(), &as_of)?;
+    Ok(construct_response(&base_address, &revision, &entry))
+}
+
+pub fn handle_diff_process_specification_revisions(from_revision_id: RevisionHash, to_revision_id: RevisionHash) -> RecordAPIResult<RevisionDiff>
+{
+    diff_record_revisions::<EntryData, EntryStorage, ProcessSpecificationAddress>(&from_revision_id, &to_revision_id)
+}
+
 pub fn handle_delete_process_specification(revision_id: RevisionHash) -> RecordAPIResult<bool>
 {
     delete_record::<EntryStorage, _>(&revision_id)
 }
 
+/// Export a page of all process specifications in the DNA as ValueFlows JSON-LD
+///
+pub fn handle_export_process_specifications<S>(entry_def_id: S, params: ExportParams) -> RecordAPIResult<ExportPage>
+    where S: AsRef<str>
+{
+    let limit = params.get_limit();
+    let page = query_root_index_page::<EntryData, EntryStorage, _,_>(&entry_def_id, &params.after, limit)?;
+    build_export_page(page, limit, |(revision, base_address, entry)| {
+        Ok(construct_jsonld(&construct_response(&base_address, &revision, &entry).process_specification))
+    })
+}
+
 /// Create response from input DHT primitives
 fn construct_response<'a>(
     address: &ProcessSpecificationAddress, revision_id: &RevisionHash, e: &EntryData,
@@ -60,3 +122,11 @@ fn construct_response<'a>(
         }
     }
 }
+
+/// Create a ValueFlows JSON-LD node from an output record
+///
+pub fn construct_jsonld(r: &Response) -> JsonLdNode {
+    JsonLdNode::new("ProcessSpecification", &r.id)
+        .literal("name", &r.name)
+        .field("note", &r.note)
+}
@@ -12,6 +12,26 @@ use hdk::prelude::*;
 use hc_zome_rea_process_specification_rpc::*;
 use hc_zome_rea_process_specification_lib::*;
 use hc_zome_rea_process_specification_storage_consts::*;
+use hc_zome_rea_process_specification_storage::EntryStorage;
+use hdk_records::validation::{get_modified_revision, validate_modification_author};
+
+#[hdk_extern]
+fn validate(validation_data: ValidateData) -> ExternResult<ValidateCallbackResult> {
+    let element = validation_data.element;
+    let (signed_header, _entry) = element.into_inner();
+    let header = signed_header.header();
+
+    // process specifications may only be updated or deleted by their original author
+    if let Some(modified) = get_modified_revision(header)? {
+        if EntryStorage::try_from(&modified.entry).is_ok() {
+            if let Err(e) = validate_modification_author(header, &modified, &[]) {
+                return Ok(ValidateCallbackResult::Invalid(e.encode()));
+            }
+        }
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
 
 #[hdk_extern]
 fn entry_defs(_: ()) -> ExternResult<EntryDefsCallbackResult> {
@@ -42,7 +62,42 @@ fn update_process_specification(UpdateParams { process_specification }: UpdatePa
     Ok(handle_update_process_specification(PROCESS_SPECIFICATION_ENTRY_TYPE, process_specification)?)
 }
 
+#[hdk_extern]
+fn get_process_specification_revision_heads(ByAddress { address }: ByAddress<ProcessSpecificationAddress>) -> ExternResult<Vec<RevisionHash>> {
+    Ok(handle_get_process_specification_revision_heads(PROCESS_SPECIFICATION_ENTRY_TYPE, address)?)
+}
+
+#[hdk_extern]
+fn merge_process_specification_revisions(MergeRevisions { revision_id, superseded_revisions }: MergeRevisions) -> ExternResult<ResponseData> {
+    Ok(handle_merge_process_specification_revisions(PROCESS_SPECIFICATION_ENTRY_TYPE, revision_id, superseded_revisions)?)
+}
+
+#[hdk_extern]
+fn get_process_specification_history(ByAddress { address }: ByAddress<ProcessSpecificationAddress>) -> ExternResult<Vec<RevisionMeta>> {
+    Ok(handle_get_process_specification_history(PROCESS_SPECIFICATION_ENTRY_TYPE, address)?)
+}
+
+#[hdk_extern]
+fn get_process_specification_at_revision(ByHeader { address }: ByHeader) -> ExternResult<ResponseData> {
+    Ok(handle_get_process_specification_at_revision(address)?)
+}
+
+#[hdk_extern]
+fn get_process_specification_as_of(ByAddressAsOf { address, as_of }: ByAddressAsOf<ProcessSpecificationAddress>) -> ExternResult<ResponseData> {
+    Ok(handle_get_process_specification_as_of(PROCESS_SPECIFICATION_ENTRY_TYPE, address, as_of)?)
+}
+
+#[hdk_extern]
+fn diff_process_specification_revisions(ByRevisionPair { from_revision_id, to_revision_id }: ByRevisionPair) -> ExternResult<RevisionDiff> {
+    Ok(handle_diff_process_specification_revisions(from_revision_id, to_revision_id)?)
+}
+
 #[hdk_extern]
 fn delete_process_specification(ByHeader { address }: ByHeader) -> ExternResult<bool> {
     Ok(handle_delete_process_specification(address)?)
 }
+
+#[hdk_extern]
+fn export_process_specifications(params: ExportParams) -> ExternResult<ExportPage> {
+    Ok(handle_export_process_specifications(PROCESS_SPECIFICATION_ENTRY_TYPE, params)?)
+}
@@ -13,10 +13,22 @@ use hdk_records::{
         create_record,
         delete_record,
         read_record_entry,
+        read_record_entry_by_header,
         update_record,
+        read_record_revision_heads,
+        merge_record_revisions,
+        get_index_changes,
+    },
+    history::{
+        Timestamp,
+        read_record_revision_history,
+        read_record_entry_as_of,
+        diff_record_revisions,
     },
 };
+pub use hdk_records::history::{RevisionMeta, RevisionDiff};
 use hdk_semantic_indexes_client_lib::*;
+use vf_attributes_hdk::{get_scope_agents, get_scoped_agents};
 
 use hc_zome_rea_proposal_rpc::*;
 use hc_zome_rea_proposal_storage::*;
@@ -25,6 +37,10 @@ pub fn handle_create_proposal<S>(entry_def_id: S, proposal: CreateRequest) -> Re
     where S: AsRef<str>,
 {
     let (revision_id, base_address, entry_resp): (_,_, EntryData) = create_record(&entry_def_id, proposal)?;
+
+    // index scope
+    update_index!(Self(proposal(&base_address).in_scope_of(get_scope_agents(&entry_resp.in_scope_of).as_slice())))?;
+
     Ok(construct_response(&base_address, &revision_id, &entry_resp, get_link_fields(&base_address)?))
 }
 
@@ -39,11 +55,77 @@ pub fn handle_update_proposal<S>(entry_def_id: S, proposal: UpdateRequest) -> Re
     where S: AsRef<str>,
 {
     let old_revision = proposal.get_revision_id().to_owned();
-    let (revision_id, base_address, new_entry, _prev_entry): (_, ProposalAddress, EntryData, EntryData) = update_record(entry_def_id, &old_revision, proposal)?;
+    let (revision_id, base_address, new_entry, prev_entry): (_, ProposalAddress, EntryData, EntryData) = update_record(entry_def_id, &old_revision, proposal)?;
+    update_proposal_indexes(&base_address, &new_entry, &prev_entry, None)?;
+
     Ok(construct_response(&base_address, &revision_id, &new_entry, get_link_fields(&base_address)?))
 }
 
+/// Update the index links of a proposal as it moves from `prev_entry` to `new_entry`.
+/// `parent_entry` is given where `prev_entry` is a superseded revision being merged.
+///
+/// @see hdk_records::records::get_index_changes
+///
+fn update_proposal_indexes(
+    base_address: &ProposalAddress, new_entry: &EntryData, prev_entry: &EntryData, parent_entry: Option<&EntryData>,
+) -> RecordAPIResult<()> {
+    // index scope
+    let (added, removed) = get_index_changes(new_entry, prev_entry, parent_entry, |e| { get_scope_agents(&e.in_scope_of) });
+    if !(added.is_empty() && removed.is_empty()) {
+        update_index!(Self(proposal(base_address).in_scope_of(added.as_slice()).not(removed.as_slice())))?;
+    }
+    Ok(())
+}
+
+pub fn handle_get_proposal_revision_heads<S>(entry_def_id: S, address: ProposalAddress) -> RecordAPIResult<Vec<RevisionHash>>
+    where S: AsRef<str>
+{
+    read_record_revision_heads::<ProposalAddress, _>(&entry_def_id, address.as_ref())
+}
+
+pub fn handle_merge_proposal_revisions<S>(entry_def_id: S, revision_id: RevisionHash, superseded_revisions: Vec<RevisionHash>) -> RecordAPIResult<ResponseData>
+    where S: AsRef<str>
+{
+    let (_revision, base_address, entry, superseded): (_, ProposalAddress, EntryData, _) = merge_record_revisions::<EntryData, EntryStorage, _>(&revision_id, superseded_revisions.as_slice())?;
+    for (superseded_entry, parent_entry) in superseded.iter() {
+        update_proposal_indexes(&base_address, &entry, superseded_entry, Some(parent_entry))?;
+    }
+    handle_get_proposal(entry_def_id, base_address)
+}
+
+pub fn handle_get_proposal_history<S>(entry_def_id: S, address: ProposalAddress) -> RecordAPIResult<Vec<RevisionMeta>>
+    where S: AsRef<str>
+{
+    read_record_revision_history::<ProposalAddress, _>(&entry_def_id, address.as_ref())
+}
+
+pub fn handle_get_proposal_at_revision(revision_id: RevisionHash) -> RecordAPIResult<ResponseData>
+{
+    let (base_address, entry) = read_record_entry_by_header::<EntryData, EntryStorage, _>(&revision_id)?;
+    Ok(construct_response(&base_address, &revision_id, &entry, get_link_fields(&base_address)?))
+}
+
+pub fn handle_get_proposal_as_of<S>(entry_def_id: S, address: ProposalAddress, as_of: Timestamp) -> RecordAPIResult<ResponseData>
+    where S: AsRef<str>
+{
+    let (revision, base_address, entry) = read_record_entry_as_of::<EntryData, EntryStorage, _,_>(&entry_def_id, address.as_ref(), &as_of)?;
+    // link fields are omitted, since indexes do not retain their history
+    Ok(construct_response(&base_address, &revision, &entry, Default::default()))
+}
+
+pub fn handle_diff_proposal_revisions(from_revision_id: RevisionHash, to_revision_id: RevisionHash) -> RecordAPIResult<RevisionDiff>
+{
+    diff_record_revisions::<EntryData, EntryStorage, ProposalAddress>(&from_revision_id, &to_revision_id)
+}
+
 pub fn handle_delete_proposal(address: RevisionHash) -> RecordAPIResult<bool> {
+    // load the record to ensure it is of the correct type
+    let (base_address, entry) = read_record_entry_by_header::<EntryData, EntryStorage, _>(&address)?;
+
+    // handle link fields
+    update_index!(Self(proposal(&base_address).in_scope_of.not(get_scope_agents(&entry.in_scope_of).as_slice())))?;
+
+    // delete entry last, as it must be present in order for links to be removed
     delete_record::<EntryStorage,_>(&address)
 }
 
@@ -68,7 +150,7 @@ fn construct_response<'a>(
             unit_based: e.unit_based.to_owned(),
             created: e.created.to_owned(),
             note: e.note.to_owned(),
-            in_scope_of: e.in_scope_of.to_owned(),
+            in_scope_of: get_scoped_agents(&e.in_scope_of),
             // link fields
             publishes: publishes.to_owned(),
             published_to: published_to.to_owned(),
@@ -9,8 +9,9 @@
 use holochain_serialized_bytes::prelude::*;
 use serde_maybe_undefined::MaybeUndefined;
 pub use vf_attributes_hdk::{
-    RevisionHash, ByAddress, ByHeader,
+    RevisionHash, ByAddress, ByHeader, MergeRevisions, ByAddressAsOf, ByRevisionPair,
     ProposalAddress, ProposedIntentAddress, ProposedToAddress, Timestamp,
+    AgentAddress, AgentRecordAddress,
 };
 
 /// Toplevel I/O structs for WASM API
@@ -46,7 +47,7 @@ pub struct Response {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub note: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub in_scope_of: Option<Vec<String>>,
+    pub in_scope_of: Option<Vec<AgentAddress>>,
     // links:
     #[serde(default)]
     #[serde(skip_serializing_if = "Vec::is_empty")]
@@ -86,7 +87,7 @@ pub struct CreateRequest {
     #[serde(default)]
     pub note: MaybeUndefined<String>,
     #[serde(default)]
-    pub in_scope_of: MaybeUndefined<Vec<String>>,
+    pub in_scope_of: MaybeUndefined<Vec<AgentAddress>>,
 }
 
 impl<'a> CreateRequest {
@@ -112,7 +113,7 @@ pub struct UpdateRequest {
     #[serde(default)]
     pub note: MaybeUndefined<String>,
     #[serde(default)]
-    pub in_scope_of: MaybeUndefined<Vec<String>>,
+    pub in_scope_of: MaybeUndefined<Vec<AgentAddress>>,
 }
 
 impl<'a> UpdateRequest {
@@ -130,4 +131,5 @@ impl<'a> UpdateRequest {
 pub struct QueryParams {
     pub publishes: Option<ProposedIntentAddress>,
     pub published_to: Option<ProposedToAddress>,
+    pub in_scope_of: Option<AgentRecordAddress>,
 }
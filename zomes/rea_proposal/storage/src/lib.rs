@@ -13,7 +13,8 @@ use hdk_records::{
     generate_record_entry,
 };
 
-pub use vf_attributes_hdk::{ ProposalAddress, ProposedIntentAddress, ProposedToAddress, Timestamp };
+pub use vf_attributes_hdk::{ ProposalAddress, ProposedIntentAddress, ProposedToAddress, Timestamp, AgentAddress };
+use vf_attributes_hdk::{ScopeReference, get_scoped_agents, update_scope_references};
 
 use hc_zome_rea_proposal_rpc::{CreateRequest, UpdateRequest};
 
@@ -40,12 +41,22 @@ pub struct EntryData {
     pub unit_based: Option<bool>,
     pub created: Option<Timestamp>,
     pub note: Option<String>,
-    pub in_scope_of: Option<Vec<String>>,
+    #[serde(default)]
+    pub in_scope_of: Option<Vec<ScopeReference>>,
     //[TODO]:
     //eligibleLocation: SpatialThing
     //publishes: [ProposedIntent!]
 }
 
+impl EntryData {
+    /// Agents other than the original author who may update or delete this proposal:
+    /// any agents it is in the scope of.
+    ///
+    pub fn get_authorised_agents(&self) -> Vec<AgentPubKey> {
+        get_scoped_agents(&self.in_scope_of).iter().flatten().map(|a| { a.1.to_owned() }).collect()
+    }
+}
+
 generate_record_entry!(EntryData, ProposalAddress, EntryStorage);
 
 //---------------- CREATE ----------------
@@ -60,7 +71,7 @@ impl From<CreateRequest> for EntryData {
             unit_based: e.unit_based.into(),
             created: e.created.into(),
             note: e.note.into(),
-            in_scope_of: e.in_scope_of.to_option(),
+            in_scope_of: update_scope_references(&None, e.in_scope_of.to_option()),
         }
     }
 }
@@ -100,7 +111,7 @@ impl Updateable<UpdateRequest> for EntryData {
             in_scope_of: if e.in_scope_of == MaybeUndefined::Undefined {
                 self.in_scope_of.to_owned()
             } else {
-                e.in_scope_of.to_owned().to_option()
+                update_scope_references(&self.in_scope_of, e.in_scope_of.to_owned().to_option())
             },
         }
     }
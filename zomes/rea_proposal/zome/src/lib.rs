@@ -12,6 +12,27 @@ use hdk::prelude::*;
 use hc_zome_rea_proposal_lib::*;
 use hc_zome_rea_proposal_rpc::*;
 use hc_zome_rea_proposal_storage_consts::*;
+use hc_zome_rea_proposal_storage::EntryStorage;
+use hdk_records::record_interface::Identified;
+use hdk_records::validation::{get_modified_revision, validate_modification_author};
+
+#[hdk_extern]
+fn validate(validation_data: ValidateData) -> ExternResult<ValidateCallbackResult> {
+    let element = validation_data.element;
+    let (signed_header, _entry) = element.into_inner();
+    let header = signed_header.header();
+
+    // updates & deletes may only be made by the original author or agents the previous revision is in the scope of
+    if let Some(modified) = get_modified_revision(header)? {
+        if let Ok(previous) = EntryStorage::try_from(&modified.entry) {
+            if let Err(e) = validate_modification_author(header, &modified, &previous.entry().get_authorised_agents()) {
+                return Ok(ValidateCallbackResult::Invalid(e.encode()));
+            }
+        }
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
 
 #[hdk_extern]
 fn entry_defs(_: ()) -> ExternResult<EntryDefsCallbackResult> {
@@ -42,6 +63,36 @@ fn update_proposal(UpdateParams { proposal }: UpdateParams) -> ExternResult<Resp
     Ok(handle_update_proposal(PROPOSAL_ENTRY_TYPE, proposal)?)
 }
 
+#[hdk_extern]
+fn get_proposal_revision_heads(ByAddress { address }: ByAddress<ProposalAddress>) -> ExternResult<Vec<RevisionHash>> {
+    Ok(handle_get_proposal_revision_heads(PROPOSAL_ENTRY_TYPE, address)?)
+}
+
+#[hdk_extern]
+fn merge_proposal_revisions(MergeRevisions { revision_id, superseded_revisions }: MergeRevisions) -> ExternResult<ResponseData> {
+    Ok(handle_merge_proposal_revisions(PROPOSAL_ENTRY_TYPE, revision_id, superseded_revisions)?)
+}
+
+#[hdk_extern]
+fn get_proposal_history(ByAddress { address }: ByAddress<ProposalAddress>) -> ExternResult<Vec<RevisionMeta>> {
+    Ok(handle_get_proposal_history(PROPOSAL_ENTRY_TYPE, address)?)
+}
+
+#[hdk_extern]
+fn get_proposal_at_revision(ByHeader { address }: ByHeader) -> ExternResult<ResponseData> {
+    Ok(handle_get_proposal_at_revision(address)?)
+}
+
+#[hdk_extern]
+fn get_proposal_as_of(ByAddressAsOf { address, as_of }: ByAddressAsOf<ProposalAddress>) -> ExternResult<ResponseData> {
+    Ok(handle_get_proposal_as_of(PROPOSAL_ENTRY_TYPE, address, as_of)?)
+}
+
+#[hdk_extern]
+fn diff_proposal_revisions(ByRevisionPair { from_revision_id, to_revision_id }: ByRevisionPair) -> ExternResult<RevisionDiff> {
+    Ok(handle_diff_proposal_revisions(from_revision_id, to_revision_id)?)
+}
+
 #[hdk_extern]
 fn delete_proposal(ByHeader { address }: ByHeader) -> ExternResult<bool> {
     Ok(handle_delete_proposal(address)?)
@@ -12,4 +12,5 @@ use hdk_semantic_indexes_zome_lib::ByAddress; // disambiguate from RPC query str
 struct Proposal {
     publishes: Local<proposed_intent, published_in>,
     published_to: Local<proposed_to, proposed>,
+    in_scope_of: Local<agent_record, proposals_in_scope>,
 }
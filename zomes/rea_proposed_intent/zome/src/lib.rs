@@ -12,6 +12,26 @@ use hdk::prelude::*;
 use hc_zome_rea_proposed_intent_lib::*;
 use hc_zome_rea_proposed_intent_rpc::*;
 use hc_zome_rea_proposed_intent_storage_consts::*;
+use hc_zome_rea_proposed_intent_storage::EntryStorage;
+use hdk_records::validation::{get_modified_revision, validate_modification_author};
+
+#[hdk_extern]
+fn validate(validation_data: ValidateData) -> ExternResult<ValidateCallbackResult> {
+    let element = validation_data.element;
+    let (signed_header, _entry) = element.into_inner();
+    let header = signed_header.header();
+
+    // deletes may only be made by the original author
+    if let Some(modified) = get_modified_revision(header)? {
+        if EntryStorage::try_from(&modified.entry).is_ok() {
+            if let Err(e) = validate_modification_author(header, &modified, &[]) {
+                return Ok(ValidateCallbackResult::Invalid(e.encode()));
+            }
+        }
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
 
 #[hdk_extern]
 fn entry_defs(_: ()) -> ExternResult<EntryDefsCallbackResult> {
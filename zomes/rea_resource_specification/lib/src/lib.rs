@@ -11,10 +11,23 @@ use hdk_records::{
     records::{
         create_record,
         read_record_entry,
+        read_record_entry_by_header,
         update_record,
+        read_record_revision_heads,
+        merge_record_revisions,
         delete_record,
     },
+    history::{
+        Timestamp,
+        read_record_revision_history,
+        read_record_entry_as_of,
+        diff_record_revisions,
+    },
+    local_indexes::query_root_index_page,
 };
+pub use hdk_records::history::{RevisionMeta, RevisionDiff};
+pub use vf_jsonld::{ExportParams, ExportPage};
+use vf_jsonld::{JsonLdNode, build_export_page};
 
 use vf_attributes_hdk::{
     EconomicResourceAddress,
@@ -46,11 +59,62 @@ pub fn handle_update_resource_specification<S>(entry_def_id: S, resource_specifi
     Ok(construct_response(&base_address, &revision_id, &new_entry, get_link_fields(&base_address)?))
 }
 
+pub fn handle_get_resource_specification_revision_heads<S>(entry_def_id: S, address: ResourceSpecificationAddress) -> RecordAPIResult<Vec<RevisionHash>>
+    where S: AsRef<str>
+{
+    read_record_revision_heads::<ResourceSpecificationAddress, _>(&entry_def_id, address.as_ref())
+}
+
+pub fn handle_merge_resource_specification_revisions<S>(entry_def_id: S, revision_id: RevisionHash, superseded_revisions: Vec<RevisionHash>) -> RecordAPIResult<ResponseData>
+    where S: AsRef<str>
+{
+    let (_revision, base_address, _entry, _superseded): (_, ResourceSpecificationAddress, EntryData, _) = merge_record_revisions::<EntryData, EntryStorage, _>(&revision_id, superseded_revisions.as_slice())?;
+    handle_get_resource_specification(entry_def_id, base_address)
+}
+
+pub fn handle_get_resource_specification_history<S>(entry_def_id: S, address: ResourceSpecificationAddress) -> RecordAPIResult<Vec<RevisionMeta>>
+    where S: AsRef<str>
+{
+    read_record_revision_history::<ResourceSpecificationAddress, _>(&entry_def_id, address.as_ref())
+}
+
+pub fn handle_get_resource_specification_at_revision(revision_id: RevisionHash) -> RecordAPIResult<ResponseData>
+{
+    let (base_address, entry) = read_record_entry_by_header::<EntryData, EntryStorage, _>(&revision_id)?;
+    Ok(construct_response(&base_address, &revision_id, &entry, get_link_fields(&base_address)?))
+}
+
+pub fn handle_get_resource_specification_as_of<S>(entry_def_id: S, address: ResourceSpecificationAddress, as_of: Timestamp) -> RecordAPIResult<ResponseData>
+    where S: AsRef<str>
+{
+    let (revision, base_address, entry) = read_record_entry_as_of::<EntryData, EntryStorage, _,_>(&entry_def_id, address.as_ref(), &as_of)?;
+    // link fields are omitted, since indexes do not retain their history
+    Ok(construct_response(&base_address, &revision, &entry, Default::default()))
+}
+
+pub fn handle_diff_resource_specification_revisions(from_revision_id: RevisionHash, to_revision_id: RevisionHash) -> RecordAPIResult<RevisionDiff>
+{
+    diff_record_revisions::<EntryData, EntryStorage, ResourceSpecificationAddress>(&from_revision_id, &to_revision_id)
+}
+
 pub fn handle_delete_resource_specification(revision_id: RevisionHash) -> RecordAPIResult<bool>
 {
     delete_record::<EntryStorage, _>(&revision_id)
 }
 
+/// Export a page of all resource specifications in the DNA as ValueFlows JSON-LD
+///
+pub fn handle_export_resource_specifications<S>(entry_def_id: S, params: ExportParams) -> RecordAPIResult<ExportPage>
+    where S: AsRef<str>
+{
+    let limit = params.get_limit();
+    let page = query_root_index_page::<EntryData, EntryStorage, _,_>(&entry_def_id, &params.after, limit)?;
+    build_export_page(page, limit, |(revision, base_address, entry)| {
+        let link_fields = get_link_fields(&base_address)?;
+        Ok(construct_jsonld(&construct_response(&base_address, &revision, &entry, link_fields).resource_specification))
+    })
+}
+
 /// Create response from input DHT primitives
 fn construct_response<'a>(
     address: &ResourceSpecificationAddress,
@@ -72,12 +136,26 @@ fn construct_response<'a>(
             image: e.image.to_owned(),
             note: e.note.to_owned(),
             default_unit_of_effort: e.default_unit_of_effort.to_owned(),
+            unit_price: e.unit_price.to_owned(),
+            ledger_mode: e.ledger_mode,
+            default_credit_limit: e.default_credit_limit.to_owned(),
+            negative_inventory_policy: e.negative_inventory_policy.to_owned(),
 
             // conforming_resources: conforming_resources.map(Cow::into_owned),
         }
     }
 }
 
+/// Create a ValueFlows JSON-LD node from an output record
+///
+pub fn construct_jsonld(r: &Response) -> JsonLdNode {
+    JsonLdNode::new("ResourceSpecification", &r.id)
+        .literal("name", &r.name)
+        .reference("image", &r.image)
+        .reference("defaultUnitOfEffort", &r.default_unit_of_effort)
+        .field("note", &r.note)
+}
+
 // @see construct_response
 fn get_link_fields(_address: &ResourceSpecificationAddress) -> RecordAPIResult<(
     Vec<EconomicResourceAddress>,
@@ -7,13 +7,18 @@
  * @package Holo-REA
  */
 use holochain_serialized_bytes::prelude::*;
-use serde_maybe_undefined::MaybeUndefined;
+use serde_maybe_undefined::{
+    MaybeUndefined,
+    default_false,
+};
+use vf_measurement::QuantityValue;
 pub use vf_attributes_hdk::{
-    RevisionHash, ByAddress, ByHeader,
+    RevisionHash, ByAddress, ByHeader, MergeRevisions, ByAddressAsOf, ByRevisionPair,
     ResourceSpecificationAddress,
     EconomicResourceAddress,
     ExternalURL,
     UnitId,
+    NegativeInventoryPolicy,
 };
 
 // toplevel I/O structs for WASM API
@@ -44,6 +49,17 @@ pub struct Response {
     pub note: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub default_unit_of_effort: Option<UnitId>,
+    // price per unit of conforming resources, expressed as a quantity of some currency
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unit_price: Option<QuantityValue>,
+    // whether conforming resources are mutual credit balances, subject to credit limits
+    pub ledger_mode: bool,
+    // credit limit assigned to new balances in ledger mode. Zero where unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_credit_limit: Option<QuantityValue>,
+    // overrides the DNA's policy for events drawing conforming resources below zero
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub negative_inventory_policy: Option<NegativeInventoryPolicy>,
 }
 
 /// I/O struct to describe what is returned outside the gateway.
@@ -70,6 +86,14 @@ pub struct CreateRequest {
     pub note: MaybeUndefined<String>,
     #[serde(default)]
     pub default_unit_of_effort: MaybeUndefined<UnitId>,
+    #[serde(default)]
+    pub unit_price: MaybeUndefined<QuantityValue>,
+    #[serde(default = "default_false")]
+    pub ledger_mode: MaybeUndefined<bool>,
+    #[serde(default)]
+    pub default_credit_limit: MaybeUndefined<QuantityValue>,
+    #[serde(default)]
+    pub negative_inventory_policy: MaybeUndefined<NegativeInventoryPolicy>,
 }
 
 impl<'a> CreateRequest {
@@ -92,6 +116,14 @@ pub struct UpdateRequest {
     pub note: MaybeUndefined<String>,
     #[serde(default)]
     pub default_unit_of_effort: MaybeUndefined<UnitId>,
+    #[serde(default)]
+    pub unit_price: MaybeUndefined<QuantityValue>,
+    #[serde(default)]
+    pub ledger_mode: MaybeUndefined<bool>,
+    #[serde(default)]
+    pub default_credit_limit: MaybeUndefined<QuantityValue>,
+    #[serde(default)]
+    pub negative_inventory_policy: MaybeUndefined<NegativeInventoryPolicy>,
 }
 
 impl<'a> UpdateRequest {
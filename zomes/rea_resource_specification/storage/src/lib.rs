@@ -16,8 +16,11 @@ use hdk_records::{
 use vf_attributes_hdk::{
     ExternalURL,
     UnitId,
+    NegativeInventoryPolicy,
 };
 
+use vf_measurement::QuantityValue;
+
 use hc_zome_rea_resource_specification_rpc::{CreateRequest, ResourceSpecificationAddress, UpdateRequest};
 
 //---------------- RECORD INTERNALS & VALIDATION ----------------
@@ -28,6 +31,14 @@ pub struct EntryData {
     pub image: Option<ExternalURL>,
     pub note: Option<String>,
     pub default_unit_of_effort: Option<UnitId>,
+    #[serde(default)]
+    pub unit_price: Option<QuantityValue>,
+    #[serde(default)]
+    pub ledger_mode: bool,
+    #[serde(default)]
+    pub default_credit_limit: Option<QuantityValue>,
+    #[serde(default)]
+    pub negative_inventory_policy: Option<NegativeInventoryPolicy>,
 }
 
 generate_record_entry!(EntryData, ResourceSpecificationAddress, EntryStorage);
@@ -42,6 +53,10 @@ impl From<CreateRequest> for EntryData {
             image: e.image.into(),
             note: e.note.into(),
             default_unit_of_effort: e.default_unit_of_effort.into(),
+            unit_price: e.unit_price.into(),
+            ledger_mode: e.ledger_mode.to_option().unwrap_or(false),
+            default_credit_limit: e.default_credit_limit.into(),
+            negative_inventory_policy: e.negative_inventory_policy.into(),
         }
     }
 }
@@ -56,6 +71,10 @@ impl Updateable<UpdateRequest> for EntryData {
             image: if e.image.is_undefined() { self.image.to_owned() } else { e.image.to_owned().into() },
             note: if e.note.is_undefined() { self.note.to_owned() } else { e.note.to_owned().into() },
             default_unit_of_effort: if e.default_unit_of_effort.is_undefined() { self.default_unit_of_effort.to_owned() } else { e.default_unit_of_effort.to_owned().into() },
+            unit_price: if e.unit_price.is_undefined() { self.unit_price.to_owned() } else { e.unit_price.to_owned().into() },
+            ledger_mode: if e.ledger_mode.is_undefined() { self.ledger_mode } else { e.ledger_mode.to_owned().to_option().unwrap_or(false) },
+            default_credit_limit: if e.default_credit_limit.is_undefined() { self.default_credit_limit.to_owned() } else { e.default_credit_limit.to_owned().into() },
+            negative_inventory_policy: if e.negative_inventory_policy.is_undefined() { self.negative_inventory_policy.to_owned() } else { e.negative_inventory_policy.to_owned().into() },
         }
     }
 }
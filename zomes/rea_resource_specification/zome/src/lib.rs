@@ -12,6 +12,26 @@ use hdk::prelude::*;
 use hc_zome_rea_resource_specification_rpc::*;
 use hc_zome_rea_resource_specification_lib::*;
 use hc_zome_rea_resource_specification_storage_consts::*;
+use hc_zome_rea_resource_specification_storage::EntryStorage;
+use hdk_records::validation::{get_modified_revision, validate_modification_author};
+
+#[hdk_extern]
+fn validate(validation_data: ValidateData) -> ExternResult<ValidateCallbackResult> {
+    let element = validation_data.element;
+    let (signed_header, _entry) = element.into_inner();
+    let header = signed_header.header();
+
+    // resource specifications may only be updated or deleted by their original author
+    if let Some(modified) = get_modified_revision(header)? {
+        if EntryStorage::try_from(&modified.entry).is_ok() {
+            if let Err(e) = validate_modification_author(header, &modified, &[]) {
+                return Ok(ValidateCallbackResult::Invalid(e.encode()));
+            }
+        }
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
 
 #[hdk_extern]
 fn entry_defs(_: ()) -> ExternResult<EntryDefsCallbackResult> {
@@ -42,7 +62,42 @@ fn update_resource_specification(UpdateParams { resource_specification }: Update
     Ok(handle_update_resource_specification(ECONOMIC_RESOURCE_SPECIFICATION_ENTRY_TYPE, resource_specification)?)
 }
 
+#[hdk_extern]
+fn get_resource_specification_revision_heads(ByAddress { address }: ByAddress<ResourceSpecificationAddress>) -> ExternResult<Vec<RevisionHash>> {
+    Ok(handle_get_resource_specification_revision_heads(RESOURCE_SPECIFICATION_ENTRY_TYPE, address)?)
+}
+
+#[hdk_extern]
+fn merge_resource_specification_revisions(MergeRevisions { revision_id, superseded_revisions }: MergeRevisions) -> ExternResult<ResponseData> {
+    Ok(handle_merge_resource_specification_revisions(RESOURCE_SPECIFICATION_ENTRY_TYPE, revision_id, superseded_revisions)?)
+}
+
+#[hdk_extern]
+fn get_resource_specification_history(ByAddress { address }: ByAddress<ResourceSpecificationAddress>) -> ExternResult<Vec<RevisionMeta>> {
+    Ok(handle_get_resource_specification_history(RESOURCE_SPECIFICATION_ENTRY_TYPE, address)?)
+}
+
+#[hdk_extern]
+fn get_resource_specification_at_revision(ByHeader { address }: ByHeader) -> ExternResult<ResponseData> {
+    Ok(handle_get_resource_specification_at_revision(address)?)
+}
+
+#[hdk_extern]
+fn get_resource_specification_as_of(ByAddressAsOf { address, as_of }: ByAddressAsOf<ResourceSpecificationAddress>) -> ExternResult<ResponseData> {
+    Ok(handle_get_resource_specification_as_of(RESOURCE_SPECIFICATION_ENTRY_TYPE, address, as_of)?)
+}
+
+#[hdk_extern]
+fn diff_resource_specification_revisions(ByRevisionPair { from_revision_id, to_revision_id }: ByRevisionPair) -> ExternResult<RevisionDiff> {
+    Ok(handle_diff_resource_specification_revisions(from_revision_id, to_revision_id)?)
+}
+
 #[hdk_extern]
 fn delete_resource_specification(ByHeader { address }: ByHeader) -> ExternResult<bool> {
     Ok(handle_delete_resource_specification(address)?)
 }
+
+#[hdk_extern]
+fn export_resource_specifications(params: ExportParams) -> ExternResult<ExportPage> {
+    Ok(handle_export_resource_specifications(ECONOMIC_RESOURCE_SPECIFICATION_ENTRY_TYPE, params)?)
+}
@@ -25,6 +25,7 @@ pub fn construct_response(address: &SatisfactionAddress, revision_id: &RevisionH
             resource_quantity: e.resource_quantity.to_owned(),
             effort_quantity: e.effort_quantity.to_owned(),
             note: e.note.to_owned(),
-        }
+        },
+        replication: vec![],
     })
 }
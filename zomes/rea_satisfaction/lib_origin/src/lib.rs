@@ -13,16 +13,32 @@ use paste::paste;
 use hdk::prelude::*;
 use crate::holo_hash::DnaHash;
 use hdk_records::{
-    RecordAPIResult,
+    RecordAPIResult, DataIntegrityError,
     records::{
         create_record,
         read_record_entry,
         read_record_entry_by_header,
         update_record,
+        read_record_revision_heads,
+        merge_record_revisions,
+        get_index_changes,
         delete_record,
     },
+    history::{
+        Timestamp,
+        read_record_revision_history,
+        read_record_entry_as_of,
+        diff_record_revisions,
+    },
     rpc::call_zome_method,
+    outbox::{
+        queue_replication,
+        deliver_replications,
+        read_replication_status,
+    },
 };
+pub use hdk_records::history::{RevisionMeta, RevisionDiff};
+pub use hdk_records::outbox::{ReplicationRequest, ReplicationAttempt};
 use hdk_semantic_indexes_client_lib::*;
 
 use hc_zome_rea_satisfaction_storage_consts::*;
@@ -39,21 +55,26 @@ pub fn handle_create_satisfaction<S>(entry_def_id: S, satisfaction: CreateReques
     create_index!(Local(satisfaction.satisfies(satisfaction.get_satisfies()), intent.satisfied_by(&satisfaction_address)))?;
 
     // link entries which may be local or remote
+    let identity_hash: &EntryHash = satisfaction_address.as_ref();
     let event_or_commitment = satisfaction.get_satisfied_by();
     if is_satisfiedby_local_commitment(event_or_commitment)? {
         // links to local commitment, create link index pair
         create_index!(Local(satisfaction.satisfied_by(event_or_commitment), commitment.satisfies(&satisfaction_address)))?;
     } else {
-        // links to remote event, ping associated foreign DNA & fail if there's an error
+        // links to remote event, queue the write for the associated foreign DNA
         // :TODO: consider the implications of this in loosely coordinated multi-network spaces
-        call_zome_method(
+        queue_replication(
+            identity_hash,
             event_or_commitment,
             &REPLICATE_CREATE_API_METHOD,
             CreateParams { satisfaction: satisfaction.to_owned() },
         )?;
     }
+    let replication = replicate_satisfactions(identity_hash)?;
 
-    construct_response(&satisfaction_address, &revision_id, &entry_resp)
+    let mut response = construct_response(&satisfaction_address, &revision_id, &entry_resp)?;
+    response.replication = replication;
+    Ok(response)
 }
 
 pub fn handle_get_satisfaction<S>(entry_def_id: S, address: SatisfactionAddress) -> RecordAPIResult<ResponseData>
@@ -79,6 +100,7 @@ pub fn handle_update_satisfaction<S>(entry_def_id: S, satisfaction: UpdateReques
     }
 
     // update commitment / event indexes in local and/or remote DNA
+    let identity_hash: &EntryHash = base_address.as_ref();
     if new_entry.satisfied_by != prev_entry.satisfied_by {
         let new_dna: &DnaHash = new_entry.satisfied_by.as_ref();
         let prev_dna: &DnaHash = prev_entry.satisfied_by.as_ref();
@@ -95,7 +117,8 @@ pub fn handle_update_satisfaction<S>(entry_def_id: S, satisfaction: UpdateReques
                 ))?;
             } else {
                 // both values were remote and in the same DNA, forward the update
-                call_zome_method(
+                queue_replication(
+                    identity_hash,
                     &prev_entry.satisfied_by,
                     &REPLICATE_UPDATE_API_METHOD,
                     UpdateParams { satisfaction: satisfaction.to_owned() },
@@ -107,7 +130,8 @@ pub fn handle_update_satisfaction<S>(entry_def_id: S, satisfaction: UpdateReques
                 update_index!(Local(satisfaction.satisfied_by.not(&vec![prev_entry.satisfied_by]), commitment.satisfies(&base_address)))?;
             } else {
                 // previous value was remote, handle the remote update as a deletion
-                call_zome_method(
+                queue_replication(
+                    identity_hash,
                     &prev_entry.satisfied_by,
                     &REPLICATE_DELETE_API_METHOD,
                     ByHeader { address: satisfaction.get_revision_id().to_owned() },
@@ -119,7 +143,8 @@ pub fn handle_update_satisfaction<S>(entry_def_id: S, satisfaction: UpdateReques
                 update_index!(Local(satisfaction.satisfied_by(&vec![new_entry.satisfied_by.to_owned()]), commitment.satisfies(&base_address)))?;
             } else {
                 // new value was remote, handle the remote update as a creation
-                call_zome_method(
+                queue_replication(
+                    identity_hash,
                     &new_entry.satisfied_by,
                     &REPLICATE_CREATE_API_METHOD,
                     CreateParams { satisfaction: CreateRequest {
@@ -132,11 +157,72 @@ pub fn handle_update_satisfaction<S>(entry_def_id: S, satisfaction: UpdateReques
                 )?;
             }
         }
+    }
+    let replication = replicate_satisfactions(identity_hash)?;
+
+    let mut response = construct_response(&base_address, &revision_id, &new_entry)?;
+    response.replication = replication;
+    Ok(response)
+}
+
+pub fn handle_get_satisfaction_revision_heads<S>(entry_def_id: S, address: SatisfactionAddress) -> RecordAPIResult<Vec<RevisionHash>>
+    where S: AsRef<str>
+{
+    read_record_revision_heads::<SatisfactionAddress, _>(&entry_def_id, address.as_ref())
+}
+
+pub fn handle_merge_satisfaction_revisions<S>(entry_def_id: S, revision_id: RevisionHash, superseded_revisions: Vec<RevisionHash>) -> RecordAPIResult<ResponseData>
+    where S: AsRef<str>
+{
+    let (_revision, base_address, entry, superseded): (_, SatisfactionAddress, EntryData, _) = merge_record_revisions::<EntryData, EntryStorage, _>(&revision_id, superseded_revisions.as_slice())?;
 
-        // :TODO: ensure correct number of operations succeeded
+    // reconcile intent & commitment indexes in local DNA with the chosen revision
+    // :TODO: replicated copies of superseded revisions in the observation DNA are not yet reconciled
+    for (superseded_entry, parent_entry) in superseded.iter() {
+        let (added, removed) = get_index_changes(&entry, superseded_entry, Some(parent_entry), |e| { vec![e.satisfies.to_owned()] });
+        if !(added.is_empty() && removed.is_empty()) {
+            update_index!(Local(satisfaction.satisfies(&added).not(&removed), intent.satisfied_by(&base_address)))?;
+        }
+
+        let (added, removed) = get_index_changes(&entry, superseded_entry, Some(parent_entry), |e| { vec![e.satisfied_by.to_owned()] });
+        let mut local_added = vec![];
+        for a in added.into_iter() {
+            if is_satisfiedby_local_commitment(&a)? { local_added.push(a); }
+        }
+        let mut local_removed = vec![];
+        for r in removed.into_iter() {
+            if is_satisfiedby_local_commitment(&r)? { local_removed.push(r); }
+        }
+        if !(local_added.is_empty() && local_removed.is_empty()) {
+            update_index!(Local(satisfaction.satisfied_by(&local_added).not(&local_removed), commitment.satisfies(&base_address)))?;
+        }
     }
 
-    construct_response(&base_address, &revision_id, &new_entry)
+    handle_get_satisfaction(entry_def_id, base_address)
+}
+
+pub fn handle_get_satisfaction_history<S>(entry_def_id: S, address: SatisfactionAddress) -> RecordAPIResult<Vec<RevisionMeta>>
+    where S: AsRef<str>
+{
+    read_record_revision_history::<SatisfactionAddress, _>(&entry_def_id, address.as_ref())
+}
+
+pub fn handle_get_satisfaction_at_revision(revision_id: RevisionHash) -> RecordAPIResult<ResponseData>
+{
+    let (base_address, entry) = read_record_entry_by_header::<EntryData, EntryStorage, _>(&revision_id)?;
+    construct_response(&base_address, &revision_id, &entry)
+}
+
+pub fn handle_get_satisfaction_as_of<S>(entry_def_id: S, address: SatisfactionAddress, as_of: Timestamp) -> RecordAPIResult<ResponseData>
+    where S: AsRef<str>
+{
+    let (revision, base_address, entry) = read_record_entry_as_of::<EntryData, EntryStorage, _,_>(&entry_def_id, address.as_ref(), &as_of)?;
+    construct_response(&base_address, &revision, &entry)
+}
+
+pub fn handle_diff_satisfaction_revisions(from_revision_id: RevisionHash, to_revision_id: RevisionHash) -> RecordAPIResult<RevisionDiff>
+{
+    diff_record_revisions::<EntryData, EntryStorage, SatisfactionAddress>(&from_revision_id, &to_revision_id)
 }
 
 pub fn handle_delete_satisfaction(revision_id: RevisionHash) -> RecordAPIResult<bool>
@@ -147,20 +233,64 @@ pub fn handle_delete_satisfaction(revision_id: RevisionHash) -> RecordAPIResult<
     update_index!(Local(satisfaction.satisfies.not(&vec![entry.satisfies]), intent.satisfied_by(&base_address)))?;
 
     // update commitment & event indexes in local or remote DNAs
+    let identity_hash: &EntryHash = base_address.as_ref();
     let event_or_commitment = entry.satisfied_by.to_owned();
     if is_satisfiedby_local_commitment(&event_or_commitment)? {
         update_index!(Local(satisfaction.satisfied_by.not(&vec![entry.satisfied_by]), commitment.satisfies(&base_address)))?;
     } else {
-        // links to remote event, ping associated foreign DNA & fail if there's an error
-        // :TODO: consider the implications of this in loosely coordinated multi-network spaces
-        call_zome_method(
+        // links to remote event, queue the write for the associated foreign DNA
+        // :NOTE: delivery failures are not returned here, query `get_satisfaction_replication_status` to inspect them
+        queue_replication(
+            identity_hash,
             &event_or_commitment,
             &REPLICATE_DELETE_API_METHOD,
             ByHeader { address: revision_id.to_owned() },
         )?;
     }
 
-    delete_record::<EntryStorage, _>(&revision_id)
+    let deleted = delete_record::<EntryStorage, _>(&revision_id)?;
+    replicate_satisfactions(identity_hash)?;
+    Ok(deleted)
+}
+
+/// Read the state of writes replicated to the observation DNA, optionally only those for a single satisfaction.
+///
+pub fn handle_get_satisfaction_replication_status(address: Option<SatisfactionAddress>) -> RecordAPIResult<Vec<ReplicationStatus>>
+{
+    let identity_hash: Option<&EntryHash> = address.as_ref().map(|a| { a.as_ref() });
+    read_replication_status(identity_hash)
+}
+
+/// Retry sending any writes which could not previously be replicated to the observation DNA.
+///
+pub fn handle_retry_satisfaction_replications() -> RecordAPIResult<Vec<ReplicationStatus>>
+{
+    deliver_replications(deliver_satisfaction_replication)
+}
+
+/// Send all pending writes to the observation DNA, returning the status of those for the given record.
+///
+fn replicate_satisfactions(record: &EntryHash) -> RecordAPIResult<Vec<ReplicationStatus>> {
+    Ok(deliver_replications(deliver_satisfaction_replication)?
+        .into_iter()
+        .filter(|status| { status.record == *record })
+        .collect())
+}
+
+fn deliver_satisfaction_replication(request: &ReplicationRequest) -> RecordAPIResult<()> {
+    match request.method.as_str() {
+        REPLICATE_CREATE_API_METHOD => {
+            let _pingback: ResponseData = call_zome_method(request, &request.method, request.decode_payload::<CreateParams>()?)?;
+        },
+        REPLICATE_UPDATE_API_METHOD => {
+            let _pingback: ResponseData = call_zome_method(request, &request.method, request.decode_payload::<UpdateParams>()?)?;
+        },
+        REPLICATE_DELETE_API_METHOD => {
+            let _pingback: bool = call_zome_method(request, &request.method, request.decode_payload::<ByHeader>()?)?;
+        },
+        _ => return Err(DataIntegrityError::RemoteRequestError(request.method.to_owned())),
+    }
+    Ok(())
 }
 
 fn is_satisfiedby_local_commitment(event_or_commitment: &EventOrCommitmentAddress) -> RecordAPIResult<bool> {
@@ -11,13 +11,14 @@ use holochain_serialized_bytes::prelude::*;
 use serde_maybe_undefined::{MaybeUndefined};
 use vf_measurement::QuantityValue;
 pub use vf_attributes_hdk::{
-    RevisionHash, ByHeader, ByAddress,
+    RevisionHash, ByHeader, ByAddress, MergeRevisions, ByAddressAsOf, ByRevisionPair,
     SatisfactionAddress,
     EventOrCommitmentAddress,
     EconomicEventAddress,
     CommitmentAddress,
     IntentAddress,
 };
+pub use hdk_records::outbox::{ReplicationStatusParams, ReplicationStatus};
 
 /// Toplevel I/O structs for WASM API
 
@@ -58,6 +59,9 @@ pub struct Response {
 #[serde(rename_all = "camelCase")]
 pub struct ResponseData {
     pub satisfaction: Response,
+    // state of any writes to the cooperating DNA triggered by the request
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub replication: Vec<ReplicationStatus>,
 }
 
 //---------------- CREATE REQUEST ----------------
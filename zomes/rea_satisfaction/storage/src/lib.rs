@@ -9,9 +9,10 @@
 use hdk::prelude::*;
 
 use hdk_records::{
-    MaybeUndefined,
+    RecordAPIResult, MaybeUndefined,
     record_interface::Updateable,
     generate_record_entry,
+    validation::must_get_local_reference,
 };
 use vf_measurement::QuantityValue;
 
@@ -62,6 +63,15 @@ pub struct EntryData {
     pub note: Option<String>,
 }
 
+impl EntryData {
+    /// Ensure the satisfied intent and satisfying event or commitment exist, where they are in this DNA.
+    ///
+    pub fn must_get_references(&self) -> RecordAPIResult<()> {
+        must_get_local_reference(&self.satisfied_by)?;
+        must_get_local_reference(&self.satisfies)
+    }
+}
+
 generate_record_entry!(EntryData, SatisfactionAddress, EntryStorage);
 
 //---------------- CREATE ----------------
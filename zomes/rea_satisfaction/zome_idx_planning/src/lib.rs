@@ -10,6 +10,7 @@ use hdk_semantic_indexes_zome_lib::ByAddress; // disambiguate from RPC query str
 
 #[index_zome]
 struct Satisfaction {
+    #[source_field]
     satisfies: Local<intent, satisfied_by>,
     satisfied_by: Local<commitment, satisfies>,
 }
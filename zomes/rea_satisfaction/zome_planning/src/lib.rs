@@ -12,6 +12,35 @@ use hdk::prelude::*;
 use hc_zome_rea_satisfaction_lib_origin::*;
 use hc_zome_rea_satisfaction_rpc::*;
 use hc_zome_rea_satisfaction_storage_consts::*;
+use hc_zome_rea_satisfaction_storage::{EntryStorage};
+use hdk_records::validation::{get_modified_revision, validate_modification_author};
+
+// interval between attempts to replicate undelivered writes to the observation DNA
+const REPLICATION_RETRY_INTERVAL_SECS: u64 = 60;
+
+#[hdk_extern]
+fn init(_: ()) -> ExternResult<InitCallbackResult> {
+    schedule("scheduled_satisfaction_replication")?;
+    Ok(InitCallbackResult::Pass)
+}
+
+#[hdk_extern]
+fn validate(validation_data: ValidateData) -> ExternResult<ValidateCallbackResult> {
+    let element = validation_data.element;
+    let (signed_header, _entry) = element.into_inner();
+    let header = signed_header.header();
+
+    // satisfactions may only be updated or deleted by their original author
+    if let Some(modified) = get_modified_revision(header)? {
+        if EntryStorage::try_from(&modified.entry).is_ok() {
+            if let Err(e) = validate_modification_author(header, &modified, &[]) {
+                return Ok(ValidateCallbackResult::Invalid(e.encode()));
+            }
+        }
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
 
 #[hdk_extern]
 fn entry_defs(_: ()) -> ExternResult<EntryDefsCallbackResult> {
@@ -23,10 +52,31 @@ fn entry_defs(_: ()) -> ExternResult<EntryDefsCallbackResult> {
             crdt_type: CrdtType,
             required_validations: 1.into(),
             required_validation_type: RequiredValidationType::default(),
-        }
+        },
+        ReplicationRequest::entry_def(),
+        ReplicationAttempt::entry_def(),
     ]))
 }
 
+#[hdk_extern]
+fn validate(validation_data: ValidateData) -> ExternResult<ValidateCallbackResult> {
+    let element = validation_data.element;
+    let entry = element.into_inner().1;
+    let entry = match entry {
+        ElementEntry::Present(e) => e,
+        _ => return Ok(ValidateCallbackResult::Valid),
+    };
+
+    match EntryStorage::try_from(&entry) {
+        Ok(satisfaction_storage) => {
+            let record = satisfaction_storage.entry();
+            record.must_get_references()?;
+            Ok(ValidateCallbackResult::Valid)
+        },
+        _ => Ok(ValidateCallbackResult::Valid),
+    }
+}
+
 #[hdk_extern]
 fn create_satisfaction(CreateParams { satisfaction }: CreateParams) -> ExternResult<ResponseData> {
     Ok(handle_create_satisfaction(SATISFACTION_ENTRY_TYPE, satisfaction)?)
@@ -42,7 +92,55 @@ fn update_satisfaction(UpdateParams { satisfaction }: UpdateParams) -> ExternRes
     Ok(handle_update_satisfaction(SATISFACTION_ENTRY_TYPE, satisfaction)?)
 }
 
+#[hdk_extern]
+fn get_satisfaction_revision_heads(ByAddress { address }: ByAddress<SatisfactionAddress>) -> ExternResult<Vec<RevisionHash>> {
+    Ok(handle_get_satisfaction_revision_heads(SATISFACTION_ENTRY_TYPE, address)?)
+}
+
+#[hdk_extern]
+fn merge_satisfaction_revisions(MergeRevisions { revision_id, superseded_revisions }: MergeRevisions) -> ExternResult<ResponseData> {
+    Ok(handle_merge_satisfaction_revisions(SATISFACTION_ENTRY_TYPE, revision_id, superseded_revisions)?)
+}
+
+#[hdk_extern]
+fn get_satisfaction_history(ByAddress { address }: ByAddress<SatisfactionAddress>) -> ExternResult<Vec<RevisionMeta>> {
+    Ok(handle_get_satisfaction_history(SATISFACTION_ENTRY_TYPE, address)?)
+}
+
+#[hdk_extern]
+fn get_satisfaction_at_revision(ByHeader { address }: ByHeader) -> ExternResult<ResponseData> {
+    Ok(handle_get_satisfaction_at_revision(address)?)
+}
+
+#[hdk_extern]
+fn get_satisfaction_as_of(ByAddressAsOf { address, as_of }: ByAddressAsOf<SatisfactionAddress>) -> ExternResult<ResponseData> {
+    Ok(handle_get_satisfaction_as_of(SATISFACTION_ENTRY_TYPE, address, as_of)?)
+}
+
+#[hdk_extern]
+fn diff_satisfaction_revisions(ByRevisionPair { from_revision_id, to_revision_id }: ByRevisionPair) -> ExternResult<RevisionDiff> {
+    Ok(handle_diff_satisfaction_revisions(from_revision_id, to_revision_id)?)
+}
+
 #[hdk_extern]
 fn delete_satisfaction(ByHeader { address }: ByHeader) -> ExternResult<bool> {
     Ok(handle_delete_satisfaction(address)?)
 }
+
+#[hdk_extern]
+fn get_satisfaction_replication_status(ReplicationStatusParams { address }: ReplicationStatusParams<SatisfactionAddress>) -> ExternResult<Vec<ReplicationStatus>> {
+    Ok(handle_get_satisfaction_replication_status(address)?)
+}
+
+#[hdk_extern]
+fn retry_satisfaction_replications(_: ()) -> ExternResult<Vec<ReplicationStatus>> {
+    Ok(handle_retry_satisfaction_replications()?)
+}
+
+#[hdk_extern(infallible)]
+fn scheduled_satisfaction_replication(_: Option<Schedule>) -> Option<Schedule> {
+    if let Err(e) = handle_retry_satisfaction_replications() {
+        warn!("satisfaction replication failed: {}", e);
+    }
+    Some(Schedule::Ephemeral(std::time::Duration::from_secs(REPLICATION_RETRY_INTERVAL_SECS)))
+}
@@ -12,9 +12,22 @@ use hdk_records::{
         create_anchored_record,
         read_anchored_record_entry,
         update_anchored_record,
+        read_anchored_record_revision_heads,
+        merge_anchored_record_revisions,
+        read_anchored_record_revision_history,
+        read_anchored_record_entry_by_header,
+        read_anchored_record_entry_as_of,
         delete_anchored_record,
+        query_anchored_root_index_page,
+    },
+    history::{
+        Timestamp,
+        diff_record_revisions,
     },
 };
+pub use hdk_records::history::{RevisionMeta, RevisionDiff};
+pub use vf_jsonld::{ExportParams, ExportPage};
+use vf_jsonld::{JsonLdNode, build_export_page};
 
 pub use vf_attributes_hdk::{
     ByHeader, ByAddress,
@@ -47,8 +60,66 @@ pub fn handle_update_unit<S>(entry_def_id: S, unit: UpdateRequest) -> RecordAPIR
     Ok(construct_response(&new_id, &new_revision, &new_entry))
 }
 
-pub fn handle_delete_unit(revision_id: RevisionHash) -> RecordAPIResult<bool> {
-    delete_anchored_record::<EntryData, RevisionHash>(&revision_id)
+pub fn handle_get_unit_revision_heads<S>(entry_def_id: S, id: UnitId) -> RecordAPIResult<Vec<RevisionHash>>
+    where S: AsRef<str>,
+{
+    let id_str: &String = id.as_ref();
+    read_anchored_record_revision_heads(&entry_def_id, id_str)
+}
+
+pub fn handle_merge_unit_revisions<S>(entry_def_id: S, revision_id: RevisionHash, superseded_revisions: Vec<RevisionHash>) -> RecordAPIResult<ResponseData>
+    where S: AsRef<str>,
+{
+    let (revision, entry_id, entry): (_,UnitId,_) = merge_anchored_record_revisions::<EntryData, EntryStorage, UnitInternalAddress, _,_>(&entry_def_id, &revision_id, superseded_revisions.as_slice())?;
+    Ok(construct_response(&entry_id, &revision, &entry))
+}
+
+pub fn handle_get_unit_history<S>(entry_def_id: S, id: UnitId) -> RecordAPIResult<Vec<RevisionMeta>>
+    where S: AsRef<str>,
+{
+    let id_str: &String = id.as_ref();
+    read_anchored_record_revision_history(&entry_def_id, id_str)
+}
+
+pub fn handle_get_unit_at_revision<S>(entry_def_id: S, revision_id: RevisionHash) -> RecordAPIResult<ResponseData>
+    where S: AsRef<str>,
+{
+    let (entry_id, entry): (UnitId,_) = read_anchored_record_entry_by_header::<EntryData, EntryStorage, UnitInternalAddress, _,_>(&entry_def_id, &revision_id)?;
+    Ok(construct_response(&entry_id, &revision_id, &entry))
+}
+
+pub fn handle_get_unit_as_of<S>(entry_def_id: S, id: UnitId, as_of: Timestamp) -> RecordAPIResult<ResponseData>
+    where S: AsRef<str>,
+{
+    let id_str: &String = id.as_ref();
+    let (revision_id, entry_id, entry): (_,UnitId,_) = read_anchored_record_entry_as_of::<EntryData, EntryStorage, UnitInternalAddress, _,_,_>(&entry_def_id, id_str, &as_of)?;
+    Ok(construct_response(&entry_id, &revision_id, &entry))
+}
+
+pub fn handle_diff_unit_revisions(from_revision_id: RevisionHash, to_revision_id: RevisionHash) -> RecordAPIResult<RevisionDiff>
+{
+    diff_record_revisions::<EntryData, EntryStorage, UnitInternalAddress>(&from_revision_id, &to_revision_id)
+}
+
+pub fn handle_delete_unit<S>(entry_def_id: S, revision_id: RevisionHash) -> RecordAPIResult<bool>
+    where S: AsRef<str>,
+{
+    delete_anchored_record::<EntryData, EntryStorage, UnitInternalAddress, _,_>(&entry_def_id, &revision_id)
+}
+
+/// Export a page of all units in the DNA as ValueFlows JSON-LD
+///
+/// Pages may hold fewer than `limit` records, since the root index of units also
+/// references their `anchor index` paths.
+///
+pub fn handle_export_units<S>(entry_def_id: S, params: ExportParams) -> RecordAPIResult<ExportPage>
+    where S: AsRef<str>,
+{
+    let limit = params.get_limit();
+    let page = query_anchored_root_index_page::<EntryData, EntryStorage, UnitInternalAddress, UnitId, _>(&entry_def_id, &params.after, limit)?;
+    build_export_page(page, limit, |(revision_id, entry_id, entry)| {
+        Ok(construct_jsonld(&construct_response(&entry_id, &revision_id, &entry).unit))
+    })
 }
 
 fn construct_response<'a>(
@@ -63,3 +134,11 @@ fn construct_response<'a>(
         }
     }
 }
+
+/// Create a ValueFlows JSON-LD node from an output record
+///
+pub fn construct_jsonld(r: &Response) -> JsonLdNode {
+    JsonLdNode::new("om2:Unit", &r.id)
+        .literal("label", &r.label)
+        .literal("symbol", &r.symbol)
+}
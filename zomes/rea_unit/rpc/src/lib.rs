@@ -17,7 +17,7 @@ use hdk_records::{
 
 // Export external type interface to allow consuming zomes to easily import & define zome API
 pub use vf_attributes_hdk::{
-    RevisionHash,
+    RevisionHash, MergeRevisions, ByAddressAsOf, ByRevisionPair,
     UnitId,
 };
 
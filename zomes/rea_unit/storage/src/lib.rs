@@ -10,6 +10,7 @@ use hdk::prelude::*;
 
 use hdk_records::{
     generate_record_entry,
+    ErrorCode, ErrorDetails,
     record_interface::{ Updateable },
 };
 
@@ -32,6 +33,13 @@ impl<'a> EntryData {
     pub fn get_symbol(&'a self) -> String {
         self.symbol.to_owned()
     }
+
+    pub fn validate_symbol(&self) -> Result<(), ErrorDetails> {
+        if self.symbol.trim().is_empty() {
+            return Err(ErrorDetails::new(ErrorCode::MissingRequiredField, "Unit symbol must not be empty").at_field("symbol"));
+        }
+        Ok(())
+    }
 }
 
 generate_record_entry!(EntryData, UnitInternalAddress, EntryStorage);
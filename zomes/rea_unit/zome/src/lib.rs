@@ -11,6 +11,50 @@ use hdk::prelude::*;
 
 use hc_zome_rea_unit_rpc::*;
 use hc_zome_rea_unit_lib::*;
+use hc_zome_rea_unit_storage::*;
+use hdk_records::{
+    validation::{get_modified_revision, validate_modification_author},
+    records_anchored::validate_anchor_link,
+};
+
+#[hdk_extern]
+fn validate(validation_data: ValidateData) -> ExternResult<ValidateCallbackResult> {
+    let element = validation_data.element;
+    let (signed_header, entry) = element.into_inner();
+    let header = signed_header.header();
+
+    // unit symbols may only be claimed by a unit revision holding that symbol, written by the same agent
+    if let Header::CreateLink(link) = header {
+        if let Err(e) = validate_anchor_link::<EntryData, EntryStorage, UnitInternalAddress, _,_>(&UNIT_ENTRY_TYPE, link, |unit| unit.get_symbol())? {
+            return Ok(ValidateCallbackResult::Invalid(e.encode()));
+        }
+        return Ok(ValidateCallbackResult::Valid);
+    }
+
+    // units may only be updated or deleted by their original author
+    if let Some(modified) = get_modified_revision(header)? {
+        if EntryStorage::try_from(&modified.entry).is_ok() {
+            if let Err(e) = validate_modification_author(header, &modified, &[]) {
+                return Ok(ValidateCallbackResult::Invalid(e.encode()));
+            }
+        }
+    }
+
+    let entry = match entry {
+        ElementEntry::Present(e) => e,
+        _ => return Ok(ValidateCallbackResult::Valid),
+    };
+
+    match EntryStorage::try_from(&entry) {
+        Ok(unit_storage) => {
+            let record = unit_storage.entry();
+            record.validate_symbol()
+                .and_then(|()| { Ok(ValidateCallbackResult::Valid) })
+                .or_else(|e| { Ok(ValidateCallbackResult::Invalid(e.encode())) })
+        },
+        _ => Ok(ValidateCallbackResult::Valid),
+    }
+}
 
 #[hdk_extern]
 fn entry_defs(_: ()) -> ExternResult<EntryDefsCallbackResult> {
@@ -57,7 +101,42 @@ fn update_unit(UpdateParams { unit }: UpdateParams) -> ExternResult<ResponseData
     Ok(handle_update_unit(UNIT_ENTRY_TYPE, unit)?)
 }
 
+#[hdk_extern]
+fn get_unit_revision_heads(ById { id }: ById) -> ExternResult<Vec<RevisionHash>> {
+    Ok(handle_get_unit_revision_heads(UNIT_ENTRY_TYPE, id)?)
+}
+
+#[hdk_extern]
+fn merge_unit_revisions(MergeRevisions { revision_id, superseded_revisions }: MergeRevisions) -> ExternResult<ResponseData> {
+    Ok(handle_merge_unit_revisions(UNIT_ENTRY_TYPE, revision_id, superseded_revisions)?)
+}
+
+#[hdk_extern]
+fn get_unit_history(ById { id }: ById) -> ExternResult<Vec<RevisionMeta>> {
+    Ok(handle_get_unit_history(UNIT_ENTRY_TYPE, id)?)
+}
+
+#[hdk_extern]
+fn get_unit_at_revision(ByHeader { address }: ByHeader) -> ExternResult<ResponseData> {
+    Ok(handle_get_unit_at_revision(UNIT_ENTRY_TYPE, address)?)
+}
+
+#[hdk_extern]
+fn get_unit_as_of(ByAddressAsOf { address, as_of }: ByAddressAsOf<UnitId>) -> ExternResult<ResponseData> {
+    Ok(handle_get_unit_as_of(UNIT_ENTRY_TYPE, address, as_of)?)
+}
+
+#[hdk_extern]
+fn diff_unit_revisions(ByRevisionPair { from_revision_id, to_revision_id }: ByRevisionPair) -> ExternResult<RevisionDiff> {
+    Ok(handle_diff_unit_revisions(from_revision_id, to_revision_id)?)
+}
+
 #[hdk_extern]
 fn delete_unit(ByHeader { address }: ByHeader) -> ExternResult<bool> {
-    Ok(handle_delete_unit(address)?)
+    Ok(handle_delete_unit(UNIT_ENTRY_TYPE, address)?)
+}
+
+#[hdk_extern]
+fn export_units(params: ExportParams) -> ExternResult<ExportPage> {
+    Ok(handle_export_units(UNIT_ENTRY_TYPE, params)?)
 }